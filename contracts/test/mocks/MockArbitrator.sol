@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+/// @title MockArbitrator - Minimal ERC-792 arbitrator for off-chain client tests
+/// @notice Exposes the IArbitrator surface used by the node's KlerosClient
+/// @dev Rulings and appeal periods are set directly by the test via giveRuling / setAppealPeriod
+contract MockArbitrator {
+    enum DisputeStatus {
+        Waiting,
+        Appealable,
+        Solved
+    }
+
+    struct Dispute {
+        address arbitrable;
+        uint256 choices;
+        uint256 ruling;
+        DisputeStatus status;
+        uint256 appealStart;
+        uint256 appealEnd;
+    }
+
+    uint256 public immutable baseCost;
+    Dispute[] public disputes;
+
+    event DisputeCreation(uint256 indexed _disputeID, address indexed _arbitrable);
+    event AppealPossible(uint256 indexed _disputeID, address indexed _arbitrable);
+    event AppealDecision(uint256 indexed _disputeID, address indexed _arbitrable);
+
+    error InsufficientFee(uint256 required, uint256 provided);
+    error InvalidRuling(uint256 ruling);
+    error NotAppealable(uint256 disputeID);
+
+    constructor(uint256 _baseCost) {
+        baseCost = _baseCost;
+    }
+
+    function createDispute(uint256 _choices, bytes calldata _extraData) external payable returns (uint256 disputeID) {
+        uint256 cost = arbitrationCost(_extraData);
+        if (msg.value < cost) revert InsufficientFee(cost, msg.value);
+
+        disputeID = disputes.length;
+        disputes.push(
+            Dispute({
+                arbitrable: msg.sender,
+                choices: _choices,
+                ruling: 0,
+                status: DisputeStatus.Waiting,
+                appealStart: 0,
+                appealEnd: 0
+            })
+        );
+        emit DisputeCreation(disputeID, msg.sender);
+    }
+
+    function arbitrationCost(bytes calldata _extraData) public view returns (uint256) {
+        // Scale by the requested juror count (second word of extraData), like KlerosLiquid
+        uint256 jurors = _extraData.length >= 64 ? abi.decode(_extraData[32:64], (uint256)) : 1;
+        return baseCost * (jurors == 0 ? 1 : jurors);
+    }
+
+    function appeal(uint256 _disputeID, bytes calldata _extraData) external payable {
+        Dispute storage dispute = disputes[_disputeID];
+        if (dispute.status != DisputeStatus.Appealable) revert NotAppealable(_disputeID);
+        uint256 cost = appealCost(_disputeID, _extraData);
+        if (msg.value < cost) revert InsufficientFee(cost, msg.value);
+
+        dispute.status = DisputeStatus.Waiting;
+        dispute.appealStart = 0;
+        dispute.appealEnd = 0;
+        emit AppealDecision(_disputeID, dispute.arbitrable);
+    }
+
+    function appealCost(uint256, bytes calldata _extraData) public view returns (uint256) {
+        return 2 * arbitrationCost(_extraData);
+    }
+
+    function appealPeriod(uint256 _disputeID) external view returns (uint256 start, uint256 end) {
+        Dispute storage dispute = disputes[_disputeID];
+        return (dispute.appealStart, dispute.appealEnd);
+    }
+
+    function disputeStatus(uint256 _disputeID) external view returns (DisputeStatus status) {
+        return disputes[_disputeID].status;
+    }
+
+    function currentRuling(uint256 _disputeID) external view returns (uint256 ruling) {
+        return disputes[_disputeID].ruling;
+    }
+
+    /// @notice Test hook: record a ruling and open an appeal window of `_appealWindow` seconds
+    function giveRuling(uint256 _disputeID, uint256 _ruling, uint256 _appealWindow) external {
+        Dispute storage dispute = disputes[_disputeID];
+        if (_ruling > dispute.choices) revert InvalidRuling(_ruling);
+
+        dispute.ruling = _ruling;
+        if (_appealWindow == 0) {
+            dispute.status = DisputeStatus.Solved;
+        } else {
+            dispute.status = DisputeStatus.Appealable;
+            dispute.appealStart = block.timestamp;
+            dispute.appealEnd = block.timestamp + _appealWindow;
+            emit AppealPossible(_disputeID, dispute.arbitrable);
+        }
+    }
+}
@@ -85,6 +85,7 @@ fn bench_message_batch_serialization(c: &mut Criterion) {
         let messages: Vec<_> = (0..*batch_size)
             .map(|i| TrustMessage::ReputationEvent {
                 did: format!("did:agoramesh:base:agent-{}", i),
+                counterparty_did: Some(format!("did:agoramesh:base:client-{}", i)),
                 success: i % 2 == 0,
                 amount: (i * 1000) as u64,
                 timestamp: 1704067200 + i as u64,
@@ -198,6 +199,7 @@ fn bench_trust_cache(c: &mut Criterion) {
                 successful_transactions: 100,
                 failed_transactions: 5,
                 endorsement_count: 3,
+                last_activity: 0,
             };
             runtime.block_on(async {
                 cache.insert(&did, info).await;
@@ -219,6 +221,7 @@ fn bench_trust_cache(c: &mut Criterion) {
                 successful_transactions: 100,
                 failed_transactions: 5,
                 endorsement_count: 3,
+                last_activity: 0,
             };
             cache.insert(&did, info).await;
         }
@@ -428,6 +431,7 @@ fn create_sample_card(did: &str) -> CapabilityCard {
             id: "code-review".to_string(),
             name: "Code Review".to_string(),
             description: Some("Automated code review service".to_string()),
+            tags: vec![],
             input_schema: None,
             output_schema: None,
         }],
@@ -438,6 +442,9 @@ fn create_sample_card(did: &str) -> CapabilityCard {
             stake: Some(1_000_000_000),
             pricing: None,
             payment_methods: vec!["x402".to_string()],
+            version: None,
+            updated_at: None,
+            proof: None,
         }),
     }
 }
@@ -0,0 +1,166 @@
+//! Vector Index Benchmarks
+//!
+//! Compares the embedded HNSW index against the brute-force in-memory scan
+//! used by hybrid search:
+//! - Query latency at 10k and 100k cards
+//! - Recall@10 of HNSW relative to the exact brute-force results
+//!
+//! Vectors are synthetic 384-dimensional embeddings drawn around a set of
+//! topic centroids, which resembles real card embeddings more closely than
+//! uniform noise. Queries come from the same centroids, so they land near
+//! indexed vectors. Recall is printed once per size before the timing runs.
+//!
+//! ## Running Benchmarks
+//!
+//! ```bash
+//! cargo bench --bench search_benchmarks
+//! cargo bench --bench search_benchmarks -- 10000
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+use agoramesh_node::search::{
+    HnswConfig, HnswIndex, InMemoryVectorIndex, VectorFilter, VectorIndex, VectorPayload,
+    VectorPoint, EMBEDDING_DIM,
+};
+
+const SIZES: [usize; 2] = [10_000, 100_000];
+const TOPICS: usize = 256;
+const QUERIES: usize = 100;
+const K: usize = 10;
+
+// ============================================================================
+// Data Generation
+// ============================================================================
+
+fn unit(vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    vector.into_iter().map(|x| x / norm).collect()
+}
+
+fn random_vector(rng: &mut StdRng) -> Vec<f32> {
+    unit(
+        (0..EMBEDDING_DIM)
+            .map(|_| rng.gen_range(-1.0..1.0))
+            .collect(),
+    )
+}
+
+fn topic_centroids(rng: &mut StdRng) -> Vec<Vec<f32>> {
+    (0..TOPICS).map(|_| random_vector(rng)).collect()
+}
+
+fn clustered_vectors(centroids: &[Vec<f32>], count: usize, rng: &mut StdRng) -> Vec<Vec<f32>> {
+    (0..count)
+        .map(|_| {
+            let centroid = &centroids[rng.gen_range(0..TOPICS)];
+            unit(
+                centroid
+                    .iter()
+                    .map(|c| c + rng.gen_range(-0.05..0.05))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn points(vectors: &[Vec<f32>]) -> Vec<VectorPoint> {
+    vectors
+        .iter()
+        .enumerate()
+        .map(|(i, embedding)| VectorPoint {
+            did: format!("did:agoramesh:base:agent-{}", i),
+            embedding: embedding.clone(),
+            payload: VectorPayload::default(),
+        })
+        .collect()
+}
+
+// ============================================================================
+// Vector Search Benchmarks
+// ============================================================================
+
+fn bench_vector_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vector_search");
+    group.sample_size(20);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let filter = VectorFilter::default();
+
+    for size in SIZES {
+        let mut rng = StdRng::seed_from_u64(42);
+        let centroids = topic_centroids(&mut rng);
+        let vectors = clustered_vectors(&centroids, size, &mut rng);
+        let queries = clustered_vectors(&centroids, QUERIES, &mut rng);
+
+        let brute_force = InMemoryVectorIndex::new();
+        let hnsw = HnswIndex::new(&HnswConfig::default(), EMBEDDING_DIM);
+        runtime.block_on(async {
+            brute_force.upsert(points(&vectors)).await.unwrap();
+            hnsw.upsert(points(&vectors)).await.unwrap();
+        });
+
+        // Recall@K of HNSW against the exact results
+        let hits: usize = runtime.block_on(async {
+            let mut hits = 0;
+            for query in &queries {
+                let exact: HashSet<String> = brute_force
+                    .search(query, &filter, K)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|m| m.did)
+                    .collect();
+                hits += hnsw
+                    .search(query, &filter, K)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .filter(|m| exact.contains(&m.did))
+                    .count();
+            }
+            hits
+        });
+        println!(
+            "vector_search/{}: hnsw recall@{} = {:.3}",
+            size,
+            K,
+            hits as f64 / (QUERIES * K) as f64
+        );
+
+        group.bench_with_input(BenchmarkId::new("brute_force", size), &size, |b, _| {
+            let mut i = 0;
+            b.iter(|| {
+                i = (i + 1) % QUERIES;
+                let matches = runtime
+                    .block_on(brute_force.search(&queries[i], &filter, K))
+                    .unwrap();
+                black_box(matches);
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("hnsw", size), &size, |b, _| {
+            let mut i = 0;
+            b.iter(|| {
+                i = (i + 1) % QUERIES;
+                let matches = runtime
+                    .block_on(hnsw.search(&queries[i], &filter, K))
+                    .unwrap();
+                black_box(matches);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// Criterion Configuration
+// ============================================================================
+
+criterion_group!(benches, bench_vector_search);
+
+criterion_main!(benches);
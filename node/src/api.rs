@@ -4,16 +4,20 @@
 //! - Health check endpoint
 //! - Agent discovery endpoints
 //! - Trust query endpoints
+//! - Node DID Document (`/.well-known/did.json`)
+//! - W3C DID Resolution endpoint (`/dids/{did}`)
+//! - AI arbitration dispute status (`/disputes/{id}`)
 //! - A2A protocol endpoints
 
 use axum::{
     extract::{Path, Query, State},
     http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -21,10 +25,18 @@ use std::time::Instant;
 use tokio::sync::RwLock;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
+use crate::arbitration::{AIArbitrator, AIDispute};
 use crate::config::ApiConfig;
-use crate::discovery::{CapabilityCard, DiscoveryService};
-use crate::error::Result;
+use crate::did::{
+    DIDDocument, DIDResolutionResult, DidResolver, DID_DOCUMENT_CONTENT_TYPE,
+    DID_RESOLUTION_CONTENT_TYPE,
+};
+use crate::discovery::{
+    CapabilityCard, DiscoveryPage, DiscoveryQuery, DiscoveryService, SchemaMatchQuery, SkillMatch,
+};
+use crate::error::{Error, Result};
 use crate::metrics::{MetricsConfig, MetricsService};
+use crate::network::{MessageHandler, NatState};
 use crate::rate_limit::{RateLimitConfig, RateLimitLayer, RateLimitService};
 use crate::search::HybridSearch;
 use crate::trust::{TrustInfo, TrustService};
@@ -43,6 +55,10 @@ pub struct HealthResponse {
 
     /// Node uptime in seconds.
     pub uptime_seconds: u64,
+
+    /// NAT reachability and relay addresses.
+    #[serde(default)]
+    pub nat: NatState,
 }
 
 /// Node identity and configuration info.
@@ -79,6 +95,17 @@ pub struct AppState {
     pub hybrid_search: Option<Arc<RwLock<HybridSearch>>>,
     /// Optional admin token for agent registration.
     pub api_token: Option<String>,
+    /// Optional gossip message handler, for exposing its statistics.
+    pub message_handler: Option<Arc<MessageHandler>>,
+    /// Optional DID Document derived from the node's persistent identity.
+    pub did_document: Option<DIDDocument>,
+    /// Optional DID resolver backing `/dids/{did}`.
+    pub did_resolver: Option<Arc<dyn DidResolver>>,
+    /// NAT traversal state, updated from swarm events.
+    pub nat_state: Arc<RwLock<NatState>>,
+    /// Optional AI arbitrator backing `/disputes/{id}`, shared with the
+    /// gossip handler and the contract event sink.
+    pub arbitrator: Option<Arc<AIArbitrator>>,
 }
 
 /// Semantic search result with scores.
@@ -86,8 +113,11 @@ pub struct AppState {
 pub struct SemanticSearchResult {
     /// Agent DID.
     pub did: String,
-    /// Combined hybrid score (0.0 - 1.0).
+    /// Ranking score (0.0 - 1.0), blending relevance with trust when
+    /// trust-aware ranking is enabled.
     pub score: f32,
+    /// Combined vector and keyword relevance (0.0 - 1.0).
+    pub relevance_score: f32,
     /// Vector similarity score.
     pub vector_score: f32,
     /// Keyword match score.
@@ -107,13 +137,6 @@ pub struct ApiServer {
     state: AppState,
 }
 
-/// Query parameters for agent search.
-#[derive(Debug, Deserialize)]
-pub struct SearchQuery {
-    /// Search query string.
-    pub q: Option<String>,
-}
-
 /// API error response.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiError {
@@ -138,6 +161,11 @@ impl ApiServer {
             metrics: Arc::new(MetricsService::new(MetricsConfig::default())),
             hybrid_search: None,
             api_token,
+            message_handler: None,
+            did_document: None,
+            did_resolver: None,
+            nat_state: Arc::new(RwLock::new(NatState::default())),
+            arbitrator: None,
         };
         Self { config, state }
     }
@@ -158,22 +186,30 @@ impl ApiServer {
             .with_trust_proxy(self.config.trust_proxy);
 
         // Routes that are rate limited (API endpoints)
-        // Note: /agents/semantic must come BEFORE /agents/{did} to avoid being captured
+        // Note: /agents/search, /agents/match and /agents/semantic must come BEFORE /agents/{did} to avoid being captured
         let rate_limited_routes = Router::new()
             .route(
                 "/agents",
                 get(search_agents_handler).post(register_agent_handler),
             )
-            .route("/agents/semantic", get(semantic_search_handler))
+            .route("/agents/search", post(query_agents_handler))
+            .route("/agents/match", post(match_agents_handler))
+            .route(
+                "/agents/semantic",
+                get(semantic_search_handler).post(semantic_query_handler),
+            )
             .route("/agents/{did}", get(get_agent_handler))
             .route("/trust/{did}", get(get_trust_handler))
+            .route("/dids/{did}", get(resolve_did_handler))
+            .route("/disputes/{id}", get(get_dispute_handler))
             .layer(rate_limit_layer);
 
         // Routes that are NOT rate limited (health checks, metadata, metrics)
         let unrestricted_routes = Router::new()
             .route("/health", get(health_handler))
             .route("/metrics", get(metrics_handler))
-            .route("/.well-known/agent.json", get(agent_card_handler));
+            .route("/.well-known/agent.json", get(agent_card_handler))
+            .route("/.well-known/did.json", get(did_document_handler));
 
         // Combine all routes
         let mut router = Router::new()
@@ -247,6 +283,12 @@ fn build_cors_layer(config: &ApiConfig) -> Option<CorsLayer> {
         HeaderName::from_static("x-api-key"),
     ]);
 
+    // Let browser clients read discovery paging headers
+    cors = cors.expose_headers([
+        HeaderName::from_static(TOTAL_COUNT_HEADER),
+        HeaderName::from_static(NEXT_CURSOR_HEADER),
+    ]);
+
     Some(cors)
 }
 
@@ -295,6 +337,7 @@ async fn health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
         version: env!("CARGO_PKG_VERSION").to_string(),
         peer_count,
         uptime_seconds,
+        nat: state.nat_state.read().await.clone(),
     })
 }
 
@@ -308,6 +351,11 @@ async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
     let peers = state.peer_count.load(Ordering::Relaxed);
     state.metrics.p2p_peers(peers);
 
+    // Update gossip message handler statistics
+    if let Some(ref handler) = state.message_handler {
+        state.metrics.message_handler_stats(&handler.stats().await);
+    }
+
     // Render metrics in Prometheus format
     let body = state.metrics.render();
 
@@ -350,33 +398,178 @@ async fn agent_card_handler(State(state): State<AppState>) -> Json<CapabilityCar
             stake: None,
             pricing: None,
             payment_methods: vec!["x402".to_string()],
+            version: None,
+            updated_at: None,
+            proof: None,
         }),
     })
 }
 
-/// Search agents handler.
-async fn search_agents_handler(
+/// Node DID Document handler.
+///
+/// Serves the `did:agoramesh` document for the node's persistent key.
+async fn did_document_handler(
     State(state): State<AppState>,
-    Query(params): Query<SearchQuery>,
-) -> std::result::Result<Json<Vec<CapabilityCard>>, (StatusCode, Json<ApiError>)> {
-    let query = params.q.unwrap_or_default();
-
-    match state.discovery.search(&query).await {
-        Ok(agents) => Ok(Json(agents)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
+) -> std::result::Result<Json<DIDDocument>, (StatusCode, Json<ApiError>)> {
+    match &state.did_document {
+        Some(document) => Ok(Json(document.clone())),
+        None => Err((
+            StatusCode::NOT_FOUND,
             Json(ApiError {
-                error: e.to_string(),
+                error: "Node DID Document not configured".to_string(),
             }),
         )),
     }
 }
 
-/// Semantic search handler using HybridSearch (vector + keyword).
+/// W3C DID Resolution handler.
+///
+/// Returns a DID Resolution result, or just the DID Document when the client
+/// asks for `application/did+ld+json`. Resolution errors map to HTTP status
+/// codes per the DID Resolution HTTP(S) binding.
+async fn resolve_did_handler(
+    State(state): State<AppState>,
+    Path(did): Path<String>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let Some(ref resolver) = state.did_resolver else {
+        return (
+            StatusCode::NOT_IMPLEMENTED,
+            Json(ApiError {
+                error: "DID resolution not available (resolver not configured)".to_string(),
+            }),
+        )
+            .into_response();
+    };
+
+    let result = resolver.resolve_result(&did).await;
+    let status = resolution_status(&result);
+
+    let wants_document = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains(DID_DOCUMENT_CONTENT_TYPE));
+    if wants_document && status == StatusCode::OK {
+        if let Some(document) = result.did_document {
+            return (
+                [(header::CONTENT_TYPE, DID_DOCUMENT_CONTENT_TYPE)],
+                Json(document),
+            )
+                .into_response();
+        }
+    }
+
+    (
+        status,
+        [(header::CONTENT_TYPE, DID_RESOLUTION_CONTENT_TYPE)],
+        Json(result),
+    )
+        .into_response()
+}
+
+/// HTTP status for a DID Resolution result.
+fn resolution_status(result: &DIDResolutionResult) -> StatusCode {
+    match result.did_resolution_metadata.error.as_deref() {
+        None if result.is_deactivated() => StatusCode::GONE,
+        None => StatusCode::OK,
+        Some("invalidDid") => StatusCode::BAD_REQUEST,
+        Some("notFound") => StatusCode::NOT_FOUND,
+        Some("methodNotSupported") => StatusCode::NOT_IMPLEMENTED,
+        Some(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Response header carrying the number of results across all pages.
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Response header carrying the cursor for the next page.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+type ApiResult<T> = std::result::Result<T, (StatusCode, Json<ApiError>)>;
+
+/// Map a query error to a response (400 for invalid queries, 500 otherwise).
+fn query_error(e: Error) -> (StatusCode, Json<ApiError>) {
+    let status = match e {
+        Error::Validation(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(ApiError {
+            error: e.to_string(),
+        }),
+    )
+}
+
+/// Split a page into paging headers and a JSON array body.
+fn page_response<T>(page: DiscoveryPage<T>) -> (HeaderMap, Json<Vec<T>>) {
+    let mut headers = HeaderMap::new();
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(page.total));
+    if let Some(cursor) = page.next_cursor {
+        if let Ok(value) = HeaderValue::from_str(&cursor) {
+            headers.insert(NEXT_CURSOR_HEADER, value);
+        }
+    }
+    (headers, Json(page.items))
+}
+
+/// Search agents handler (query parameters).
+async fn search_agents_handler(
+    State(state): State<AppState>,
+    Query(query): Query<DiscoveryQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<CapabilityCard>>)> {
+    run_agent_query(&state, &query).await
+}
+
+/// Search agents handler (JSON body).
+async fn query_agents_handler(
+    State(state): State<AppState>,
+    Json(query): Json<DiscoveryQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<CapabilityCard>>)> {
+    run_agent_query(&state, &query).await
+}
+
+async fn run_agent_query(
+    state: &AppState,
+    query: &DiscoveryQuery,
+) -> ApiResult<(HeaderMap, Json<Vec<CapabilityCard>>)> {
+    let page = state.discovery.query(query).await.map_err(query_error)?;
+    Ok(page_response(page))
+}
+
+/// Schema match handler: agents whose skill schemas accept a sample payload.
+async fn match_agents_handler(
+    State(state): State<AppState>,
+    Json(query): Json<SchemaMatchQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<SkillMatch>>)> {
+    let page = state
+        .discovery
+        .match_schema(&query)
+        .await
+        .map_err(query_error)?;
+    Ok(page_response(page))
+}
+
+/// Semantic search handler using HybridSearch (query parameters).
 async fn semantic_search_handler(
     State(state): State<AppState>,
-    Query(params): Query<SearchQuery>,
-) -> std::result::Result<Json<Vec<SemanticSearchResult>>, (StatusCode, Json<ApiError>)> {
+    Query(query): Query<DiscoveryQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<SemanticSearchResult>>)> {
+    run_semantic_query(&state, &query).await
+}
+
+/// Semantic search handler using HybridSearch (JSON body).
+async fn semantic_query_handler(
+    State(state): State<AppState>,
+    Json(query): Json<DiscoveryQuery>,
+) -> ApiResult<(HeaderMap, Json<Vec<SemanticSearchResult>>)> {
+    run_semantic_query(&state, &query).await
+}
+
+async fn run_semantic_query(
+    state: &AppState,
+    query: &DiscoveryQuery,
+) -> ApiResult<(HeaderMap, Json<Vec<SemanticSearchResult>>)> {
     // Check if HybridSearch is configured
     let hybrid = match &state.hybrid_search {
         Some(h) => h,
@@ -391,43 +584,58 @@ async fn semantic_search_handler(
         }
     };
 
-    let query = params.q.unwrap_or_default();
-    if query.is_empty() {
-        return Ok(Json(vec![]));
+    query.validate().map_err(query_error)?;
+    query.require_local_scope().map_err(query_error)?;
+    if query.query_text().is_empty() {
+        return Ok(page_response(DiscoveryPage {
+            items: vec![],
+            total: 0,
+            next_cursor: None,
+        }));
     }
 
     // Perform semantic search
-    let hybrid_guard = hybrid.read().await;
-    match hybrid_guard.search(&query).await {
-        Ok(results) => {
-            let mut response: Vec<SemanticSearchResult> = results
-                .into_iter()
-                .map(|r| SemanticSearchResult {
-                    did: r.did,
-                    score: r.score,
-                    vector_score: r.vector_score,
-                    keyword_score: r.keyword_score,
-                    card: r.card,
-                    trust: None,
-                })
-                .collect();
-
-            // Enrich each result with live trust data from TrustService
-            for result in &mut response {
-                if let Ok(trust_info) = state.trust.get_trust(&result.did).await {
-                    result.trust = Some(trust_info);
+    let page = hybrid
+        .read()
+        .await
+        .query(query)
+        .await
+        .map_err(query_error)?;
+    let mut page = page.map(|r| SemanticSearchResult {
+        did: r.did,
+        score: r.score,
+        relevance_score: r.relevance_score,
+        vector_score: r.vector_score,
+        keyword_score: r.keyword_score,
+        card: r.card,
+        trust: r.trust,
+    });
+
+    // Results ranked without trust data are enriched in one batch
+    let missing: Vec<String> = page
+        .items
+        .iter()
+        .filter(|r| r.trust.is_none())
+        .map(|r| r.did.clone())
+        .collect();
+    if !missing.is_empty() {
+        match state.trust.get_trust_many(&missing).await {
+            Ok(infos) => {
+                let mut infos: HashMap<String, TrustInfo> = infos
+                    .into_iter()
+                    .map(|info| (info.did.clone(), info))
+                    .collect();
+                for result in &mut page.items {
+                    if result.trust.is_none() {
+                        result.trust = infos.remove(&result.did);
+                    }
                 }
             }
-
-            Ok(Json(response))
+            Err(e) => tracing::warn!("Failed to enrich search results with trust: {}", e),
         }
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiError {
-                error: e.to_string(),
-            }),
-        )),
     }
+
+    Ok(page_response(page))
 }
 
 /// Get agent by DID handler.
@@ -519,15 +727,42 @@ async fn get_trust_handler(
     }
 }
 
+/// Get an AI arbitration dispute by ID.
+async fn get_dispute_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> std::result::Result<Json<AIDispute>, (StatusCode, Json<ApiError>)> {
+    let Some(ref arbitrator) = state.arbitrator else {
+        return Err((
+            StatusCode::NOT_IMPLEMENTED,
+            Json(ApiError {
+                error: "Arbitration not available (arbitrator not configured)".to_string(),
+            }),
+        ));
+    };
+
+    arbitrator.get_dispute(&id).map(Json).map_err(|e| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::discovery::{AgoraMeshExtension, PricingInfo, PricingModel, ProviderInfo, Skill};
+    use crate::discovery::{
+        AgoraMeshExtension, PricingInfo, PricingModel, ProviderInfo, QuerySort, SearchScope, Skill,
+    };
+    use crate::network::Reachability;
     use axum_test::TestServer;
 
     fn test_state() -> AppState {
         AppState {
-            discovery: Arc::new(DiscoveryService::new()),
+            discovery: Arc::new(DiscoveryService::new().with_required_card_signatures(false)),
             trust: Arc::new(TrustService::new(
                 "https://sepolia.base.org".to_string(),
                 None,
@@ -542,6 +777,11 @@ mod tests {
             // No hybrid search by default
             hybrid_search: None,
             api_token: None,
+            message_handler: None,
+            did_document: None,
+            did_resolver: None,
+            nat_state: Arc::new(RwLock::new(NatState::default())),
+            arbitrator: None,
         }
     }
 
@@ -570,6 +810,7 @@ mod tests {
                 id: "translate".to_string(),
                 name: "Translation".to_string(),
                 description: Some("Translates text".to_string()),
+                tags: vec![],
                 input_schema: None,
                 output_schema: None,
             }],
@@ -584,6 +825,9 @@ mod tests {
                     model: PricingModel::PerRequest,
                 }),
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         }
     }
@@ -602,6 +846,20 @@ mod tests {
         assert!(!health.version.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_health_endpoint_reports_nat_state() {
+        let state = test_state();
+        state.nat_state.write().await.reachability = Reachability::Private;
+        let server = test_server(state);
+
+        let response = server.get("/health").await;
+
+        response.assert_status_ok();
+        let health: HealthResponse = response.json();
+        assert_eq!(health.nat.reachability, Reachability::Private);
+        assert!(health.nat.relay_addresses.is_empty());
+    }
+
     #[tokio::test]
     async fn test_health_endpoint_returns_uptime() {
         let state = test_state();
@@ -643,6 +901,17 @@ mod tests {
         response.assert_header("content-type", "text/plain; version=0.0.4; charset=utf-8");
     }
 
+    #[tokio::test]
+    async fn test_metrics_endpoint_with_message_handler_returns_ok() {
+        let mut state = test_state();
+        state.message_handler = Some(Arc::new(MessageHandler::new(state.discovery.clone())));
+        let server = test_server(state);
+
+        let response = server.get("/metrics").await;
+
+        response.assert_status_ok();
+    }
+
     #[tokio::test]
     async fn test_metrics_endpoint_is_not_rate_limited() {
         // Use a very restrictive rate limiter
@@ -707,6 +976,157 @@ mod tests {
         assert_eq!(card.name, "AgoraMesh Node");
     }
 
+    // ========== TDD Tests: GET /.well-known/did.json ==========
+
+    #[tokio::test]
+    async fn test_did_document_served_from_node_identity() {
+        let identity = crate::identity::NodeIdentity::generate();
+        let did = identity.default_did(84532);
+        let mut state = test_state();
+        state.did_document = Some(
+            identity
+                .did_document(&did, 84532, Some("http://localhost:8080"))
+                .unwrap(),
+        );
+
+        let server = test_server(state);
+        let response = server.get("/.well-known/did.json").await;
+
+        response.assert_status_ok();
+        let doc: DIDDocument = response.json();
+        assert_eq!(doc.id, did);
+        assert!(doc.get_verification_method("key-1").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_did_document_not_found_without_identity() {
+        let server = test_server(test_state());
+
+        let response = server.get("/.well-known/did.json").await;
+
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    // ========== TDD Tests: GET /dids/{did} ==========
+
+    fn resolver_state() -> (AppState, Arc<crate::did::InMemoryDidResolver>) {
+        use crate::did::{AgoraMeshDidResolver, CompositeDidResolver, DidCacheConfig};
+
+        let local = Arc::new(crate::did::InMemoryDidResolver::new());
+        let resolver = CompositeDidResolver::new(DidCacheConfig::disabled())
+            .with_agoramesh(AgoraMeshDidResolver::new().with_local_documents(local.clone()))
+            .with_method("key", Arc::new(crate::did::KeyDidResolver::new()));
+        let mut state = test_state();
+        state.did_resolver = Some(Arc::new(resolver));
+        (state, local)
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_returns_resolution_result() {
+        let (state, local) = resolver_state();
+        let identity = crate::identity::NodeIdentity::generate();
+        let did = identity.default_did(84532);
+        local
+            .insert(identity.did_document(&did, 84532, None).unwrap())
+            .unwrap();
+
+        let server = test_server(state);
+        let response = server.get(&format!("/dids/{}", did)).await;
+
+        response.assert_status_ok();
+        assert_eq!(
+            response.header(header::CONTENT_TYPE),
+            DID_RESOLUTION_CONTENT_TYPE
+        );
+        let result: DIDResolutionResult = response.json();
+        assert!(result.did_resolution_metadata.error.is_none());
+        assert_eq!(result.did_document.unwrap().id, did);
+        assert_eq!(
+            result.did_document_metadata.unwrap().deactivated,
+            Some(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_returns_document_for_did_accept_header() {
+        let (state, _) = resolver_state();
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let did = format!(
+            "did:key:{}",
+            crate::did::encode_ed25519_multibase(&keypair.public())
+        );
+
+        let server = test_server(state);
+        let response = server
+            .get(&format!("/dids/{}", did))
+            .add_header(
+                header::ACCEPT,
+                HeaderValue::from_static("application/did+ld+json"),
+            )
+            .await;
+
+        response.assert_status_ok();
+        assert_eq!(
+            response.header(header::CONTENT_TYPE),
+            "application/did+ld+json"
+        );
+        let document: DIDDocument = response.json();
+        assert_eq!(document.id, did);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_maps_errors_to_status_codes() {
+        let (state, _) = resolver_state();
+        let server = test_server(state);
+
+        let cases = [
+            (
+                "did:agoramesh:base:missing",
+                StatusCode::NOT_FOUND,
+                "notFound",
+            ),
+            ("not-a-did", StatusCode::BAD_REQUEST, "invalidDid"),
+            (
+                "did:example:123",
+                StatusCode::NOT_IMPLEMENTED,
+                "methodNotSupported",
+            ),
+        ];
+        for (did, status, code) in cases {
+            let response = server.get(&format!("/dids/{}", did)).await;
+
+            response.assert_status(status);
+            let result: DIDResolutionResult = response.json();
+            assert_eq!(
+                result.did_resolution_metadata.error.as_deref(),
+                Some(code),
+                "{}",
+                did
+            );
+            assert!(result.did_document.is_none());
+        }
+    }
+
+    #[test]
+    fn test_resolution_status_for_deactivated_did() {
+        let document = crate::did::DIDDocumentBuilder::new("base", "retired")
+            .build()
+            .unwrap();
+
+        let result = DIDResolutionResult::deactivated(document);
+
+        assert_eq!(resolution_status(&result), StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_without_resolver_is_not_implemented() {
+        let server = test_server(test_state());
+
+        let response = server.get("/dids/did:agoramesh:base:any").await;
+
+        response.assert_status(StatusCode::NOT_IMPLEMENTED);
+    }
+
     // ========== TDD Tests: GET /agents ==========
 
     #[tokio::test]
@@ -756,6 +1176,182 @@ mod tests {
         assert_eq!(agents.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_search_agents_pages_with_cursor() {
+        let state = test_state();
+        for i in 0..3 {
+            let card = sample_capability_card(&format!("did:agoramesh:base:agent-{}", i));
+            state.discovery.register(&card).await.unwrap();
+        }
+        let server = test_server(state);
+
+        let first = server.get("/agents?limit=2").await;
+
+        first.assert_status_ok();
+        assert_eq!(first.header(TOTAL_COUNT_HEADER), "3");
+        assert_eq!(first.json::<Vec<CapabilityCard>>().len(), 2);
+        let cursor = first.header(NEXT_CURSOR_HEADER);
+
+        let second = server
+            .get(&format!(
+                "/agents?limit=2&cursor={}",
+                cursor.to_str().unwrap()
+            ))
+            .await;
+
+        second.assert_status_ok();
+        assert_eq!(second.json::<Vec<CapabilityCard>>().len(), 1);
+        assert!(second.maybe_header(NEXT_CURSOR_HEADER).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_agents_applies_filters_from_query_params() {
+        let state = test_state();
+        let cheap = sample_capability_card("did:agoramesh:base:cheap");
+        let mut pricey = sample_capability_card("did:agoramesh:base:pricey");
+        if let Some(pricing) = pricey.agoramesh.as_mut().and_then(|e| e.pricing.as_mut()) {
+            pricing.base_price = 5_000_000;
+        }
+        let mut other_org = sample_capability_card("did:agoramesh:base:other-org");
+        other_org.provider = Some(ProviderInfo {
+            organization: "Elsewhere".to_string(),
+            url: None,
+        });
+        for card in [&cheap, &pricey, &other_org] {
+            state.discovery.register(card).await.unwrap();
+        }
+        let server = test_server(state);
+
+        let response = server
+            .get("/agents?skill_id=translate&max_price=1000000&provider=test%20org")
+            .await;
+
+        response.assert_status_ok();
+        let agents: Vec<CapabilityCard> = response.json();
+        assert_eq!(agents.len(), 1);
+        assert_eq!(
+            agents[0].agoramesh.as_ref().unwrap().did,
+            "did:agoramesh:base:cheap"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_agents_search_accepts_json_query() {
+        let state = test_state();
+        let mut cheap = sample_capability_card("did:agoramesh:base:cheap");
+        if let Some(pricing) = cheap.agoramesh.as_mut().and_then(|e| e.pricing.as_mut()) {
+            pricing.base_price = 10;
+        }
+        let expensive = sample_capability_card("did:agoramesh:base:expensive");
+        state.discovery.register(&expensive).await.unwrap();
+        state.discovery.register(&cheap).await.unwrap();
+        let server = test_server(state);
+
+        let response = server
+            .post("/agents/search")
+            .json(&serde_json::json!({ "text": "translation", "sort": "price" }))
+            .await;
+
+        response.assert_status_ok();
+        let dids: Vec<String> = response
+            .json::<Vec<CapabilityCard>>()
+            .into_iter()
+            .filter_map(|c| c.agoramesh.map(|e| e.did))
+            .collect();
+        assert_eq!(
+            dids,
+            vec!["did:agoramesh:base:cheap", "did:agoramesh:base:expensive"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_agents_rejects_invalid_query() {
+        let server = test_server(test_state());
+
+        server
+            .get("/agents?limit=0")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        server
+            .post("/agents/search")
+            .json(&serde_json::json!({ "cursor": "not-a-cursor" }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_search_agents_with_network_scope_includes_local_results() {
+        let state = test_state();
+        state
+            .discovery
+            .register(&sample_capability_card("did:agoramesh:base:local"))
+            .await
+            .unwrap();
+        let server = test_server(state);
+
+        let response = server.get("/agents?q=translation&scope=network").await;
+
+        response.assert_status_ok();
+        assert_eq!(response.header(TOTAL_COUNT_HEADER), "1");
+        server
+            .get("/agents?scope=everywhere")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // ========== TDD Tests: POST /agents/match ==========
+
+    #[tokio::test]
+    async fn test_match_agents_returns_skills_accepting_payload() {
+        let state = test_state();
+        let mut typed = sample_capability_card("did:agoramesh:base:typed");
+        typed.skills[0].input_schema = Some(serde_json::json!({
+            "type": "object",
+            "properties": { "text": { "type": "string" } },
+            "required": ["text"]
+        }));
+        state.discovery.register(&typed).await.unwrap();
+        state
+            .discovery
+            .register(&sample_capability_card("did:agoramesh:base:untyped"))
+            .await
+            .unwrap();
+        let server = test_server(state);
+
+        let response = server
+            .post("/agents/match")
+            .json(&serde_json::json!({ "input": { "text": "Hello" }, "limit": 10 }))
+            .await;
+
+        response.assert_status_ok();
+        assert_eq!(response.header(TOTAL_COUNT_HEADER), "1");
+        let matches: Vec<SkillMatch> = response.json();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].did, "did:agoramesh:base:typed");
+        assert_eq!(matches[0].skills, vec![typed.skills[0].id.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_match_agents_rejects_invalid_requests() {
+        let server = test_server(test_state());
+
+        server
+            .post("/agents/match")
+            .json(&serde_json::json!({ "input": {}, "limit": 0 }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        server
+            .post("/agents/match")
+            .json(&serde_json::json!({ "q": "translate" }))
+            .await
+            .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        server
+            .post("/agents/match")
+            .json(&serde_json::json!({ "input": {}, "scope": "network" }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
     // ========== TDD Tests: GET /agents/:did ==========
 
     #[tokio::test]
@@ -852,6 +1448,41 @@ mod tests {
         assert!(error.error.contains("DID"));
     }
 
+    // ========== TDD Tests: GET /disputes/{id} ==========
+
+    #[tokio::test]
+    async fn test_get_dispute_reads_shared_arbitrator() {
+        let arbitrator = Arc::new(AIArbitrator::new(Default::default()).unwrap());
+        let dispute_id = arbitrator
+            .create_dispute(
+                "escrow-1",
+                "did:agoramesh:base:client",
+                "did:agoramesh:base:provider",
+                100_000_000,
+            )
+            .unwrap();
+        let mut state = test_state();
+        state.arbitrator = Some(arbitrator);
+        let server = test_server(state);
+
+        let response = server.get(&format!("/disputes/{}", dispute_id)).await;
+        response.assert_status_ok();
+        let dispute: AIDispute = response.json();
+        assert_eq!(dispute.escrow_id, "escrow-1");
+
+        let response = server.get("/disputes/unknown").await;
+        response.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_get_dispute_without_arbitrator_is_not_implemented() {
+        let server = test_server(test_state());
+
+        let response = server.get("/disputes/any").await;
+
+        response.assert_status(StatusCode::NOT_IMPLEMENTED);
+    }
+
     // ========== TDD Tests: Rate Limiting ==========
 
     fn test_state_with_rate_limit(requests_per_second: u32, burst_size: u32) -> AppState {
@@ -872,6 +1503,11 @@ mod tests {
             metrics: Arc::new(MetricsService::disabled()),
             hybrid_search: None,
             api_token: None,
+            message_handler: None,
+            did_document: None,
+            did_resolver: None,
+            nat_state: Arc::new(RwLock::new(NatState::default())),
+            arbitrator: None,
         }
     }
 
@@ -992,6 +1628,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_discovery_query_parses_from_query_params() {
+        let uri: axum::http::Uri =
+            "/agents/semantic?q=translate&pricing_model=per_token&payment_method=x402&min_trust=0.5&skill_id=translate&max_price=200000&provider=Acme&limit=5&offset=10&sort=price&scope=network"
+                .parse()
+                .unwrap();
+        let Query(query) = Query::<DiscoveryQuery>::try_from_uri(&uri).unwrap();
+
+        assert_eq!(query.query_text(), "translate");
+        assert_eq!(query.skill_id.as_deref(), Some("translate"));
+        assert_eq!(query.max_price, Some(200_000));
+        assert_eq!(query.provider.as_deref(), Some("Acme"));
+        assert_eq!(query.page_start().unwrap(), 10);
+        assert_eq!(query.page_size(), 5);
+        assert_eq!(query.sort, QuerySort::Price);
+        assert_eq!(query.scope, SearchScope::Network);
+
+        let filter = query.vector_filter();
+        assert_eq!(filter.pricing_models, vec![PricingModel::PerToken]);
+        assert_eq!(filter.payment_methods, vec!["x402".to_string()]);
+        assert_eq!(filter.min_trust, Some(0.5));
+        assert!(DiscoveryQuery::default().vector_filter().is_empty());
+    }
+
     #[tokio::test]
     async fn test_semantic_search_returns_empty_when_no_results() {
         // With HybridSearch configured but no indexed cards
@@ -1059,6 +1719,8 @@ mod tests {
             assert!(results[0].score > 0.0, "Should have combined score");
             assert!(results[0].vector_score >= 0.0, "Should have vector score");
             assert!(results[0].keyword_score >= 0.0, "Should have keyword score");
+            assert!(results[0].relevance_score > 0.0, "Should have relevance");
+            assert!(results[0].trust.is_some(), "Should be enriched with trust");
         }
     }
 
@@ -1083,6 +1745,11 @@ mod tests {
             metrics: Arc::new(MetricsService::disabled()),
             hybrid_search: Some(Arc::new(RwLock::new(hybrid))),
             api_token: None,
+            message_handler: None,
+            did_document: None,
+            did_resolver: None,
+            nat_state: Arc::new(RwLock::new(NatState::default())),
+            arbitrator: None,
         })
     }
 }
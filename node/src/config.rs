@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 use crate::error::{Error, Result};
+use crate::network::{NatConfig, PrivateMeshConfig};
 use crate::persistence::PersistenceConfig;
+use crate::search::SearchConfig;
 
 /// Main configuration for an AgoraMesh node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +30,10 @@ pub struct NodeConfig {
     #[serde(default)]
     pub persistence: PersistenceConfig,
 
+    /// Semantic search configuration.
+    #[serde(default)]
+    pub search: SearchConfig,
+
     /// Node info for capability card (optional).
     #[serde(default)]
     pub node_info: NodeInfoConfig,
@@ -37,16 +43,26 @@ pub struct NodeConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentityConfig {
     /// Path to private key file (will be generated if not exists).
+    ///
+    /// Encrypted with `AGORAMESH_KEY_PASSPHRASE` when set.
     pub key_file: String,
 
-    /// Optional DID for this node.
+    /// Optional DID for this node (defaults to `did:agoramesh:{chain}:{peer_id}`).
     pub did: Option<String>,
 }
 
+/// Default P2P listen addresses: TCP and QUIC on IPv4 and IPv6.
+pub const DEFAULT_LISTEN_ADDRESSES: [&str; 4] = [
+    "/ip4/0.0.0.0/tcp/9000",
+    "/ip6/::/tcp/9000",
+    "/ip4/0.0.0.0/udp/9000/quic-v1",
+    "/ip6/::/udp/9000/quic-v1",
+];
+
 /// P2P network configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
-    /// Listen addresses for P2P connections.
+    /// Listen addresses for P2P connections (`/tcp/<port>` or `/udp/<port>/quic-v1`).
     pub listen_addresses: Vec<String>,
 
     /// Bootstrap peers to connect to on startup.
@@ -54,6 +70,22 @@ pub struct NetworkConfig {
 
     /// Maximum number of connections.
     pub max_connections: u32,
+
+    /// NAT traversal settings.
+    #[serde(default)]
+    pub nat: NatConfig,
+
+    /// Discover peers on the local network with mDNS.
+    #[serde(default = "default_mdns")]
+    pub mdns: bool,
+
+    /// Private mesh settings: pre-shared key and peer allowlist.
+    #[serde(default)]
+    pub private: PrivateMeshConfig,
+}
+
+fn default_mdns() -> bool {
+    true
 }
 
 /// HTTP API configuration.
@@ -88,6 +120,20 @@ pub struct TrustConfig {
 
     /// Minimum stake amount in USDC (6 decimals).
     pub min_stake: u64,
+
+    /// Reject gossip messages that are not signed envelopes.
+    ///
+    /// Reputation events and dispute messages are always required to be signed.
+    #[serde(default)]
+    pub require_signed_messages: bool,
+
+    /// Reject capability cards without a proof signed by the agent's DID.
+    #[serde(default = "default_require_signed_cards")]
+    pub require_signed_cards: bool,
+}
+
+fn default_require_signed_cards() -> bool {
+    true
 }
 
 /// Node info configuration for capability card.
@@ -117,6 +163,10 @@ pub struct BlockchainConfig {
 
     /// Escrow contract address.
     pub escrow_address: Option<String>,
+
+    /// WebSocket RPC endpoint for contract event subscriptions (None = disabled).
+    #[serde(default)]
+    pub ws_url: Option<String>,
 }
 
 impl Default for NodeConfig {
@@ -127,9 +177,15 @@ impl Default for NodeConfig {
                 did: None,
             },
             network: NetworkConfig {
-                listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
+                listen_addresses: DEFAULT_LISTEN_ADDRESSES
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect(),
                 bootstrap_peers: vec![],
                 max_connections: 50,
+                nat: NatConfig::default(),
+                mdns: true,
+                private: PrivateMeshConfig::default(),
             },
             api: ApiConfig {
                 listen_address: "0.0.0.0:8080".to_string(),
@@ -142,14 +198,18 @@ impl Default for NodeConfig {
                 min_trust_score: 0.5,
                 require_stake: false,
                 min_stake: 0,
+                require_signed_messages: false,
+                require_signed_cards: true,
             },
             blockchain: BlockchainConfig {
                 chain_id: 84532, // Base Sepolia
                 rpc_url: "https://sepolia.base.org".to_string(),
                 trust_registry_address: None,
                 escrow_address: None,
+                ws_url: None,
             },
             persistence: PersistenceConfig::default(),
+            search: SearchConfig::default(),
             node_info: NodeInfoConfig::default(),
         }
     }
@@ -336,4 +396,45 @@ url = "https://loaded.example.com"
         assert!(config.node_info.description.is_none());
         assert!(config.node_info.url.is_none());
     }
+
+    #[test]
+    fn test_network_config_parses_private_mesh_settings() {
+        let network: NetworkConfig = toml::from_str(
+            r#"
+listen_addresses = ["/ip4/0.0.0.0/tcp/9000"]
+bootstrap_peers = []
+max_connections = 50
+mdns = false
+
+[private]
+psk_file = "swarm.key"
+allowed_peers = ["12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"]
+"#,
+        )
+        .unwrap();
+
+        assert!(!network.mdns);
+        assert_eq!(network.private.psk_file.as_deref(), Some("swarm.key"));
+        assert_eq!(network.private.allowed_peers.len(), 1);
+        assert!(network.private.denied_peers.is_empty());
+    }
+
+    #[test]
+    fn test_default_config_listens_dual_stack_on_tcp_and_quic() {
+        let config = NodeConfig::default();
+        let addrs = &config.network.listen_addresses;
+
+        assert!(addrs
+            .iter()
+            .any(|a| a.starts_with("/ip6/") && a.ends_with("/quic-v1")));
+        assert!(addrs
+            .iter()
+            .any(|a| a.starts_with("/ip4/") && a.ends_with("/quic-v1")));
+        assert!(addrs
+            .iter()
+            .any(|a| a.starts_with("/ip6/") && a.contains("/tcp/")));
+        assert!(addrs
+            .iter()
+            .any(|a| a.starts_with("/ip4/") && a.contains("/tcp/")));
+    }
 }
@@ -3,6 +3,9 @@
 //! This module provides a client for interacting with the TrustRegistry
 //! smart contract on Base L2.
 
+use std::collections::HashMap;
+use std::sync::RwLock;
+
 use alloy::primitives::{Address, FixedBytes};
 use alloy::providers::ProviderBuilder;
 use alloy::sol;
@@ -73,6 +76,28 @@ sol!(
                 {"name": "successRate", "type": "uint256"}
             ],
             "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "getAgent",
+            "inputs": [
+                {"name": "didHash", "type": "bytes32"}
+            ],
+            "outputs": [
+                {
+                    "name": "",
+                    "type": "tuple",
+                    "internalType": "struct ITrustRegistry.AgentInfo",
+                    "components": [
+                        {"name": "didHash", "type": "bytes32"},
+                        {"name": "owner", "type": "address"},
+                        {"name": "capabilityCardCID", "type": "string"},
+                        {"name": "registeredAt", "type": "uint256"},
+                        {"name": "isActive", "type": "bool"}
+                    ]
+                }
+            ],
+            "stateMutability": "view"
         }
     ]"#
 );
@@ -90,6 +115,19 @@ pub struct OnChainTrustDetails {
     pub composite_score: u64,
 }
 
+/// Agent registration record from the contract.
+#[derive(Debug, Clone)]
+pub struct OnChainAgent {
+    /// Owner address that manages the agent.
+    pub owner: Address,
+    /// IPFS CID of the agent's capability card.
+    pub capability_card_cid: String,
+    /// Registration timestamp (Unix seconds).
+    pub registered_at: u64,
+    /// Whether the agent is currently active.
+    pub is_active: bool,
+}
+
 /// Client for interacting with TrustRegistry contract.
 pub struct TrustRegistryClient {
     rpc_url: String,
@@ -216,6 +254,106 @@ impl TrustRegistryClient {
             result.successRate.try_into().unwrap_or(0),
         ))
     }
+
+    /// Get the registration record for an agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `did` - Agent's DID string
+    ///
+    /// # Returns
+    ///
+    /// `None` if the DID was never registered.
+    pub async fn get_agent(&self, did: &str) -> Result<Option<OnChainAgent>> {
+        let provider = ProviderBuilder::new().connect_http(
+            self.rpc_url
+                .parse()
+                .map_err(|e| Error::Network(format!("Invalid RPC URL: {}", e)))?,
+        );
+
+        let contract = TrustRegistry::new(self.contract_address, provider);
+        let did_hash = Self::did_to_hash(did);
+
+        let result = contract
+            .getAgent(did_hash)
+            .call()
+            .await
+            .map_err(|e| Error::Contract(format!("Failed to get agent: {}", e)))?;
+
+        // Unregistered agents come back as a zeroed struct
+        if result.owner == Address::ZERO {
+            return Ok(None);
+        }
+
+        Ok(Some(OnChainAgent {
+            owner: result.owner,
+            capability_card_cid: result.capabilityCardCID,
+            registered_at: result.registeredAt.try_into().unwrap_or(0),
+            is_active: result.isActive,
+        }))
+    }
+
+    /// The TrustRegistry contract address.
+    pub fn contract_address(&self) -> Address {
+        self.contract_address
+    }
+}
+
+/// Reverse index from contract DID hashes to DIDs.
+///
+/// Contracts identify agents by [`TrustRegistryClient::did_to_hash`]. Services
+/// that learn about DIDs record them here as they go, so contract events can
+/// be mapped back to a DID without scanning every known agent.
+#[derive(Debug, Default)]
+pub struct DidHashIndex {
+    dids: RwLock<HashMap<FixedBytes<32>, String>>,
+}
+
+impl DidHashIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index a DID under its contract hash.
+    pub fn insert(&self, did: &str) -> Result<()> {
+        self.dids
+            .write()
+            .map_err(|e| Error::Internal(format!("Lock error: {}", e)))?
+            .entry(TrustRegistryClient::did_to_hash(did))
+            .or_insert_with(|| did.to_string());
+        Ok(())
+    }
+
+    /// Drop a DID from the index.
+    pub fn remove(&self, did: &str) -> Result<bool> {
+        Ok(self
+            .dids
+            .write()
+            .map_err(|e| Error::Internal(format!("Lock error: {}", e)))?
+            .remove(&TrustRegistryClient::did_to_hash(did))
+            .is_some())
+    }
+
+    /// Look up the DID for a contract hash.
+    pub fn get(&self, did_hash: &FixedBytes<32>) -> Result<Option<String>> {
+        Ok(self
+            .dids
+            .read()
+            .map_err(|e| Error::Internal(format!("Lock error: {}", e)))?
+            .get(did_hash)
+            .cloned())
+    }
+
+    /// Number of indexed DIDs.
+    pub fn len(&self) -> usize {
+        self.dids.read().map(|dids| dids.len()).unwrap_or(0)
+    }
+
+    /// Whether the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 #[cfg(test)]
@@ -260,6 +398,35 @@ mod tests {
         assert!(result.is_ok(), "Should accept valid address");
     }
 
+    // ========== TDD Tests: DidHashIndex ==========
+
+    #[test]
+    fn test_did_hash_index_maps_hash_to_did() {
+        let index = DidHashIndex::new();
+        let did = "did:agoramesh:base:agent1";
+
+        index.insert(did).unwrap();
+
+        let hash = TrustRegistryClient::did_to_hash(did);
+        assert_eq!(index.get(&hash).unwrap().as_deref(), Some(did));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_did_hash_index_remove() {
+        let index = DidHashIndex::new();
+        let did = "did:agoramesh:base:agent1";
+        index.insert(did).unwrap();
+
+        assert!(index.remove(did).unwrap());
+        assert!(!index.remove(did).unwrap());
+        assert!(index
+            .get(&TrustRegistryClient::did_to_hash(did))
+            .unwrap()
+            .is_none());
+        assert!(index.is_empty());
+    }
+
     #[test]
     fn test_did_hash_is_32_bytes() {
         let hash = TrustRegistryClient::did_to_hash("did:agoramesh:base:test");
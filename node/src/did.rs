@@ -2,16 +2,48 @@
 //!
 //! This module provides:
 //! - DID Document creation and validation
-//! - DID resolution via DHT
+//! - DID resolution for `did:agoramesh` (DHT + TrustRegistry), `did:key` and `did:web`
 //! - Verification method management
+//! - Signature verification against verification methods
 
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use alloy::primitives::{Address, Signature};
+use async_trait::async_trait;
+use libp2p::identity::ed25519;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
 
+mod resolver;
+
+pub use resolver::{
+    did_document_key, parse_did_syntax, peer_id_key, AgoraMeshDidResolver, CompositeDidResolver,
+    DidCacheConfig, DidDocumentRecord, KeyDidResolver, WebDidResolver, DID_DOCUMENT_KEY_PREFIX,
+    DID_DOCUMENT_SIGNING_TOPIC, MAX_WEB_DID_DOCUMENT_SIZE,
+};
+
 /// DID method for AgoraMesh.
 pub const DID_METHOD: &str = "agoramesh";
 
+/// Verification method type for Ed25519 public keys.
+pub const ED25519_KEY_TYPE: &str = "Ed25519VerificationKey2020";
+
+/// Verification method type for Ethereum accounts (recoverable secp256k1).
+pub const ETHEREUM_ACCOUNT_TYPE: &str = "EcdsaSecp256k1RecoveryMethod2020";
+
+/// Content type of a W3C DID Resolution result.
+pub const DID_RESOLUTION_CONTENT_TYPE: &str =
+    "application/ld+json;profile=\"https://w3id.org/did-resolution\"";
+
+/// Content type of a DID Document (JSON-LD representation).
+pub const DID_DOCUMENT_CONTENT_TYPE: &str = "application/did+ld+json";
+
+/// Multicodec prefix for Ed25519 public keys (`ed25519-pub`, varint 0xed).
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
 /// DID Document following W3C DID Core 1.0 spec.
 ///
 /// <https://www.w3.org/TR/did-core/>
@@ -151,7 +183,7 @@ impl DIDDocumentBuilder {
         let did = self.did();
         self.verification_methods.push(VerificationMethod {
             id: format!("{}#{}", did, key_id),
-            method_type: "Ed25519VerificationKey2020".to_string(),
+            method_type: ED25519_KEY_TYPE.to_string(),
             controller: did.clone(),
             public_key_multibase: Some(public_key_multibase.to_string()),
             public_key_jwk: None,
@@ -167,7 +199,7 @@ impl DIDDocumentBuilder {
         let account_id = format!("eip155:{}:{}", chain_id, address);
         self.verification_methods.push(VerificationMethod {
             id: format!("{}#{}", did, key_id),
-            method_type: "EcdsaSecp256k1RecoveryMethod2020".to_string(),
+            method_type: ETHEREUM_ACCOUNT_TYPE.to_string(),
             controller: did.clone(),
             public_key_multibase: None,
             public_key_jwk: None,
@@ -372,6 +404,13 @@ impl DIDDocument {
         })
     }
 
+    /// Get a verification method by its full ID (`did:...#key-1`).
+    pub fn find_verification_method(&self, method_id: &str) -> Option<&VerificationMethod> {
+        self.verification_method
+            .as_ref()
+            .and_then(|methods| methods.iter().find(|m| m.id == method_id))
+    }
+
     /// Serialize to JSON.
     pub fn to_json(&self) -> Result<String> {
         serde_json::to_string_pretty(self)
@@ -385,6 +424,152 @@ impl DIDDocument {
     }
 }
 
+impl VerificationMethod {
+    /// Verify a signature over `message` with this verification method.
+    ///
+    /// Supported method types:
+    /// - [`ED25519_KEY_TYPE`]: raw 64-byte Ed25519 signature
+    /// - [`ETHEREUM_ACCOUNT_TYPE`]: 65-byte EIP-191 (`personal_sign`) signature
+    ///   whose recovered address must match `blockchainAccountId`
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        match self.method_type.as_str() {
+            ED25519_KEY_TYPE => {
+                let multibase = self.public_key_multibase.as_deref().ok_or_else(|| {
+                    Error::Did(format!(
+                        "Verification method '{}' has no public key",
+                        self.id
+                    ))
+                })?;
+                let public_key = decode_ed25519_multibase(multibase)?;
+                if public_key.verify(message, signature) {
+                    Ok(())
+                } else {
+                    Err(Error::Did(
+                        "Ed25519 signature verification failed".to_string(),
+                    ))
+                }
+            }
+            ETHEREUM_ACCOUNT_TYPE => {
+                let expected = self.ethereum_address()?;
+                let signature = Signature::try_from(signature)
+                    .map_err(|e| Error::Did(format!("Invalid Ethereum signature: {}", e)))?;
+                let recovered = signature
+                    .recover_address_from_msg(message)
+                    .map_err(|e| Error::Did(format!("Failed to recover signer: {}", e)))?;
+                if recovered == expected {
+                    Ok(())
+                } else {
+                    Err(Error::Did(format!(
+                        "Signature recovered {} but method '{}' expects {}",
+                        recovered, self.id, expected
+                    )))
+                }
+            }
+            other => Err(Error::Did(format!(
+                "Unsupported verification method type: '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Parse the Ethereum address from a CAIP-10 `blockchainAccountId`.
+    fn ethereum_address(&self) -> Result<Address> {
+        let account_id = self.blockchain_account_id.as_deref().ok_or_else(|| {
+            Error::Did(format!(
+                "Verification method '{}' has no blockchain account",
+                self.id
+            ))
+        })?;
+        // CAIP-10 format: eip155:{chainId}:{address}
+        let address = account_id.rsplit(':').next().unwrap_or(account_id);
+        Address::from_str(address).map_err(|e| {
+            Error::Did(format!(
+                "Invalid blockchain account '{}': {}",
+                account_id, e
+            ))
+        })
+    }
+}
+
+/// Encode an Ed25519 public key as `publicKeyMultibase` (base58btc, multicodec-prefixed).
+pub fn encode_ed25519_multibase(public_key: &ed25519::PublicKey) -> String {
+    let mut bytes = ED25519_MULTICODEC.to_vec();
+    bytes.extend_from_slice(&public_key.to_bytes());
+    format!("z{}", bs58::encode(bytes).into_string())
+}
+
+/// Decode an Ed25519 `publicKeyMultibase` value.
+///
+/// Accepts base58btc (`z` prefix) keys with or without the multicodec prefix.
+pub fn decode_ed25519_multibase(multibase: &str) -> Result<ed25519::PublicKey> {
+    let encoded = multibase.strip_prefix('z').ok_or_else(|| {
+        Error::Did(format!(
+            "Unsupported multibase encoding: '{}' (expected base58btc)",
+            multibase
+        ))
+    })?;
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| Error::Did(format!("Invalid base58 public key: {}", e)))?;
+    let raw = bytes
+        .strip_prefix(&ED25519_MULTICODEC[..])
+        .unwrap_or(&bytes);
+    ed25519::PublicKey::try_from_bytes(raw)
+        .map_err(|e| Error::Did(format!("Invalid Ed25519 public key: {}", e)))
+}
+
+/// Resolves DIDs to DID documents.
+#[async_trait]
+pub trait DidResolver: Send + Sync {
+    /// Resolve a DID, returning `None` if it is unknown.
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>>;
+
+    /// Resolve a DID into a W3C DID Resolution result with metadata.
+    ///
+    /// Validation errors map to `invalidDid`, other errors to `internalError`.
+    async fn resolve_result(&self, did: &str) -> DIDResolutionResult {
+        match self.resolve(did).await {
+            Ok(Some(document)) => DIDResolutionResult::success(document),
+            Ok(None) => DIDResolutionResult::not_found(did),
+            Err(e) => DIDResolutionResult::from_error(e),
+        }
+    }
+}
+
+/// In-memory DID resolver backed by explicitly registered documents.
+#[derive(Debug, Default)]
+pub struct InMemoryDidResolver {
+    documents: RwLock<HashMap<String, DIDDocument>>,
+}
+
+impl InMemoryDidResolver {
+    /// Create an empty resolver.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a DID document.
+    pub fn insert(&self, document: DIDDocument) -> Result<()> {
+        document.validate()?;
+        self.documents
+            .write()
+            .map_err(|e| Error::Did(format!("Failed to acquire resolver lock: {}", e)))?
+            .insert(document.id.clone(), document);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DidResolver for InMemoryDidResolver {
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+        let documents = self
+            .documents
+            .read()
+            .map_err(|e| Error::Did(format!("Failed to acquire resolver lock: {}", e)))?;
+        Ok(documents.get(did).cloned())
+    }
+}
+
 /// DID Resolution result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -481,7 +666,7 @@ impl DIDResolutionResult {
         Self {
             did_document: Some(document),
             did_resolution_metadata: DIDResolutionMetadata {
-                content_type: Some("application/did+ld+json".to_string()),
+                content_type: Some(DID_DOCUMENT_CONTENT_TYPE.to_string()),
                 error: None,
                 message: None,
             },
@@ -508,11 +693,53 @@ impl DIDResolutionResult {
 
     /// Create an invalid DID error result.
     pub fn invalid_did(message: &str) -> Self {
+        Self::error("invalidDid", message)
+    }
+
+    /// Create a result for a deactivated DID, keeping its last document.
+    pub fn deactivated(document: DIDDocument) -> Self {
+        let mut result = Self::success(document);
+        if let Some(ref mut metadata) = result.did_document_metadata {
+            metadata.deactivated = Some(true);
+        }
+        result
+    }
+
+    /// Create a method not supported error result.
+    pub fn method_not_supported(method: &str) -> Self {
+        Self::error(
+            "methodNotSupported",
+            &format!("DID method '{}' is not supported", method),
+        )
+    }
+
+    /// Create an internal error result.
+    pub fn internal_error(message: &str) -> Self {
+        Self::error("internalError", message)
+    }
+
+    /// Map a resolver error to an `invalidDid` or `internalError` result.
+    pub fn from_error(error: Error) -> Self {
+        match error {
+            Error::Validation(message) => Self::invalid_did(&message),
+            other => Self::internal_error(&other.to_string()),
+        }
+    }
+
+    /// Whether the DID has been deactivated.
+    pub fn is_deactivated(&self) -> bool {
+        self.did_document_metadata
+            .as_ref()
+            .and_then(|m| m.deactivated)
+            .unwrap_or(false)
+    }
+
+    fn error(code: &str, message: &str) -> Self {
         Self {
             did_document: None,
             did_resolution_metadata: DIDResolutionMetadata {
                 content_type: None,
-                error: Some("invalidDid".to_string()),
+                error: Some(code.to_string()),
                 message: Some(message.to_string()),
             },
             did_document_metadata: None,
@@ -846,4 +1073,89 @@ mod tests {
             Some("invalidDid".to_string())
         );
     }
+
+    // ========== TDD Tests: Signature verification ==========
+
+    #[test]
+    fn test_ed25519_multibase_roundtrip() {
+        let keypair = ed25519::Keypair::generate();
+
+        let encoded = encode_ed25519_multibase(&keypair.public());
+        let decoded = decode_ed25519_multibase(&encoded).unwrap();
+
+        assert!(encoded.starts_with("z6Mk"), "Multicodec-prefixed key");
+        assert_eq!(decoded, keypair.public());
+    }
+
+    #[test]
+    fn test_decode_ed25519_multibase_rejects_other_bases() {
+        assert!(decode_ed25519_multibase("f00ff").is_err());
+    }
+
+    #[test]
+    fn test_ed25519_verification_method_verifies_signature() {
+        let keypair = ed25519::Keypair::generate();
+        let doc = DIDDocumentBuilder::new("base", "signer")
+            .add_ed25519_key("key-1", &encode_ed25519_multibase(&keypair.public()))
+            .build()
+            .unwrap();
+        let method = doc
+            .find_verification_method("did:agoramesh:base:signer#key-1")
+            .unwrap();
+        let signature = keypair.sign(b"hello");
+
+        assert!(method.verify(b"hello", &signature).is_ok());
+        assert!(method.verify(b"tampered", &signature).is_err());
+    }
+
+    #[test]
+    fn test_ethereum_verification_method_verifies_signature() {
+        use alloy::signers::{local::PrivateKeySigner, SignerSync};
+
+        let signer = PrivateKeySigner::random();
+        let doc = DIDDocumentBuilder::new("base", "eth-signer")
+            .add_ethereum_account("eth", &signer.address().to_string(), 8453)
+            .build()
+            .unwrap();
+        let method = doc
+            .find_verification_method("did:agoramesh:base:eth-signer#eth")
+            .unwrap();
+        let signature = signer.sign_message_sync(b"hello").unwrap().as_bytes();
+
+        assert!(method.verify(b"hello", &signature).is_ok());
+        assert!(method.verify(b"tampered", &signature).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_verification_method_is_rejected() {
+        let method = VerificationMethod {
+            id: "did:agoramesh:base:x#jwk".to_string(),
+            method_type: "JsonWebKey2020".to_string(),
+            controller: "did:agoramesh:base:x".to_string(),
+            public_key_multibase: None,
+            public_key_jwk: None,
+            blockchain_account_id: None,
+        };
+
+        let err = method.verify(b"hello", &[0u8; 64]).unwrap_err();
+        assert!(err.to_string().contains("Unsupported"));
+    }
+
+    // ========== TDD Tests: InMemoryDidResolver ==========
+
+    #[tokio::test]
+    async fn test_in_memory_resolver_returns_registered_document() {
+        let resolver = InMemoryDidResolver::new();
+        let doc = DIDDocumentBuilder::new("base", "known").build().unwrap();
+        resolver.insert(doc).unwrap();
+
+        let resolved = resolver.resolve("did:agoramesh:base:known").await.unwrap();
+        let missing = resolver
+            .resolve("did:agoramesh:base:unknown")
+            .await
+            .unwrap();
+
+        assert!(resolved.is_some());
+        assert!(missing.is_none());
+    }
 }
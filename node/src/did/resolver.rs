@@ -0,0 +1,1378 @@
+//! DID resolvers for the methods AgoraMesh understands.
+//!
+//! - [`AgoraMeshDidResolver`]: `did:agoramesh` documents published to the
+//!   Kademlia DHT, cross-checked against the on-chain TrustRegistry
+//! - [`KeyDidResolver`]: `did:key`, Ed25519 keys only
+//! - [`WebDidResolver`]: `did:web` over HTTPS
+//! - [`CompositeDidResolver`]: dispatches by method and caches results
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use libp2p::identity::{ed25519, PeerId, PublicKey};
+use moka::future::Cache;
+use tokio::sync::mpsc;
+
+use super::{
+    encode_ed25519_multibase, DIDDocument, DIDDocumentBuilder, DIDResolutionResult, DidResolver,
+    InMemoryDidResolver, VerificationMethod, DID_METHOD, ED25519_KEY_TYPE, ED25519_MULTICODEC,
+    ETHEREUM_ACCOUNT_TYPE,
+};
+use crate::contract::{OnChainAgent, TrustRegistryClient};
+use crate::error::{Error, Result};
+use crate::network::{EnvelopeSigner, SignedEnvelope, SwarmCommand};
+
+/// DHT key prefix under which DID Documents are published.
+pub const DID_DOCUMENT_KEY_PREFIX: &str = "/agoramesh/did/";
+
+/// Default timeout for DHT lookups.
+const DEFAULT_DHT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default timeout for `did:web` HTTP requests.
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest `did.json` body the did:web resolver reads (64 KiB).
+pub const MAX_WEB_DID_DOCUMENT_SIZE: usize = 64 * 1024;
+
+/// Default chain ID for documents synthesized from on-chain data (Base Sepolia).
+const DEFAULT_CHAIN_ID: u64 = 84532;
+
+/// Default TTL for cached resolution results (5 minutes).
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+/// Default maximum cached resolution results.
+pub const DEFAULT_CACHE_MAX_ENTRIES: u64 = 10_000;
+
+/// Topic bound into the signature of DID Documents published to the DHT.
+pub const DID_DOCUMENT_SIGNING_TOPIC: &str = "agoramesh/did-document";
+
+/// DHT key of the DID Document for `did`.
+pub fn did_document_key(did: &str) -> Vec<u8> {
+    format!("{}{}", DID_DOCUMENT_KEY_PREFIX, did).into_bytes()
+}
+
+/// Ed25519 key a `did:agoramesh` DID is derived from.
+///
+/// Node DIDs end in the libp2p peer ID, which inlines the node's public
+/// key. Returns `None` for DIDs whose identifier is not such a peer ID.
+pub fn peer_id_key(did: &str) -> Option<ed25519::PublicKey> {
+    let (_, _, identifier) = DIDDocument::parse_did(did).ok()?;
+    let peer_id: PeerId = identifier.parse().ok()?;
+    let multihash = peer_id.as_ref();
+    // Identity multihash: the digest is the protobuf-encoded public key
+    if multihash.code() != 0 {
+        return None;
+    }
+    PublicKey::try_decode_protobuf(multihash.digest())
+        .ok()?
+        .try_into_ed25519()
+        .ok()
+}
+
+/// A DID Document published to the DHT, wrapped in a [`SignedEnvelope`].
+///
+/// Anyone can write to the DHT, so a record only counts once it is signed
+/// by an authority over the DID: the key of a peer-ID DID (see
+/// [`peer_id_key`]), or a key in an authority document such as the
+/// on-chain registration or the previously stored document.
+#[derive(Debug, Clone)]
+pub struct DidDocumentRecord {
+    /// The published document.
+    pub document: DIDDocument,
+    /// The envelope carrying the document and its signature.
+    pub envelope: SignedEnvelope,
+}
+
+impl DidDocumentRecord {
+    /// Serialize `document` as a DHT record value signed by `signer`.
+    pub fn seal(document: &DIDDocument, signer: &EnvelopeSigner) -> Result<Vec<u8>> {
+        signer.seal(DID_DOCUMENT_SIGNING_TOPIC, document.to_json()?.as_bytes())
+    }
+
+    /// Parse the record stored for `did`, without checking its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a signed DID Document, or the
+    /// document or signer do not match `did`.
+    pub fn parse(did: &str, value: &[u8]) -> Result<Self> {
+        let envelope: SignedEnvelope = serde_json::from_slice(value)
+            .map_err(|e| Error::Did(format!("DID Document record is not signed: {}", e)))?;
+        let document = DIDDocument::from_json(&envelope.payload)?;
+        if document.id != did || envelope.signer != did {
+            return Err(Error::Did(format!(
+                "DID Document record for {} describes {} signed by {}",
+                did, document.id, envelope.signer
+            )));
+        }
+        document.validate()?;
+
+        Ok(Self { document, envelope })
+    }
+
+    /// Check that the record is signed by an authority over its DID.
+    ///
+    /// Peer-ID DIDs must be signed by their own key. Other DIDs must be
+    /// signed through a verification method of `authority`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no authority applies or the signature does not
+    /// verify.
+    pub fn verify(&self, authority: Option<&DIDDocument>) -> Result<()> {
+        let did = &self.document.id;
+        if let Some(key) = peer_id_key(did) {
+            let method = self
+                .document
+                .find_verification_method(&self.envelope.verification_method)
+                .ok_or_else(|| {
+                    Error::Did(format!(
+                        "Verification method '{}' not found for {}",
+                        self.envelope.verification_method, did
+                    ))
+                })?;
+            if method.public_key_multibase.as_deref()
+                != Some(encode_ed25519_multibase(&key).as_str())
+            {
+                return Err(Error::Did(format!(
+                    "DID Document for {} is not signed by its peer ID key",
+                    did
+                )));
+            }
+            return self
+                .envelope
+                .verify(DID_DOCUMENT_SIGNING_TOPIC, &self.document);
+        }
+
+        let authority = authority.ok_or_else(|| {
+            Error::Did(format!(
+                "No authority to verify the DID Document for {}",
+                did
+            ))
+        })?;
+        self.envelope.verify(DID_DOCUMENT_SIGNING_TOPIC, authority)
+    }
+
+    /// When the record was signed (Unix seconds).
+    pub fn timestamp(&self) -> u64 {
+        self.envelope.timestamp
+    }
+}
+
+/// Split a DID into `(method, method-specific-id)` following DID Core syntax.
+///
+/// Returns [`Error::Validation`] for malformed DIDs.
+pub fn parse_did_syntax(did: &str) -> Result<(&str, &str)> {
+    let rest = did
+        .strip_prefix("did:")
+        .ok_or_else(|| Error::Validation(format!("'{}' is not a DID", did)))?;
+    let (method, id) = rest
+        .split_once(':')
+        .ok_or_else(|| Error::Validation(format!("DID '{}' has no method-specific id", did)))?;
+
+    if method.is_empty()
+        || !method
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        return Err(Error::Validation(format!(
+            "Invalid DID method name '{}'",
+            method
+        )));
+    }
+    if id.is_empty()
+        || id.ends_with(':')
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '%'))
+    {
+        return Err(Error::Validation(format!(
+            "Invalid method-specific id in DID '{}'",
+            did
+        )));
+    }
+
+    Ok((method, id))
+}
+
+/// Re-tag DID format errors as validation errors (`invalidDid`).
+fn invalid_did(error: Error) -> Error {
+    match error {
+        Error::Did(message) => Error::Validation(message),
+        other => other,
+    }
+}
+
+// ========== did:agoramesh ==========
+
+/// Resolves `did:agoramesh` DIDs.
+///
+/// Lookup order:
+/// 1. Documents registered locally (e.g. this node's own DID)
+/// 2. DID Documents published to the DHT under [`did_document_key`], if
+///    signed by the DID's peer ID key or its on-chain owner
+/// 3. The TrustRegistry contract, which adds the registered owner account
+///    and the active flag, or synthesizes a minimal document when nothing
+///    was published
+///
+/// DIDs deactivated on-chain resolve to `None` via [`DidResolver::resolve`]
+/// and to a `deactivated` result via [`DidResolver::resolve_result`].
+pub struct AgoraMeshDidResolver {
+    local: Option<Arc<InMemoryDidResolver>>,
+    network_tx: Option<mpsc::Sender<SwarmCommand>>,
+    registry: Option<Arc<TrustRegistryClient>>,
+    chain_id: u64,
+    dht_timeout: Duration,
+}
+
+impl Default for AgoraMeshDidResolver {
+    fn default() -> Self {
+        Self {
+            local: None,
+            network_tx: None,
+            registry: None,
+            chain_id: DEFAULT_CHAIN_ID,
+            dht_timeout: DEFAULT_DHT_TIMEOUT,
+        }
+    }
+}
+
+impl AgoraMeshDidResolver {
+    /// Create a resolver with no backends configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consult locally registered documents first.
+    pub fn with_local_documents(mut self, local: Arc<InMemoryDidResolver>) -> Self {
+        self.local = Some(local);
+        self
+    }
+
+    /// Look up published documents in the DHT.
+    pub fn with_network(mut self, network_tx: mpsc::Sender<SwarmCommand>) -> Self {
+        self.network_tx = Some(network_tx);
+        self
+    }
+
+    /// Cross-check registrations against the TrustRegistry contract.
+    pub fn with_trust_registry(mut self, registry: Arc<TrustRegistryClient>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Chain ID used for on-chain account references.
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Timeout for DHT lookups.
+    pub fn with_dht_timeout(mut self, timeout: Duration) -> Self {
+        self.dht_timeout = timeout;
+        self
+    }
+
+    /// Look up a document and whether the DID is active.
+    async fn lookup(&self, did: &str) -> Result<Option<(DIDDocument, bool)>> {
+        let (_, chain, identifier) = DIDDocument::parse_did(did).map_err(invalid_did)?;
+
+        let local = match self.local {
+            Some(ref local) => local.resolve(did).await?,
+            None => None,
+        };
+        let record = match local {
+            Some(_) => None,
+            None => self.dht_lookup(did).await?,
+        };
+
+        let agent = match self.registry {
+            Some(ref registry) => match registry.get_agent(did).await {
+                Ok(agent) => agent,
+                // A published document is still usable while the chain is unreachable
+                Err(e) if local.is_some() || record.is_some() => {
+                    tracing::warn!("TrustRegistry lookup failed for {}: {}", did, e);
+                    None
+                }
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+
+        let document = match (local, record) {
+            (Some(document), _) => Some(document),
+            (None, Some(record)) => {
+                // Non-peer-ID DIDs are vouched for by their on-chain owner
+                let authority = match agent {
+                    Some(ref agent) => Some(self.onchain_document(&chain, &identifier, agent)?),
+                    None => None,
+                };
+                match record.verify(authority.as_ref()) {
+                    Ok(()) => Some(record.document),
+                    Err(e) => {
+                        tracing::warn!("Ignoring unauthenticated DHT DID Document: {}", e);
+                        None
+                    }
+                }
+            }
+            (None, None) => None,
+        };
+
+        Ok(match (document, agent) {
+            (Some(mut document), Some(agent)) => {
+                self.add_owner_account(&mut document, &agent);
+                Some((document, agent.is_active))
+            }
+            (Some(document), None) => Some((document, true)),
+            (None, Some(agent)) => {
+                let document = self.onchain_document(&chain, &identifier, &agent)?;
+                Some((document, agent.is_active))
+            }
+            (None, None) => None,
+        })
+    }
+
+    /// Fetch and parse a published DID Document record from the DHT.
+    ///
+    /// The signature is not checked here; see [`DidDocumentRecord::verify`].
+    async fn dht_lookup(&self, did: &str) -> Result<Option<DidDocumentRecord>> {
+        let Some(ref tx) = self.network_tx else {
+            return Ok(None);
+        };
+
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        tx.send(SwarmCommand::GetRecord {
+            key: did_document_key(did),
+            response_tx,
+        })
+        .await
+        .map_err(|e| Error::Did(format!("Failed to send DHT get command: {}", e)))?;
+
+        let data = match tokio::time::timeout(self.dht_timeout, response_rx).await {
+            Ok(Ok(Some(data))) => data,
+            Ok(Ok(None)) | Ok(Err(_)) => return Ok(None),
+            Err(_) => {
+                tracing::debug!("DHT DID Document lookup timed out for {}", did);
+                return Ok(None);
+            }
+        };
+
+        match DidDocumentRecord::parse(did, &data) {
+            Ok(record) => Ok(Some(record)),
+            Err(e) => {
+                tracing::warn!("Ignoring malformed DHT DID Document for {}: {}", did, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Add the on-chain owner as an Ethereum verification method.
+    fn add_owner_account(&self, document: &mut DIDDocument, agent: &OnChainAgent) {
+        let account_id = format!("eip155:{}:{}", self.chain_id, agent.owner);
+        let methods = document.verification_method.get_or_insert_with(Vec::new);
+        if methods
+            .iter()
+            .any(|m| m.blockchain_account_id.as_deref() == Some(account_id.as_str()))
+        {
+            return;
+        }
+
+        methods.push(VerificationMethod {
+            id: format!("{}#owner", document.id),
+            method_type: ETHEREUM_ACCOUNT_TYPE.to_string(),
+            controller: document.id.clone(),
+            public_key_multibase: None,
+            public_key_jwk: None,
+            blockchain_account_id: Some(account_id),
+        });
+    }
+
+    /// Minimal document for a DID that is registered on-chain only.
+    fn onchain_document(
+        &self,
+        chain: &str,
+        identifier: &str,
+        agent: &OnChainAgent,
+    ) -> Result<DIDDocument> {
+        let mut builder = DIDDocumentBuilder::new(chain, identifier)
+            .add_ethereum_account("owner", &agent.owner.to_string(), self.chain_id)
+            .chain_id(self.chain_id);
+        if let Some(ref registry) = self.registry {
+            builder = builder.trust_registry(&registry.contract_address().to_string());
+        }
+        if !agent.capability_card_cid.is_empty() {
+            builder = builder
+                .add_capability_card_service(&format!("ipfs://{}", agent.capability_card_cid));
+        }
+
+        let mut document = builder.build()?;
+        if let Some(ref mut metadata) = document.metadata {
+            metadata.created = agent.registered_at;
+            metadata.updated = agent.registered_at;
+        }
+        Ok(document)
+    }
+}
+
+#[async_trait]
+impl DidResolver for AgoraMeshDidResolver {
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+        Ok(self
+            .lookup(did)
+            .await?
+            .and_then(|(document, active)| active.then_some(document)))
+    }
+
+    async fn resolve_result(&self, did: &str) -> DIDResolutionResult {
+        match self.lookup(did).await {
+            Ok(Some((document, true))) => DIDResolutionResult::success(document),
+            Ok(Some((document, false))) => DIDResolutionResult::deactivated(document),
+            Ok(None) => DIDResolutionResult::not_found(did),
+            Err(e) => DIDResolutionResult::from_error(e),
+        }
+    }
+}
+
+// ========== did:key ==========
+
+/// Resolves `did:key` DIDs for Ed25519 keys.
+///
+/// The document is derived entirely from the DID, so resolution never fails
+/// with `notFound`.
+#[derive(Debug, Default)]
+pub struct KeyDidResolver;
+
+impl KeyDidResolver {
+    /// Create a `did:key` resolver.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DidResolver for KeyDidResolver {
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+        let (method, multibase) = parse_did_syntax(did)?;
+        if method != "key" {
+            return Err(Error::Validation(format!("'{}' is not a did:key", did)));
+        }
+
+        let encoded = multibase.strip_prefix('z').ok_or_else(|| {
+            Error::Validation(format!(
+                "did:key '{}' must use base58btc multibase encoding",
+                did
+            ))
+        })?;
+        let bytes = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|e| Error::Validation(format!("Invalid did:key encoding: {}", e)))?;
+        let raw = bytes.strip_prefix(&ED25519_MULTICODEC[..]).ok_or_else(|| {
+            Error::Validation(format!(
+                "Unsupported did:key type in '{}' (only Ed25519 is supported)",
+                did
+            ))
+        })?;
+        libp2p::identity::ed25519::PublicKey::try_from_bytes(raw)
+            .map_err(|e| Error::Validation(format!("Invalid Ed25519 public key: {}", e)))?;
+
+        let method_id = format!("{}#{}", did, multibase);
+        Ok(Some(DIDDocument {
+            context: vec![
+                "https://www.w3.org/ns/did/v1".to_string(),
+                "https://w3id.org/security/suites/ed25519-2020/v1".to_string(),
+            ],
+            id: did.to_string(),
+            controller: None,
+            verification_method: Some(vec![VerificationMethod {
+                id: method_id.clone(),
+                method_type: ED25519_KEY_TYPE.to_string(),
+                controller: did.to_string(),
+                public_key_multibase: Some(multibase.to_string()),
+                public_key_jwk: None,
+                blockchain_account_id: None,
+            }]),
+            authentication: Some(vec![method_id.clone()]),
+            assertion_method: Some(vec![method_id]),
+            service: None,
+            metadata: None,
+        }))
+    }
+}
+
+// ========== did:web ==========
+
+/// Resolves `did:web` DIDs by fetching `did.json` over HTTPS.
+///
+/// `did:web:example.com` maps to `https://example.com/.well-known/did.json`
+/// and `did:web:example.com:user:alice` to `https://example.com/user/alice/did.json`.
+///
+/// DIDs reach this resolver from the public API and from peers, so requests
+/// only go to globally routable addresses, redirects are not followed and
+/// documents larger than [`MAX_WEB_DID_DOCUMENT_SIZE`] are rejected.
+pub struct WebDidResolver {
+    client: reqwest::Client,
+    scheme: &'static str,
+    allow_private: Arc<AtomicBool>,
+}
+
+/// DNS resolver that drops addresses outside the public internet.
+struct PublicAddressResolver {
+    allow_private: Arc<AtomicBool>,
+}
+
+impl reqwest::dns::Resolve for PublicAddressResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let allow_private = self.allow_private.load(Ordering::Relaxed);
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allow_private || is_public_ip(&addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", host).into());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Whether `ip` is routable on the public internet.
+///
+/// Rejects loopback, private, link-local, shared (CGNAT), documentation,
+/// benchmarking, multicast, broadcast and unspecified addresses.
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(&mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, _, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+impl WebDidResolver {
+    /// Create a resolver with the default request timeout.
+    pub fn new() -> Result<Self> {
+        Self::with_timeout(DEFAULT_HTTP_TIMEOUT)
+    }
+
+    /// Create a resolver with a custom request timeout.
+    pub fn with_timeout(timeout: Duration) -> Result<Self> {
+        let allow_private = Arc::new(AtomicBool::new(false));
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicAddressResolver {
+                allow_private: allow_private.clone(),
+            }))
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to build did:web HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            scheme: "https",
+            allow_private,
+        })
+    }
+
+    /// Fetch documents over plain HTTP (local development and tests only).
+    pub fn allow_insecure_http(mut self) -> Self {
+        self.scheme = "http";
+        self
+    }
+
+    /// Fetch documents from loopback and private addresses too (local
+    /// development and tests only).
+    pub fn allow_private_hosts(self) -> Self {
+        self.allow_private.store(true, Ordering::Relaxed);
+        self
+    }
+
+    /// Reject IP literal hosts outside the public internet.
+    ///
+    /// Named hosts are checked when they resolve.
+    fn check_host(&self, url: &str) -> Result<()> {
+        if self.allow_private.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| Error::Validation(format!("Invalid did:web URL {}: {}", url, e)))?;
+        let host = parsed.host_str().unwrap_or_default();
+        let Ok(ip) = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        else {
+            return Ok(());
+        };
+        if is_public_ip(&ip) {
+            Ok(())
+        } else {
+            Err(Error::Validation(format!(
+                "did:web host {} is not a public address",
+                ip
+            )))
+        }
+    }
+
+    /// URL of the `did.json` document for a `did:web` DID.
+    pub fn document_url(&self, did: &str) -> Result<String> {
+        let (method, id) = parse_did_syntax(did)?;
+        if method != "web" {
+            return Err(Error::Validation(format!("'{}' is not a did:web", did)));
+        }
+
+        let mut segments = id.split(':');
+        let host = segments.next().unwrap_or_default();
+        let host = urlencoding::decode(host)
+            .map_err(|e| Error::Validation(format!("Invalid did:web host encoding: {}", e)))?;
+        if host.is_empty() || host.contains('/') {
+            return Err(Error::Validation(format!(
+                "Invalid did:web host in '{}'",
+                did
+            )));
+        }
+
+        let path: Vec<&str> = segments.collect();
+        Ok(if path.is_empty() {
+            format!("{}://{}/.well-known/did.json", self.scheme, host)
+        } else {
+            format!("{}://{}/{}/did.json", self.scheme, host, path.join("/"))
+        })
+    }
+}
+
+#[async_trait]
+impl DidResolver for WebDidResolver {
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+        let url = self.document_url(did)?;
+        self.check_host(&url)?;
+
+        let mut response = self
+            .client
+            .get(&url)
+            .header(
+                reqwest::header::ACCEPT,
+                "application/did+json, application/json",
+            )
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to fetch {}: {}", url, e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::Network(format!(
+                "Fetching {} returned HTTP {}",
+                url,
+                response.status()
+            )));
+        }
+
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_WEB_DID_DOCUMENT_SIZE as u64)
+        {
+            return Err(Error::Did(format!("DID Document at {} is too large", url)));
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to read {}: {}", url, e)))?
+        {
+            if body.len() + chunk.len() > MAX_WEB_DID_DOCUMENT_SIZE {
+                return Err(Error::Did(format!("DID Document at {} is too large", url)));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let document: DIDDocument = serde_json::from_slice(&body)
+            .map_err(|e| Error::Did(format!("Invalid DID Document at {}: {}", url, e)))?;
+        if document.id != did {
+            return Err(Error::Did(format!(
+                "DID Document at {} is for '{}', expected '{}'",
+                url, document.id, did
+            )));
+        }
+
+        Ok(Some(document))
+    }
+}
+
+// ========== Composite ==========
+
+/// Configuration for the resolution result cache.
+#[derive(Debug, Clone)]
+pub struct DidCacheConfig {
+    /// Time-to-live for cached results.
+    pub ttl: Duration,
+
+    /// Maximum number of cached results.
+    pub max_entries: u64,
+
+    /// Whether caching is enabled.
+    pub enabled: bool,
+}
+
+impl Default for DidCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            enabled: true,
+        }
+    }
+}
+
+impl DidCacheConfig {
+    /// Create a disabled cache config.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// Routes DIDs to the resolver registered for their method.
+///
+/// Successful (and deactivated) results are cached; `notFound` and errors
+/// are not, so newly published documents become visible immediately.
+pub struct CompositeDidResolver {
+    resolvers: HashMap<String, Arc<dyn DidResolver>>,
+    cache: Option<Cache<String, DIDResolutionResult>>,
+}
+
+impl CompositeDidResolver {
+    /// Create a resolver with no methods registered.
+    pub fn new(config: DidCacheConfig) -> Self {
+        let cache = config.enabled.then(|| {
+            Cache::builder()
+                .max_capacity(config.max_entries)
+                .time_to_live(config.ttl)
+                .build()
+        });
+
+        Self {
+            resolvers: HashMap::new(),
+            cache,
+        }
+    }
+
+    /// Register the resolver for a DID method (e.g. `"key"`).
+    pub fn with_method(mut self, method: &str, resolver: Arc<dyn DidResolver>) -> Self {
+        self.resolvers.insert(method.to_string(), resolver);
+        self
+    }
+
+    /// Register the `did:agoramesh` resolver.
+    pub fn with_agoramesh(self, resolver: AgoraMeshDidResolver) -> Self {
+        self.with_method(DID_METHOD, Arc::new(resolver))
+    }
+
+    /// Whether a resolver is registered for `method`.
+    pub fn supports(&self, method: &str) -> bool {
+        self.resolvers.contains_key(method)
+    }
+
+    /// Drop a cached result, e.g. after the DID's document changed.
+    pub async fn invalidate(&self, did: &str) {
+        if let Some(ref cache) = self.cache {
+            cache.invalidate(did).await;
+        }
+    }
+}
+
+#[async_trait]
+impl DidResolver for CompositeDidResolver {
+    async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+        let result = self.resolve_result(did).await;
+        if result.is_deactivated() {
+            return Ok(None);
+        }
+
+        let metadata = result.did_resolution_metadata;
+        match metadata.error.as_deref() {
+            None => Ok(result.did_document),
+            Some("notFound") | Some("methodNotSupported") => Ok(None),
+            Some("invalidDid") => Err(Error::Validation(metadata.message.unwrap_or_default())),
+            Some(_) => Err(Error::Did(metadata.message.unwrap_or_default())),
+        }
+    }
+
+    async fn resolve_result(&self, did: &str) -> DIDResolutionResult {
+        let method = match parse_did_syntax(did) {
+            Ok((method, _)) => method,
+            Err(e) => return DIDResolutionResult::from_error(e),
+        };
+
+        if let Some(ref cache) = self.cache {
+            if let Some(result) = cache.get(did).await {
+                return result;
+            }
+        }
+
+        let Some(resolver) = self.resolvers.get(method) else {
+            return DIDResolutionResult::method_not_supported(method);
+        };
+
+        let result = resolver.resolve_result(did).await;
+        if let Some(ref cache) = self.cache {
+            if result.did_resolution_metadata.error.is_none() {
+                cache.insert(did.to_string(), result.clone()).await;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::did::encode_ed25519_multibase;
+    use libp2p::identity::ed25519;
+
+    fn agoramesh_document(did: &str, keypair: &ed25519::Keypair) -> DIDDocument {
+        let (_, chain, identifier) = DIDDocument::parse_did(did).unwrap();
+        DIDDocumentBuilder::new(&chain, &identifier)
+            .add_ed25519_key("key-1", &encode_ed25519_multibase(&keypair.public()))
+            .build()
+            .unwrap()
+    }
+
+    fn did_key(keypair: &ed25519::Keypair) -> String {
+        format!("did:key:{}", encode_ed25519_multibase(&keypair.public()))
+    }
+
+    /// Answer DHT GetRecord commands from a fixed record map.
+    fn spawn_dht(records: HashMap<Vec<u8>, Vec<u8>>) -> mpsc::Sender<SwarmCommand> {
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Some(command) = rx.recv().await {
+                if let SwarmCommand::GetRecord { key, response_tx } = command {
+                    let _ = response_tx.send(records.get(&key).cloned());
+                }
+            }
+        });
+        tx
+    }
+
+    // ========== TDD Tests: DID syntax ==========
+
+    #[test]
+    fn test_parse_did_syntax_splits_method() {
+        assert_eq!(
+            parse_did_syntax("did:web:example.com:user").unwrap(),
+            ("web", "example.com:user")
+        );
+        assert_eq!(
+            parse_did_syntax("did:agoramesh:base:agent-1").unwrap(),
+            ("agoramesh", "base:agent-1")
+        );
+    }
+
+    #[test]
+    fn test_parse_did_syntax_rejects_malformed() {
+        for did in [
+            "",
+            "did",
+            "did:",
+            "did:web",
+            "did:web:",
+            "did:Web:example.com",
+            "did:web:example.com:",
+            "did:web:exa mple.com",
+            "urn:web:example.com",
+        ] {
+            assert!(
+                matches!(parse_did_syntax(did), Err(Error::Validation(_))),
+                "'{}' should be rejected",
+                did
+            );
+        }
+    }
+
+    #[test]
+    fn test_did_document_key_is_namespaced() {
+        assert_eq!(
+            did_document_key("did:agoramesh:base:a"),
+            b"/agoramesh/did/did:agoramesh:base:a".to_vec()
+        );
+    }
+
+    // ========== TDD Tests: did:key ==========
+
+    #[tokio::test]
+    async fn test_key_resolver_derives_document() {
+        let keypair = ed25519::Keypair::generate();
+        let did = did_key(&keypair);
+
+        let document = KeyDidResolver::new().resolve(&did).await.unwrap().unwrap();
+
+        assert_eq!(document.id, did);
+        let method = document
+            .verification_method
+            .as_ref()
+            .and_then(|methods| methods.first())
+            .unwrap();
+        assert_eq!(method.controller, did);
+        let signature = keypair.sign(b"payload");
+        method.verify(b"payload", &signature).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_key_resolver_rejects_non_ed25519_keys() {
+        // secp256k1-pub multicodec (0xe7 0x01) with a dummy key
+        let mut bytes = vec![0xe7, 0x01];
+        bytes.extend_from_slice(&[2u8; 33]);
+        let did = format!("did:key:z{}", bs58::encode(bytes).into_string());
+
+        let result = KeyDidResolver::new().resolve_result(&did).await;
+
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("invalidDid")
+        );
+    }
+
+    // ========== TDD Tests: did:web ==========
+
+    #[test]
+    fn test_web_document_url() {
+        let resolver = WebDidResolver::new().unwrap();
+
+        assert_eq!(
+            resolver.document_url("did:web:example.com").unwrap(),
+            "https://example.com/.well-known/did.json"
+        );
+        assert_eq!(
+            resolver
+                .document_url("did:web:example.com:user:alice")
+                .unwrap(),
+            "https://example.com/user/alice/did.json"
+        );
+        assert_eq!(
+            resolver.document_url("did:web:localhost%3A8443").unwrap(),
+            "https://localhost:8443/.well-known/did.json"
+        );
+        assert!(resolver.document_url("did:key:z6Mk").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_web_resolver_fetches_document() {
+        use axum::{routing::get, Json, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let did = format!("did:web:127.0.0.1%3A{}", port);
+        let keypair = ed25519::Keypair::generate();
+        let mut document = KeyDidResolver::new()
+            .resolve(&did_key(&keypair))
+            .await
+            .unwrap()
+            .unwrap();
+        document.id = did.clone();
+
+        let app = Router::new().route(
+            "/.well-known/did.json",
+            get(move || {
+                let document = document.clone();
+                async move { Json(document) }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let resolver = WebDidResolver::new()
+            .unwrap()
+            .allow_insecure_http()
+            .allow_private_hosts();
+        let resolved = resolver.resolve(&did).await.unwrap().unwrap();
+        assert_eq!(resolved.id, did);
+
+        let missing = format!("did:web:127.0.0.1%3A{}:nobody", port);
+        assert!(resolver.resolve(&missing).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["93.184.216.34", "2606:2800:220:1::1"] {
+            assert!(is_public_ip(&ip.parse().unwrap()), "{} is public", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(&ip.parse().unwrap()), "{} is not public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_web_resolver_refuses_private_hosts() {
+        let resolver = WebDidResolver::new().unwrap().allow_insecure_http();
+
+        for did in [
+            "did:web:127.0.0.1%3A9",
+            "did:web:169.254.169.254",
+            "did:web:%5B%3A%3A1%5D%3A9",
+            "did:web:localhost%3A9",
+        ] {
+            assert!(
+                resolver.resolve(did).await.is_err(),
+                "{} must be refused",
+                did
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_web_resolver_rejects_redirects_and_oversized_documents() {
+        use axum::{response::Redirect, routing::get, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = Router::new()
+            .route(
+                "/.well-known/did.json",
+                get(|| async { "x".repeat(MAX_WEB_DID_DOCUMENT_SIZE + 1) }),
+            )
+            .route(
+                "/moved/did.json",
+                get(|| async { Redirect::temporary("/.well-known/did.json") }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let resolver = WebDidResolver::new()
+            .unwrap()
+            .allow_insecure_http()
+            .allow_private_hosts();
+
+        let oversized = format!("did:web:127.0.0.1%3A{}", port);
+        assert!(resolver.resolve(&oversized).await.is_err());
+        let redirected = format!("did:web:127.0.0.1%3A{}:moved", port);
+        assert!(resolver.resolve(&redirected).await.is_err());
+    }
+
+    // ========== TDD Tests: did:agoramesh ==========
+
+    #[tokio::test]
+    async fn test_agoramesh_resolver_prefers_local_documents() {
+        let keypair = ed25519::Keypair::generate();
+        let did = "did:agoramesh:base:local-node";
+        let local = Arc::new(InMemoryDidResolver::new());
+        local.insert(agoramesh_document(did, &keypair)).unwrap();
+
+        let resolver = AgoraMeshDidResolver::new().with_local_documents(local);
+
+        let result = resolver.resolve_result(did).await;
+        assert!(result.did_resolution_metadata.error.is_none());
+        assert_eq!(result.did_document.unwrap().id, did);
+    }
+
+    /// DHT record value for `document`, signed by `keypair` as `key-1`.
+    fn signed_record(document: &DIDDocument, keypair: &ed25519::Keypair) -> Vec<u8> {
+        let signer = EnvelopeSigner::new(
+            &document.id,
+            &format!("{}#key-1", document.id),
+            keypair.clone(),
+        );
+        DidDocumentRecord::seal(document, &signer).unwrap()
+    }
+
+    fn peer_did(keypair: &ed25519::Keypair) -> String {
+        let public = libp2p::identity::PublicKey::from(keypair.public());
+        format!("did:agoramesh:base:{}", public.to_peer_id())
+    }
+
+    #[test]
+    fn test_peer_id_key_extracts_node_key() {
+        let keypair = ed25519::Keypair::generate();
+
+        assert_eq!(peer_id_key(&peer_did(&keypair)), Some(keypair.public()));
+        assert_eq!(peer_id_key("did:agoramesh:base:named-agent"), None);
+    }
+
+    #[tokio::test]
+    async fn test_agoramesh_resolver_reads_dht_record() {
+        let keypair = ed25519::Keypair::generate();
+        let did = peer_did(&keypair);
+        let document = agoramesh_document(&did, &keypair);
+        let records = HashMap::from([(did_document_key(&did), signed_record(&document, &keypair))]);
+
+        let resolver = AgoraMeshDidResolver::new().with_network(spawn_dht(records));
+
+        let resolved = resolver.resolve(&did).await.unwrap().unwrap();
+        assert!(resolved.get_verification_method("key-1").is_some());
+        assert!(resolver
+            .resolve("did:agoramesh:base:unknown")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_agoramesh_resolver_ignores_mismatched_dht_record() {
+        let keypair = ed25519::Keypair::generate();
+        let did = peer_did(&keypair);
+        let forged = agoramesh_document("did:agoramesh:base:attacker", &keypair);
+        let records = HashMap::from([(did_document_key(&did), signed_record(&forged, &keypair))]);
+
+        let resolver = AgoraMeshDidResolver::new().with_network(spawn_dht(records));
+
+        assert!(resolver.resolve(&did).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_agoramesh_resolver_rejects_unauthenticated_dht_records() {
+        let victim = ed25519::Keypair::generate();
+        let attacker = ed25519::Keypair::generate();
+        let did = peer_did(&victim);
+        let named = "did:agoramesh:base:named-agent";
+
+        // Attacker key under the victim's peer-ID DID, self-signed
+        let forged = agoramesh_document(&did, &attacker);
+        // Unsigned document
+        let unsigned = agoramesh_document(&did, &victim).to_json().unwrap();
+        // Named DID without an on-chain owner to vouch for it
+        let unowned = agoramesh_document(named, &attacker);
+        let resolver = |value: Vec<u8>, did: &str| {
+            AgoraMeshDidResolver::new()
+                .with_network(spawn_dht(HashMap::from([(did_document_key(did), value)])))
+        };
+
+        assert!(resolver(signed_record(&forged, &attacker), &did)
+            .resolve(&did)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(resolver(unsigned.into_bytes(), &did)
+            .resolve(&did)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(resolver(signed_record(&unowned, &attacker), named)
+            .resolve(named)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_dht_record_for_named_did_verifies_against_owner() {
+        use alloy::signers::local::PrivateKeySigner;
+
+        let owner = PrivateKeySigner::random();
+        let did = "did:agoramesh:base:named-agent";
+        let agent = OnChainAgent {
+            owner: owner.address(),
+            capability_card_cid: String::new(),
+            registered_at: 1,
+            is_active: true,
+        };
+        let authority = AgoraMeshDidResolver::new()
+            .onchain_document("base", "named-agent", &agent)
+            .unwrap();
+        let document = agoramesh_document(did, &ed25519::Keypair::generate());
+        let envelope = SignedEnvelope::sign_ethereum(
+            DID_DOCUMENT_SIGNING_TOPIC,
+            document.to_json().unwrap(),
+            did,
+            &format!("{}#owner", did),
+            &owner,
+        )
+        .unwrap();
+        let record =
+            DidDocumentRecord::parse(did, &serde_json::to_vec(&envelope).unwrap()).unwrap();
+
+        assert!(record.verify(Some(&authority)).is_ok());
+        assert!(record.verify(None).is_err());
+
+        let stranger = AgoraMeshDidResolver::new()
+            .onchain_document(
+                "base",
+                "named-agent",
+                &OnChainAgent {
+                    owner: PrivateKeySigner::random().address(),
+                    ..agent
+                },
+            )
+            .unwrap();
+        assert!(record.verify(Some(&stranger)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_agoramesh_resolver_rejects_invalid_did() {
+        let result = AgoraMeshDidResolver::new()
+            .resolve_result("did:agoramesh:base")
+            .await;
+
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("invalidDid")
+        );
+    }
+
+    #[test]
+    fn test_agoramesh_resolver_adds_onchain_owner_once() {
+        let keypair = ed25519::Keypair::generate();
+        let did = "did:agoramesh:base:owned";
+        let mut document = agoramesh_document(did, &keypair);
+        let agent = OnChainAgent {
+            owner: "0x0000000000000000000000000000000000000001"
+                .parse()
+                .unwrap(),
+            capability_card_cid: String::new(),
+            registered_at: 1,
+            is_active: true,
+        };
+        let resolver = AgoraMeshDidResolver::new().with_chain_id(8453);
+
+        resolver.add_owner_account(&mut document, &agent);
+        resolver.add_owner_account(&mut document, &agent);
+
+        let methods = document.verification_method.unwrap();
+        assert_eq!(methods.len(), 2);
+        assert_eq!(
+            methods[1].blockchain_account_id.as_deref(),
+            Some("eip155:8453:0x0000000000000000000000000000000000000001")
+        );
+    }
+
+    #[test]
+    fn test_agoramesh_resolver_synthesizes_onchain_document() {
+        let agent = OnChainAgent {
+            owner: "0x0000000000000000000000000000000000000002"
+                .parse()
+                .unwrap(),
+            capability_card_cid: "bafy123".to_string(),
+            registered_at: 1_700_000_000,
+            is_active: false,
+        };
+
+        let document = AgoraMeshDidResolver::new()
+            .onchain_document("base", "chain-only", &agent)
+            .unwrap();
+
+        assert_eq!(document.id, "did:agoramesh:base:chain-only");
+        assert!(document.get_verification_method("owner").is_some());
+        assert_eq!(document.capability_card_url(), Some("ipfs://bafy123"));
+        assert_eq!(document.metadata.unwrap().created, 1_700_000_000);
+    }
+
+    // ========== TDD Tests: CompositeDidResolver ==========
+
+    /// Counts lookups to observe caching.
+    #[derive(Default)]
+    struct CountingResolver {
+        inner: KeyDidResolver,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DidResolver for CountingResolver {
+        async fn resolve(&self, did: &str) -> Result<Option<DIDDocument>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.resolve(did).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_composite_dispatches_by_method() {
+        let keypair = ed25519::Keypair::generate();
+        let resolver = CompositeDidResolver::new(DidCacheConfig::default())
+            .with_method("key", Arc::new(KeyDidResolver::new()));
+
+        assert!(resolver.supports("key"));
+        assert!(resolver
+            .resolve(&did_key(&keypair))
+            .await
+            .unwrap()
+            .is_some());
+
+        let result = resolver.resolve_result("did:example:123").await;
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("methodNotSupported")
+        );
+        assert!(resolver.resolve("did:example:123").await.unwrap().is_none());
+
+        let result = resolver.resolve_result("not-a-did").await;
+        assert_eq!(
+            result.did_resolution_metadata.error.as_deref(),
+            Some("invalidDid")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_composite_caches_successful_results() {
+        let keypair = ed25519::Keypair::generate();
+        let did = did_key(&keypair);
+        let counting = Arc::new(CountingResolver::default());
+        let resolver = CompositeDidResolver::new(DidCacheConfig::default())
+            .with_method("key", counting.clone());
+
+        resolver.resolve(&did).await.unwrap();
+        resolver.resolve(&did).await.unwrap();
+        assert_eq!(counting.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        resolver.invalidate(&did).await;
+        resolver.resolve(&did).await.unwrap();
+        assert_eq!(counting.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_composite_does_not_cache_not_found() {
+        let did = "did:agoramesh:base:later";
+        let local = Arc::new(InMemoryDidResolver::new());
+        let resolver = CompositeDidResolver::new(DidCacheConfig::default())
+            .with_agoramesh(AgoraMeshDidResolver::new().with_local_documents(local.clone()));
+
+        assert!(resolver.resolve(did).await.unwrap().is_none());
+
+        local
+            .insert(agoramesh_document(did, &ed25519::Keypair::generate()))
+            .unwrap();
+        assert!(resolver.resolve(did).await.unwrap().is_some());
+    }
+}
@@ -3,17 +3,38 @@
 //! This module handles:
 //! - Capability Card registration and lookup
 //! - Semantic search for agent discovery
+//! - Schema-aware matching of skills against sample payloads
 //! - DHT-based decentralized registry
 
+use libp2p::PeerId;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
+use crate::contract::DidHashIndex;
+use crate::did::{parse_did_syntax, DidResolver};
 use crate::error::{Error, Result};
-use crate::network::SwarmCommand;
-use crate::search::HybridSearch;
+use crate::network::{topics, SearchRequest, SearchResponse, SwarmCommand};
+use crate::persistence::CapabilityCardStore;
+use crate::search::{Bm25Index, HybridSearch, RankingWeights, TrustRanker, VectorFilter};
+
+mod federation;
+mod providers;
+mod schema;
+mod signing;
+
+pub use federation::{
+    merge_ranked, NetworkSearchConfig, PeerBudget, DEFAULT_PEER_QUERIES_PER_MINUTE,
+    DEFAULT_SEARCH_FANOUT, DEFAULT_SEARCH_TIMEOUT_MS,
+};
+pub use providers::{
+    card_capabilities, normalize_capability, provider_keys, skill_provider_key,
+    SKILL_PROVIDER_KEY_PREFIX,
+};
+pub use schema::{SchemaMatchQuery, SchemaValidators, SkillMatch, SCHEMA_VALIDATOR_CACHE_SIZE};
+pub use signing::{check_card_update, CardProof};
 
 /// A2A-compatible Capability Card for agent discovery.
 ///
@@ -92,6 +113,10 @@ pub struct Skill {
     /// Skill description.
     pub description: Option<String>,
 
+    /// Taxonomy tags (e.g. `"nlp"`); agents can be found by tag like by ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     /// Input schema (JSON Schema).
     #[serde(rename = "inputSchema")]
     pub input_schema: Option<serde_json::Value>,
@@ -128,6 +153,18 @@ pub struct AgoraMeshExtension {
 
     /// Supported payment methods.
     pub payment_methods: Vec<String>,
+
+    /// Card version; each update must not go below the previous one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+
+    /// When this version was published (Unix seconds); breaks version ties.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+
+    /// Signature by the agent's DID over the rest of the card.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<CardProof>,
 }
 
 /// Pricing information for agent services.
@@ -144,7 +181,7 @@ pub struct PricingInfo {
 }
 
 /// Pricing model types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PricingModel {
     /// Fixed price per request.
@@ -160,6 +197,277 @@ pub enum PricingModel {
     Custom,
 }
 
+/// Default page size for discovery queries.
+pub const DEFAULT_QUERY_LIMIT: usize = 20;
+
+/// Largest page a discovery query may request.
+pub const MAX_QUERY_LIMIT: usize = 100;
+
+/// Largest offset a discovery query may start its page at.
+pub const MAX_QUERY_OFFSET: usize = 1_000;
+
+/// Result ordering for discovery queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuerySort {
+    /// Best match first (hybrid or BM25 score).
+    #[default]
+    Relevance,
+
+    /// Highest advertised trust score first.
+    Trust,
+
+    /// Lowest base price first; unpriced cards last.
+    Price,
+}
+
+/// Which nodes a discovery query is answered by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchScope {
+    /// Only this node's cache.
+    #[default]
+    Local,
+
+    /// This node and up to [`NetworkSearchConfig::fanout`] connected peers.
+    Network,
+}
+
+/// Structured discovery query.
+///
+/// Accepted both as URL query parameters and as a JSON body. Every field
+/// is optional and filters combine with AND.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryQuery {
+    /// Free-text query (`q` in URLs).
+    #[serde(alias = "q")]
+    pub text: Option<String>,
+
+    /// Only agents offering a skill with this ID or tag (see
+    /// [`normalize_capability`]).
+    pub skill_id: Option<String>,
+
+    /// Only agents advertising at least this trust score (0.0 - 1.0).
+    #[serde(alias = "minTrust")]
+    pub min_trust: Option<f64>,
+
+    /// Only agents whose base price is at most this, in USDC (6 decimals).
+    pub max_price: Option<u64>,
+
+    /// Only agents using this pricing model.
+    pub pricing_model: Option<PricingModel>,
+
+    /// Only agents accepting this payment method.
+    pub payment_method: Option<String>,
+
+    /// Only agents from this provider organization (case-insensitive).
+    pub provider: Option<String>,
+
+    /// Number of results to skip.
+    pub offset: Option<usize>,
+
+    /// Opaque cursor from a previous page; takes precedence over `offset`.
+    pub cursor: Option<String>,
+
+    /// Page size (default: 20, max: 100).
+    pub limit: Option<usize>,
+
+    /// Result ordering.
+    pub sort: QuerySort,
+
+    /// Whether connected peers are queried too.
+    pub scope: SearchScope,
+}
+
+/// One page of discovery results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryPage<T> {
+    /// Results on this page.
+    pub items: Vec<T>,
+
+    /// Number of results across all pages.
+    pub total: usize,
+
+    /// Cursor for the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> DiscoveryPage<T> {
+    /// Convert the items of this page.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> DiscoveryPage<U> {
+        DiscoveryPage {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+fn card_trust(card: &CapabilityCard) -> f64 {
+    card.agoramesh
+        .as_ref()
+        .and_then(|e| e.trust_score)
+        .unwrap_or(0.0)
+}
+
+fn card_price(card: &CapabilityCard) -> Option<u64> {
+    card.agoramesh
+        .as_ref()
+        .and_then(|e| e.pricing.as_ref())
+        .map(|p| p.base_price)
+}
+
+impl DiscoveryQuery {
+    /// Create a query with only free text.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Get the trimmed free-text query ("" when absent).
+    pub fn query_text(&self) -> &str {
+        self.text.as_deref().unwrap_or("").trim()
+    }
+
+    /// Check that limits, trust bounds and the cursor are well-formed.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_QUERY_LIMIT {
+                return Err(Error::Validation(format!(
+                    "limit must be between 1 and {}",
+                    MAX_QUERY_LIMIT
+                )));
+            }
+        }
+        if let Some(min_trust) = self.min_trust {
+            if !(0.0..=1.0).contains(&min_trust) {
+                return Err(Error::Validation(
+                    "min_trust must be between 0.0 and 1.0".to_string(),
+                ));
+            }
+        }
+        self.page_start()?;
+        Ok(())
+    }
+
+    /// Reject [`SearchScope::Network`] where only local search is supported.
+    pub fn require_local_scope(&self) -> Result<()> {
+        match self.scope {
+            SearchScope::Local => Ok(()),
+            SearchScope::Network => Err(Error::Validation(
+                "scope=network is only supported by /agents and /agents/search".to_string(),
+            )),
+        }
+    }
+
+    /// Index of the first result on the requested page.
+    pub fn page_start(&self) -> Result<usize> {
+        let start = match &self.cursor {
+            Some(cursor) => cursor
+                .parse()
+                .map_err(|_| Error::Validation(format!("Invalid cursor: {}", cursor)))?,
+            None => self.offset.unwrap_or(0),
+        };
+        if start > MAX_QUERY_OFFSET {
+            return Err(Error::Validation(format!(
+                "offset must be at most {}",
+                MAX_QUERY_OFFSET
+            )));
+        }
+        Ok(start)
+    }
+
+    /// Index one past the last result on the requested page.
+    pub fn page_end(&self) -> Result<usize> {
+        Ok(self.page_start()?.saturating_add(self.page_size()))
+    }
+
+    /// Number of results per page.
+    pub fn page_size(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT)
+    }
+
+    /// Filters that vector indexes can evaluate themselves.
+    pub fn vector_filter(&self) -> VectorFilter {
+        VectorFilter {
+            pricing_models: self.pricing_model.iter().cloned().collect(),
+            payment_methods: self.payment_method.iter().cloned().collect(),
+            min_trust: self.min_trust,
+        }
+    }
+
+    /// Check whether a card satisfies every filter of this query.
+    pub fn matches(&self, card: &CapabilityCard) -> bool {
+        if !self.vector_filter().matches_card(card) {
+            return false;
+        }
+
+        if let Some(skill_id) = &self.skill_id {
+            if !card.skills.iter().any(|skill| skill.offers(skill_id)) {
+                return false;
+            }
+        }
+
+        if let Some(provider) = &self.provider {
+            if !card
+                .provider
+                .as_ref()
+                .is_some_and(|p| p.organization.eq_ignore_ascii_case(provider))
+            {
+                return false;
+            }
+        }
+
+        if let Some(max_price) = self.max_price {
+            if card_price(card).is_none_or(|price| price > max_price) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Reorder relevance-ranked items by the requested sort.
+    ///
+    /// The sort is stable, so ties keep their relevance order.
+    pub fn sort<T>(&self, items: &mut [T], card: impl Fn(&T) -> &CapabilityCard) {
+        match self.sort {
+            QuerySort::Relevance => {}
+            QuerySort::Trust => items.sort_by(|a, b| {
+                card_trust(card(b))
+                    .partial_cmp(&card_trust(card(a)))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            QuerySort::Price => {
+                items.sort_by_key(|item| card_price(card(item)).unwrap_or(u64::MAX))
+            }
+        }
+    }
+
+    /// Cut the requested page out of the full, ordered result list.
+    pub fn paginate<T>(&self, items: Vec<T>) -> Result<DiscoveryPage<T>> {
+        let total = items.len();
+        let start = self.page_start()?;
+        let items: Vec<T> = items
+            .into_iter()
+            .skip(start)
+            .take(self.page_size())
+            .collect();
+        let end = start.saturating_add(items.len());
+
+        Ok(DiscoveryPage {
+            items,
+            total,
+            next_cursor: (end < total).then(|| end.to_string()),
+        })
+    }
+}
+
 /// Local discovery cache tuning.
 #[derive(Debug, Clone)]
 pub struct DiscoveryCacheConfig {
@@ -282,8 +590,49 @@ pub struct DiscoveryService {
     /// Falls back to simple keyword matching if not available.
     /// Wrapped in Arc so it can be shared with the API layer for semantic search queries.
     hybrid_search: Option<Arc<tokio::sync::RwLock<HybridSearch>>>,
+
+    /// BM25 index over cached cards, used when hybrid search is unavailable.
+    keyword_index: RwLock<Bm25Index>,
+
+    /// Optional re-ranker blending keyword relevance with live trust data,
+    /// and the weights it applies.
+    trust_ranker: Option<(TrustRanker, RankingWeights)>,
+
+    /// Optional durable store; registrations are written through to it.
+    card_store: Option<CapabilityCardStore>,
+
+    /// Optional resolver for verifying card proofs.
+    did_resolver: Option<Arc<dyn DidResolver>>,
+    /// Whether cards without a verifiable proof are rejected.
+    require_signed_cards: bool,
+
+    /// Optional contract hash index, kept in line with known cards.
+    did_index: Option<Arc<DidHashIndex>>,
+
+    /// Fan-out, timeout and rate budget for `scope=network` queries.
+    network_search: NetworkSearchConfig,
+    /// Queries sent to each peer in the current window.
+    outbound_budget: Mutex<PeerBudget>,
+    /// Queries answered for each peer in the current window.
+    inbound_budget: Mutex<PeerBudget>,
+
+    /// Cards registered on this node, re-announced on discovery requests.
+    local_cards: RwLock<HashMap<String, CapabilityCard>>,
+    /// Agents deactivated on-chain; their cards are kept but not served.
+    inactive: RwLock<HashSet<String>>,
+    /// When local cards were last re-announced (rate limits responses).
+    last_reannounce: Mutex<Option<Instant>>,
+
+    /// Compiled skill schemas for [`Self::match_schema`].
+    schema_validators: SchemaValidators,
 }
 
+/// Minimum interval between re-announcements triggered by discovery requests.
+pub const REANNOUNCE_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How long to wait for a DHT provider lookup.
+pub const PROVIDER_LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl DiscoveryService {
     fn from_parts(
         network_tx: Option<mpsc::Sender<SwarmCommand>>,
@@ -295,7 +644,137 @@ impl DiscoveryService {
             cache_config,
             network_tx,
             hybrid_search,
+            keyword_index: RwLock::new(Bm25Index::new()),
+            trust_ranker: None,
+            card_store: None,
+            did_resolver: None,
+            require_signed_cards: true,
+            did_index: None,
+            outbound_budget: Mutex::new(NetworkSearchConfig::default().budget()),
+            inbound_budget: Mutex::new(NetworkSearchConfig::default().budget()),
+            network_search: NetworkSearchConfig::default(),
+            local_cards: RwLock::new(HashMap::new()),
+            inactive: RwLock::new(HashSet::new()),
+            last_reannounce: Mutex::new(None),
+            schema_validators: SchemaValidators::default(),
+        }
+    }
+
+    /// Attach a durable capability card store.
+    ///
+    /// Registered cards are written through to the store, and cache misses
+    /// consult it before querying the DHT. Call [`Self::rehydrate`] after
+    /// attaching to load previously persisted cards.
+    pub fn with_card_store(mut self, store: CapabilityCardStore) -> Self {
+        self.card_store = Some(store);
+        self
+    }
+
+    /// Re-rank keyword search results with live trust data.
+    ///
+    /// Only affects the BM25 fallback; attach the ranker to
+    /// [`HybridSearch`] as well to rank semantic results.
+    pub fn with_trust_ranker(mut self, ranker: TrustRanker, weights: RankingWeights) -> Self {
+        self.trust_ranker = Some((ranker, weights));
+        self
+    }
+
+    /// Verify card proofs against DID documents from `resolver`.
+    ///
+    /// Without a resolver, proofs cannot be checked and signed cards are
+    /// treated like unsigned ones.
+    pub fn with_did_resolver(mut self, resolver: Arc<dyn DidResolver>) -> Self {
+        self.did_resolver = Some(resolver);
+        self
+    }
+
+    /// Require every card to carry a proof that verifies.
+    ///
+    /// Defaults to `true`; pass `false` to accept unsigned cards from older
+    /// agents.
+    pub fn with_required_card_signatures(mut self, required: bool) -> Self {
+        self.require_signed_cards = required;
+        self
+    }
+
+    /// Record the DID of every card this service learns about in `index`,
+    /// and drop it again when the agent is removed.
+    pub fn with_did_index(mut self, index: Arc<DidHashIndex>) -> Self {
+        self.did_index = Some(index);
+        self
+    }
+
+    /// Configure how `scope=network` queries fan out to peers and how many
+    /// queries each peer may send to this node.
+    pub fn with_network_search(mut self, config: NetworkSearchConfig) -> Self {
+        self.outbound_budget = Mutex::new(config.budget());
+        self.inbound_budget = Mutex::new(config.budget());
+        self.network_search = config;
+        self
+    }
+
+    /// Load all persisted cards into the local cache and search index.
+    ///
+    /// Cards registered on this node are restored as local cards, so
+    /// [`Self::reannounce_local_cards`] covers them again, but nothing is
+    /// announced here. Returns the number of cards loaded (0 when no store
+    /// is attached).
+    pub async fn rehydrate(&self) -> Result<usize> {
+        let Some(ref store) = self.card_store else {
+            return Ok(0);
+        };
+
+        let inactive: HashSet<String> = store.inactive()?.into_iter().collect();
+        let cards: Vec<(String, CapabilityCard)> = store
+            .all()?
+            .into_iter()
+            .filter(|(did, _)| !inactive.contains(did))
+            .collect();
+        let count = cards.len();
+        if let Some(ref index) = self.did_index {
+            for did in &inactive {
+                index.insert(did)?;
+            }
+        }
+        self.inactive
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?
+            .extend(inactive);
+
+        for (did, card) in &cards {
+            self.cache_insert(did.clone(), card.clone()).await?;
+        }
+
+        // Cards registered before the restart are re-announced again
+        let local = store.local()?;
+        self.local_cards
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .extend(local);
+
+        // Index in one pass so only cards without a stored embedding are embedded
+        if let Some(ref hybrid_search) = self.hybrid_search {
+            let cards: Vec<CapabilityCard> = cards.into_iter().map(|(_, card)| card).collect();
+            let mut search = hybrid_search.write().await;
+            match search.index_cards(&cards).await {
+                Ok(embedded) => {
+                    tracing::info!("Indexed {} persisted cards ({} embedded)", count, embedded)
+                }
+                Err(e) => tracing::warn!("Failed to index persisted cards in hybrid search: {}", e),
+            }
+            match search.prune_embeddings() {
+                Ok(0) => {}
+                Ok(removed) => tracing::debug!("Pruned {} stale embeddings", removed),
+                Err(e) => tracing::warn!("Failed to prune stale embeddings: {}", e),
+            }
+            match search.prune_vector_index().await {
+                Ok(0) => {}
+                Ok(removed) => tracing::debug!("Pruned {} stale indexed vectors", removed),
+                Err(e) => tracing::warn!("Failed to prune stale indexed vectors: {}", e),
+            }
         }
+
+        Ok(count)
     }
 
     /// Create a new discovery service without network integration.
@@ -362,15 +841,27 @@ impl DiscoveryService {
         if dids.is_empty() {
             return;
         }
+        if let Ok(mut keywords) = self.keyword_index.write() {
+            for did in &dids {
+                keywords.remove(did);
+            }
+        }
         if let Some(ref hybrid_search) = self.hybrid_search {
             let mut search = hybrid_search.write().await;
             for did in dids {
-                search.remove_card(&did);
+                search.remove_card(&did).await;
             }
         }
     }
 
     async fn cache_insert(&self, did: String, card: CapabilityCard) -> Result<()> {
+        if let Some(ref index) = self.did_index {
+            index.insert(&did)?;
+        }
+        self.keyword_index
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire keyword index lock: {}", e)))?
+            .index_card(&did, &card);
         let evicted = {
             let mut cache = self.cache.write().map_err(|e| {
                 Error::Discovery(format!("Failed to acquire cache write lock: {}", e))
@@ -414,6 +905,213 @@ impl DiscoveryService {
         Ok(removed)
     }
 
+    /// Forget an agent entirely.
+    ///
+    /// Unlike [`Self::invalidate`], the card is also dropped from the search
+    /// index, the durable store and the set of locally registered cards, so
+    /// it is neither served nor re-announced. Returns `true` if the card was
+    /// known. Agents deactivated on-chain use [`Self::deactivate`] instead,
+    /// which keeps the card.
+    pub async fn remove(&self, did: &str) -> Result<bool> {
+        let cached = {
+            let mut cache = self.cache.write().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire cache write lock: {}", e))
+            })?;
+            cache.remove(did)
+        };
+        let local_card = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .get(did)
+            .cloned();
+        if let Some(ref card) = local_card {
+            let stale_keys = self.exclusive_provider_keys(did, provider_keys(card))?;
+            self.stop_providing(stale_keys).await?;
+        }
+        self.local_cards
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .remove(did);
+        self.inactive
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?
+            .remove(did);
+        let stored = match self.card_store {
+            Some(ref store) if store.contains(did)? => {
+                store.delete(did)?;
+                true
+            }
+            _ => false,
+        };
+        self.remove_from_search_index(vec![did.to_string()]).await;
+        if let Some(ref index) = self.did_index {
+            index.remove(did)?;
+        }
+
+        Ok(cached || local_card.is_some() || stored)
+    }
+
+    /// Mark an agent as deactivated on-chain.
+    ///
+    /// The card stays in the durable store and the contract hash index so
+    /// later events still resolve, but it is dropped from the cache and the
+    /// search indexes, no longer announced or advertised as a skill
+    /// provider, and cards for the agent are rejected until
+    /// [`Self::reactivate`]. Returns `true` if the agent was active.
+    pub async fn deactivate(&self, did: &str) -> Result<bool> {
+        let newly_inactive = self
+            .inactive
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?
+            .insert(did.to_string());
+        if !newly_inactive {
+            return Ok(false);
+        }
+        if let Some(ref store) = self.card_store {
+            store.set_inactive(did, true)?;
+        }
+
+        let local_card = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .get(did)
+            .cloned();
+        if let Some(ref card) = local_card {
+            let stale_keys = self.exclusive_provider_keys(did, provider_keys(card))?;
+            self.stop_providing(stale_keys).await?;
+        }
+        {
+            let mut cache = self.cache.write().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire cache write lock: {}", e))
+            })?;
+            cache.remove(did);
+        }
+        self.remove_from_search_index(vec![did.to_string()]).await;
+
+        Ok(true)
+    }
+
+    /// Serve an agent deactivated by [`Self::deactivate`] again.
+    ///
+    /// Its stored card is cached and indexed again, and a local card is
+    /// advertised as a skill provider once more. Returns `true` if the agent
+    /// was inactive.
+    pub async fn reactivate(&self, did: &str) -> Result<bool> {
+        let was_inactive = self
+            .inactive
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?
+            .remove(did);
+        if !was_inactive {
+            return Ok(false);
+        }
+
+        let Some(ref store) = self.card_store else {
+            return Ok(true);
+        };
+        store.set_inactive(did, false)?;
+        if let Some(card) = store.get(did)? {
+            self.cache_insert(did.to_string(), card.clone()).await?;
+            if let Some(ref hybrid_search) = self.hybrid_search {
+                if let Err(e) = hybrid_search.write().await.index_card(&card).await {
+                    tracing::warn!("Failed to index reactivated card: {}", e);
+                }
+            }
+        }
+
+        let local_card = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .get(did)
+            .cloned();
+        if let (Some(card), Some(tx)) = (local_card, self.network_tx.as_ref()) {
+            for key in provider_keys(&card) {
+                tx.send(SwarmCommand::StartProviding { key })
+                    .await
+                    .map_err(|e| {
+                        Error::Discovery(format!("Failed to send DHT provide command: {}", e))
+                    })?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Whether the agent was deactivated on-chain.
+    pub fn is_inactive(&self, did: &str) -> Result<bool> {
+        Ok(self
+            .inactive
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?
+            .contains(did))
+    }
+
+    /// Those of `keys` that no other active local card is announced under.
+    fn exclusive_provider_keys(&self, did: &str, keys: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        let inactive = self
+            .inactive
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?;
+        let local_cards = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?;
+        let remaining: HashSet<Vec<u8>> = local_cards
+            .iter()
+            .filter(|(other, _)| other.as_str() != did && !inactive.contains(*other))
+            .flat_map(|(_, card)| provider_keys(card))
+            .collect();
+
+        Ok(keys
+            .into_iter()
+            .filter(|key| !remaining.contains(key))
+            .collect())
+    }
+
+    /// Withdraw provider records for `keys` from the DHT.
+    async fn stop_providing(&self, keys: Vec<Vec<u8>>) -> Result<()> {
+        let Some(ref tx) = self.network_tx else {
+            return Ok(());
+        };
+        for key in keys {
+            tx.send(SwarmCommand::StopProviding { key })
+                .await
+                .map_err(|e| {
+                    Error::Discovery(format!("Failed to send DHT unprovide command: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// DIDs of all cards known to this node (cached, local or persisted).
+    pub fn known_dids(&self) -> Result<Vec<String>> {
+        let mut dids: HashSet<String> = self
+            .cache
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire cache read lock: {}", e)))?
+            .entries
+            .keys()
+            .cloned()
+            .collect();
+        dids.extend(
+            self.local_cards
+                .read()
+                .map_err(|e| {
+                    Error::Discovery(format!("Failed to acquire local cards lock: {}", e))
+                })?
+                .keys()
+                .cloned(),
+        );
+        if let Some(ref store) = self.card_store {
+            dids.extend(store.all()?.into_iter().map(|(did, _)| did));
+        }
+
+        Ok(dids.into_iter().collect())
+    }
+
     /// Clear all cached entries.
     pub async fn clear_cache(&self) -> Result<()> {
         {
@@ -422,9 +1120,12 @@ impl DiscoveryService {
             })?;
             cache.clear();
         }
+        if let Ok(mut keywords) = self.keyword_index.write() {
+            keywords.clear();
+        }
         if let Some(ref hybrid_search) = self.hybrid_search {
             let mut search = hybrid_search.write().await;
-            search.clear();
+            search.clear().await?;
         }
         Ok(())
     }
@@ -444,6 +1145,10 @@ impl DiscoveryService {
 
     /// Register a capability card.
     ///
+    /// The card is cached, indexed, stored in the DHT and announced via
+    /// GossipSub. It is also remembered as a local card and re-announced
+    /// when peers send discovery requests.
+    ///
     /// # Arguments
     ///
     /// * `card` - The capability card to register
@@ -453,33 +1158,28 @@ impl DiscoveryService {
     /// Returns an error if:
     /// - The card is missing the AgoraMesh extension with DID
     /// - The DID format is invalid
+    /// - The card fails [`Self::verify_card`]
     pub async fn register(&self, card: &CapabilityCard) -> Result<()> {
-        // Validate: card must have agoramesh extension with DID
-        let agoramesh = card
-            .agoramesh
-            .as_ref()
-            .ok_or_else(|| Error::Discovery("Missing agoramesh extension with DID".to_string()))?;
-
-        let did = &agoramesh.did;
+        let did = Self::card_did(card)?;
+        self.verify_card(card).await?;
 
-        // Validate DID format: must start with "did:"
-        if !did.starts_with("did:") {
-            return Err(Error::Discovery(format!(
-                "Invalid DID format: '{}'. DID must start with 'did:'",
-                did
-            )));
-        }
-
-        // Store in local cache
-        self.cache_insert(did.clone(), card.clone()).await?;
+        self.store_locally(did, card, true).await?;
 
-        // Index in hybrid search if available
-        if let Some(ref hybrid_search) = self.hybrid_search {
-            let mut search = hybrid_search.write().await;
-            // Log but don't fail if indexing fails - cache still works
-            if let Err(e) = search.index_card(card).await {
-                tracing::warn!("Failed to index card in hybrid search: {}", e);
-            }
+        let previous = self
+            .local_cards
+            .write()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .insert(did.to_string(), card.clone());
+
+        // Withdraw skills the previous version offered and this one does not
+        if let Some(previous) = previous {
+            let current: HashSet<Vec<u8>> = provider_keys(card).into_iter().collect();
+            let dropped: Vec<Vec<u8>> = provider_keys(&previous)
+                .into_iter()
+                .filter(|key| !current.contains(key))
+                .collect();
+            let stale_keys = self.exclusive_provider_keys(did, dropped)?;
+            self.stop_providing(stale_keys).await?;
         }
 
         // Store in DHT and announce via GossipSub if network is available
@@ -497,33 +1197,220 @@ impl DiscoveryService {
 
             // Announce via GossipSub for real-time discovery
             tx.send(SwarmCommand::Publish {
-                topic: "/agoramesh/discovery/1.0.0".to_string(),
+                topic: topics::DISCOVERY.to_string(),
                 data: serialized,
             })
             .await
             .map_err(|e| Error::Discovery(format!("Failed to send GossipSub publish: {}", e)))?;
+
+            // Advertise as a provider of each skill ID and tag
+            for key in provider_keys(card) {
+                tx.send(SwarmCommand::StartProviding { key })
+                    .await
+                    .map_err(|e| {
+                        Error::Discovery(format!("Failed to send DHT provide command: {}", e))
+                    })?;
+            }
         }
 
         Ok(())
     }
 
-    /// Search for agents by capability.
+    /// Ingest a capability card received from a peer.
     ///
-    /// When HybridSearch is available, uses combined vector similarity and keyword
-    /// matching for semantic search. Falls back to simple keyword matching otherwise.
+    /// Like [`Self::register`], but the card is only cached, persisted and
+    /// indexed locally. It is neither written to the DHT nor re-published,
+    /// so gossip announcements are not echoed back into the mesh.
     ///
-    /// **Design Note:** Search operates on local cache only. The cache is populated from:
-    /// - Direct registrations via `register()`
-    /// - DHT queries via `get()` for specific DIDs
-    /// - GossipSub announcements (when subscribed to discovery topic)
+    /// # Errors
     ///
-    /// DHT (Kademlia) is a key-value store that doesn't support full-text search.
-    /// For broader discovery, call `request_registry_broadcast()` to request peers
-    /// to announce their known agents.
+    /// Returns an error if the card has no AgoraMesh DID, the DID is invalid
+    /// or the card fails [`Self::verify_card`].
+    pub async fn ingest(&self, card: &CapabilityCard) -> Result<()> {
+        let did = Self::card_did(card)?;
+        self.verify_card(card).await?;
+        // Our own cards echoed back by peers stay local
+        let local = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .contains_key(did);
+        self.store_locally(did, card, local).await
+    }
+
+    /// Re-announce cards registered on this node via GossipSub.
     ///
-    /// # Arguments
+    /// Used to answer discovery requests from peers. Responses are rate
+    /// limited to one per [`REANNOUNCE_COOLDOWN`] so a burst of requests
+    /// does not flood the mesh.
     ///
-    /// * `query` - Search query string
+    /// # Returns
+    ///
+    /// The number of cards announced (0 without network or during cooldown).
+    pub async fn reannounce_local_cards(&self) -> Result<usize> {
+        let Some(ref tx) = self.network_tx else {
+            return Ok(0);
+        };
+
+        {
+            let mut last = self.last_reannounce.lock().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire re-announce lock: {}", e))
+            })?;
+            let now = Instant::now();
+            if last.is_some_and(|at| now.duration_since(at) < REANNOUNCE_COOLDOWN) {
+                return Ok(0);
+            }
+            *last = Some(now);
+        }
+
+        let cards: Vec<CapabilityCard> = {
+            let inactive = self
+                .inactive
+                .read()
+                .map_err(|e| Error::Discovery(format!("Failed to acquire inactive lock: {}", e)))?;
+            self.local_cards
+                .read()
+                .map_err(|e| {
+                    Error::Discovery(format!("Failed to acquire local cards lock: {}", e))
+                })?
+                .iter()
+                .filter(|(did, _)| !inactive.contains(*did))
+                .map(|(_, card)| card.clone())
+                .collect()
+        };
+
+        for card in &cards {
+            let serialized = serde_json::to_vec(card)
+                .map_err(|e| Error::Discovery(format!("Failed to serialize card: {}", e)))?;
+            tx.send(SwarmCommand::Publish {
+                topic: topics::DISCOVERY.to_string(),
+                data: serialized,
+            })
+            .await
+            .map_err(|e| Error::Discovery(format!("Failed to send GossipSub publish: {}", e)))?;
+        }
+
+        Ok(cards.len())
+    }
+
+    /// Extract and validate the DID of a capability card.
+    fn card_did(card: &CapabilityCard) -> Result<&str> {
+        // Validate: card must have agoramesh extension with DID
+        let agoramesh = card
+            .agoramesh
+            .as_ref()
+            .ok_or_else(|| Error::Discovery("Missing agoramesh extension with DID".to_string()))?;
+
+        let did = &agoramesh.did;
+        parse_did_syntax(did)
+            .map_err(|e| Error::Validation(format!("Invalid DID format: {}", e)))?;
+
+        Ok(did)
+    }
+
+    /// Check that a card may replace what is known under its DID.
+    ///
+    /// - A signed card's proof must verify against the DID document from
+    ///   the configured resolver; unknown DIDs fail
+    /// - Cards without a verified proof fail when signatures are required
+    /// - The card must not be older than the known copy, nor unsigned when
+    ///   the known copy is signed (see [`check_card_update`])
+    /// - The agent must not be deactivated (see [`Self::deactivate`])
+    pub async fn verify_card(&self, card: &CapabilityCard) -> Result<()> {
+        let did = Self::card_did(card)?;
+        if self.is_inactive(did)? {
+            return Err(Error::Validation(format!("Agent {} is deactivated", did)));
+        }
+
+        match self.did_resolver {
+            Some(ref resolver) if card.is_signed() => {
+                let document = resolver.resolve(did).await?.ok_or_else(|| {
+                    Error::Validation(format!("Cannot verify card: unknown DID {}", did))
+                })?;
+                card.verify_proof(&document)?;
+            }
+            _ if self.require_signed_cards => {
+                return Err(Error::Validation(format!(
+                    "Card for {} has no verifiable signature",
+                    did
+                )));
+            }
+            _ => {}
+        }
+
+        if let Some(current) = self.known_card(did)? {
+            check_card_update(&current, card)?;
+        }
+        Ok(())
+    }
+
+    /// The card currently held for `did` (cached, local or persisted).
+    fn known_card(&self, did: &str) -> Result<Option<CapabilityCard>> {
+        if let Some(card) = self.cache_get(did)? {
+            return Ok(Some(card));
+        }
+        let local = self
+            .local_cards
+            .read()
+            .map_err(|e| Error::Discovery(format!("Failed to acquire local cards lock: {}", e)))?
+            .get(did)
+            .cloned();
+        if local.is_some() {
+            return Ok(local);
+        }
+        match self.card_store {
+            Some(ref store) => store.get(did),
+            None => Ok(None),
+        }
+    }
+
+    /// Persist, cache and index a card without touching the network.
+    ///
+    /// `local` marks cards registered on this node so they are restored as
+    /// local cards by [`Self::rehydrate`].
+    async fn store_locally(&self, did: &str, card: &CapabilityCard, local: bool) -> Result<()> {
+        // Write through to durable storage before touching the cache
+        if let Some(ref store) = self.card_store {
+            if local {
+                store.put_local(did, card)?;
+            } else {
+                store.put(did, card)?;
+            }
+        }
+
+        // Store in local cache
+        self.cache_insert(did.to_string(), card.clone()).await?;
+
+        // Index in hybrid search if available
+        if let Some(ref hybrid_search) = self.hybrid_search {
+            let mut search = hybrid_search.write().await;
+            // Log but don't fail if indexing fails - cache still works
+            if let Err(e) = search.index_card(card).await {
+                tracing::warn!("Failed to index card in hybrid search: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Search for agents by capability.
+    ///
+    /// When HybridSearch is available, uses combined vector similarity and keyword
+    /// matching for semantic search. Falls back to simple keyword matching otherwise.
+    ///
+    /// **Design Note:** Search operates on local cache only. The cache is populated from:
+    /// - Direct registrations via `register()`
+    /// - DHT queries via `get()` for specific DIDs
+    /// - GossipSub announcements (when subscribed to discovery topic)
+    ///
+    /// DHT (Kademlia) is a key-value store that doesn't support full-text search.
+    /// For broader discovery, run a [`Self::query`] with [`SearchScope::Network`],
+    /// or call `request_registry_broadcast()` to request peers to announce their
+    /// known agents.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Search query string
     ///
     /// # Returns
     ///
@@ -548,50 +1435,354 @@ impl DiscoveryService {
         self.search_simple(query).await
     }
 
-    /// Simple keyword search (fallback when hybrid search is unavailable).
-    async fn search_simple(&self, query: &str) -> Result<Vec<CapabilityCard>> {
-        self.prune_expired_cache().await?;
+    /// Run a structured discovery query.
+    ///
+    /// Uses hybrid search when available and the BM25 keyword index
+    /// otherwise. Filters, sorting and paging are applied the same way on
+    /// both paths. With [`SearchScope::Local`] only locally known cards are
+    /// considered; [`SearchScope::Network`] also asks connected peers and
+    /// merges their results with the local ones.
+    pub async fn query(&self, query: &DiscoveryQuery) -> Result<DiscoveryPage<CapabilityCard>> {
+        query.validate()?;
+
+        match query.scope {
+            SearchScope::Local => self.query_local(query).await,
+            SearchScope::Network => self.query_network(query).await,
+        }
+    }
 
-        let cache = self
-            .cache
-            .read()
-            .map_err(|e| Error::Discovery(format!("Failed to acquire cache read lock: {}", e)))?;
+    async fn query_local(&self, query: &DiscoveryQuery) -> Result<DiscoveryPage<CapabilityCard>> {
+        let cards = self.ranked_local(query).await?;
+        query.paginate(cards)
+    }
+
+    /// Filter and sort locally known cards without paging them.
+    async fn ranked_local(&self, query: &DiscoveryQuery) -> Result<Vec<CapabilityCard>> {
+        if let Some(ref hybrid_search) = self.hybrid_search {
+            let search = hybrid_search.read().await;
+            match search.ranked(query).await {
+                Ok(results) => return Ok(results.into_iter().map(|r| r.card).collect()),
+                Err(e) => {
+                    tracing::warn!("Hybrid search failed, falling back to simple search: {}", e);
+                }
+            }
+        }
 
-        let query_lower = query.to_lowercase();
+        let mut cards: Vec<CapabilityCard> = self
+            .search_simple(query.query_text())
+            .await?
+            .into_iter()
+            .filter(|card| query.matches(card))
+            .collect();
+        query.sort(&mut cards, |card| card);
+        Ok(cards)
+    }
 
-        let mut matches: Vec<CapabilityCard> = cache
-            .entries
-            .values()
-            .map(|entry| &entry.card)
-            .filter(|card| self.card_matches(card, &query_lower))
-            .cloned()
+    /// Locally known results up to the end of the requested page.
+    async fn local_head(&self, query: &DiscoveryQuery) -> Result<Vec<CapabilityCard>> {
+        let end = query.page_end()?;
+        let mut cards = self.ranked_local(query).await?;
+        cards.truncate(end);
+        Ok(cards)
+    }
+
+    /// Run a query locally and on up to `fanout` peers, then merge.
+    ///
+    /// Every node returns its results up to the end of the requested page;
+    /// the lists are fused by rank, deduplicated by DID, re-checked against
+    /// the filters and paged here. Peer cards are not cached.
+    async fn query_network(&self, query: &DiscoveryQuery) -> Result<DiscoveryPage<CapabilityCard>> {
+        let scoped = DiscoveryQuery {
+            offset: Some(query.page_start()?),
+            cursor: None,
+            scope: SearchScope::Local,
+            ..query.clone()
+        };
+
+        let peers = self.connected_peers().await?;
+        let mut lists = vec![self.local_head(&scoped).await?];
+        lists.extend(self.query_peers(peers, &scoped).await?);
+
+        let mut cards: Vec<CapabilityCard> = merge_ranked(lists)
+            .into_iter()
+            .filter(|card| query.matches(card))
             .collect();
+        query.sort(&mut cards, |card| card);
+        query.paginate(cards)
+    }
 
-        // Note: DHT doesn't support text search - it's a key-value store.
-        // Search works on local cache, which is populated from:
-        // 1. Direct registrations
-        // 2. GossipSub announcements from peers
-        // 3. Explicit DHT queries for known DIDs
+    /// Currently connected peers (none without network).
+    async fn connected_peers(&self) -> Result<Vec<PeerId>> {
+        let Some(ref tx) = self.network_tx else {
+            return Ok(Vec::new());
+        };
 
-        // Rank results by trust score (highest first)
-        matches.sort_by(|a, b| {
-            let score_a = a
-                .agoramesh
-                .as_ref()
-                .and_then(|e| e.trust_score)
-                .unwrap_or(0.0);
-            let score_b = b
-                .agoramesh
-                .as_ref()
-                .and_then(|e| e.trust_score)
-                .unwrap_or(0.0);
-            // Sort descending (higher scores first)
+        let (peers_tx, peers_rx) = oneshot::channel();
+        tx.send(SwarmCommand::GetPeers(peers_tx))
+            .await
+            .map_err(|e| Error::Discovery(format!("Failed to send get peers command: {}", e)))?;
+        peers_rx
+            .await
+            .map_err(|e| Error::Discovery(format!("Failed to receive peers: {}", e)))
+    }
+
+    /// Send a query to up to `fanout` of `peers` within their budget.
+    ///
+    /// Returns the ranked cards of every peer that answered before the
+    /// timeout, keeping only cards that pass [`Self::verify_card`]; no
+    /// network means no answers.
+    async fn query_peers(
+        &self,
+        peers: Vec<PeerId>,
+        query: &DiscoveryQuery,
+    ) -> Result<Vec<Vec<CapabilityCard>>> {
+        let Some(ref tx) = self.network_tx else {
+            return Ok(Vec::new());
+        };
+
+        let selected: Vec<PeerId> = {
+            let mut budget = self.outbound_budget.lock().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire peer budget lock: {}", e))
+            })?;
+            let now = Instant::now();
+            peers
+                .into_iter()
+                .filter(|peer| budget.try_acquire(peer, now))
+                .take(self.network_search.fanout)
+                .collect()
+        };
+
+        let mut pending = Vec::with_capacity(selected.len());
+        for peer in selected {
+            let (response_tx, response_rx) = oneshot::channel();
+            tx.send(SwarmCommand::SearchPeer {
+                peer,
+                request: SearchRequest {
+                    query: query.clone(),
+                },
+                response_tx,
+            })
+            .await
+            .map_err(|e| Error::Discovery(format!("Failed to send search request: {}", e)))?;
+            pending.push((peer, response_rx));
+        }
+
+        let limit = query.page_end()?;
+        let deadline = tokio::time::Instant::now() + self.network_search.timeout();
+        let answers =
+            futures::future::join_all(pending.into_iter().map(|(peer, response_rx)| async move {
+                match tokio::time::timeout_at(deadline, response_rx).await {
+                    Ok(Ok(Some(SearchResponse::Results { mut cards }))) => {
+                        cards.truncate(limit);
+                        Some(cards)
+                    }
+                    Ok(Ok(Some(SearchResponse::RateLimited))) => {
+                        tracing::debug!("Peer {} rate limited our search", peer);
+                        None
+                    }
+                    Ok(Ok(None)) | Ok(Err(_)) => {
+                        tracing::debug!("Search request to {} failed", peer);
+                        None
+                    }
+                    Err(_) => {
+                        tracing::debug!("Search request to {} timed out", peer);
+                        None
+                    }
+                }
+            }))
+            .await;
+
+        let mut lists = Vec::with_capacity(answers.len());
+        for cards in answers.into_iter().flatten() {
+            let mut verified = Vec::with_capacity(cards.len());
+            for card in cards {
+                match self.verify_card(&card).await {
+                    Ok(()) => verified.push(card),
+                    Err(e) => tracing::debug!("Dropping peer search result: {}", e),
+                }
+            }
+            lists.push(verified);
+        }
+        Ok(lists)
+    }
+
+    /// Find agents offering a skill through DHT provider records.
+    ///
+    /// Looks up the nodes announced as providers of the normalised skill ID
+    /// or tag, asks up to `fanout` of them (within their query budget) for
+    /// matching cards and merges the answers with matching local cards, so
+    /// no gossip history is needed. Resolved cards are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `skill` normalises to nothing.
+    pub async fn find_providers(&self, skill: &str) -> Result<Vec<CapabilityCard>> {
+        let key = skill_provider_key(skill)
+            .ok_or_else(|| Error::Validation(format!("Invalid skill: {:?}", skill)))?;
+        let query = DiscoveryQuery {
+            skill_id: Some(skill.to_string()),
+            limit: Some(MAX_QUERY_LIMIT),
+            ..Default::default()
+        };
+
+        let mut lists = vec![self.query_local(&query).await?.items];
+
+        if let Some(ref tx) = self.network_tx {
+            let (response_tx, response_rx) = oneshot::channel();
+            tx.send(SwarmCommand::GetProviders { key, response_tx })
+                .await
+                .map_err(|e| {
+                    Error::Discovery(format!("Failed to send DHT get providers command: {}", e))
+                })?;
+
+            let providers = match tokio::time::timeout(PROVIDER_LOOKUP_TIMEOUT, response_rx).await {
+                Ok(Ok(providers)) => providers,
+                Ok(Err(_)) => {
+                    tracing::debug!("DHT provider query channel closed for {}", skill);
+                    Vec::new()
+                }
+                Err(_) => {
+                    tracing::debug!("DHT provider query timeout for {}", skill);
+                    Vec::new()
+                }
+            };
+            lists.extend(self.query_peers(providers, &query).await?);
+        }
+
+        Ok(merge_ranked(lists)
+            .into_iter()
+            .filter(|card| query.matches(card))
+            .collect())
+    }
+
+    /// Answer a search request from a peer.
+    ///
+    /// The query runs against the local cache only, so requests never fan
+    /// out further, and the answer holds every result up to the end of the
+    /// requested page so the peer can fuse it by rank. Peers over their query budget get
+    /// [`SearchResponse::RateLimited`] and invalid queries get no results.
+    pub async fn serve_peer_query(&self, peer: &PeerId, request: SearchRequest) -> SearchResponse {
+        let allowed = self
+            .inbound_budget
+            .lock()
+            .map(|mut budget| budget.try_acquire(peer, Instant::now()))
+            .unwrap_or(false);
+        if !allowed {
+            tracing::debug!("Peer {} exceeded its search budget", peer);
+            return SearchResponse::RateLimited;
+        }
+
+        let query = DiscoveryQuery {
+            scope: SearchScope::Local,
+            ..request.query
+        };
+        let head = match query.validate() {
+            Ok(()) => self.local_head(&query).await,
+            Err(e) => Err(e),
+        };
+        match head {
+            Ok(cards) => SearchResponse::Results { cards },
+            Err(e) => {
+                tracing::debug!("Failed to answer search from {}: {}", peer, e);
+                SearchResponse::Results { cards: Vec::new() }
+            }
+        }
+    }
+
+    /// Find agents whose skill schemas accept a sample payload.
+    ///
+    /// Candidates are the locally known cards matching the query's text
+    /// and filters, in keyword relevance order; each result lists the
+    /// skills whose `inputSchema` (and `outputSchema`, when a sample
+    /// output is given) validate the payload. Compiled schemas are reused
+    /// across queries until the card changes them.
+    pub async fn match_schema(
+        &self,
+        query: &SchemaMatchQuery,
+    ) -> Result<DiscoveryPage<SkillMatch>> {
+        query.query.validate()?;
+        query.query.require_local_scope()?;
+
+        let mut matches: Vec<SkillMatch> = self
+            .search_simple(query.query.query_text())
+            .await?
+            .into_iter()
+            .filter(|card| query.query.matches(card))
+            .filter_map(|card| query.match_card(&self.schema_validators, card))
+            .collect();
+        query.query.sort(&mut matches, |m| &m.card);
+        query.query.paginate(matches)
+    }
+
+    /// BM25 keyword search (fallback when hybrid search is unavailable).
+    ///
+    /// Ranks cards by keyword relevance; equally relevant cards are ordered
+    /// by trust score (highest first). With a trust ranker attached,
+    /// relevance is instead blended with live trust data. An empty query
+    /// returns every cached card.
+    async fn search_simple(&self, query: &str) -> Result<Vec<CapabilityCard>> {
+        self.prune_expired_cache().await?;
+
+        let mut matches: Vec<(f32, CapabilityCard)> = {
+            let cache = self.cache.read().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire cache read lock: {}", e))
+            })?;
+            let keywords = self.keyword_index.read().map_err(|e| {
+                Error::Discovery(format!("Failed to acquire keyword index lock: {}", e))
+            })?;
+
+            // Note: DHT doesn't support text search - it's a key-value store.
+            // Search works on local cache, which is populated from:
+            // 1. Direct registrations
+            // 2. GossipSub announcements from peers
+            // 3. Explicit DHT queries for known DIDs
+            if query.trim().is_empty() {
+                cache
+                    .entries
+                    .values()
+                    .map(|entry| (0.0, entry.card.clone()))
+                    .collect()
+            } else {
+                keywords
+                    .search(query)
+                    .into_iter()
+                    .filter_map(|m| {
+                        cache
+                            .entries
+                            .get(&m.did)
+                            .map(|entry| (m.score, entry.card.clone()))
+                    })
+                    .collect()
+            }
+        };
+
+        matches.sort_by(|(score_a, a), (score_b, b)| {
             score_b
-                .partial_cmp(&score_a)
+                .partial_cmp(score_a)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    card_trust(b)
+                        .partial_cmp(&card_trust(a))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
         });
 
-        Ok(matches)
+        let Some((ranker, weights)) = &self.trust_ranker else {
+            return Ok(matches.into_iter().map(|(_, card)| card).collect());
+        };
+
+        // Normalize BM25 against the best match so it blends on a 0-1 scale
+        let best = matches.first().map(|(score, _)| *score).unwrap_or(0.0);
+        let scored: Vec<(f32, CapabilityCard)> = matches
+            .into_iter()
+            .map(|(score, card)| (if best > 0.0 { score / best } else { 0.0 }, card))
+            .collect();
+        Ok(ranker
+            .rerank(weights, scored, |card| card)
+            .await
+            .into_iter()
+            .map(|ranked| ranked.item)
+            .collect())
     }
 
     /// Request peers to broadcast their known agents.
@@ -617,7 +1808,7 @@ impl DiscoveryService {
             });
 
             tx.send(SwarmCommand::Publish {
-                topic: "/agoramesh/discovery/1.0.0".to_string(),
+                topic: topics::DISCOVERY.to_string(),
                 data: serde_json::to_vec(&request)
                     .map_err(|e| Error::Discovery(format!("Failed to serialize request: {}", e)))?,
             })
@@ -638,33 +1829,6 @@ impl DiscoveryService {
         self.cache.read().map(|c| c.entries.len()).unwrap_or(0)
     }
 
-    /// Check if a capability card matches the search query.
-    fn card_matches(&self, card: &CapabilityCard, query_lower: &str) -> bool {
-        // Match against name
-        if card.name.to_lowercase().contains(query_lower) {
-            return true;
-        }
-
-        // Match against description
-        if card.description.to_lowercase().contains(query_lower) {
-            return true;
-        }
-
-        // Match against skill names
-        for skill in &card.skills {
-            if skill.name.to_lowercase().contains(query_lower) {
-                return true;
-            }
-            if let Some(desc) = &skill.description {
-                if desc.to_lowercase().contains(query_lower) {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
-
     /// Get a specific agent's capability card by DID.
     ///
     /// # Arguments
@@ -673,20 +1837,37 @@ impl DiscoveryService {
     ///
     /// # Returns
     ///
-    /// The capability card if found, None otherwise.
+    /// The capability card if found, None otherwise or when the agent was
+    /// deactivated on-chain.
+    ///
+    /// # Lookup Order
+    ///
+    /// Local cache first, then the durable card store (if attached).
     ///
     /// # DHT Query Behavior
     ///
-    /// If the card is not in the local cache and network is available:
+    /// If the card is not found locally and network is available:
     /// - Sends a GetRecord command to the DHT
     /// - Waits up to 10 seconds for a response
     /// - Caches successful responses for future lookups
     pub async fn get(&self, did: &str) -> Result<Option<CapabilityCard>> {
+        if self.is_inactive(did)? {
+            return Ok(None);
+        }
+
         // Check local cache first
         if let Some(card) = self.cache_get(did)? {
             return Ok(Some(card));
         }
 
+        // Then the durable store (survives restarts and cache expiry)
+        if let Some(ref store) = self.card_store {
+            if let Some(card) = store.get(did)? {
+                self.cache_insert(did.to_string(), card.clone()).await?;
+                return Ok(Some(card));
+            }
+        }
+
         // Query DHT if network is available and not in cache
         if let Some(ref tx) = self.network_tx {
             let (response_tx, response_rx) = tokio::sync::oneshot::channel();
@@ -704,11 +1885,19 @@ impl DiscoveryService {
                 Ok(Ok(Some(data))) => {
                     // Parse the capability card from DHT data
                     match serde_json::from_slice::<CapabilityCard>(&data) {
-                        Ok(card) => {
-                            // Cache the result for future lookups
-                            self.cache_insert(did.to_string(), card.clone()).await?;
-                            return Ok(Some(card));
+                        Ok(card) if Self::card_did(&card).ok() != Some(did) => {
+                            tracing::warn!("DHT record for {} holds another agent's card", did);
                         }
+                        Ok(card) => match self.verify_card(&card).await {
+                            Ok(()) => {
+                                // Cache the result for future lookups
+                                self.cache_insert(did.to_string(), card.clone()).await?;
+                                return Ok(Some(card));
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected DHT record for {}: {}", did, e);
+                            }
+                        },
                         Err(e) => {
                             tracing::warn!("Failed to parse DHT record for {}: {}", did, e);
                         }
@@ -760,6 +1949,7 @@ mod tests {
                 id: "translate".to_string(),
                 name: "Translation".to_string(),
                 description: Some("Translates text".to_string()),
+                tags: vec![],
                 input_schema: None,
                 output_schema: None,
             }],
@@ -774,6 +1964,9 @@ mod tests {
                     model: PricingModel::PerRequest,
                 }),
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         }
     }
@@ -783,7 +1976,7 @@ mod tests {
     #[tokio::test]
     async fn test_register_valid_capability_card() {
         // Arrange
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let card = sample_capability_card("did:agoramesh:base:test-agent-123");
 
         // Act
@@ -836,7 +2029,7 @@ mod tests {
     #[tokio::test]
     async fn test_register_stores_card_for_later_retrieval() {
         // Arrange
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let did = "did:agoramesh:base:retrievable-agent";
         let card = sample_capability_card(did);
 
@@ -878,7 +2071,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_returns_correct_card_among_multiple() {
         // Arrange
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let did1 = "did:agoramesh:base:agent-1";
         let did2 = "did:agoramesh:base:agent-2";
         let did3 = "did:agoramesh:base:agent-3";
@@ -908,7 +2101,7 @@ mod tests {
     #[tokio::test]
     async fn test_search_returns_empty_for_no_matches() {
         // Arrange
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let card = sample_capability_card("did:agoramesh:base:translator");
         service.register(&card).await.unwrap();
 
@@ -924,81 +2117,1055 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_search_finds_agents_by_name() {
-        // Arrange
-        let service = DiscoveryService::new();
-        let mut card1 = sample_capability_card("did:agoramesh:base:translator-1");
-        card1.name = "French Translator".to_string();
-        let mut card2 = sample_capability_card("did:agoramesh:base:coder-1");
-        card2.name = "Code Assistant".to_string();
-        let mut card3 = sample_capability_card("did:agoramesh:base:translator-2");
-        card3.name = "Spanish Translator".to_string();
+    async fn test_search_finds_agents_by_name() {
+        // Arrange
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let mut card1 = sample_capability_card("did:agoramesh:base:translator-1");
+        card1.name = "French Translator".to_string();
+        let mut card2 = sample_capability_card("did:agoramesh:base:coder-1");
+        card2.name = "Code Assistant".to_string();
+        let mut card3 = sample_capability_card("did:agoramesh:base:translator-2");
+        card3.name = "Spanish Translator".to_string();
+
+        service.register(&card1).await.unwrap();
+        service.register(&card2).await.unwrap();
+        service.register(&card3).await.unwrap();
+
+        // Act
+        let result = service.search("Translator").await;
+
+        // Assert
+        assert!(result.is_ok());
+        let matches = result.unwrap();
+        assert_eq!(matches.len(), 2, "Should find 2 translators");
+        assert!(matches.iter().all(|c| c.name.contains("Translator")));
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_agents_by_capability() {
+        // Arrange
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let card = sample_capability_card("did:agoramesh:base:agent");
+        service.register(&card).await.unwrap();
+
+        // Act - search for capability name "Translation"
+        let result = service.search("Translation").await;
+
+        // Assert
+        assert!(result.is_ok());
+        let matches = result.unwrap();
+        assert_eq!(
+            matches.len(),
+            1,
+            "Should find 1 agent with Translation capability"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_is_case_insensitive() {
+        // Arrange
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let mut card = sample_capability_card("did:agoramesh:base:agent");
+        card.name = "MyAgent".to_string();
+        service.register(&card).await.unwrap();
+
+        // Act
+        let result = service.search("myagent").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().len(),
+            1,
+            "Search should be case-insensitive"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_empty_on_empty_cache() {
+        // Arrange
+        let service = DiscoveryService::new();
+
+        // Act
+        let result = service.search("anything").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
+    // ========== TDD Tests: trust-aware ranking ==========
+
+    fn service_with_trust_ranker() -> (DiscoveryService, Arc<crate::trust::TrustService>) {
+        let trust = Arc::new(crate::trust::TrustService::new(
+            "https://sepolia.base.org".to_string(),
+            None,
+        ));
+        let ranker = TrustRanker::new(
+            trust.clone(),
+            Arc::new(crate::trust_cache::TrustCache::with_defaults()),
+        );
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_trust_ranker(ranker, RankingWeights::default());
+        (service, trust)
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_by_live_trust_not_self_reported_trust() {
+        // Arrange: the unknown agent claims a higher trust score
+        let (service, trust) = service_with_trust_ranker();
+        let boastful = priced_card("did:agoramesh:base:boastful", Some(100_000), 0.99);
+        let proven = priced_card("did:agoramesh:base:proven", Some(100_000), 0.1);
+        service.register(&boastful).await.unwrap();
+        service.register(&proven).await.unwrap();
+        trust.seed_trust_data("did:agoramesh:base:proven", 10_000_000_000, 100, 0, 0);
+
+        // Act
+        let results = service.search("translation").await.unwrap();
+
+        // Assert
+        assert_eq!(
+            card_dids(&results),
+            vec!["did:agoramesh:base:proven", "did:agoramesh:base:boastful"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trust_ranking_keeps_strong_relevance_ahead() {
+        // Arrange: a trusted agent that barely matches the query
+        let (service, trust) = service_with_trust_ranker();
+        let mut relevant = sample_capability_card("did:agoramesh:base:relevant");
+        relevant.name = "Legal Contract Review".to_string();
+        relevant.description = "Reviews legal contracts and contract clauses".to_string();
+        let mut trusted = sample_capability_card("did:agoramesh:base:trusted");
+        trusted.name = "General Assistant".to_string();
+        trusted.description = "Helps with email, travel and contract questions".to_string();
+        service.register(&relevant).await.unwrap();
+        service.register(&trusted).await.unwrap();
+        trust.seed_trust_data("did:agoramesh:base:trusted", 10_000_000_000, 100, 0, 0);
+
+        // Act
+        let results = service.search("legal contract review").await.unwrap();
+
+        // Assert
+        assert_eq!(card_dids(&results)[0], "did:agoramesh:base:relevant");
+    }
+
+    // ========== TDD Tests: DiscoveryQuery ==========
+
+    fn priced_card(did: &str, price: Option<u64>, trust: f64) -> CapabilityCard {
+        let mut card = sample_capability_card(did);
+        if let Some(ref mut ext) = card.agoramesh {
+            ext.trust_score = Some(trust);
+            match price {
+                Some(price) => {
+                    if let Some(ref mut pricing) = ext.pricing {
+                        pricing.base_price = price;
+                    }
+                }
+                None => ext.pricing = None,
+            }
+        }
+        card
+    }
+
+    fn card_dids(cards: &[CapabilityCard]) -> Vec<&str> {
+        cards
+            .iter()
+            .filter_map(|c| c.agoramesh.as_ref().map(|e| e.did.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_discovery_query_matches_all_filters() {
+        let card = sample_capability_card("did:agoramesh:base:agent");
+        let query = DiscoveryQuery {
+            skill_id: Some("TRANSLATE".to_string()),
+            min_trust: Some(0.8),
+            max_price: Some(100_000),
+            pricing_model: Some(PricingModel::PerRequest),
+            payment_method: Some("x402".to_string()),
+            provider: Some("test org".to_string()),
+            ..Default::default()
+        };
+
+        assert!(query.matches(&card));
+        for failing in [
+            DiscoveryQuery {
+                skill_id: Some("summarize".to_string()),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                max_price: Some(99_999),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                provider: Some("Other Org".to_string()),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                min_trust: Some(0.9),
+                ..Default::default()
+            },
+        ] {
+            assert!(!failing.matches(&card), "{:?}", failing);
+        }
+    }
+
+    #[test]
+    fn test_discovery_query_max_price_excludes_unpriced_cards() {
+        let query = DiscoveryQuery {
+            max_price: Some(u64::MAX),
+            ..Default::default()
+        };
+
+        assert!(!query.matches(&priced_card("did:agoramesh:base:free", None, 0.5)));
+    }
+
+    #[test]
+    fn test_discovery_query_sorts_by_price_and_trust() {
+        let mut cards = vec![
+            priced_card("did:agoramesh:base:unpriced", None, 0.9),
+            priced_card("did:agoramesh:base:pricey", Some(500), 0.2),
+            priced_card("did:agoramesh:base:cheap", Some(5), 0.5),
+        ];
+
+        let by_price = DiscoveryQuery {
+            sort: QuerySort::Price,
+            ..Default::default()
+        };
+        by_price.sort(&mut cards, |c| c);
+        assert_eq!(
+            card_dids(&cards),
+            vec![
+                "did:agoramesh:base:cheap",
+                "did:agoramesh:base:pricey",
+                "did:agoramesh:base:unpriced"
+            ]
+        );
+
+        let by_trust = DiscoveryQuery {
+            sort: QuerySort::Trust,
+            ..Default::default()
+        };
+        by_trust.sort(&mut cards, |c| c);
+        assert_eq!(
+            card_dids(&cards),
+            vec![
+                "did:agoramesh:base:unpriced",
+                "did:agoramesh:base:cheap",
+                "did:agoramesh:base:pricey"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discovery_query_paginates_with_cursor() {
+        let query = DiscoveryQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let first = query.paginate((0..5).collect::<Vec<_>>()).unwrap();
+        assert_eq!(first.items, vec![0, 1]);
+        assert_eq!(first.total, 5);
+
+        let next = DiscoveryQuery {
+            cursor: first.next_cursor,
+            ..query.clone()
+        };
+        let second = next.paginate((0..5).collect::<Vec<_>>()).unwrap();
+        assert_eq!(second.items, vec![2, 3]);
+
+        let last = DiscoveryQuery {
+            offset: Some(4),
+            ..query
+        }
+        .paginate((0..5).collect::<Vec<_>>())
+        .unwrap();
+        assert_eq!(last.items, vec![4]);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_discovery_query_validation() {
+        assert!(DiscoveryQuery::default().validate().is_ok());
+        for invalid in [
+            DiscoveryQuery {
+                limit: Some(0),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                limit: Some(MAX_QUERY_LIMIT + 1),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                min_trust: Some(1.5),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                cursor: Some("abc".to_string()),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                offset: Some(MAX_QUERY_OFFSET + 1),
+                ..Default::default()
+            },
+            DiscoveryQuery {
+                cursor: Some(usize::MAX.to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(
+                matches!(invalid.validate(), Err(Error::Validation(_))),
+                "{:?}",
+                invalid
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_sorts_and_pages_keyword_results() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        for card in [
+            priced_card("did:agoramesh:base:a", Some(300), 0.5),
+            priced_card("did:agoramesh:base:b", Some(100), 0.5),
+            priced_card("did:agoramesh:base:c", Some(200), 0.5),
+            priced_card("did:agoramesh:base:d", Some(900), 0.5),
+        ] {
+            service.register(&card).await.unwrap();
+        }
+        let query = DiscoveryQuery {
+            text: Some("translation".to_string()),
+            max_price: Some(500),
+            sort: QuerySort::Price,
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let page = service.query(&query).await.unwrap();
+
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            card_dids(&page.items),
+            vec!["did:agoramesh:base:b", "did:agoramesh:base:c"]
+        );
+        assert_eq!(page.next_cursor.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_query_without_text_lists_filtered_cards() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let mut other = sample_capability_card("did:agoramesh:base:other");
+        other.skills[0].id = "summarize".to_string();
+        service
+            .register(&sample_capability_card("did:agoramesh:base:translator"))
+            .await
+            .unwrap();
+        service.register(&other).await.unwrap();
+
+        let page = service
+            .query(&DiscoveryQuery {
+                skill_id: Some("summarize".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(card_dids(&page.items), vec!["did:agoramesh:base:other"]);
+    }
+
+    // ========== TDD Tests: match_schema() ==========
+
+    fn schema_card(did: &str, price: u64, input_schema: serde_json::Value) -> CapabilityCard {
+        let mut card = priced_card(did, Some(price), 0.5);
+        card.skills[0].input_schema = Some(input_schema);
+        card
+    }
+
+    #[tokio::test]
+    async fn test_match_schema_returns_agents_accepting_payload() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let text_schema = serde_json::json!({
+            "type": "object",
+            "properties": { "text": { "type": "string" } },
+            "required": ["text"]
+        });
+        for card in [
+            schema_card("did:agoramesh:base:text-a", 200, text_schema.clone()),
+            schema_card(
+                "did:agoramesh:base:image",
+                100,
+                serde_json::json!({ "type": "object", "required": ["image_url"] }),
+            ),
+            schema_card("did:agoramesh:base:text-b", 100, text_schema),
+        ] {
+            service.register(&card).await.unwrap();
+        }
+        // No schema at all: never matched
+        service
+            .register(&sample_capability_card("did:agoramesh:base:untyped"))
+            .await
+            .unwrap();
+
+        let query = SchemaMatchQuery::new(serde_json::json!({ "text": "Hello" })).with_query(
+            DiscoveryQuery {
+                sort: QuerySort::Price,
+                ..Default::default()
+            },
+        );
+        let page = service.match_schema(&query).await.unwrap();
+
+        assert_eq!(page.total, 2);
+        let dids: Vec<&str> = page.items.iter().map(|m| m.did.as_str()).collect();
+        assert_eq!(
+            dids,
+            vec!["did:agoramesh:base:text-b", "did:agoramesh:base:text-a"]
+        );
+        assert_eq!(page.items[0].skills, vec!["translate"]);
+    }
+
+    #[tokio::test]
+    async fn test_match_schema_applies_query_filters() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let schema = serde_json::json!({ "type": "string" });
+        service
+            .register(&schema_card(
+                "did:agoramesh:base:cheap",
+                100,
+                schema.clone(),
+            ))
+            .await
+            .unwrap();
+        service
+            .register(&schema_card("did:agoramesh:base:pricey", 900, schema))
+            .await
+            .unwrap();
+
+        let query = SchemaMatchQuery::new(serde_json::json!("Hello")).with_query(DiscoveryQuery {
+            max_price: Some(500),
+            ..Default::default()
+        });
+        let page = service.match_schema(&query).await.unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].did, "did:agoramesh:base:cheap");
+    }
+
+    #[tokio::test]
+    async fn test_match_schema_rejects_invalid_query() {
+        let service = DiscoveryService::new();
+        let query = SchemaMatchQuery::new(serde_json::json!({})).with_query(DiscoveryQuery {
+            limit: Some(0),
+            ..Default::default()
+        });
+
+        let result = service.match_schema(&query).await;
+
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    // ========== TDD Tests: Network-scoped queries ==========
+
+    fn peer_id() -> PeerId {
+        PeerId::from(libp2p::identity::Keypair::generate_ed25519().public())
+    }
+
+    fn network_search(fanout: usize, peer_queries_per_minute: u32) -> NetworkSearchConfig {
+        NetworkSearchConfig {
+            fanout,
+            timeout_ms: 100,
+            peer_queries_per_minute,
+        }
+    }
+
+    /// Stand in for the swarm: `peers` are connected and provide every DHT
+    /// key, and each answers searches with its cards or, for `None`, never
+    /// answers.
+    ///
+    /// Returns the peers that were sent a search request.
+    fn mock_search_swarm(
+        mut rx: mpsc::Receiver<SwarmCommand>,
+        peers: Vec<(PeerId, Option<Vec<CapabilityCard>>)>,
+    ) -> Arc<Mutex<Vec<PeerId>>> {
+        let queried = Arc::new(Mutex::new(Vec::new()));
+        let log = queried.clone();
+        tokio::spawn(async move {
+            let mut unanswered = Vec::new();
+            while let Some(command) = rx.recv().await {
+                match command {
+                    SwarmCommand::GetPeers(tx) => {
+                        let _ = tx.send(peers.iter().map(|(peer, _)| *peer).collect());
+                    }
+                    SwarmCommand::GetProviders { response_tx, .. } => {
+                        let _ = response_tx.send(peers.iter().map(|(peer, _)| *peer).collect());
+                    }
+                    SwarmCommand::SearchPeer {
+                        peer, response_tx, ..
+                    } => {
+                        log.lock().unwrap().push(peer);
+                        let answer = peers
+                            .iter()
+                            .find(|(p, _)| *p == peer)
+                            .and_then(|(_, cards)| cards.clone());
+                        match answer {
+                            Some(cards) => {
+                                let _ = response_tx.send(Some(SearchResponse::Results { cards }));
+                            }
+                            None => unanswered.push(response_tx),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        queried
+    }
+
+    fn network_query(text: &str) -> DiscoveryQuery {
+        DiscoveryQuery {
+            scope: SearchScope::Network,
+            ..DiscoveryQuery::text(text)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_network_query_merges_peer_results_with_local() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        let local = "did:agoramesh:base:local";
+        let remote = "did:agoramesh:base:remote";
+        let mut remote_copy = sample_capability_card(local);
+        remote_copy.name = "Remote Copy".to_string();
+        let (answering, silent) = (peer_id(), peer_id());
+        let queried = mock_search_swarm(
+            rx,
+            vec![
+                (
+                    answering,
+                    Some(vec![sample_capability_card(remote), remote_copy]),
+                ),
+                (silent, None),
+            ],
+        );
+        let service = DiscoveryService::with_network(tx)
+            .with_required_card_signatures(false)
+            .with_network_search(network_search(8, 30));
+        service
+            .register(&sample_capability_card(local))
+            .await
+            .unwrap();
+
+        // Act
+        let page = service.query(&network_query("translation")).await.unwrap();
+
+        // Assert
+        assert_eq!(page.total, 2, "Duplicates should be merged by DID");
+        let local_card = page
+            .items
+            .iter()
+            .find(|card| card.agoramesh.as_ref().unwrap().did == local)
+            .expect("Local result should be included");
+        assert_eq!(local_card.name, "Test Agent", "Local copy should win");
+        assert!(page
+            .items
+            .iter()
+            .any(|card| card.agoramesh.as_ref().unwrap().did == remote));
+        assert_eq!(queried.lock().unwrap().len(), 2);
+        assert_eq!(service.cache_size(), 1, "Peer cards should not be cached");
+    }
+
+    #[tokio::test]
+    async fn test_network_query_drops_peer_cards_failing_filters() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        let pricey = priced_card("did:agoramesh:base:pricey", Some(5_000_000), 0.9);
+        mock_search_swarm(rx, vec![(peer_id(), Some(vec![pricey]))]);
+        let service = DiscoveryService::with_network(tx).with_network_search(network_search(8, 30));
+        let query = DiscoveryQuery {
+            max_price: Some(1_000),
+            ..network_query("")
+        };
+
+        // Act
+        let page = service.query(&query).await.unwrap();
+
+        // Assert
+        assert!(page.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_network_query_respects_fanout_and_peer_budget() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        let (first, second) = (peer_id(), peer_id());
+        let queried = mock_search_swarm(rx, vec![(first, Some(vec![])), (second, Some(vec![]))]);
+        let service = DiscoveryService::with_network(tx).with_network_search(network_search(1, 1));
+
+        // Act
+        for _ in 0..3 {
+            service.query(&network_query("translate")).await.unwrap();
+        }
+
+        // Assert
+        assert_eq!(
+            *queried.lock().unwrap(),
+            vec![first, second],
+            "Each peer should be queried once, one peer per query"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_network_query_without_network_returns_local_results() {
+        // Arrange
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let did = "did:agoramesh:base:offline";
+        service
+            .register(&sample_capability_card(did))
+            .await
+            .unwrap();
+
+        // Act
+        let page = service.query(&network_query("translate")).await.unwrap();
+
+        // Assert
+        assert_eq!(page.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_serve_peer_query_answers_locally_within_budget() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        let queried = mock_search_swarm(rx, vec![(peer_id(), Some(vec![]))]);
+        let service = DiscoveryService::with_network(tx)
+            .with_required_card_signatures(false)
+            .with_network_search(network_search(8, 1));
+        let did = "did:agoramesh:base:served";
+        service
+            .register(&sample_capability_card(did))
+            .await
+            .unwrap();
+        let requester = peer_id();
+        let request = SearchRequest {
+            query: network_query("translate"),
+        };
+
+        // Act
+        let first = service.serve_peer_query(&requester, request.clone()).await;
+        let second = service.serve_peer_query(&requester, request).await;
+
+        // Assert
+        let cards = first.into_cards();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].agoramesh.as_ref().unwrap().did, did);
+        assert!(matches!(second, SearchResponse::RateLimited));
+        assert!(
+            queried.lock().unwrap().is_empty(),
+            "Peer queries must not fan out further"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_network_query_pages_past_max_limit() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(256);
+        mock_search_swarm(rx, vec![(peer_id(), Some(vec![]))]);
+        let service = DiscoveryService::with_network(tx)
+            .with_required_card_signatures(false)
+            .with_network_search(network_search(8, 30));
+        for i in 0..MAX_QUERY_LIMIT + 10 {
+            service
+                .register(&sample_capability_card(&format!(
+                    "did:agoramesh:base:agent{:03}",
+                    i
+                )))
+                .await
+                .unwrap();
+        }
+        let query = DiscoveryQuery {
+            offset: Some(MAX_QUERY_LIMIT),
+            limit: Some(20),
+            ..network_query("")
+        };
+
+        // Act
+        let page = service.query(&query).await.unwrap();
+
+        // Assert
+        assert_eq!(page.total, MAX_QUERY_LIMIT + 10);
+        assert_eq!(page.items.len(), 10);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_serve_peer_query_returns_results_up_to_page_end() {
+        // Arrange
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        for did in [
+            "did:agoramesh:base:a",
+            "did:agoramesh:base:b",
+            "did:agoramesh:base:c",
+        ] {
+            service
+                .register(&sample_capability_card(did))
+                .await
+                .unwrap();
+        }
+        let page = |offset| SearchRequest {
+            query: DiscoveryQuery {
+                offset: Some(offset),
+                limit: Some(1),
+                ..DiscoveryQuery::default()
+            },
+        };
+
+        // Act
+        let head = service.serve_peer_query(&peer_id(), page(1)).await;
+        let too_deep = service.serve_peer_query(&peer_id(), page(usize::MAX)).await;
+
+        // Assert
+        assert_eq!(head.into_cards().len(), 2, "Offset 1 + limit 1 ends at 2");
+        assert!(too_deep.into_cards().is_empty());
+    }
+
+    // ========== TDD Tests: Skill provider records ==========
+
+    fn tagged_card(did: &str, skill_id: &str, tags: &[&str]) -> CapabilityCard {
+        let mut card = sample_capability_card(did);
+        card.skills[0].id = skill_id.to_string();
+        card.skills[0].tags = tags.iter().map(|t| t.to_string()).collect();
+        card
+    }
+
+    fn provided_keys(rx: &mut mpsc::Receiver<SwarmCommand>) -> (Vec<String>, Vec<String>) {
+        let (mut started, mut stopped) = (Vec::new(), Vec::new());
+        while let Ok(command) = rx.try_recv() {
+            match command {
+                SwarmCommand::StartProviding { key } => {
+                    started.push(String::from_utf8(key).unwrap())
+                }
+                SwarmCommand::StopProviding { key } => {
+                    stopped.push(String::from_utf8(key).unwrap())
+                }
+                _ => {}
+            }
+        }
+        (started, stopped)
+    }
+
+    #[tokio::test]
+    async fn test_register_advertises_skill_and_tag_providers() {
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        let card = tagged_card("did:agoramesh:base:provider", "Translate", &["NLP"]);
+
+        // Act
+        service.register(&card).await.unwrap();
+
+        // Assert
+        let (started, stopped) = provided_keys(&mut rx);
+        assert_eq!(
+            started,
+            vec!["/agoramesh/skill/nlp", "/agoramesh/skill/translate"]
+        );
+        assert!(stopped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_remove_stops_providing_only_unshared_keys() {
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        let removed = "did:agoramesh:base:removed";
+        service
+            .register(&tagged_card(removed, "translate", &["nlp"]))
+            .await
+            .unwrap();
+        service
+            .register(&tagged_card(
+                "did:agoramesh:base:kept",
+                "summarize",
+                &["nlp"],
+            ))
+            .await
+            .unwrap();
+        provided_keys(&mut rx);
+
+        // Act
+        assert!(service.remove(removed).await.unwrap());
+
+        // Assert
+        let (_, stopped) = provided_keys(&mut rx);
+        assert_eq!(stopped, vec!["/agoramesh/skill/translate"]);
+    }
+
+    #[tokio::test]
+    async fn test_reregister_withdraws_dropped_skills() {
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(20);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        let did = "did:agoramesh:base:retooled";
+        service
+            .register(&tagged_card(did, "translate", &["nlp", "text"]))
+            .await
+            .unwrap();
+        service
+            .register(&tagged_card("did:agoramesh:base:other", "chat", &["text"]))
+            .await
+            .unwrap();
+        provided_keys(&mut rx);
+
+        // Act
+        service
+            .register(&tagged_card(did, "summarize", &["nlp"]))
+            .await
+            .unwrap();
+
+        // Assert: "text" is still offered by the other local card
+        let (started, stopped) = provided_keys(&mut rx);
+        assert_eq!(stopped, vec!["/agoramesh/skill/translate"]);
+        assert!(started.contains(&"/agoramesh/skill/summarize".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_find_providers_merges_local_and_provider_cards() {
+        // Arrange
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        let remote = tagged_card("did:agoramesh:base:remote", "deepl", &["Translate"]);
+        let unrelated = tagged_card("did:agoramesh:base:unrelated", "summarize", &[]);
+        mock_search_swarm(
+            rx,
+            vec![
+                (peer_id(), Some(vec![remote])),
+                (peer_id(), Some(vec![unrelated])),
+                (peer_id(), None),
+            ],
+        );
+        let service = DiscoveryService::with_network(tx)
+            .with_required_card_signatures(false)
+            .with_network_search(network_search(8, 30));
+        let local = "did:agoramesh:base:local";
+        service
+            .register(&sample_capability_card(local))
+            .await
+            .unwrap();
+
+        // Act
+        let cards = service.find_providers("translate").await.unwrap();
+
+        // Assert
+        let dids: Vec<&str> = cards
+            .iter()
+            .map(|card| card.agoramesh.as_ref().unwrap().did.as_str())
+            .collect();
+        assert_eq!(dids, vec![local, "did:agoramesh:base:remote"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_providers_rejects_empty_skill() {
+        let service = DiscoveryService::new();
+
+        let result = service.find_providers(" _ ").await;
+
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    // ========== TDD Tests: Signed, versioned cards ==========
+
+    /// A resolver knowing one Ed25519 identity, its key and a card for it.
+    fn signed_card_identity(
+        name: &str,
+    ) -> (
+        Arc<crate::did::InMemoryDidResolver>,
+        libp2p::identity::ed25519::Keypair,
+        CapabilityCard,
+    ) {
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let document = crate::did::DIDDocumentBuilder::new("base", name)
+            .add_ed25519_key(
+                "key-1",
+                &crate::did::encode_ed25519_multibase(&keypair.public()),
+            )
+            .build()
+            .unwrap();
+        let card = sample_capability_card(&document.id);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        resolver.insert(document).unwrap();
+        (resolver, keypair, card)
+    }
+
+    fn signed(
+        mut card: CapabilityCard,
+        version: u64,
+        keypair: &libp2p::identity::ed25519::Keypair,
+    ) -> CapabilityCard {
+        let ext = card.agoramesh.as_mut().unwrap();
+        ext.version = Some(version);
+        let key_id = format!("{}#key-1", ext.did);
+        card.sign_ed25519(&key_id, keypair).unwrap();
+        card
+    }
+
+    #[tokio::test]
+    async fn test_register_accepts_card_signed_by_its_did() {
+        let (resolver, keypair, card) = signed_card_identity("signed-agent");
+        let service = DiscoveryService::new()
+            .with_did_resolver(resolver)
+            .with_required_card_signatures(true);
+
+        let result = service.register(&signed(card, 1, &keypair)).await;
+
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_forged_card() {
+        let (resolver, _, card) = signed_card_identity("forged-agent");
+        let service = DiscoveryService::new().with_did_resolver(resolver);
+        let forger = libp2p::identity::ed25519::Keypair::generate();
+
+        let result = service.register(&signed(card, 1, &forger)).await;
+
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_required_signatures_reject_unsigned_card() {
+        let (resolver, _, card) = signed_card_identity("unsigned-agent");
+        let service = DiscoveryService::new()
+            .with_did_resolver(resolver)
+            .with_required_card_signatures(true);
 
-        service.register(&card1).await.unwrap();
-        service.register(&card2).await.unwrap();
-        service.register(&card3).await.unwrap();
+        let err = service.ingest(&card).await.unwrap_err();
+
+        assert!(err.to_string().contains("no verifiable signature"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rejects_stale_or_unsigned_replacement() {
+        // Arrange: version 2 of a signed card is known
+        let (resolver, keypair, card) = signed_card_identity("versioned-agent");
+        let service = DiscoveryService::new().with_did_resolver(resolver);
+        let did = card.agoramesh.as_ref().unwrap().did.clone();
+        service
+            .ingest(&signed(card.clone(), 2, &keypair))
+            .await
+            .unwrap();
 
         // Act
-        let result = service.search("Translator").await;
+        let stale = service.ingest(&signed(card.clone(), 1, &keypair)).await;
+        let mut unsigned = card.clone();
+        unsigned.agoramesh.as_mut().unwrap().version = Some(3);
+        unsigned.name = "Hijacked".to_string();
+        let downgrade = service.ingest(&unsigned).await;
+        let newer = service.ingest(&signed(card, 3, &keypair)).await;
 
         // Assert
-        assert!(result.is_ok());
-        let matches = result.unwrap();
-        assert_eq!(matches.len(), 2, "Should find 2 translators");
-        assert!(matches.iter().all(|c| c.name.contains("Translator")));
+        assert!(stale.unwrap_err().to_string().contains("Stale card"));
+        assert!(downgrade.is_err());
+        assert!(newer.is_ok());
+        let current = service.get(&did).await.unwrap().unwrap();
+        assert_eq!(current.revision().0, 3);
+        assert_eq!(current.name, "Test Agent");
     }
 
     #[tokio::test]
-    async fn test_search_finds_agents_by_capability() {
-        // Arrange
-        let service = DiscoveryService::new();
-        let card = sample_capability_card("did:agoramesh:base:agent");
-        service.register(&card).await.unwrap();
+    async fn test_network_query_and_providers_drop_unverified_peer_cards() {
+        // Arrange: one peer answers with a forged and an unsigned card,
+        // another with the genuine signed card
+        let (resolver, keypair, card) = signed_card_identity("peer-signed");
+        let did = card.agoramesh.as_ref().unwrap().did.clone();
+        let mut forged = tagged_card(&did, "translate", &[]);
+        forged.name = "Forged".to_string();
+        let forged = signed(forged, 1, &libp2p::identity::ed25519::Keypair::generate());
+        let unsigned = tagged_card("did:agoramesh:base:unsigned", "translate", &[]);
+        let genuine = signed(tagged_card(&did, "translate", &[]), 1, &keypair);
+        let (tx, rx) = mpsc::channel::<SwarmCommand>(32);
+        mock_search_swarm(
+            rx,
+            vec![
+                (peer_id(), Some(vec![forged, unsigned])),
+                (peer_id(), Some(vec![genuine])),
+            ],
+        );
+        let service = DiscoveryService::with_network(tx)
+            .with_network_search(network_search(8, 30))
+            .with_did_resolver(resolver)
+            .with_required_card_signatures(true);
 
-        // Act - search for capability name "Translation"
-        let result = service.search("Translation").await;
+        // Act
+        let page = service.query(&network_query("")).await.unwrap();
+        let providers = service.find_providers("translate").await.unwrap();
 
         // Assert
-        assert!(result.is_ok());
-        let matches = result.unwrap();
-        assert_eq!(
-            matches.len(),
-            1,
-            "Should find 1 agent with Translation capability"
-        );
+        for cards in [page.items, providers] {
+            assert_eq!(cards.len(), 1);
+            assert_eq!(cards[0].agoramesh.as_ref().unwrap().did, did);
+            assert_eq!(cards[0].name, "Test Agent");
+        }
     }
 
     #[tokio::test]
-    async fn test_search_is_case_insensitive() {
-        // Arrange
-        let service = DiscoveryService::new();
-        let mut card = sample_capability_card("did:agoramesh:base:agent");
-        card.name = "MyAgent".to_string();
-        service.register(&card).await.unwrap();
+    async fn test_unsigned_max_version_card_does_not_lock_out_signed_card() {
+        // Arrange: signatures optional, an unsigned forgery claims the top version
+        let (resolver, keypair, card) = signed_card_identity("squatted-agent");
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_did_resolver(resolver);
+        let did = card.agoramesh.as_ref().unwrap().did.clone();
+        let mut squatter = card.clone();
+        squatter.agoramesh.as_mut().unwrap().version = Some(u64::MAX);
+        squatter.name = "Squatter".to_string();
+        service.ingest(&squatter).await.unwrap();
 
         // Act
-        let result = service.search("myagent").await;
+        let genuine = service.ingest(&signed(card.clone(), 1, &keypair)).await;
+        let forged = service
+            .ingest(&signed(
+                card,
+                2,
+                &libp2p::identity::ed25519::Keypair::generate(),
+            ))
+            .await;
 
         // Assert
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().len(),
-            1,
-            "Search should be case-insensitive"
-        );
+        assert!(genuine.is_ok(), "{:?}", genuine);
+        assert!(forged.is_err());
+        let current = service.get(&did).await.unwrap().unwrap();
+        assert!(current.is_signed());
+        assert_eq!(current.revision().0, 1);
+        assert_eq!(current.name, "Test Agent");
     }
 
     #[tokio::test]
-    async fn test_search_returns_empty_on_empty_cache() {
+    async fn test_get_rejects_forged_dht_record() {
         // Arrange
-        let service = DiscoveryService::new();
+        let (resolver, _, card) = signed_card_identity("dht-forged");
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = Arc::new(DiscoveryService::with_network(tx).with_did_resolver(resolver));
+        let did = card.agoramesh.as_ref().unwrap().did.clone();
+        let forged = serde_json::to_vec(&signed(
+            card,
+            1,
+            &libp2p::identity::ed25519::Keypair::generate(),
+        ))
+        .unwrap();
 
         // Act
-        let result = service.search("anything").await;
+        let task = {
+            let service = service.clone();
+            let did = did.clone();
+            tokio::spawn(async move { service.get(&did).await })
+        };
+        if let Some(SwarmCommand::GetRecord { response_tx, .. }) = rx.recv().await {
+            let _ = response_tx.send(Some(forged));
+        }
 
         // Assert
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_empty());
+        assert!(task.await.unwrap().unwrap().is_none());
+        assert_eq!(service.cache_size(), 0);
     }
 
     // ========== TDD Tests: DHT Integration ==========
@@ -1010,7 +3177,7 @@ mod tests {
 
         // Arrange
         let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
-        let service = DiscoveryService::with_network(tx);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
         let did = "did:agoramesh:base:dht-test-agent";
         let card = sample_capability_card(did);
 
@@ -1038,7 +3205,7 @@ mod tests {
     #[tokio::test]
     async fn test_register_still_works_without_network() {
         // Arrange - no network configured
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let did = "did:agoramesh:base:offline-agent";
         let card = sample_capability_card(did);
 
@@ -1087,7 +3254,7 @@ mod tests {
 
         // Arrange
         let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
-        let service = DiscoveryService::with_network(tx);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
         let did = "did:agoramesh:base:dht-stored-agent";
         let card = sample_capability_card(did);
         let serialized_card = serde_json::to_vec(&card).unwrap();
@@ -1170,7 +3337,9 @@ mod tests {
 
         // Arrange
         let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
-        let service = std::sync::Arc::new(DiscoveryService::with_network(tx));
+        let service = std::sync::Arc::new(
+            DiscoveryService::with_network(tx).with_required_card_signatures(false),
+        );
         let did = "did:agoramesh:base:cache-from-dht-agent";
         let card = sample_capability_card(did);
         let serialized_card = serde_json::to_vec(&card).unwrap();
@@ -1212,15 +3381,14 @@ mod tests {
 
         // Arrange
         let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
-        let service = DiscoveryService::with_network(tx);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
         let did = "did:agoramesh:base:cached-agent";
         let card = sample_capability_card(did);
 
         // Pre-populate cache
         service.register(&card).await.unwrap();
-        // Drain the PutRecord and Publish commands from registration
-        let _ = rx.try_recv(); // PutRecord
-        let _ = rx.try_recv(); // Publish
+        // Drain the PutRecord, Publish and StartProviding commands from registration
+        while rx.try_recv().is_ok() {}
 
         // Act - get cached agent
         let result = service.get(did).await;
@@ -1243,7 +3411,7 @@ mod tests {
 
         // Arrange
         let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
-        let service = DiscoveryService::with_network(tx);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
         let did = "did:agoramesh:base:announced-agent";
         let card = sample_capability_card(did);
 
@@ -1278,7 +3446,7 @@ mod tests {
     #[tokio::test]
     async fn test_search_ranks_results_by_trust_score() {
         // Arrange
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
 
         // Create agents with different trust scores
         let mut low_trust = sample_capability_card("did:agoramesh:base:low-trust");
@@ -1331,7 +3499,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_cache_size_reflects_registered_agents() {
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
 
         service
             .register(&sample_capability_card("did:agoramesh:base:agent1"))
@@ -1351,7 +3519,8 @@ mod tests {
         let service = DiscoveryService::with_cache_config(DiscoveryCacheConfig {
             ttl: Duration::from_millis(25),
             max_entries: 100,
-        });
+        })
+        .with_required_card_signatures(false);
         let did = "did:agoramesh:base:ttl-expire";
         service
             .register(&sample_capability_card(did))
@@ -1371,7 +3540,8 @@ mod tests {
         let service = DiscoveryService::with_cache_config(DiscoveryCacheConfig {
             ttl: Duration::from_secs(60),
             max_entries: 2,
-        });
+        })
+        .with_required_card_signatures(false);
         let did1 = "did:agoramesh:base:lru-1";
         let did2 = "did:agoramesh:base:lru-2";
         let did3 = "did:agoramesh:base:lru-3";
@@ -1412,7 +3582,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_invalidate_removes_specific_cached_entry() {
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
         let did = "did:agoramesh:base:invalidate-target";
         service
             .register(&sample_capability_card(did))
@@ -1467,6 +3637,117 @@ mod tests {
         }
     }
 
+    // ========== TDD Tests: ingest() and reannounce_local_cards() ==========
+
+    #[tokio::test]
+    async fn test_ingest_caches_card_without_publishing() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        let card = sample_capability_card("did:agoramesh:base:remote-agent");
+
+        // Act
+        service.ingest(&card).await.unwrap();
+
+        // Assert
+        assert!(service
+            .get("did:agoramesh:base:remote-agent")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(
+            rx.try_recv().is_err(),
+            "Ingested cards must not be re-published"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rejects_card_without_did() {
+        let service = DiscoveryService::new();
+        let mut card = sample_capability_card("did:agoramesh:base:agent");
+        card.agoramesh = None;
+
+        assert!(service.ingest(&card).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reannounce_publishes_only_local_cards() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        service
+            .register(&sample_capability_card("did:agoramesh:base:local-agent"))
+            .await
+            .unwrap();
+        service
+            .ingest(&sample_capability_card("did:agoramesh:base:remote-agent"))
+            .await
+            .unwrap();
+        // Drain the PutRecord + Publish sent by register()
+        while rx.try_recv().is_ok() {}
+
+        // Act
+        let announced = service.reannounce_local_cards().await.unwrap();
+
+        // Assert
+        assert_eq!(announced, 1);
+        match rx.try_recv().expect("Should publish local card") {
+            SwarmCommand::Publish { topic, data } => {
+                assert_eq!(topic, topics::DISCOVERY);
+                let card: CapabilityCard = serde_json::from_slice(&data).unwrap();
+                assert_eq!(
+                    card.agoramesh.unwrap().did,
+                    "did:agoramesh:base:local-agent"
+                );
+            }
+            cmd => panic!("Expected Publish command, got {:?}", cmd),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reannounce_respects_cooldown() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx).with_required_card_signatures(false);
+        service
+            .register(&sample_capability_card("did:agoramesh:base:local-agent"))
+            .await
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+
+        // Act
+        let first = service.reannounce_local_cards().await.unwrap();
+        let second = service.reannounce_local_cards().await.unwrap();
+
+        // Assert
+        assert_eq!(first, 1);
+        assert_eq!(
+            second, 0,
+            "Second request within cooldown should be ignored"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reannounce_without_network_is_noop() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        service
+            .register(&sample_capability_card("did:agoramesh:base:local-agent"))
+            .await
+            .unwrap();
+
+        assert_eq!(service.reannounce_local_cards().await.unwrap(), 0);
+    }
+
     // ========== TDD Tests: HybridSearch Integration ==========
 
     /// Helper to create DiscoveryService with HybridSearch if embedding model is available.
@@ -1547,6 +3828,7 @@ mod tests {
             id: "code-review".to_string(),
             name: "Code Review".to_string(),
             description: Some("Analyzes source code quality".to_string()),
+            tags: vec![],
             input_schema: None,
             output_schema: None,
         }];
@@ -1575,7 +3857,7 @@ mod tests {
     #[tokio::test]
     async fn test_search_falls_back_to_simple_search_without_hybrid() {
         // Create service without hybrid search
-        let service = DiscoveryService::new();
+        let service = DiscoveryService::new().with_required_card_signatures(false);
 
         // Register a card
         let mut card = sample_capability_card("did:agoramesh:base:simple-agent");
@@ -1589,6 +3871,53 @@ mod tests {
         assert_eq!(matches.len(), 1, "Simple search should find matching agent");
     }
 
+    #[tokio::test]
+    async fn test_simple_search_matches_stemmed_terms() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+        let card = sample_capability_card("did:agoramesh:base:translator");
+        service.register(&card).await.unwrap();
+
+        let results = service.search("translating texts").await.unwrap();
+
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_simple_search_ranks_by_keyword_relevance_before_trust() {
+        let service = DiscoveryService::new().with_required_card_signatures(false);
+
+        let mut relevant = sample_capability_card("did:agoramesh:base:invoice-parser");
+        relevant.name = "Invoice Parser".to_string();
+        relevant.description = "Extracts invoice line items".to_string();
+        if let Some(ref mut ext) = relevant.agoramesh {
+            ext.trust_score = Some(0.3);
+        }
+
+        let mut incidental = sample_capability_card("did:agoramesh:base:doc-helper");
+        incidental.name = "Document Helper".to_string();
+        incidental.description = "General paperwork, occasionally an invoice".to_string();
+        if let Some(ref mut ext) = incidental.agoramesh {
+            ext.trust_score = Some(0.95);
+        }
+
+        service.register(&incidental).await.unwrap();
+        service.register(&relevant).await.unwrap();
+
+        let results = service.search("invoice").await.unwrap();
+
+        let dids: Vec<&str> = results
+            .iter()
+            .filter_map(|c| c.agoramesh.as_ref().map(|e| e.did.as_str()))
+            .collect();
+        assert_eq!(
+            dids,
+            vec![
+                "did:agoramesh:base:invoice-parser",
+                "did:agoramesh:base:doc-helper"
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_hybrid_search_ranks_by_relevance_not_trust_score() {
         let Some(service) = try_get_service_with_search() else {
@@ -1665,4 +3994,170 @@ mod tests {
             "Should send PutRecord"
         );
     }
+
+    // ========== TDD Tests: Persistence ==========
+
+    fn memory_card_store() -> CapabilityCardStore {
+        CapabilityCardStore::new(Arc::new(crate::persistence::MemoryStore::new()))
+    }
+
+    #[tokio::test]
+    async fn test_register_writes_through_to_card_store() {
+        // Arrange
+        let store = memory_card_store();
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(store.clone());
+        let did = "did:agoramesh:base:persisted-agent";
+
+        // Act
+        service
+            .register(&sample_capability_card(did))
+            .await
+            .unwrap();
+
+        // Assert
+        let persisted = store.get(did).unwrap().expect("Card should be persisted");
+        assert_eq!(persisted.name, "Test Agent");
+    }
+
+    #[tokio::test]
+    async fn test_rehydrate_loads_persisted_cards_into_cache() {
+        // Arrange: simulate a previous run
+        let store = memory_card_store();
+        let did = "did:agoramesh:base:restarted-agent";
+        store.put(did, &sample_capability_card(did)).unwrap();
+
+        // Act
+        let service = DiscoveryService::new().with_card_store(store);
+        let loaded = service.rehydrate().await.unwrap();
+
+        // Assert
+        assert_eq!(loaded, 1);
+        assert_eq!(service.cache_size(), 1);
+        let results = service.search("translation").await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rehydrate_restores_local_cards() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange: a card registered and a card ingested in a previous run
+        let store = memory_card_store();
+        let before = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(store.clone());
+        let local = "did:agoramesh:base:own-agent";
+        let remote = "did:agoramesh:base:peer-agent";
+        before
+            .register(&sample_capability_card(local))
+            .await
+            .unwrap();
+        before
+            .ingest(&sample_capability_card(remote))
+            .await
+            .unwrap();
+
+        // Act
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service = DiscoveryService::with_network(tx)
+            .with_required_card_signatures(false)
+            .with_card_store(store);
+        service.rehydrate().await.unwrap();
+        let announced = service.reannounce_local_cards().await.unwrap();
+
+        // Assert
+        assert_eq!(announced, 1);
+        match rx.try_recv().unwrap() {
+            SwarmCommand::Publish { data, .. } => {
+                let card: CapabilityCard = serde_json::from_slice(&data).unwrap();
+                assert_eq!(card.agoramesh.unwrap().did, local);
+            }
+            other => panic!("Expected Publish, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rehydrate_without_store_is_noop() {
+        let service = DiscoveryService::new();
+        assert_eq!(service.rehydrate().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_falls_back_to_card_store_after_invalidate() {
+        // Arrange
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(memory_card_store());
+        let did = "did:agoramesh:base:evicted-agent";
+        service
+            .register(&sample_capability_card(did))
+            .await
+            .unwrap();
+        service.invalidate(did).await.unwrap();
+
+        // Act
+        let card = service.get(did).await.unwrap();
+
+        // Assert
+        assert!(
+            card.is_some(),
+            "Store should serve cards missing from cache"
+        );
+        assert_eq!(service.cache_size(), 1, "Store hit should repopulate cache");
+    }
+
+    #[tokio::test]
+    async fn test_remove_drops_card_from_cache_store_and_local_cards() {
+        // Arrange
+        let store = memory_card_store();
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(store.clone());
+        let did = "did:agoramesh:base:deactivated-agent";
+        service
+            .register(&sample_capability_card(did))
+            .await
+            .unwrap();
+
+        // Act
+        let removed = service.remove(did).await.unwrap();
+
+        // Assert
+        assert!(removed);
+        assert_eq!(service.cache_size(), 0);
+        assert!(store.get(did).unwrap().is_none());
+        assert!(service.get(did).await.unwrap().is_none());
+        assert!(
+            !service.remove(did).await.unwrap(),
+            "Second remove is a no-op"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_known_dids_includes_cached_and_persisted_cards() {
+        // Arrange
+        let store = memory_card_store();
+        let persisted = "did:agoramesh:base:persisted-only";
+        store
+            .put(persisted, &sample_capability_card(persisted))
+            .unwrap();
+        let service = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(store);
+        let registered = "did:agoramesh:base:registered";
+        service
+            .register(&sample_capability_card(registered))
+            .await
+            .unwrap();
+
+        // Act
+        let mut dids = service.known_dids().unwrap();
+        dids.sort();
+
+        // Assert
+        assert_eq!(dids, vec![persisted.to_string(), registered.to_string()]);
+    }
 }
@@ -0,0 +1,284 @@
+//! Discovery queries fanned out across peers.
+//!
+//! With `scope=network`, a node sends its [`DiscoveryQuery`] to up to
+//! [`NetworkSearchConfig::fanout`] connected peers over the search
+//! request-response protocol and merges their ranked answers with its own
+//! results:
+//! - Lists are fused by reciprocal rank, so agents found by several nodes
+//!   rise and peers' incomparable scores never need to be mixed
+//! - Cards are deduplicated by DID, keeping the local copy when there is one
+//! - Peers that do not answer within the timeout are left out
+//!
+//! A [`PeerBudget`] caps how many queries are sent to, and answered for,
+//! each peer per minute.
+
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::CapabilityCard;
+
+/// Default number of peers a network query is sent to.
+pub const DEFAULT_SEARCH_FANOUT: usize = 8;
+
+/// Default time to wait for peers' answers, in milliseconds.
+pub const DEFAULT_SEARCH_TIMEOUT_MS: u64 = 2_000;
+
+/// Default number of queries per peer per minute, in each direction.
+pub const DEFAULT_PEER_QUERIES_PER_MINUTE: u32 = 30;
+
+/// Rank offset for reciprocal rank fusion; dampens the weight of top ranks.
+const RRF_K: f32 = 60.0;
+
+/// Network-wide search tuning (`[search.network]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSearchConfig {
+    /// Maximum number of peers a query is sent to.
+    pub fanout: usize,
+
+    /// Time to wait for peers' answers, in milliseconds.
+    pub timeout_ms: u64,
+
+    /// Queries sent to or answered for a single peer per minute.
+    pub peer_queries_per_minute: u32,
+}
+
+impl Default for NetworkSearchConfig {
+    fn default() -> Self {
+        Self {
+            fanout: DEFAULT_SEARCH_FANOUT,
+            timeout_ms: DEFAULT_SEARCH_TIMEOUT_MS,
+            peer_queries_per_minute: DEFAULT_PEER_QUERIES_PER_MINUTE,
+        }
+    }
+}
+
+impl NetworkSearchConfig {
+    /// Time to wait for peers' answers.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// A per-peer budget using this configuration's rate.
+    pub fn budget(&self) -> PeerBudget {
+        PeerBudget::new(self.peer_queries_per_minute, Duration::from_secs(60))
+    }
+}
+
+/// Sliding-window count of queries per peer.
+#[derive(Debug)]
+pub struct PeerBudget {
+    limit: u32,
+    window: Duration,
+    queries: HashMap<PeerId, VecDeque<Instant>>,
+}
+
+impl PeerBudget {
+    /// Allow `limit` queries per peer within each `window`.
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            queries: HashMap::new(),
+        }
+    }
+
+    /// Record a query for `peer` if it is within budget.
+    ///
+    /// Returns `false`, recording nothing, once the peer has used up its
+    /// budget for the current window.
+    pub fn try_acquire(&mut self, peer: &PeerId, now: Instant) -> bool {
+        let window = self.window;
+        self.queries.retain(|_, times| {
+            while times
+                .front()
+                .is_some_and(|t| now.saturating_duration_since(*t) >= window)
+            {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+
+        let times = self.queries.entry(*peer).or_default();
+        if times.len() >= self.limit as usize {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
+
+fn card_did(card: &CapabilityCard) -> Option<&str> {
+    card.agoramesh.as_ref().map(|ext| ext.did.as_str())
+}
+
+/// Merge ranked result lists into one, deduplicated by DID.
+///
+/// Each card scores `1 / (60 + rank)` per list it appears in, at its best
+/// rank in that list, so repeating a DID within one list gains nothing. The
+/// first copy of a card is kept, so pass the local list first. Cards without
+/// a DID are dropped.
+pub fn merge_ranked(lists: Vec<Vec<CapabilityCard>>) -> Vec<CapabilityCard> {
+    let mut merged: Vec<(f32, CapabilityCard)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for list in lists {
+        let mut seen: HashSet<String> = HashSet::new();
+        for (rank, card) in list.into_iter().enumerate() {
+            let Some(did) = card_did(&card).map(str::to_string) else {
+                continue;
+            };
+            if !seen.insert(did.clone()) {
+                continue;
+            }
+            let score = 1.0 / (RRF_K + rank as f32 + 1.0);
+            match positions.get(&did) {
+                Some(&i) => merged[i].0 += score,
+                None => {
+                    positions.insert(did, merged.len());
+                    merged.push((score, card));
+                }
+            }
+        }
+    }
+
+    // Stable: ties keep the order in which cards were first seen
+    merged.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    merged.into_iter().map(|(_, card)| card).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::AgoraMeshExtension;
+
+    fn card(did: &str, name: &str) -> CapabilityCard {
+        CapabilityCard {
+            name: name.to_string(),
+            description: "A test agent".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills: vec![],
+            authentication: None,
+            agoramesh: Some(AgoraMeshExtension {
+                did: did.to_string(),
+                trust_score: None,
+                stake: None,
+                pricing: None,
+                payment_methods: vec![],
+                version: None,
+                updated_at: None,
+                proof: None,
+            }),
+        }
+    }
+
+    fn peer() -> PeerId {
+        PeerId::from(libp2p::identity::Keypair::generate_ed25519().public())
+    }
+
+    fn dids(cards: &[CapabilityCard]) -> Vec<&str> {
+        cards.iter().filter_map(card_did).collect()
+    }
+
+    // ========== TDD Tests: merge_ranked() ==========
+
+    #[test]
+    fn test_merge_deduplicates_and_keeps_first_copy() {
+        let merged = merge_ranked(vec![
+            vec![card("did:a", "local"), card("did:b", "local")],
+            vec![card("did:a", "remote"), card("did:c", "remote")],
+        ]);
+
+        assert_eq!(merged.len(), 3);
+        let a = merged
+            .iter()
+            .find(|c| card_did(c) == Some("did:a"))
+            .unwrap();
+        assert_eq!(a.name, "local");
+    }
+
+    #[test]
+    fn test_merge_ranks_agents_found_by_several_nodes_first() {
+        let merged = merge_ranked(vec![
+            vec![card("did:local-only", "x"), card("did:shared", "x")],
+            vec![card("did:peer-only", "x"), card("did:shared", "x")],
+            vec![card("did:shared", "x")],
+        ]);
+
+        assert_eq!(
+            dids(&merged),
+            vec!["did:shared", "did:local-only", "did:peer-only"]
+        );
+    }
+
+    #[test]
+    fn test_merge_ignores_repeats_within_one_list() {
+        let merged = merge_ranked(vec![
+            vec![card("did:honest", "x"), card("did:other", "x")],
+            vec![
+                card("did:spammed", "x"),
+                card("did:spammed", "x"),
+                card("did:spammed", "x"),
+                card("did:honest", "x"),
+            ],
+        ]);
+
+        assert_eq!(
+            dids(&merged),
+            vec!["did:honest", "did:spammed", "did:other"]
+        );
+    }
+
+    #[test]
+    fn test_merge_drops_cards_without_did() {
+        let mut anonymous = card("did:x", "anonymous");
+        anonymous.agoramesh = None;
+
+        let merged = merge_ranked(vec![vec![anonymous, card("did:a", "a")]]);
+
+        assert_eq!(dids(&merged), vec!["did:a"]);
+    }
+
+    // ========== TDD Tests: PeerBudget ==========
+
+    #[test]
+    fn test_budget_limits_queries_per_peer() {
+        let mut budget = PeerBudget::new(2, Duration::from_secs(60));
+        let (busy, other) = (peer(), peer());
+        let now = Instant::now();
+
+        assert!(budget.try_acquire(&busy, now));
+        assert!(budget.try_acquire(&busy, now));
+        assert!(!budget.try_acquire(&busy, now));
+        assert!(budget.try_acquire(&other, now));
+    }
+
+    #[test]
+    fn test_budget_refills_after_window() {
+        let mut budget = PeerBudget::new(1, Duration::from_secs(60));
+        let id = peer();
+        let now = Instant::now();
+
+        assert!(budget.try_acquire(&id, now));
+        assert!(!budget.try_acquire(&id, now + Duration::from_secs(59)));
+        assert!(budget.try_acquire(&id, now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_config_parses_from_toml_with_defaults() {
+        let config: NetworkSearchConfig = toml::from_str("fanout = 3").unwrap();
+
+        assert_eq!(config.fanout, 3);
+        assert_eq!(
+            config.timeout(),
+            Duration::from_millis(DEFAULT_SEARCH_TIMEOUT_MS)
+        );
+        assert_eq!(
+            config.peer_queries_per_minute,
+            DEFAULT_PEER_QUERIES_PER_MINUTE
+        );
+    }
+}
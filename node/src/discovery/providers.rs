@@ -0,0 +1,175 @@
+//! Skill-keyed DHT provider records.
+//!
+//! Card records in the DHT are keyed by DID, which only answers "what does
+//! this agent offer". To answer "who offers skill X", a node registering a
+//! card also announces itself as a Kademlia provider for every skill ID and
+//! tag on the card, under [`SKILL_PROVIDER_KEY_PREFIX`] followed by the
+//! normalised capability name.
+//!
+//! Provider records point at nodes, not agents: finding the agents behind a
+//! provider takes a search request to that node.
+
+use std::collections::BTreeSet;
+
+use super::{CapabilityCard, Skill};
+
+/// DHT key prefix under which skill providers are announced.
+pub const SKILL_PROVIDER_KEY_PREFIX: &str = "/agoramesh/skill/";
+
+/// Normalise a skill ID or tag for use in a provider key.
+///
+/// Lowercases the name and collapses every run of characters other than
+/// ASCII letters and digits into a single `-`, so `"Code Review"`,
+/// `"code_review"` and `"code-review"` share a key. Returns `None` when
+/// nothing is left.
+pub fn normalize_capability(name: &str) -> Option<String> {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            normalized.push(c.to_ascii_lowercase());
+        } else if !normalized.is_empty() && !normalized.ends_with('-') {
+            normalized.push('-');
+        }
+    }
+    while normalized.ends_with('-') {
+        normalized.pop();
+    }
+
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn provider_key(normalized: &str) -> Vec<u8> {
+    format!("{}{}", SKILL_PROVIDER_KEY_PREFIX, normalized).into_bytes()
+}
+
+/// DHT provider key for a skill ID or tag, if it normalises to anything.
+pub fn skill_provider_key(capability: &str) -> Option<Vec<u8>> {
+    normalize_capability(capability).map(|name| provider_key(&name))
+}
+
+/// Normalised skill IDs and tags advertised by a card, sorted and unique.
+pub fn card_capabilities(card: &CapabilityCard) -> BTreeSet<String> {
+    card.skills
+        .iter()
+        .flat_map(|skill| std::iter::once(&skill.id).chain(&skill.tags))
+        .filter_map(|name| normalize_capability(name))
+        .collect()
+}
+
+/// DHT provider keys a card is announced under.
+pub fn provider_keys(card: &CapabilityCard) -> Vec<Vec<u8>> {
+    card_capabilities(card)
+        .iter()
+        .map(|name| provider_key(name))
+        .collect()
+}
+
+impl Skill {
+    /// Check whether this skill's ID or one of its tags names `capability`,
+    /// comparing normalised names.
+    pub fn offers(&self, capability: &str) -> bool {
+        let Some(wanted) = normalize_capability(capability) else {
+            return false;
+        };
+        std::iter::once(&self.id)
+            .chain(&self.tags)
+            .any(|name| normalize_capability(name).as_ref() == Some(&wanted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(id: &str, tags: &[&str]) -> Skill {
+        Skill {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            input_schema: None,
+            output_schema: None,
+        }
+    }
+
+    fn card(skills: Vec<Skill>) -> CapabilityCard {
+        CapabilityCard {
+            name: "Test Agent".to_string(),
+            description: "A test agent".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills,
+            authentication: None,
+            agoramesh: None,
+        }
+    }
+
+    // ========== TDD Tests: normalize_capability() ==========
+
+    #[test]
+    fn test_normalize_capability_unifies_spelling() {
+        for name in [
+            "Code Review",
+            "code_review",
+            "  code--review ",
+            "CODE/REVIEW",
+        ] {
+            assert_eq!(
+                normalize_capability(name).as_deref(),
+                Some("code-review"),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_capability_rejects_empty_names() {
+        assert_eq!(normalize_capability(""), None);
+        assert_eq!(normalize_capability(" -_/ "), None);
+    }
+
+    #[test]
+    fn test_skill_provider_key_uses_prefix() {
+        assert_eq!(
+            skill_provider_key("Translate").unwrap(),
+            b"/agoramesh/skill/translate".to_vec()
+        );
+        assert!(skill_provider_key("   ").is_none());
+    }
+
+    // ========== TDD Tests: provider_keys() ==========
+
+    #[test]
+    fn test_provider_keys_cover_skill_ids_and_tags_once() {
+        let card = card(vec![
+            skill("translate", &["Language", "nlp"]),
+            skill("summarize", &["NLP", ""]),
+        ]);
+
+        let keys: Vec<String> = provider_keys(&card)
+            .into_iter()
+            .map(|key| String::from_utf8(key).unwrap())
+            .collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "/agoramesh/skill/language",
+                "/agoramesh/skill/nlp",
+                "/agoramesh/skill/summarize",
+                "/agoramesh/skill/translate",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skill_offers_matches_id_or_tag() {
+        let translate = skill("text_translate", &["Language"]);
+
+        assert!(translate.offers("Text Translate"));
+        assert!(translate.offers("language"));
+        assert!(!translate.offers("summarize"));
+        assert!(!translate.offers(""));
+    }
+}
@@ -0,0 +1,475 @@
+//! Schema-aware skill matching.
+//!
+//! Skills may declare JSON Schemas for their input (`inputSchema`) and
+//! output (`outputSchema`). [`SchemaMatchQuery`] checks a sample payload
+//! against those schemas, so an orchestrator can pick agents that accept
+//! its data mechanically instead of relying on text similarity.
+//!
+//! Schemas are compiled with the `jsonschema` crate; the draft is taken
+//! from `$schema` and defaults to 2020-12. Remote `$ref`s are not fetched,
+//! so schemas that depend on them never match. Compiled validators are
+//! kept in [`SchemaValidators`] so repeated queries reuse them.
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use super::{CapabilityCard, DiscoveryQuery, Skill};
+
+/// Default number of compiled schemas kept by [`SchemaValidators`].
+pub const SCHEMA_VALIDATOR_CACHE_SIZE: usize = 1_024;
+
+/// Which of a skill's schemas a validator was compiled from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SchemaSide {
+    Input,
+    Output,
+}
+
+/// Agent DID, skill ID and schema side.
+type ValidatorKey = (String, String, SchemaSide);
+
+/// A compiled schema, or `None` when it failed to compile.
+type CachedValidator = (Value, Option<Arc<jsonschema::Validator>>);
+
+/// Compiled skill schemas, keyed by agent DID and skill ID.
+///
+/// Each entry remembers the schema it was compiled from and is rebuilt
+/// when a card update changes it. Schemas that fail to compile are cached
+/// too, so they are not recompiled on every query.
+pub struct SchemaValidators {
+    cache: Mutex<LruCache<ValidatorKey, CachedValidator>>,
+}
+
+impl SchemaValidators {
+    /// Create a cache holding up to `capacity` compiled schemas.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+        }
+    }
+
+    /// Number of cached schemas.
+    pub fn len(&self) -> usize {
+        self.cache.lock().map(|cache| cache.len()).unwrap_or(0)
+    }
+
+    /// Whether no schema is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check `instance` against one of a skill's schemas.
+    fn accepts(
+        &self,
+        did: &str,
+        skill: &Skill,
+        side: SchemaSide,
+        schema: &Value,
+        instance: &Value,
+    ) -> bool {
+        match self.validator(did, skill, side, schema) {
+            Some(validator) => validator.is_valid(instance),
+            None => false,
+        }
+    }
+
+    fn validator(
+        &self,
+        did: &str,
+        skill: &Skill,
+        side: SchemaSide,
+        schema: &Value,
+    ) -> Option<Arc<jsonschema::Validator>> {
+        let key = (did.to_string(), skill.id.clone(), side);
+        if let Ok(mut cache) = self.cache.lock() {
+            if let Some((compiled_from, validator)) = cache.get(&key) {
+                if compiled_from == schema {
+                    return validator.clone();
+                }
+            }
+        }
+
+        // Compile outside the lock; a concurrent miss only duplicates work
+        let validator = match jsonschema::validator_for(schema) {
+            Ok(validator) => Some(Arc::new(validator)),
+            Err(e) => {
+                debug!("Ignoring invalid schema of skill {}: {}", skill.id, e);
+                None
+            }
+        };
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.put(key, (schema.clone(), validator.clone()));
+        }
+        validator
+    }
+}
+
+impl Default for SchemaValidators {
+    fn default() -> Self {
+        Self::new(SCHEMA_VALIDATOR_CACHE_SIZE)
+    }
+}
+
+/// Find agents whose skill schemas accept a sample payload.
+///
+/// Accepted as a JSON body; the [`DiscoveryQuery`] fields sit alongside
+/// `input` and `output` and filter, sort and page the matching agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaMatchQuery {
+    /// Sample input that a skill's `inputSchema` must accept.
+    pub input: Value,
+
+    /// Optional sample output that a skill's `outputSchema` must accept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+
+    /// Filters, sorting and paging for the matching agents.
+    #[serde(flatten)]
+    pub query: DiscoveryQuery,
+}
+
+/// An agent together with its skills that accept a payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillMatch {
+    /// Agent DID.
+    pub did: String,
+
+    /// IDs of the matching skills, in card order.
+    pub skills: Vec<String>,
+
+    /// The capability card.
+    pub card: CapabilityCard,
+}
+
+impl SchemaMatchQuery {
+    /// Create a query for skills accepting `input`.
+    pub fn new(input: Value) -> Self {
+        Self {
+            input,
+            output: None,
+            query: DiscoveryQuery::default(),
+        }
+    }
+
+    /// Also require the skill's output schema to accept `output`.
+    pub fn with_output(mut self, output: Value) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Apply filters, sorting and paging from `query`.
+    pub fn with_query(mut self, query: DiscoveryQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Check whether a skill of agent `did` accepts the sample payloads.
+    ///
+    /// A skill without an input schema never matches, nor does one without
+    /// an output schema when a sample output is given. Schemas that fail to
+    /// compile are treated as not matching.
+    pub fn accepts(&self, validators: &SchemaValidators, did: &str, skill: &Skill) -> bool {
+        let Some(input_schema) = &skill.input_schema else {
+            return false;
+        };
+        if !validators.accepts(did, skill, SchemaSide::Input, input_schema, &self.input) {
+            return false;
+        }
+
+        match &self.output {
+            Some(output) => skill.output_schema.as_ref().is_some_and(|schema| {
+                validators.accepts(did, skill, SchemaSide::Output, schema, output)
+            }),
+            None => true,
+        }
+    }
+
+    /// Get the IDs of a card's skills that accept the sample payloads.
+    ///
+    /// Limited to the query's `skill_id` when one is set.
+    pub fn matching_skills(
+        &self,
+        validators: &SchemaValidators,
+        card: &CapabilityCard,
+    ) -> Vec<String> {
+        let did = card.agoramesh.as_ref().map_or("", |ext| ext.did.as_str());
+        card.skills
+            .iter()
+            .filter(|skill| {
+                self.query
+                    .skill_id
+                    .as_ref()
+                    .is_none_or(|id| skill.offers(id))
+            })
+            .filter(|skill| self.accepts(validators, did, skill))
+            .map(|skill| skill.id.clone())
+            .collect()
+    }
+
+    /// Match a card, returning `None` when it has no DID or no skill
+    /// accepts the payloads.
+    pub fn match_card(
+        &self,
+        validators: &SchemaValidators,
+        card: CapabilityCard,
+    ) -> Option<SkillMatch> {
+        let did = card.agoramesh.as_ref()?.did.clone();
+        let skills = self.matching_skills(validators, &card);
+        if skills.is_empty() {
+            return None;
+        }
+        Some(SkillMatch { did, skills, card })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::AgoraMeshExtension;
+    use serde_json::json;
+
+    fn translate_input_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": { "type": "string" },
+                "target_language": { "type": "string", "enum": ["de", "fr", "es"] }
+            },
+            "required": ["text", "target_language"]
+        })
+    }
+
+    fn skill(id: &str, input_schema: Option<Value>, output_schema: Option<Value>) -> Skill {
+        Skill {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            tags: vec![],
+            input_schema,
+            output_schema,
+        }
+    }
+
+    fn card(did: &str, skills: Vec<Skill>) -> CapabilityCard {
+        CapabilityCard {
+            name: "Test Agent".to_string(),
+            description: "A test agent".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills,
+            authentication: None,
+            agoramesh: Some(AgoraMeshExtension {
+                did: did.to_string(),
+                trust_score: None,
+                stake: None,
+                pricing: None,
+                payment_methods: vec![],
+                version: None,
+                updated_at: None,
+                proof: None,
+            }),
+        }
+    }
+
+    const DID: &str = "did:agoramesh:base:agent";
+
+    // ========== TDD Tests: accepts() ==========
+
+    #[test]
+    fn test_accepts_payload_valid_for_input_schema() {
+        let translate = skill("translate", Some(translate_input_schema()), None);
+        let query = SchemaMatchQuery::new(json!({ "text": "Hello", "target_language": "de" }));
+
+        assert!(query.accepts(&SchemaValidators::default(), DID, &translate));
+    }
+
+    #[test]
+    fn test_rejects_payload_invalid_for_input_schema() {
+        let translate = skill("translate", Some(translate_input_schema()), None);
+        let validators = SchemaValidators::default();
+
+        for input in [
+            json!({ "text": "Hello" }),
+            json!({ "text": "Hello", "target_language": "jp" }),
+            json!({ "text": 42, "target_language": "de" }),
+            json!("Hello"),
+        ] {
+            assert!(
+                !SchemaMatchQuery::new(input.clone()).accepts(&validators, DID, &translate),
+                "{} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_skill_without_input_schema_never_matches() {
+        let untyped = skill("anything", None, None);
+
+        assert!(!SchemaMatchQuery::new(json!({})).accepts(
+            &SchemaValidators::default(),
+            DID,
+            &untyped
+        ));
+    }
+
+    #[test]
+    fn test_invalid_schema_never_matches() {
+        let broken = skill("broken", Some(json!({ "type": "no-such-type" })), None);
+
+        assert!(!SchemaMatchQuery::new(json!({})).accepts(
+            &SchemaValidators::default(),
+            DID,
+            &broken
+        ));
+    }
+
+    #[test]
+    fn test_output_sample_must_match_output_schema() {
+        let output_schema = json!({
+            "type": "object",
+            "properties": { "translation": { "type": "string" } },
+            "required": ["translation"]
+        });
+        let translate = skill(
+            "translate",
+            Some(translate_input_schema()),
+            Some(output_schema),
+        );
+        let no_output_schema = skill("translate", Some(translate_input_schema()), None);
+        let query = SchemaMatchQuery::new(json!({ "text": "Hi", "target_language": "fr" }));
+        let validators = SchemaValidators::default();
+
+        assert!(query
+            .clone()
+            .with_output(json!({ "translation": "Salut" }))
+            .accepts(&validators, DID, &translate));
+        assert!(!query
+            .clone()
+            .with_output(json!({ "summary": "Salut" }))
+            .accepts(&validators, DID, &translate));
+        assert!(!query
+            .with_output(json!({ "translation": "Salut" }))
+            .accepts(&validators, "did:agoramesh:base:other", &no_output_schema));
+    }
+
+    // ========== TDD Tests: match_card() ==========
+
+    #[test]
+    fn test_match_card_lists_only_accepting_skills() {
+        let agent = card(
+            "did:agoramesh:base:agent",
+            vec![
+                skill("translate", Some(translate_input_schema()), None),
+                skill("count", Some(json!({ "type": "array" })), None),
+                skill("echo", Some(json!({})), None),
+            ],
+        );
+        let query = SchemaMatchQuery::new(json!({ "text": "Hi", "target_language": "es" }));
+
+        let matched = query
+            .match_card(&SchemaValidators::default(), agent)
+            .expect("Should match");
+
+        assert_eq!(matched.did, "did:agoramesh:base:agent");
+        assert_eq!(matched.skills, vec!["translate", "echo"]);
+    }
+
+    #[test]
+    fn test_match_card_respects_skill_id_filter() {
+        let agent = card(
+            "did:agoramesh:base:agent",
+            vec![
+                skill("translate", Some(translate_input_schema()), None),
+                skill("echo", Some(json!({})), None),
+            ],
+        );
+        let query = SchemaMatchQuery::new(json!({ "text": "Hi", "target_language": "es" }))
+            .with_query(DiscoveryQuery {
+                skill_id: Some("ECHO".to_string()),
+                ..Default::default()
+            });
+
+        assert_eq!(
+            query
+                .match_card(&SchemaValidators::default(), agent)
+                .unwrap()
+                .skills,
+            vec!["echo"]
+        );
+    }
+
+    #[test]
+    fn test_match_card_returns_none_without_matching_skill() {
+        let agent = card(
+            "did:agoramesh:base:agent",
+            vec![skill("count", Some(json!({ "type": "array" })), None)],
+        );
+
+        assert!(SchemaMatchQuery::new(json!({}))
+            .match_card(&SchemaValidators::default(), agent)
+            .is_none());
+    }
+
+    // ========== TDD Tests: SchemaValidators ==========
+
+    #[test]
+    fn test_validators_compile_each_skill_schema_once() {
+        let validators = SchemaValidators::default();
+        let translate = skill("translate", Some(translate_input_schema()), None);
+        let broken = skill("broken", Some(json!({ "type": "no-such-type" })), None);
+        let query = SchemaMatchQuery::new(json!({ "text": "Hi", "target_language": "de" }));
+
+        for _ in 0..3 {
+            assert!(query.accepts(&validators, DID, &translate));
+            assert!(!query.accepts(&validators, DID, &broken));
+        }
+
+        assert_eq!(validators.len(), 2);
+    }
+
+    #[test]
+    fn test_validators_recompile_changed_schema() {
+        let validators = SchemaValidators::default();
+        let query = SchemaMatchQuery::new(json!("Hello"));
+        let before = skill("echo", Some(json!({ "type": "array" })), None);
+        let after = skill("echo", Some(json!({ "type": "string" })), None);
+
+        assert!(!query.accepts(&validators, DID, &before));
+        assert!(query.accepts(&validators, DID, &after));
+        assert_eq!(validators.len(), 1);
+    }
+
+    // ========== TDD Tests: serialization ==========
+
+    #[test]
+    fn test_query_parses_with_inline_discovery_fields() {
+        let query: SchemaMatchQuery = serde_json::from_value(json!({
+            "input": { "text": "Hi" },
+            "output": { "translation": "Salut" },
+            "q": "translate",
+            "min_trust": 0.5,
+            "limit": 5
+        }))
+        .unwrap();
+
+        assert_eq!(query.input, json!({ "text": "Hi" }));
+        assert_eq!(query.output, Some(json!({ "translation": "Salut" })));
+        assert_eq!(query.query.query_text(), "translate");
+        assert_eq!(query.query.min_trust, Some(0.5));
+        assert_eq!(query.query.limit, Some(5));
+    }
+
+    #[test]
+    fn test_query_requires_input() {
+        let result = serde_json::from_value::<SchemaMatchQuery>(json!({ "q": "translate" }));
+
+        assert!(result.is_err());
+    }
+}
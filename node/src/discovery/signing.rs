@@ -0,0 +1,344 @@
+//! Signed, versioned capability cards.
+//!
+//! An agent signs its card with a verification method from its DID
+//! document. The detached [`CardProof`] lives in the card's `x-agoramesh`
+//! extension and covers every other field, including the card `version`
+//! and `updated_at`, so a signed card cannot be altered or re-attributed.
+//!
+//! Versions only move forward: [`check_card_update`] refuses to let an
+//! older card, or an unsigned card, replace the one already known.
+
+use alloy::primitives::hex;
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use libp2p::identity::ed25519;
+use serde::{Deserialize, Serialize};
+
+use super::CapabilityCard;
+use crate::did::DIDDocument;
+use crate::error::{Error, Result};
+
+/// Domain separator included in every signed card.
+const SIGNING_DOMAIN: &str = "agoramesh-capability-card";
+
+/// Detached signature over a capability card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardProof {
+    /// Full ID of the verification method used (`did:...#key-1`).
+    pub verification_method: String,
+
+    /// Hex-encoded signature over [`CapabilityCard::signing_bytes`].
+    pub signature: String,
+}
+
+impl CapabilityCard {
+    /// Bytes covered by the card signature: the card without its proof.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card has no AgoraMesh extension.
+    pub fn signing_bytes(&self) -> Result<Vec<u8>> {
+        let mut unsigned = self.clone();
+        unsigned
+            .agoramesh
+            .as_mut()
+            .ok_or_else(|| Error::Validation("Card has no AgoraMesh DID to sign".to_string()))?
+            .proof = None;
+        Ok(serde_json::to_vec(&(SIGNING_DOMAIN, &unsigned))?)
+    }
+
+    /// Sign the card with an Ed25519 key, replacing any existing proof.
+    pub fn sign_ed25519(
+        &mut self,
+        verification_method: &str,
+        keypair: &ed25519::Keypair,
+    ) -> Result<()> {
+        let signature = keypair.sign(&self.signing_bytes()?);
+        self.set_proof(verification_method, hex::encode(signature));
+        Ok(())
+    }
+
+    /// Sign the card with an Ethereum account (EIP-191 `personal_sign`),
+    /// replacing any existing proof.
+    pub fn sign_ethereum(
+        &mut self,
+        verification_method: &str,
+        wallet: &PrivateKeySigner,
+    ) -> Result<()> {
+        let signature = wallet
+            .sign_message_sync(&self.signing_bytes()?)
+            .map_err(|e| Error::Did(format!("Failed to sign card: {}", e)))?;
+        self.set_proof(verification_method, hex::encode(signature.as_bytes()));
+        Ok(())
+    }
+
+    fn set_proof(&mut self, verification_method: &str, signature: String) {
+        if let Some(ext) = self.agoramesh.as_mut() {
+            ext.proof = Some(CardProof {
+                verification_method: verification_method.to_string(),
+                signature,
+            });
+        }
+    }
+
+    /// Whether the card carries a proof (verified or not).
+    pub fn is_signed(&self) -> bool {
+        self.agoramesh
+            .as_ref()
+            .is_some_and(|ext| ext.proof.is_some())
+    }
+
+    /// Verify the card proof against the agent's DID document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card is unsigned, the document does not
+    /// belong to the card's DID, the verification method is unknown, or
+    /// the signature does not match.
+    pub fn verify_proof(&self, document: &DIDDocument) -> Result<()> {
+        let ext = self
+            .agoramesh
+            .as_ref()
+            .ok_or_else(|| Error::Validation("Card has no AgoraMesh DID".to_string()))?;
+        let proof = ext
+            .proof
+            .as_ref()
+            .ok_or_else(|| Error::Validation(format!("Card for {} is not signed", ext.did)))?;
+        if document.id != ext.did {
+            return Err(Error::Validation(format!(
+                "DID document '{}' does not match card DID '{}'",
+                document.id, ext.did
+            )));
+        }
+
+        let method = document
+            .find_verification_method(&proof.verification_method)
+            .filter(|m| m.controller == ext.did)
+            .ok_or_else(|| {
+                Error::Validation(format!(
+                    "Verification method '{}' not found for {}",
+                    proof.verification_method, ext.did
+                ))
+            })?;
+
+        let signature = hex::decode(&proof.signature)
+            .map_err(|e| Error::Validation(format!("Invalid signature encoding: {}", e)))?;
+        method
+            .verify(&self.signing_bytes()?, &signature)
+            .map_err(|e| Error::Validation(format!("Invalid card signature: {}", e)))
+    }
+
+    /// `(version, updated_at)`, missing values counting as 0.
+    pub fn revision(&self) -> (u64, u64) {
+        self.agoramesh.as_ref().map_or((0, 0), |ext| {
+            (ext.version.unwrap_or(0), ext.updated_at.unwrap_or(0))
+        })
+    }
+}
+
+/// Check that `candidate` may replace `current`, the known copy of the
+/// same agent's card.
+///
+/// Rejects candidates with a lower version (or the same version and an
+/// earlier `updated_at`), and unsigned candidates replacing a signed card.
+/// Re-sending the current revision is allowed.
+///
+/// A signed candidate always replaces an unsigned card: anyone can claim
+/// any version without a signature, so an unsigned copy must not hold back
+/// the agent's own cards. The candidate's proof is verified separately.
+pub fn check_card_update(current: &CapabilityCard, candidate: &CapabilityCard) -> Result<()> {
+    if candidate.is_signed() && !current.is_signed() {
+        return Ok(());
+    }
+
+    let (current_version, current_updated) = current.revision();
+    let (version, updated) = candidate.revision();
+    if (version, updated) < (current_version, current_updated) {
+        return Err(Error::Validation(format!(
+            "Stale card: version {} (updated {}) is older than known version {} (updated {})",
+            version, updated, current_version, current_updated
+        )));
+    }
+    if current.is_signed() && !candidate.is_signed() {
+        return Err(Error::Validation(
+            "Unsigned card cannot replace a signed card".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::did::{encode_ed25519_multibase, DIDDocumentBuilder};
+    use crate::discovery::AgoraMeshExtension;
+
+    const DID: &str = "did:agoramesh:base:signer";
+    const KEY_ID: &str = "did:agoramesh:base:signer#key-1";
+
+    fn card(version: Option<u64>) -> CapabilityCard {
+        CapabilityCard {
+            name: "Signed Agent".to_string(),
+            description: "An agent with a signed card".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills: vec![],
+            authentication: None,
+            agoramesh: Some(AgoraMeshExtension {
+                did: DID.to_string(),
+                trust_score: None,
+                stake: None,
+                pricing: None,
+                payment_methods: vec![],
+                version,
+                updated_at: None,
+                proof: None,
+            }),
+        }
+    }
+
+    fn ed25519_identity() -> (ed25519::Keypair, DIDDocument) {
+        let keypair = ed25519::Keypair::generate();
+        let doc = DIDDocumentBuilder::new("base", "signer")
+            .add_ed25519_key("key-1", &encode_ed25519_multibase(&keypair.public()))
+            .build()
+            .unwrap();
+        (keypair, doc)
+    }
+
+    // ========== TDD Tests: Signing and verification ==========
+
+    #[test]
+    fn test_ed25519_signed_card_verifies() {
+        let (keypair, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        assert!(signed.is_signed());
+        assert!(signed.verify_proof(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_ethereum_signed_card_verifies() {
+        let wallet = PrivateKeySigner::random();
+        let doc = DIDDocumentBuilder::new("base", "signer")
+            .add_ethereum_account("eth", &wallet.address().to_string(), 8453)
+            .build()
+            .unwrap();
+        let mut signed = card(Some(1));
+
+        signed
+            .sign_ethereum("did:agoramesh:base:signer#eth", &wallet)
+            .unwrap();
+
+        assert!(signed.verify_proof(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_signed_card_survives_json_round_trip() {
+        let (keypair, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        let parsed: CapabilityCard =
+            serde_json::from_slice(&serde_json::to_vec(&signed).unwrap()).unwrap();
+
+        assert!(parsed.verify_proof(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_card_is_rejected() {
+        let (keypair, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        signed.url = "https://attacker.example.com".to_string();
+
+        assert!(signed.verify_proof(&doc).is_err());
+    }
+
+    #[test]
+    fn test_version_is_covered_by_signature() {
+        let (keypair, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        signed.agoramesh.as_mut().unwrap().version = Some(99);
+
+        assert!(signed.verify_proof(&doc).is_err());
+    }
+
+    #[test]
+    fn test_card_signed_with_foreign_key_is_rejected() {
+        let (_, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+
+        signed
+            .sign_ed25519(KEY_ID, &ed25519::Keypair::generate())
+            .unwrap();
+
+        assert!(signed.verify_proof(&doc).is_err());
+    }
+
+    #[test]
+    fn test_card_rejects_document_of_other_did() {
+        let (keypair, doc) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.agoramesh.as_mut().unwrap().did = "did:agoramesh:base:impostor".to_string();
+
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        assert!(signed.verify_proof(&doc).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_card_fails_verification() {
+        let (_, doc) = ed25519_identity();
+
+        let err = card(Some(1)).verify_proof(&doc).unwrap_err();
+
+        assert!(err.to_string().contains("not signed"));
+    }
+
+    // ========== TDD Tests: check_card_update() ==========
+
+    #[test]
+    fn test_update_rejects_older_version() {
+        assert!(check_card_update(&card(Some(2)), &card(Some(1))).is_err());
+        assert!(check_card_update(&card(Some(2)), &card(None)).is_err());
+        assert!(check_card_update(&card(Some(2)), &card(Some(2))).is_ok());
+        assert!(check_card_update(&card(Some(2)), &card(Some(3))).is_ok());
+    }
+
+    #[test]
+    fn test_update_breaks_version_ties_by_updated_at() {
+        let mut current = card(Some(1));
+        current.agoramesh.as_mut().unwrap().updated_at = Some(2_000);
+        let mut earlier = card(Some(1));
+        earlier.agoramesh.as_mut().unwrap().updated_at = Some(1_000);
+
+        assert!(check_card_update(&current, &earlier).is_err());
+        assert!(check_card_update(&earlier, &current).is_ok());
+    }
+
+    #[test]
+    fn test_update_rejects_unsigned_replacement_of_signed_card() {
+        let (keypair, _) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        let err = check_card_update(&signed, &card(Some(5))).unwrap_err();
+
+        assert!(err.to_string().contains("Unsigned"));
+        assert!(check_card_update(&card(Some(1)), &signed).is_ok());
+    }
+
+    #[test]
+    fn test_update_signed_card_replaces_unsigned_of_any_version() {
+        let (keypair, _) = ed25519_identity();
+        let mut signed = card(Some(1));
+        signed.sign_ed25519(KEY_ID, &keypair).unwrap();
+
+        assert!(check_card_update(&card(Some(u64::MAX)), &signed).is_ok());
+    }
+}
@@ -8,21 +8,28 @@
 //! - **Real-time subscriptions**: WebSocket-based event streaming
 //! - **Type-safe events**: Strongly-typed event decoding via Alloy's `sol!` macro
 //! - **Automatic reconnection**: Resilient connections with exponential backoff
+//! - **Gap backfill**: Missed block ranges are replayed via `eth_getLogs`
+//!   from the last processed block after every reconnect
+//! - **Deduplication**: Logs are delivered once per `(tx_hash, log_index)`,
+//!   also across restarts when a checkpoint store is attached
 //! - **Event filtering**: Filter by contract, event type, or topic
+//! - **State sync**: [`ContractEventSink`] applies events to trust, discovery
+//!   and arbitration state
 //!
 //! ## Usage
 //!
 //! ```rust,ignore
 //! use agoramesh_node::events::{EventListener, EventListenerConfig, ContractEvent};
 //!
-//! let config = EventListenerConfig {
-//!     ws_url: "wss://sepolia.base.org".to_string(),
-//!     trust_registry_address: "0x...".to_string(),
-//!     ..Default::default()
-//! };
+//! let config = EventListenerConfig::new("wss://sepolia.base.org")
+//!     .with_trust_registry("0x...");
 //!
 //! let (listener, mut rx) = EventListener::new(config)?;
-//! listener.start().await?;
+//! let listener = Arc::new(listener.with_checkpoint_store(store));
+//! tokio::spawn({
+//!     let listener = listener.clone();
+//!     async move { listener.run().await }
+//! });
 //!
 //! while let Some(event) = rx.recv().await {
 //!     match event {
@@ -34,14 +41,36 @@
 //! }
 //! ```
 
-use alloy::primitives::{Address, FixedBytes, U256};
+use alloy::primitives::{Address, FixedBytes, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder, WsConnect};
+use alloy::rpc::types::{Filter, Log};
 use alloy::sol;
+use alloy::sol_types::SolEventInterface;
+use futures::StreamExt;
+use lru::LruCache;
+use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
 
 use crate::error::{Error, Result};
+use crate::persistence::{MemoryStore, Store};
+
+mod sink;
+
+pub use sink::{ContractEventSink, ContractEventSinkStats};
+
+/// Store key holding the last processed block number (big-endian u64).
+const LAST_BLOCK_KEY: &str = "last_processed_block";
+
+/// Store key prefix of logs delivered from the checkpoint block onwards
+/// (`seen_log/{block}/{tx_hash}/{log_index}`).
+///
+/// Backfill rescans the checkpoint block, so these keys keep a restart from
+/// delivering its logs twice. Keys below the checkpoint are pruned.
+const SEEN_LOG_PREFIX: &str = "seen_log/";
 
 // ========== Event Definitions via sol! macro ==========
 
@@ -233,6 +262,8 @@ pub enum ContractEvent {
         amount: U256,
         block_number: u64,
         tx_hash: FixedBytes<32>,
+        /// Position of the log in its block, so replays can be recognised.
+        log_index: u64,
     },
     /// Stake slashed
     StakeSlashed {
@@ -241,6 +272,8 @@ pub enum ContractEvent {
         reason: FixedBytes<32>,
         block_number: u64,
         tx_hash: FixedBytes<32>,
+        /// Position of the log in its block, so replays can be recognised.
+        log_index: u64,
     },
     /// Endorsement added
     EndorsementAdded {
@@ -414,6 +447,15 @@ pub struct EventListenerConfig {
 
     /// Reconnection settings.
     pub reconnect: ReconnectConfig,
+
+    /// Block to backfill from when no checkpoint exists (None = live events only).
+    pub start_block: Option<u64>,
+
+    /// Maximum block range per `eth_getLogs` request during backfill.
+    pub backfill_chunk_size: u64,
+
+    /// Number of recent `(tx_hash, log_index)` pairs remembered for deduplication.
+    pub dedup_capacity: usize,
 }
 
 /// Reconnection configuration.
@@ -455,6 +497,9 @@ impl Default for EventListenerConfig {
             escrow_address: None,
             channel_buffer_size: 1000,
             reconnect: ReconnectConfig::default(),
+            start_block: None,
+            backfill_chunk_size: 2000,
+            dedup_capacity: 10_000,
         }
     }
 }
@@ -479,6 +524,12 @@ impl EventListenerConfig {
         self.escrow_address = Some(address.into());
         self
     }
+
+    /// Backfill from this block on first start.
+    pub fn with_start_block(mut self, block: u64) -> Self {
+        self.start_block = Some(block);
+        self
+    }
 }
 
 // ========== Event Listener Statistics ==========
@@ -496,6 +547,10 @@ pub struct EventListenerStats {
     pub reconnections: AtomicU64,
     /// Number of connection errors.
     pub connection_errors: AtomicU64,
+    /// Number of logs skipped as already delivered.
+    pub duplicates_skipped: AtomicU64,
+    /// Number of logs replayed through `eth_getLogs` backfill.
+    pub logs_backfilled: AtomicU64,
 }
 
 impl EventListenerStats {
@@ -524,6 +579,16 @@ impl EventListenerStats {
         self.connection_errors.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a duplicate log.
+    pub fn record_duplicate(&self) {
+        self.duplicates_skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record logs fetched during backfill.
+    pub fn record_backfilled(&self, count: u64) {
+        self.logs_backfilled.fetch_add(count, Ordering::Relaxed);
+    }
+
     /// Get success rate (decoded / received).
     pub fn success_rate(&self) -> f64 {
         let received = self.events_received.load(Ordering::Relaxed);
@@ -539,15 +604,33 @@ impl EventListenerStats {
 
 // ========== Event Listener ==========
 
+/// Store key of a delivered log.
+fn seen_log_key(block: u64, tx_hash: &B256, log_index: u64) -> String {
+    format!("{}{}/{}/{}", SEEN_LOG_PREFIX, block, tx_hash, log_index)
+}
+
+/// Resolve once the listener has been stopped.
+///
+/// Drops the `watch` borrow before returning so the guard is never held
+/// across an await in a `select!` arm.
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stopped| *stopped).await;
+}
+
 /// Real-time event listener for AgoraMesh contracts.
 ///
 /// Subscribes to contract events via WebSocket and emits typed events
 /// through a channel for processing.
 pub struct EventListener {
     config: EventListenerConfig,
+    trust_registry: Option<Address>,
+    escrow: Option<Address>,
     stats: Arc<EventListenerStats>,
     running: Arc<AtomicBool>,
+    shutdown: watch::Sender<bool>,
     event_tx: mpsc::Sender<ContractEvent>,
+    seen: Mutex<LruCache<(B256, u64), ()>>,
+    checkpoints: Arc<dyn Store>,
 }
 
 impl EventListener {
@@ -560,27 +643,57 @@ impl EventListener {
             return Err(Error::Config("WebSocket URL is required".to_string()));
         }
 
+        let trust_registry = config
+            .trust_registry_address
+            .as_deref()
+            .map(Self::parse_address)
+            .transpose()?;
+        let escrow = config
+            .escrow_address
+            .as_deref()
+            .map(Self::parse_address)
+            .transpose()?;
+
         let (event_tx, event_rx) = mpsc::channel(config.channel_buffer_size);
+        let seen = Self::dedup_cache(config.dedup_capacity);
 
         let listener = Self {
             config,
+            trust_registry,
+            escrow,
             stats: Arc::new(EventListenerStats::default()),
             running: Arc::new(AtomicBool::new(false)),
+            shutdown: watch::channel(false).0,
             event_tx,
+            seen,
+            checkpoints: Arc::new(MemoryStore::new()),
         };
 
         Ok((listener, event_rx))
     }
 
+    /// Persist the last processed block in `store` so backfill survives restarts.
+    pub fn with_checkpoint_store(mut self, store: Arc<dyn Store>) -> Self {
+        self.checkpoints = store;
+        self
+    }
+
     /// Create a disabled event listener (for testing).
     pub fn disabled() -> (Self, mpsc::Receiver<ContractEvent>) {
         let (event_tx, event_rx) = mpsc::channel(1);
+        let config = EventListenerConfig::default();
+        let seen = Self::dedup_cache(config.dedup_capacity);
 
         let listener = Self {
-            config: EventListenerConfig::default(),
+            config,
+            trust_registry: None,
+            escrow: None,
             stats: Arc::new(EventListenerStats::default()),
             running: Arc::new(AtomicBool::new(false)),
+            shutdown: watch::channel(false).0,
             event_tx,
+            seen,
+            checkpoints: Arc::new(MemoryStore::new()),
         };
 
         (listener, event_rx)
@@ -602,7 +715,6 @@ impl EventListener {
     }
 
     /// Parse contract address from string.
-    #[allow(dead_code)]
     fn parse_address(address: &str) -> Result<Address> {
         address
             .parse::<Address>()
@@ -610,8 +722,12 @@ impl EventListener {
     }
 
     /// Stop the event listener.
+    ///
+    /// A running [`run`](Self::run) loop returns `Ok(())` promptly, and a
+    /// later call to `run` returns without connecting.
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
+        self.shutdown.send_replace(true);
     }
 
     /// Emit an event to the channel.
@@ -631,6 +747,388 @@ impl EventListener {
         let clamped = delay_secs.min(config.max_delay.as_secs_f64());
         Duration::from_secs_f64(clamped)
     }
+
+    fn dedup_cache(capacity: usize) -> Mutex<LruCache<(B256, u64), ()>> {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Mutex::new(LruCache::new(capacity))
+    }
+
+    /// Log filter covering every known event of the configured contracts.
+    fn log_filter(&self) -> Result<Filter> {
+        let mut addresses = Vec::new();
+        let mut signatures: Vec<B256> = Vec::new();
+
+        if let Some(address) = self.trust_registry {
+            addresses.push(address);
+            signatures.extend(
+                TrustRegistryEvents::TrustRegistryEventsEvents::SELECTORS
+                    .iter()
+                    .map(|selector| B256::from(*selector)),
+            );
+        }
+        if let Some(address) = self.escrow {
+            addresses.push(address);
+            signatures.extend(
+                EscrowEvents::EscrowEventsEvents::SELECTORS
+                    .iter()
+                    .map(|selector| B256::from(*selector)),
+            );
+        }
+
+        if addresses.is_empty() {
+            return Err(Error::Config(
+                "Event listener needs a TrustRegistry or Escrow address".to_string(),
+            ));
+        }
+
+        Ok(Filter::new().address(addresses).event_signature(signatures))
+    }
+
+    /// Last fully scanned block, if any.
+    pub fn last_processed_block(&self) -> Result<Option<u64>> {
+        let Some(bytes) = self.checkpoints.get(LAST_BLOCK_KEY)? else {
+            return Ok(None);
+        };
+        let bytes: [u8; 8] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::Persistence("Corrupt event listener checkpoint".to_string()))?;
+        Ok(Some(u64::from_be_bytes(bytes)))
+    }
+
+    /// Move the checkpoint forward (never backwards).
+    ///
+    /// Logs seen below the new checkpoint are forgotten: backfill never
+    /// rescans those blocks.
+    fn advance_checkpoint(&self, block: u64) -> Result<()> {
+        if self
+            .last_processed_block()?
+            .is_some_and(|current| current >= block)
+        {
+            return Ok(());
+        }
+        self.checkpoints.put(LAST_BLOCK_KEY, &block.to_be_bytes())?;
+
+        for (key, _) in self.checkpoints.iter_prefix(SEEN_LOG_PREFIX)? {
+            let seen_block = key[SEEN_LOG_PREFIX.len()..]
+                .split('/')
+                .next()
+                .and_then(|block| block.parse::<u64>().ok());
+            if seen_block.is_none_or(|seen| seen < block) {
+                self.checkpoints.delete(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode a log from a configured contract into a [`ContractEvent`].
+    ///
+    /// Returns `Ok(None)` for logs from other addresses.
+    pub fn decode_log(&self, log: &Log) -> Result<Option<ContractEvent>> {
+        let block_number = log.block_number.unwrap_or_default();
+        let tx_hash = log.transaction_hash.unwrap_or_default();
+        let address = log.address();
+
+        if Some(address) == self.trust_registry {
+            use TrustRegistryEvents::TrustRegistryEventsEvents as E;
+
+            let decoded = E::decode_log(&log.inner)
+                .map_err(|e| {
+                    Error::Blockchain(format!("Failed to decode TrustRegistry log: {}", e))
+                })?
+                .data;
+            let event = match decoded {
+                E::AgentRegistered(e) => ContractEvent::AgentRegistered {
+                    did_hash: e.didHash,
+                    owner: e.owner,
+                    capability_card_cid: e.capabilityCardCID,
+                    block_number,
+                    tx_hash,
+                },
+                E::AgentUpdated(e) => ContractEvent::AgentUpdated {
+                    did_hash: e.didHash,
+                    new_cid: e.newCID,
+                    block_number,
+                    tx_hash,
+                },
+                E::AgentDeactivated(e) => ContractEvent::AgentDeactivated {
+                    did_hash: e.didHash,
+                    block_number,
+                    tx_hash,
+                },
+                E::ReputationUpdated(e) => ContractEvent::ReputationUpdated {
+                    did_hash: e.didHash,
+                    new_score: e.newScore.saturating_to(),
+                    total_transactions: e.totalTransactions.saturating_to(),
+                    block_number,
+                    tx_hash,
+                },
+                E::StakeDeposited(e) => ContractEvent::StakeDeposited {
+                    did_hash: e.didHash,
+                    amount: e.amount,
+                    block_number,
+                    tx_hash,
+                    log_index: log.log_index.unwrap_or_default(),
+                },
+                E::StakeSlashed(e) => ContractEvent::StakeSlashed {
+                    did_hash: e.didHash,
+                    amount: e.amount,
+                    reason: e.reason,
+                    block_number,
+                    tx_hash,
+                    log_index: log.log_index.unwrap_or_default(),
+                },
+                E::EndorsementAdded(e) => ContractEvent::EndorsementAdded {
+                    endorser: e.endorser,
+                    endorsee: e.endorsee,
+                    message: e.message,
+                    block_number,
+                    tx_hash,
+                },
+                E::EndorsementRevoked(e) => ContractEvent::EndorsementRevoked {
+                    endorser: e.endorser,
+                    endorsee: e.endorsee,
+                    block_number,
+                    tx_hash,
+                },
+            };
+            return Ok(Some(event));
+        }
+
+        if Some(address) == self.escrow {
+            use EscrowEvents::EscrowEventsEvents as E;
+
+            let decoded = E::decode_log(&log.inner)
+                .map_err(|e| Error::Blockchain(format!("Failed to decode Escrow log: {}", e)))?
+                .data;
+            let event = match decoded {
+                E::EscrowCreated(e) => ContractEvent::EscrowCreated {
+                    escrow_id: e.escrowId,
+                    client: e.client,
+                    provider: e.provider,
+                    amount: e.amount,
+                    client_did: e.clientDid,
+                    provider_did: e.providerDid,
+                    block_number,
+                    tx_hash,
+                },
+                E::EscrowFunded(e) => ContractEvent::EscrowFunded {
+                    escrow_id: e.escrowId,
+                    block_number,
+                    tx_hash,
+                },
+                E::EscrowReleased(e) => ContractEvent::EscrowReleased {
+                    escrow_id: e.escrowId,
+                    block_number,
+                    tx_hash,
+                },
+                E::EscrowRefunded(e) => ContractEvent::EscrowRefunded {
+                    escrow_id: e.escrowId,
+                    block_number,
+                    tx_hash,
+                },
+                E::DisputeInitiated(e) => ContractEvent::DisputeInitiated {
+                    escrow_id: e.escrowId,
+                    initiator: e.initiator,
+                    block_number,
+                    tx_hash,
+                },
+                E::DisputeResolved(e) => ContractEvent::DisputeResolved {
+                    escrow_id: e.escrowId,
+                    released_to_provider: e.releasedToProvider,
+                    provider_amount: e.providerAmount,
+                    block_number,
+                    tx_hash,
+                },
+            };
+            return Ok(Some(event));
+        }
+
+        Ok(None)
+    }
+
+    /// Deduplicate, decode and emit a single log.
+    ///
+    /// Returns `true` if an event was emitted.
+    pub async fn process_log(&self, log: Log) -> Result<bool> {
+        if log.removed {
+            return Ok(false);
+        }
+        self.stats.record_event_received();
+
+        let seen_key = match (log.block_number, log.transaction_hash, log.log_index) {
+            (Some(block), Some(tx_hash), Some(log_index)) => {
+                Some(seen_log_key(block, &tx_hash, log_index))
+            }
+            _ => None,
+        };
+        if let (Some(tx_hash), Some(log_index)) = (log.transaction_hash, log.log_index) {
+            let mut duplicate = self
+                .seen
+                .lock()
+                .map_err(|e| Error::Internal(format!("Lock error: {}", e)))?
+                .put((tx_hash, log_index), ())
+                .is_some();
+            if let Some(ref key) = seen_key {
+                duplicate |= self.checkpoints.contains(key)?;
+            }
+            if duplicate {
+                self.stats.record_duplicate();
+                return Ok(false);
+            }
+        }
+
+        let emitted = match self.decode_log(&log) {
+            Ok(Some(event)) => {
+                self.emit_event(event).await?;
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                self.stats.record_decode_error();
+                warn!(error = %e, tx_hash = ?log.transaction_hash, "Skipping undecodable log");
+                false
+            }
+        };
+
+        if let Some(key) = seen_key {
+            self.checkpoints.put(&key, &[])?;
+        }
+        if let Some(block) = log.block_number {
+            self.advance_checkpoint(block)?;
+        }
+
+        Ok(emitted)
+    }
+
+    /// Replay logs from the checkpoint (inclusive) up to the current head.
+    ///
+    /// The checkpoint block is rescanned because it may have been only partly
+    /// processed; deduplication drops the logs already delivered, including
+    /// those delivered before a restart.
+    async fn backfill<P: Provider>(&self, provider: &P, filter: &Filter) -> Result<()> {
+        let head = provider
+            .get_block_number()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to get block number: {}", e)))?;
+
+        let Some(mut from) = self.last_processed_block()?.or(self.config.start_block) else {
+            // First start without a start block: only stream from here on
+            return self.advance_checkpoint(head);
+        };
+
+        let chunk = self.config.backfill_chunk_size.max(1);
+        while from <= head {
+            let to = from.saturating_add(chunk - 1).min(head);
+            let logs = provider
+                .get_logs(&filter.clone().from_block(from).to_block(to))
+                .await
+                .map_err(|e| {
+                    Error::Network(format!("Failed to get logs {}-{}: {}", from, to, e))
+                })?;
+
+            self.stats.record_backfilled(logs.len() as u64);
+            for log in logs {
+                self.process_log(log).await?;
+            }
+            self.advance_checkpoint(to)?;
+            from = to + 1;
+        }
+
+        Ok(())
+    }
+
+    /// One connection: subscribe, backfill the gap, then stream until stopped.
+    async fn run_session(
+        &self,
+        filter: &Filter,
+        shutdown: &mut watch::Receiver<bool>,
+        attempt: &mut u32,
+    ) -> Result<()> {
+        let provider = ProviderBuilder::new()
+            .connect_ws(WsConnect::new(self.config.ws_url.clone()))
+            .await
+            .map_err(|e| {
+                Error::Network(format!(
+                    "Failed to connect to {}: {}",
+                    self.config.ws_url, e
+                ))
+            })?;
+
+        // Subscribe before backfilling so nothing falls between the two
+        let subscription = provider
+            .subscribe_logs(filter)
+            .await
+            .map_err(|e| Error::Network(format!("Failed to subscribe to logs: {}", e)))?;
+        let mut stream = subscription.into_stream();
+        *attempt = 0;
+
+        self.backfill(&provider, filter).await?;
+        info!(ws_url = %self.config.ws_url, "Subscribed to contract events");
+
+        loop {
+            tokio::select! {
+                _ = stopped(shutdown) => return Ok(()),
+                log = stream.next() => match log {
+                    Some(log) => {
+                        self.process_log(log).await?;
+                    }
+                    None => {
+                        return Err(Error::Network("Log subscription closed".to_string()));
+                    }
+                },
+            }
+        }
+    }
+
+    /// Connect to `ws_url` and stream contract events until [`stop`](Self::stop).
+    ///
+    /// Reconnects with exponential backoff per [`ReconnectConfig`] and backfills
+    /// any blocks missed while disconnected. Returns an error once reconnection
+    /// is disabled or `max_attempts` is exhausted. Returns `Ok(())` at once if
+    /// the listener was already stopped.
+    pub async fn run(&self) -> Result<()> {
+        let filter = self.log_filter()?;
+        let mut shutdown = self.shutdown.subscribe();
+        if *shutdown.borrow() {
+            return Ok(());
+        }
+        self.running.store(true, Ordering::SeqCst);
+
+        let mut attempt = 0u32;
+        let result = loop {
+            if !self.is_running() {
+                break Ok(());
+            }
+
+            let error = match self.run_session(&filter, &mut shutdown, &mut attempt).await {
+                Ok(()) => break Ok(()),
+                Err(e) => e,
+            };
+            self.stats.record_connection_error();
+
+            let reconnect = &self.config.reconnect;
+            if !reconnect.enabled
+                || (reconnect.max_attempts > 0 && attempt >= reconnect.max_attempts)
+            {
+                break Err(error);
+            }
+
+            let delay = self.calculate_reconnect_delay(attempt);
+            warn!(error = %error, attempt, delay_ms = delay.as_millis() as u64, "Event subscription lost, reconnecting");
+            attempt += 1;
+
+            tokio::select! {
+                _ = stopped(&mut shutdown) => break Ok(()),
+                _ = tokio::time::sleep(delay) => {}
+            }
+            self.stats.record_reconnection();
+            debug!(attempt, "Reconnecting event listener");
+        };
+
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
 }
 
 // ========== TDD Tests ==========
@@ -1054,6 +1552,7 @@ mod tests {
                     amount: U256::ZERO,
                     block_number: 0,
                     tx_hash: FixedBytes::ZERO,
+                    log_index: 0,
                 },
             ),
             (
@@ -1064,6 +1563,7 @@ mod tests {
                     reason: FixedBytes::ZERO,
                     block_number: 0,
                     tx_hash: FixedBytes::ZERO,
+                    log_index: 1,
                 },
             ),
             (
@@ -1157,4 +1657,236 @@ mod tests {
             assert_eq!(event.event_name(), expected_name);
         }
     }
+
+    // ========== RED Phase: Log Processing Tests ==========
+
+    const ESCROW: &str = "0x00000000000000000000000000000000000000e5";
+    const REGISTRY: &str = "0x00000000000000000000000000000000000000a1";
+
+    fn rpc_log(
+        address: &str,
+        data: alloy::primitives::LogData,
+        block: u64,
+        tx: u8,
+        index: u64,
+    ) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: address.parse().unwrap(),
+                data,
+            },
+            block_number: Some(block),
+            transaction_hash: Some(B256::repeat_byte(tx)),
+            log_index: Some(index),
+            ..Default::default()
+        }
+    }
+
+    fn escrow_funded_log(escrow_id: u64, block: u64, tx: u8, index: u64) -> Log {
+        use alloy::sol_types::SolEvent;
+
+        let data = EscrowEvents::EscrowFunded {
+            escrowId: U256::from(escrow_id),
+        }
+        .encode_log_data();
+        rpc_log(ESCROW, data, block, tx, index)
+    }
+
+    fn contract_listener() -> (EventListener, mpsc::Receiver<ContractEvent>) {
+        let config = EventListenerConfig::default()
+            .with_trust_registry(REGISTRY)
+            .with_escrow(ESCROW);
+        EventListener::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_new_listener_rejects_invalid_contract_address() {
+        let config = EventListenerConfig::default().with_escrow("not-an-address");
+
+        assert!(EventListener::new(config).is_err());
+    }
+
+    #[test]
+    fn test_log_filter_requires_a_contract() {
+        let (listener, _rx) = EventListener::new(EventListenerConfig::default()).unwrap();
+
+        assert!(listener.log_filter().is_err());
+    }
+
+    #[test]
+    fn test_decode_trust_registry_log() {
+        use alloy::sol_types::SolEvent;
+
+        let (listener, _rx) = contract_listener();
+        let data = TrustRegistryEvents::ReputationUpdated {
+            didHash: FixedBytes::repeat_byte(0x11),
+            newScore: U256::from(8_500),
+            totalTransactions: U256::from(42),
+        }
+        .encode_log_data();
+
+        let event = listener
+            .decode_log(&rpc_log(REGISTRY, data, 7, 0xAA, 0))
+            .unwrap()
+            .expect("registry log should decode");
+
+        match event {
+            ContractEvent::ReputationUpdated {
+                did_hash,
+                new_score,
+                total_transactions,
+                block_number,
+                tx_hash,
+            } => {
+                assert_eq!(did_hash, FixedBytes::repeat_byte(0x11));
+                assert_eq!(new_score, 8_500);
+                assert_eq!(total_transactions, 42);
+                assert_eq!(block_number, 7);
+                assert_eq!(tx_hash, B256::repeat_byte(0xAA));
+            }
+            other => panic!("Wrong event type decoded: {}", other.event_name()),
+        }
+    }
+
+    #[test]
+    fn test_decode_escrow_log() {
+        let (listener, _rx) = contract_listener();
+
+        let event = listener
+            .decode_log(&escrow_funded_log(9, 3, 0xBB, 0))
+            .unwrap()
+            .expect("escrow log should decode");
+
+        assert!(matches!(
+            event,
+            ContractEvent::EscrowFunded { escrow_id, .. } if escrow_id == U256::from(9)
+        ));
+    }
+
+    #[test]
+    fn test_decode_ignores_unknown_address() {
+        let (listener, _rx) = contract_listener();
+        let mut log = escrow_funded_log(1, 1, 0x01, 0);
+        log.inner.address = Address::repeat_byte(0xFF);
+
+        assert!(listener.decode_log(&log).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_log_deduplicates_by_tx_and_index() {
+        let (listener, mut rx) = contract_listener();
+
+        assert!(listener
+            .process_log(escrow_funded_log(1, 5, 0x01, 0))
+            .await
+            .unwrap());
+        assert!(!listener
+            .process_log(escrow_funded_log(1, 5, 0x01, 0))
+            .await
+            .unwrap());
+        // Same transaction, different log index is a distinct event
+        assert!(listener
+            .process_log(escrow_funded_log(2, 5, 0x01, 1))
+            .await
+            .unwrap());
+
+        assert!(rx.recv().await.is_some());
+        assert!(rx.recv().await.is_some());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(
+            listener.stats().duplicates_skipped.load(Ordering::Relaxed),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restart_does_not_redeliver_checkpoint_block() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::new());
+        let (listener, mut rx) = contract_listener();
+        let listener = listener.with_checkpoint_store(store.clone());
+        assert!(listener
+            .process_log(escrow_funded_log(1, 5, 0x01, 0))
+            .await
+            .unwrap());
+        assert!(rx.recv().await.is_some());
+
+        // Backfill after a restart rescans block 5 with an empty LRU
+        let (restarted, mut rx) = contract_listener();
+        let restarted = restarted.with_checkpoint_store(store.clone());
+        assert!(!restarted
+            .process_log(escrow_funded_log(1, 5, 0x01, 0))
+            .await
+            .unwrap());
+        assert!(restarted
+            .process_log(escrow_funded_log(2, 5, 0x01, 1))
+            .await
+            .unwrap());
+        assert!(rx.recv().await.is_some());
+        assert!(rx.try_recv().is_err());
+
+        // Seen logs below the checkpoint are pruned
+        restarted
+            .process_log(escrow_funded_log(3, 6, 0x02, 0))
+            .await
+            .unwrap();
+        assert_eq!(store.iter_prefix(SEEN_LOG_PREFIX).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_returns_immediately_when_stopped_before_start() {
+        let config = EventListenerConfig::new("ws://127.0.0.1:1").with_escrow(ESCROW);
+        let (listener, _rx) = EventListener::new(config).unwrap();
+
+        listener.stop();
+        let result = tokio::time::timeout(Duration::from_secs(1), listener.run()).await;
+
+        assert!(matches!(result, Ok(Ok(()))));
+        assert!(!listener.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_process_log_skips_removed_logs() {
+        let (listener, mut rx) = contract_listener();
+        let mut log = escrow_funded_log(1, 5, 0x01, 0);
+        log.removed = true;
+
+        assert!(!listener.process_log(log).await.unwrap());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(listener.last_processed_block().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_process_log_counts_decode_errors() {
+        let (listener, mut rx) = contract_listener();
+        let mut log = escrow_funded_log(1, 5, 0x01, 0);
+        log.inner.data =
+            alloy::primitives::LogData::new_unchecked(vec![B256::ZERO], Default::default());
+
+        assert!(!listener.process_log(log).await.unwrap());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(listener.stats().decode_errors.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_only_moves_forward() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::new());
+        let (listener, _rx) = contract_listener();
+        let listener = listener.with_checkpoint_store(store.clone());
+
+        listener
+            .process_log(escrow_funded_log(1, 10, 0x01, 0))
+            .await
+            .unwrap();
+        listener
+            .process_log(escrow_funded_log(2, 8, 0x02, 0))
+            .await
+            .unwrap();
+
+        assert_eq!(listener.last_processed_block().unwrap(), Some(10));
+
+        // A fresh listener sharing the store resumes from the same block
+        let (restarted, _rx) = contract_listener();
+        let restarted = restarted.with_checkpoint_store(store);
+        assert_eq!(restarted.last_processed_block().unwrap(), Some(10));
+    }
 }
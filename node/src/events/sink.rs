@@ -0,0 +1,911 @@
+//! Applies decoded contract events to the node's local services.
+//!
+//! [`ContractEventSink`] consumes the [`ContractEvent`] stream produced by
+//! [`EventListener`](super::EventListener) and keeps local state in line
+//! with the chain:
+//! - TrustRegistry reputation, stake and endorsement events update
+//!   [`TrustService`] data and invalidate [`TrustCache`] entries
+//! - `AgentDeactivated` marks the agent inactive in [`DiscoveryService`],
+//!   and `AgentRegistered` serves it again
+//! - `DisputeInitiated` opens an [`AIArbitrator`] dispute, and escrow
+//!   settlement closes it
+//!
+//! Escrow parties from `EscrowCreated` are kept in the event store until
+//! the escrow is released, refunded or resolved, so disputes on escrows
+//! created before a restart still reach the arbitrator.
+//!
+//! Contracts identify agents by `keccak256(did)`. The sink resolves these
+//! hashes through a [`DidHashIndex`] shared with discovery and trust, which
+//! record DIDs as they learn about them. The index is seeded from all known
+//! cards and trust records when the sink starts; a hash that is still
+//! unknown triggers one more rescan and is then remembered as a miss.
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use alloy::primitives::{FixedBytes, U256};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use super::ContractEvent;
+use crate::arbitration::AIArbitrator;
+use crate::contract::DidHashIndex;
+use crate::discovery::DiscoveryService;
+use crate::error::{Error, Result};
+use crate::persistence::{MemoryStore, Store};
+use crate::trust::TrustService;
+use crate::trust_cache::TrustCache;
+
+/// Number of unresolved DID hashes remembered to skip repeated rescans.
+const UNRESOLVED_CACHE_SIZE: usize = 4096;
+
+/// Parties of an escrow, remembered from `EscrowCreated` until settlement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EscrowParties {
+    client_did: FixedBytes<32>,
+    provider_did: FixedBytes<32>,
+    amount: U256,
+}
+
+/// Statistics for the contract event sink.
+#[derive(Debug, Default)]
+pub struct ContractEventSinkStats {
+    /// Events that changed local state.
+    pub events_applied: AtomicU64,
+    /// Events ignored (not relevant or nothing to update).
+    pub events_ignored: AtomicU64,
+    /// Events whose DID hash could not be resolved.
+    pub unresolved_dids: AtomicU64,
+    /// Events that failed to apply.
+    pub apply_errors: AtomicU64,
+}
+
+/// Store key prefix marking stake logs already applied
+/// (`applied_stake/{tx_hash}/{log_index}`).
+const APPLIED_STAKE_PREFIX: &str = "applied_stake/";
+
+/// Store key prefix of open escrows' parties (`escrow/{escrow_id}`).
+const ESCROW_PREFIX: &str = "escrow/";
+
+/// Maps [`ContractEvent`]s onto trust, discovery and arbitration state.
+///
+/// Every target service is optional; events for a missing service are
+/// ignored.
+pub struct ContractEventSink {
+    trust: Option<Arc<TrustService>>,
+    trust_cache: Option<Arc<TrustCache>>,
+    discovery: Option<Arc<DiscoveryService>>,
+    arbitrator: Option<Arc<AIArbitrator>>,
+    did_index: Arc<DidHashIndex>,
+    unresolved: Mutex<LruCache<FixedBytes<32>, ()>>,
+    store: Arc<dyn Store>,
+    stats: ContractEventSinkStats,
+}
+
+impl Default for ContractEventSink {
+    fn default() -> Self {
+        Self {
+            trust: None,
+            trust_cache: None,
+            discovery: None,
+            arbitrator: None,
+            did_index: Arc::new(DidHashIndex::new()),
+            unresolved: Mutex::new(LruCache::new(
+                NonZeroUsize::new(UNRESOLVED_CACHE_SIZE).unwrap_or(NonZeroUsize::MIN),
+            )),
+            store: Arc::new(MemoryStore::new()),
+            stats: ContractEventSinkStats::default(),
+        }
+    }
+}
+
+impl ContractEventSink {
+    /// Create a sink without any target services.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply trust events to this service.
+    pub fn with_trust_service(mut self, trust: Arc<TrustService>) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    /// Invalidate entries of this cache whenever an agent's trust changes.
+    pub fn with_trust_cache(mut self, cache: Arc<TrustCache>) -> Self {
+        self.trust_cache = Some(cache);
+        self
+    }
+
+    /// Deactivate and reactivate agents in this discovery service.
+    pub fn with_discovery(mut self, discovery: Arc<DiscoveryService>) -> Self {
+        self.discovery = Some(discovery);
+        self
+    }
+
+    /// Open and close disputes on this arbitrator.
+    pub fn with_arbitrator(mut self, arbitrator: Arc<AIArbitrator>) -> Self {
+        self.arbitrator = Some(arbitrator);
+        self
+    }
+
+    /// Resolve contract DID hashes through `index`.
+    ///
+    /// Share the index with [`DiscoveryService::with_did_index`] and
+    /// [`TrustService::with_did_index`] so agents learned after startup
+    /// resolve without a rescan.
+    pub fn with_did_index(mut self, index: Arc<DidHashIndex>) -> Self {
+        self.did_index = index;
+        self
+    }
+
+    /// Remember applied stake logs and open escrows in `store`.
+    ///
+    /// Stake events add to or subtract from the local stake, so each log
+    /// must apply once even when it is delivered again after a restart.
+    /// Escrow parties must outlive a restart for later disputes to resolve.
+    pub fn with_event_store(mut self, store: Arc<dyn Store>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Get sink statistics.
+    pub fn stats(&self) -> &ContractEventSinkStats {
+        &self.stats
+    }
+
+    /// Add a DID to the reverse index.
+    pub fn index_did(&self, did: &str) -> Result<()> {
+        self.did_index.insert(did)
+    }
+
+    /// Rebuild the reverse index from discovery cards and trust records.
+    ///
+    /// Returns the number of indexed DIDs.
+    pub fn rebuild_index(&self) -> Result<usize> {
+        let mut dids = Vec::new();
+        if let Some(ref discovery) = self.discovery {
+            dids.extend(discovery.known_dids()?);
+        }
+        if let Some(ref trust) = self.trust {
+            dids.extend(trust.known_dids()?);
+        }
+
+        for did in dids {
+            self.did_index.insert(&did)?;
+        }
+
+        Ok(self.did_index.len())
+    }
+
+    /// Resolve a contract DID hash to a DID.
+    ///
+    /// The first miss for a hash rescans known DIDs; later misses for the
+    /// same hash return `None` straight away until the DID is indexed.
+    pub fn resolve_did(&self, did_hash: &FixedBytes<32>) -> Result<Option<String>> {
+        if let Some(did) = self.did_index.get(did_hash)? {
+            return Ok(Some(did));
+        }
+
+        let mut unresolved = self
+            .unresolved
+            .lock()
+            .map_err(|e| Error::Internal(format!("Lock error: {}", e)))?;
+        if unresolved.get(did_hash).is_some() {
+            return Ok(None);
+        }
+        self.rebuild_index()?;
+        let did = self.did_index.get(did_hash)?;
+        if did.is_none() {
+            unresolved.put(*did_hash, ());
+        }
+        Ok(did)
+    }
+
+    /// Resolve a DID hash, counting misses.
+    fn resolve_or_skip(&self, did_hash: &FixedBytes<32>) -> Result<Option<String>> {
+        let did = self.resolve_did(did_hash)?;
+        if did.is_none() {
+            self.stats.unresolved_dids.fetch_add(1, Ordering::Relaxed);
+            debug!(did_hash = %did_hash, "No known DID for contract event");
+        }
+        Ok(did)
+    }
+
+    async fn invalidate_trust(&self, did: &str) {
+        if let Some(ref cache) = self.trust_cache {
+            cache.invalidate(did).await;
+        }
+    }
+
+    /// Apply a single event.
+    ///
+    /// Returns `true` if local state changed.
+    pub async fn apply(&self, event: &ContractEvent) -> Result<bool> {
+        let applied = match event {
+            ContractEvent::ReputationUpdated {
+                did_hash,
+                new_score,
+                total_transactions,
+                ..
+            } => {
+                self.update_trust(did_hash, |trust, did| {
+                    trust.apply_onchain_reputation(did, *new_score, *total_transactions)
+                })
+                .await?
+            }
+            ContractEvent::StakeDeposited {
+                did_hash,
+                amount,
+                tx_hash,
+                log_index,
+                ..
+            } => {
+                let amount: u64 = amount.saturating_to();
+                self.apply_stake_once(tx_hash, *log_index, did_hash, |trust, did| {
+                    trust.add_stake(did, amount)
+                })
+                .await?
+            }
+            ContractEvent::StakeSlashed {
+                did_hash,
+                amount,
+                tx_hash,
+                log_index,
+                ..
+            } => {
+                let amount: u64 = amount.saturating_to();
+                self.apply_stake_once(tx_hash, *log_index, did_hash, |trust, did| {
+                    trust.slash_stake(did, amount)
+                })
+                .await?
+            }
+            ContractEvent::EndorsementAdded {
+                endorser, endorsee, ..
+            } => self.endorsement_added(endorser, endorsee).await?,
+            ContractEvent::EndorsementRevoked {
+                endorser, endorsee, ..
+            } => self.endorsement_revoked(endorser, endorsee).await?,
+            ContractEvent::AgentDeactivated { did_hash, .. } => {
+                self.agent_deactivated(did_hash).await?
+            }
+            ContractEvent::AgentRegistered { did_hash, .. } => {
+                self.agent_registered(did_hash).await?
+            }
+            ContractEvent::EscrowCreated {
+                escrow_id,
+                amount,
+                client_did,
+                provider_did,
+                ..
+            } => {
+                self.escrow_created(
+                    escrow_id,
+                    &EscrowParties {
+                        client_did: *client_did,
+                        provider_did: *provider_did,
+                        amount: *amount,
+                    },
+                )?;
+                false
+            }
+            ContractEvent::DisputeInitiated { escrow_id, .. } => {
+                self.dispute_initiated(escrow_id)?
+            }
+            ContractEvent::EscrowReleased { escrow_id, .. }
+            | ContractEvent::EscrowRefunded { escrow_id, .. }
+            | ContractEvent::DisputeResolved { escrow_id, .. } => self.escrow_settled(escrow_id)?,
+            ContractEvent::AgentUpdated { .. } | ContractEvent::EscrowFunded { .. } => false,
+        };
+
+        let counter = if applied {
+            &self.stats.events_applied
+        } else {
+            &self.stats.events_ignored
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        Ok(applied)
+    }
+
+    /// Consume events until the channel closes.
+    ///
+    /// Errors are logged and counted; a failing event never stops the loop.
+    pub async fn run(&self, mut events: mpsc::Receiver<ContractEvent>) {
+        if let Err(e) = self.rebuild_index() {
+            warn!("Failed to build DID index for contract events: {}", e);
+        }
+
+        while let Some(event) = events.recv().await {
+            if let Err(e) = self.apply(&event).await {
+                self.stats.apply_errors.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    event = event.event_name(),
+                    tx_hash = %event.tx_hash(),
+                    "Failed to apply contract event: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    async fn update_trust<F>(&self, did_hash: &FixedBytes<32>, f: F) -> Result<bool>
+    where
+        F: FnOnce(&TrustService, &str) -> Result<()>,
+    {
+        let Some(ref trust) = self.trust else {
+            return Ok(false);
+        };
+        let Some(did) = self.resolve_or_skip(did_hash)? else {
+            return Ok(false);
+        };
+
+        f(trust, &did)?;
+        self.invalidate_trust(&did).await;
+        Ok(true)
+    }
+
+    /// Apply a stake change unless its log was applied before.
+    async fn apply_stake_once<F>(
+        &self,
+        tx_hash: &FixedBytes<32>,
+        log_index: u64,
+        did_hash: &FixedBytes<32>,
+        f: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce(&TrustService, &str) -> Result<()>,
+    {
+        let key = format!("{}{}/{}", APPLIED_STAKE_PREFIX, tx_hash, log_index);
+        if self.store.contains(&key)? {
+            debug!(%tx_hash, log_index, "Stake log already applied");
+            return Ok(false);
+        }
+
+        let applied = self.update_trust(did_hash, f).await?;
+        if applied {
+            self.store.put(&key, &[])?;
+        }
+        Ok(applied)
+    }
+
+    async fn endorsement_added(
+        &self,
+        endorser: &FixedBytes<32>,
+        endorsee: &FixedBytes<32>,
+    ) -> Result<bool> {
+        let Some(ref trust) = self.trust else {
+            return Ok(false);
+        };
+        let (Some(endorser), Some(endorsee)) = (
+            self.resolve_or_skip(endorser)?,
+            self.resolve_or_skip(endorsee)?,
+        ) else {
+            return Ok(false);
+        };
+
+        // On-chain endorsements are direct; replays must not double count
+        if trust.has_endorsement(&endorser, &endorsee)? {
+            return Ok(false);
+        }
+        trust
+            .add_endorsement_with_hop(&endorser, &endorsee, 1)
+            .await?;
+        self.invalidate_trust(&endorsee).await;
+        Ok(true)
+    }
+
+    async fn endorsement_revoked(
+        &self,
+        endorser: &FixedBytes<32>,
+        endorsee: &FixedBytes<32>,
+    ) -> Result<bool> {
+        let Some(ref trust) = self.trust else {
+            return Ok(false);
+        };
+        let (Some(endorser), Some(endorsee)) = (
+            self.resolve_or_skip(endorser)?,
+            self.resolve_or_skip(endorsee)?,
+        ) else {
+            return Ok(false);
+        };
+
+        let removed = trust.remove_endorsement(&endorser, &endorsee)?;
+        if removed {
+            self.invalidate_trust(&endorsee).await;
+        }
+        Ok(removed)
+    }
+
+    async fn agent_deactivated(&self, did_hash: &FixedBytes<32>) -> Result<bool> {
+        let Some(did) = self.resolve_or_skip(did_hash)? else {
+            return Ok(false);
+        };
+
+        let deactivated = match self.discovery {
+            Some(ref discovery) => discovery.deactivate(&did).await?,
+            None => false,
+        };
+        self.invalidate_trust(&did).await;
+        Ok(deactivated)
+    }
+
+    async fn agent_registered(&self, did_hash: &FixedBytes<32>) -> Result<bool> {
+        let Some(ref discovery) = self.discovery else {
+            return Ok(false);
+        };
+        let Some(did) = self.resolve_or_skip(did_hash)? else {
+            return Ok(false);
+        };
+
+        discovery.reactivate(&did).await
+    }
+
+    fn escrow_key(escrow_id: &U256) -> String {
+        format!("{}{}", ESCROW_PREFIX, escrow_id)
+    }
+
+    fn escrow_created(&self, escrow_id: &U256, parties: &EscrowParties) -> Result<()> {
+        let data = serde_json::to_vec(parties)?;
+        self.store.put(&Self::escrow_key(escrow_id), &data)
+    }
+
+    fn escrow_parties(&self, escrow_id: &U256) -> Result<Option<EscrowParties>> {
+        match self.store.get(&Self::escrow_key(escrow_id))? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn dispute_initiated(&self, escrow_id: &U256) -> Result<bool> {
+        let Some(ref arbitrator) = self.arbitrator else {
+            return Ok(false);
+        };
+        let Some(parties) = self.escrow_parties(escrow_id)? else {
+            debug!(%escrow_id, "Dispute for an escrow this node never saw created");
+            return Ok(false);
+        };
+        let (Some(client_did), Some(provider_did)) = (
+            self.resolve_or_skip(&parties.client_did)?,
+            self.resolve_or_skip(&parties.provider_did)?,
+        ) else {
+            return Ok(false);
+        };
+
+        let escrow_key = escrow_id.to_string();
+        if arbitrator
+            .get_disputes_by_escrow(&escrow_key)?
+            .iter()
+            .any(|dispute| dispute.state.is_active())
+        {
+            return Ok(false);
+        }
+
+        // Only Tier 2 amounts go to the AI arbitrator
+        match arbitrator.create_dispute(
+            escrow_key,
+            client_did,
+            provider_did,
+            parties.amount.saturating_to(),
+        ) {
+            Ok(dispute_id) => {
+                debug!(%escrow_id, %dispute_id, "Opened AI dispute for escrow");
+                Ok(true)
+            }
+            Err(e) => {
+                debug!(%escrow_id, "Escrow dispute not handled by AI arbitration: {}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    fn escrow_settled(&self, escrow_id: &U256) -> Result<bool> {
+        self.store.delete(&Self::escrow_key(escrow_id))?;
+
+        let Some(ref arbitrator) = self.arbitrator else {
+            return Ok(false);
+        };
+        let closed = arbitrator.close_disputes_for_escrow(&escrow_id.to_string())?;
+        Ok(!closed.is_empty())
+    }
+}
+
+// Ensure the sink can be shared with a spawned task
+static_assertions::assert_impl_all!(ContractEventSink: Send, Sync);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitration::AIDisputeState;
+    use crate::contract::TrustRegistryClient;
+    use crate::discovery::{AgoraMeshExtension, CapabilityCard};
+    use crate::persistence::CapabilityCardStore;
+    use alloy::primitives::Address;
+
+    const ALICE: &str = "did:agoramesh:base:alice";
+    const BOB: &str = "did:agoramesh:base:bob";
+
+    fn hash(did: &str) -> FixedBytes<32> {
+        TrustRegistryClient::did_to_hash(did)
+    }
+
+    fn card(did: &str) -> CapabilityCard {
+        CapabilityCard {
+            name: "Agent".to_string(),
+            description: "Test agent".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills: vec![],
+            authentication: None,
+            agoramesh: Some(AgoraMeshExtension {
+                did: did.to_string(),
+                trust_score: None,
+                stake: None,
+                pricing: None,
+                payment_methods: vec![],
+                version: None,
+                updated_at: None,
+                proof: None,
+            }),
+        }
+    }
+
+    fn trust_service() -> Arc<TrustService> {
+        Arc::new(TrustService::new(
+            "https://sepolia.base.org".to_string(),
+            None,
+        ))
+    }
+
+    fn trust_sink(trust: &Arc<TrustService>) -> ContractEventSink {
+        let sink = ContractEventSink::new().with_trust_service(trust.clone());
+        sink.index_did(ALICE).unwrap();
+        sink.index_did(BOB).unwrap();
+        sink
+    }
+
+    fn escrow_created(escrow_id: u64, amount: u64) -> ContractEvent {
+        ContractEvent::EscrowCreated {
+            escrow_id: U256::from(escrow_id),
+            client: Address::ZERO,
+            provider: Address::ZERO,
+            amount: U256::from(amount),
+            client_did: hash(ALICE),
+            provider_did: hash(BOB),
+            block_number: 1,
+            tx_hash: FixedBytes::ZERO,
+        }
+    }
+
+    fn dispute_initiated(escrow_id: u64) -> ContractEvent {
+        ContractEvent::DisputeInitiated {
+            escrow_id: U256::from(escrow_id),
+            initiator: Address::ZERO,
+            block_number: 2,
+            tx_hash: FixedBytes::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_follows_shared_index() {
+        let index = Arc::new(DidHashIndex::new());
+        let discovery = Arc::new(
+            DiscoveryService::new()
+                .with_required_card_signatures(false)
+                .with_did_index(index.clone()),
+        );
+        let sink = ContractEventSink::new()
+            .with_discovery(discovery.clone())
+            .with_did_index(index);
+        assert_eq!(sink.resolve_did(&hash(ALICE)).unwrap(), None);
+
+        // Registered after the miss was remembered
+        discovery.register(&card(ALICE)).await.unwrap();
+        assert_eq!(
+            sink.resolve_did(&hash(ALICE)).unwrap(),
+            Some(ALICE.to_string())
+        );
+
+        discovery.remove(ALICE).await.unwrap();
+        assert_eq!(sink.resolve_did(&hash(ALICE)).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_rescans_once_per_unknown_hash() {
+        let discovery = Arc::new(DiscoveryService::new().with_required_card_signatures(false));
+        let sink = ContractEventSink::new().with_discovery(discovery.clone());
+
+        // First miss rescans and finds cards the index was not told about
+        discovery.register(&card(ALICE)).await.unwrap();
+        assert_eq!(
+            sink.resolve_did(&hash(ALICE)).unwrap(),
+            Some(ALICE.to_string())
+        );
+
+        // A remembered miss is not rescanned
+        assert_eq!(sink.resolve_did(&hash(BOB)).unwrap(), None);
+        discovery.register(&card(BOB)).await.unwrap();
+        assert_eq!(sink.resolve_did(&hash(BOB)).unwrap(), None);
+
+        sink.index_did(BOB).unwrap();
+        assert_eq!(sink.resolve_did(&hash(BOB)).unwrap(), Some(BOB.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_reputation_updated_updates_trust() {
+        let trust = trust_service();
+        let sink = trust_sink(&trust);
+
+        let applied = sink
+            .apply(&ContractEvent::ReputationUpdated {
+                did_hash: hash(ALICE),
+                new_score: 8_000,
+                total_transactions: 10,
+                block_number: 1,
+                tx_hash: FixedBytes::ZERO,
+            })
+            .await
+            .unwrap();
+
+        assert!(applied);
+        let info = trust.get_trust(ALICE).await.unwrap();
+        assert_eq!(info.successful_transactions, 8);
+        assert_eq!(info.failed_transactions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stake_events_update_trust_and_invalidate_cache() {
+        let trust = trust_service();
+        let cache = Arc::new(TrustCache::with_defaults());
+        let sink = trust_sink(&trust).with_trust_cache(cache.clone());
+        cache
+            .insert(ALICE, trust.get_trust(ALICE).await.unwrap())
+            .await;
+
+        sink.apply(&ContractEvent::StakeDeposited {
+            did_hash: hash(ALICE),
+            amount: U256::from(3_000),
+            block_number: 1,
+            tx_hash: FixedBytes::ZERO,
+            log_index: 2,
+        })
+        .await
+        .unwrap();
+        sink.apply(&ContractEvent::StakeSlashed {
+            did_hash: hash(ALICE),
+            amount: U256::from(1_000),
+            reason: FixedBytes::ZERO,
+            block_number: 2,
+            tx_hash: FixedBytes::ZERO,
+            log_index: 3,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(trust.get_trust(ALICE).await.unwrap().stake_amount, 2_000);
+        assert!(cache.get(ALICE).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stake_log_applies_once_across_restarts() {
+        let trust = trust_service();
+        let applied: Arc<dyn Store> = Arc::new(MemoryStore::new());
+        let deposit = ContractEvent::StakeDeposited {
+            did_hash: hash(ALICE),
+            amount: U256::from(3_000),
+            block_number: 1,
+            tx_hash: FixedBytes::repeat_byte(0x01),
+            log_index: 0,
+        };
+
+        let sink = trust_sink(&trust).with_event_store(applied.clone());
+        assert!(sink.apply(&deposit).await.unwrap());
+        assert!(!sink.apply(&deposit).await.unwrap());
+
+        // The backfill after a restart delivers the same log again
+        let restarted = trust_sink(&trust).with_event_store(applied);
+        assert!(!restarted.apply(&deposit).await.unwrap());
+
+        assert_eq!(trust.get_trust(ALICE).await.unwrap().stake_amount, 3_000);
+    }
+
+    #[tokio::test]
+    async fn test_endorsement_events_are_idempotent() {
+        let trust = trust_service();
+        let sink = trust_sink(&trust);
+        let added = ContractEvent::EndorsementAdded {
+            endorser: hash(ALICE),
+            endorsee: hash(BOB),
+            message: "great work".to_string(),
+            block_number: 1,
+            tx_hash: FixedBytes::ZERO,
+        };
+
+        assert!(sink.apply(&added).await.unwrap());
+        assert!(!sink.apply(&added).await.unwrap());
+        assert_eq!(trust.get_trust(BOB).await.unwrap().endorsement_count, 1);
+
+        let revoked = ContractEvent::EndorsementRevoked {
+            endorser: hash(ALICE),
+            endorsee: hash(BOB),
+            block_number: 2,
+            tx_hash: FixedBytes::ZERO,
+        };
+        assert!(sink.apply(&revoked).await.unwrap());
+        assert!(!trust.has_endorsement(ALICE, BOB).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_did_is_ignored() {
+        let trust = trust_service();
+        let sink = trust_sink(&trust);
+
+        let applied = sink
+            .apply(&ContractEvent::StakeDeposited {
+                did_hash: FixedBytes::repeat_byte(0x42),
+                amount: U256::from(1),
+                block_number: 1,
+                tx_hash: FixedBytes::ZERO,
+                log_index: 4,
+            })
+            .await
+            .unwrap();
+
+        assert!(!applied);
+        assert_eq!(sink.stats().unresolved_dids.load(Ordering::Relaxed), 1);
+        assert_eq!(sink.stats().events_ignored.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_agent_deactivated_hides_card_until_registered_again() {
+        let cards = CapabilityCardStore::new(Arc::new(MemoryStore::new()));
+        let discovery = Arc::new(
+            DiscoveryService::new()
+                .with_required_card_signatures(false)
+                .with_card_store(cards.clone()),
+        );
+        discovery.register(&card(ALICE)).await.unwrap();
+        let sink = ContractEventSink::new().with_discovery(discovery.clone());
+        let deactivated = ContractEvent::AgentDeactivated {
+            did_hash: hash(ALICE),
+            block_number: 1,
+            tx_hash: FixedBytes::ZERO,
+        };
+
+        assert!(sink.apply(&deactivated).await.unwrap());
+        assert!(!sink.apply(&deactivated).await.unwrap());
+
+        assert!(discovery.get(ALICE).await.unwrap().is_none());
+        assert_eq!(discovery.cache_size(), 0);
+        assert!(discovery.register(&card(ALICE)).await.is_err());
+        assert!(cards.get(ALICE).unwrap().is_some(), "Card must be kept");
+        assert_eq!(
+            sink.resolve_did(&hash(ALICE)).unwrap().as_deref(),
+            Some(ALICE)
+        );
+
+        let registered = ContractEvent::AgentRegistered {
+            did_hash: hash(ALICE),
+            owner: Address::ZERO,
+            capability_card_cid: String::new(),
+            block_number: 2,
+            tx_hash: FixedBytes::ZERO,
+        };
+        assert!(sink.apply(&registered).await.unwrap());
+        assert!(discovery.get(ALICE).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deactivation_survives_restart() {
+        let cards = CapabilityCardStore::new(Arc::new(MemoryStore::new()));
+        let discovery = Arc::new(
+            DiscoveryService::new()
+                .with_required_card_signatures(false)
+                .with_card_store(cards.clone()),
+        );
+        discovery.register(&card(ALICE)).await.unwrap();
+        discovery.deactivate(ALICE).await.unwrap();
+
+        let restarted = DiscoveryService::new()
+            .with_required_card_signatures(false)
+            .with_card_store(cards);
+        assert_eq!(restarted.rehydrate().await.unwrap(), 0);
+
+        assert!(restarted.is_inactive(ALICE).unwrap());
+        assert!(restarted.get(ALICE).await.unwrap().is_none());
+        assert!(restarted.known_dids().unwrap().contains(&ALICE.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_dispute_lifecycle_follows_escrow_events() {
+        let arbitrator = Arc::new(AIArbitrator::disabled());
+        let sink = trust_sink(&trust_service()).with_arbitrator(arbitrator.clone());
+
+        sink.apply(&escrow_created(7, 100_000_000)).await.unwrap();
+        assert!(sink.apply(&dispute_initiated(7)).await.unwrap());
+        assert!(
+            !sink.apply(&dispute_initiated(7)).await.unwrap(),
+            "Replayed dispute must not open a second case"
+        );
+
+        let disputes = arbitrator.get_disputes_by_escrow("7").unwrap();
+        assert_eq!(disputes.len(), 1);
+        assert_eq!(disputes[0].client_did, ALICE);
+        assert_eq!(disputes[0].provider_did, BOB);
+        assert_eq!(disputes[0].amount_usdc, 100_000_000);
+
+        let released = ContractEvent::EscrowReleased {
+            escrow_id: U256::from(7),
+            block_number: 3,
+            tx_hash: FixedBytes::ZERO,
+        };
+        assert!(sink.apply(&released).await.unwrap());
+        assert_eq!(
+            arbitrator.get_dispute(&disputes[0].id).unwrap().state,
+            AIDisputeState::Resolved
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escrow_parties_survive_restart_until_settled() {
+        let events: Arc<dyn Store> = Arc::new(MemoryStore::new());
+        let arbitrator = Arc::new(AIArbitrator::disabled());
+        trust_sink(&trust_service())
+            .with_event_store(events.clone())
+            .apply(&escrow_created(9, 100_000_000))
+            .await
+            .unwrap();
+
+        // The listener resumes from its checkpoint after the restart
+        let restarted = trust_sink(&trust_service())
+            .with_arbitrator(arbitrator.clone())
+            .with_event_store(events.clone());
+        assert!(restarted.apply(&dispute_initiated(9)).await.unwrap());
+        assert_eq!(arbitrator.get_disputes_by_escrow("9").unwrap().len(), 1);
+
+        let refunded = ContractEvent::EscrowRefunded {
+            escrow_id: U256::from(9),
+            block_number: 3,
+            tx_hash: FixedBytes::ZERO,
+        };
+        restarted.apply(&refunded).await.unwrap();
+        assert!(events.iter_prefix(ESCROW_PREFIX).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dispute_outside_tier_2_is_ignored() {
+        let arbitrator = Arc::new(AIArbitrator::disabled());
+        let sink = trust_sink(&trust_service()).with_arbitrator(arbitrator.clone());
+
+        // $5,000 escrow belongs to Tier 3
+        sink.apply(&escrow_created(8, 5_000_000_000)).await.unwrap();
+
+        assert!(!sink.apply(&dispute_initiated(8)).await.unwrap());
+        assert!(arbitrator.get_disputes_by_escrow("8").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_applies_events_from_channel() {
+        let trust = trust_service();
+        let sink = trust_sink(&trust);
+        let (tx, rx) = mpsc::channel(4);
+
+        tx.send(ContractEvent::StakeDeposited {
+            did_hash: hash(BOB),
+            amount: U256::from(500),
+            block_number: 1,
+            tx_hash: FixedBytes::ZERO,
+            log_index: 5,
+        })
+        .await
+        .unwrap();
+        drop(tx);
+        sink.run(rx).await;
+
+        assert_eq!(trust.get_trust(BOB).await.unwrap().stake_amount, 500);
+        assert_eq!(sink.stats().events_applied.load(Ordering::Relaxed), 1);
+    }
+}
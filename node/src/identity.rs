@@ -0,0 +1,591 @@
+//! Persistent node identity.
+//!
+//! This module provides:
+//! - Loading or generating the node's Ed25519 key at `IdentityConfig.key_file`
+//! - Optional passphrase encryption (Argon2id + ChaCha20-Poly1305)
+//! - Owner-only file permissions on Unix
+//! - Derivation of the libp2p identity and the node's `did:agoramesh` DID Document
+//!
+//! ## Key File Format
+//!
+//! The key is stored as JSON so the format can evolve:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "key_type": "ed25519",
+//!   "secret": "<hex secret key, or hex ciphertext when encrypted>",
+//!   "encryption": {
+//!     "kdf": "argon2id", "cipher": "chacha20poly1305",
+//!     "salt": "<hex>", "nonce": "<hex>",
+//!     "m_cost": 19456, "t_cost": 2, "p_cost": 1
+//!   }
+//! }
+//! ```
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use alloy::hex;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use libp2p::identity::{self, ed25519};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::did::{encode_ed25519_multibase, DIDDocument, DIDDocumentBuilder, DID_METHOD};
+use crate::error::{Error, Result};
+use crate::multichain::get_chain_info;
+use crate::network::EnvelopeSigner;
+
+/// Current key file format version.
+const KEY_FILE_VERSION: u8 = 1;
+
+/// Key type stored in the key file.
+const KEY_TYPE_ED25519: &str = "ed25519";
+
+/// Key derivation function used for encrypted key files.
+const KDF_ARGON2ID: &str = "argon2id";
+
+/// Cipher used for encrypted key files.
+const CIPHER_CHACHA20POLY1305: &str = "chacha20poly1305";
+
+/// Salt length for passphrase key derivation.
+const SALT_LEN: usize = 16;
+
+/// Verification method ID of the node key in its DID Document.
+pub const NODE_KEY_ID: &str = "key-1";
+
+/// On-disk representation of the node key.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    version: u8,
+    key_type: String,
+    secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<KeyEncryption>,
+}
+
+/// Parameters needed to decrypt an encrypted key file.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyEncryption {
+    kdf: String,
+    cipher: String,
+    salt: String,
+    nonce: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+/// The node's long-lived Ed25519 identity.
+///
+/// The same key backs the libp2p `PeerId` and the node's DID Document, so
+/// restarting the node keeps its peer ID, DHT records and peer scores.
+#[derive(Clone)]
+pub struct NodeIdentity {
+    keypair: ed25519::Keypair,
+}
+
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeIdentity")
+            .field("peer_id", &self.peer_id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl NodeIdentity {
+    /// Generate a fresh, unsaved identity.
+    pub fn generate() -> Self {
+        Self {
+            keypair: ed25519::Keypair::generate(),
+        }
+    }
+
+    /// Wrap an existing Ed25519 keypair.
+    pub fn from_keypair(keypair: ed25519::Keypair) -> Self {
+        Self { keypair }
+    }
+
+    /// Load the identity at `path`, generating and saving a new one if the
+    /// file does not exist.
+    ///
+    /// When `passphrase` is set, newly generated keys are encrypted with it
+    /// and existing encrypted keys are decrypted with it.
+    pub fn load_or_generate(path: impl AsRef<Path>, passphrase: Option<&str>) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Self::load(path, passphrase);
+        }
+
+        let identity = Self::generate();
+        identity.save(path, passphrase)?;
+        tracing::info!(
+            "Generated new node identity {} at {}",
+            identity.peer_id(),
+            path.display()
+        );
+        Ok(identity)
+    }
+
+    /// Load the identity from an existing key file.
+    pub fn load(path: impl AsRef<Path>, passphrase: Option<&str>) -> Result<Self> {
+        let path = path.as_ref();
+        warn_if_insecure_permissions(path);
+
+        let contents = fs::read_to_string(path)?;
+        let file: KeyFile = serde_json::from_str(&contents)
+            .map_err(|e| Error::Did(format!("Invalid key file {}: {}", path.display(), e)))?;
+
+        if file.version != KEY_FILE_VERSION {
+            return Err(Error::Did(format!(
+                "Unsupported key file version {} (expected {})",
+                file.version, KEY_FILE_VERSION
+            )));
+        }
+        if file.key_type != KEY_TYPE_ED25519 {
+            return Err(Error::Did(format!(
+                "Unsupported key type '{}' (expected '{}')",
+                file.key_type, KEY_TYPE_ED25519
+            )));
+        }
+
+        let secret = decode_hex("secret", &file.secret)?;
+        let mut secret = match (&file.encryption, passphrase) {
+            (Some(encryption), Some(passphrase)) => decrypt(encryption, &secret, passphrase)?,
+            (Some(_), None) => {
+                return Err(Error::Did(format!(
+                    "Key file {} is encrypted but no passphrase was provided",
+                    path.display()
+                )))
+            }
+            (None, passphrase) => {
+                if passphrase.is_some() {
+                    tracing::warn!(
+                        "Key file {} is not encrypted; ignoring the configured passphrase",
+                        path.display()
+                    );
+                }
+                secret
+            }
+        };
+
+        let secret = ed25519::SecretKey::try_from_bytes(&mut secret)
+            .map_err(|e| Error::Did(format!("Invalid Ed25519 secret key: {}", e)))?;
+        Ok(Self {
+            keypair: ed25519::Keypair::from(secret),
+        })
+    }
+
+    /// Save the identity to `path`, refusing to overwrite an existing file.
+    ///
+    /// On Unix the file is created with mode `0600`.
+    pub fn save(&self, path: impl AsRef<Path>, passphrase: Option<&str>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let secret = self.keypair.secret();
+        let file = match passphrase {
+            Some(passphrase) => {
+                let (encryption, ciphertext) = encrypt(secret.as_ref(), passphrase)?;
+                KeyFile {
+                    version: KEY_FILE_VERSION,
+                    key_type: KEY_TYPE_ED25519.to_string(),
+                    secret: hex::encode(ciphertext),
+                    encryption: Some(encryption),
+                }
+            }
+            None => KeyFile {
+                version: KEY_FILE_VERSION,
+                key_type: KEY_TYPE_ED25519.to_string(),
+                secret: hex::encode(secret.as_ref()),
+                encryption: None,
+            },
+        };
+        let contents = serde_json::to_string_pretty(&file)?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut out = options.open(path)?;
+        out.write_all(contents.as_bytes())?;
+        out.sync_all()?;
+        Ok(())
+    }
+
+    /// The underlying Ed25519 keypair (used for signing gossip envelopes).
+    pub fn ed25519_keypair(&self) -> &ed25519::Keypair {
+        &self.keypair
+    }
+
+    /// The node's Ed25519 public key.
+    pub fn public_key(&self) -> ed25519::PublicKey {
+        self.keypair.public()
+    }
+
+    /// The libp2p identity keypair derived from the node key.
+    pub fn libp2p_keypair(&self) -> identity::Keypair {
+        identity::Keypair::from(self.keypair.clone())
+    }
+
+    /// The libp2p peer ID derived from the node key.
+    pub fn peer_id(&self) -> PeerId {
+        self.libp2p_keypair().public().to_peer_id()
+    }
+
+    /// Default DID for this identity: `did:agoramesh:{chain}:{peer_id}`.
+    pub fn default_did(&self, chain_id: u64) -> String {
+        format!(
+            "did:{}:{}:{}",
+            DID_METHOD,
+            chain_name(chain_id),
+            self.peer_id()
+        )
+    }
+
+    /// Signer for outbound gossip as `did`, using the node key.
+    ///
+    /// Peers verify the envelopes against the document from
+    /// [`Self::did_document`].
+    pub fn envelope_signer(&self, did: &str) -> EnvelopeSigner {
+        EnvelopeSigner::new(
+            did,
+            &format!("{}#{}", did, NODE_KEY_ID),
+            self.keypair.clone(),
+        )
+    }
+
+    /// Build the DID Document for `did`, controlled by this node key.
+    ///
+    /// When `service_url` is set, A2A and capability card services pointing
+    /// at the node's public URL are included.
+    pub fn did_document(
+        &self,
+        did: &str,
+        chain_id: u64,
+        service_url: Option<&str>,
+    ) -> Result<DIDDocument> {
+        let (_, chain, identifier) = DIDDocument::parse_did(did)?;
+        let mut builder = DIDDocumentBuilder::new(&chain, &identifier)
+            .add_ed25519_key(NODE_KEY_ID, &encode_ed25519_multibase(&self.public_key()))
+            .chain_id(chain_id);
+
+        if let Some(url) = service_url {
+            let url = url.trim_end_matches('/');
+            builder = builder
+                .add_a2a_service(url)
+                .add_capability_card_service(&format!("{}/.well-known/agent.json", url));
+        }
+
+        builder.build()
+    }
+}
+
+/// DID chain segment for a chain ID, e.g. `base-sepolia` for 84532.
+fn chain_name(chain_id: u64) -> String {
+    match get_chain_info(chain_id) {
+        Some(info) => info
+            .name
+            .trim_end_matches(" Mainnet")
+            .to_lowercase()
+            .replace(' ', "-"),
+        None => format!("eip155-{}", chain_id),
+    }
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+    hex::decode(value).map_err(|e| Error::Did(format!("Invalid key file {}: {}", field, e)))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: Params) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| Error::Did(format!("Failed to derive key from passphrase: {}", e)))?;
+    Ok(key)
+}
+
+fn encrypt(secret: &[u8], passphrase: &str) -> Result<(KeyEncryption, Vec<u8>)> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let params = Params::default();
+    let key = derive_key(passphrase, &salt, params.clone())?;
+
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, secret)
+        .map_err(|e| Error::Did(format!("Failed to encrypt node key: {}", e)))?;
+
+    let encryption = KeyEncryption {
+        kdf: KDF_ARGON2ID.to_string(),
+        cipher: CIPHER_CHACHA20POLY1305.to_string(),
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        m_cost: params.m_cost(),
+        t_cost: params.t_cost(),
+        p_cost: params.p_cost(),
+    };
+    Ok((encryption, ciphertext))
+}
+
+fn decrypt(encryption: &KeyEncryption, ciphertext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if encryption.kdf != KDF_ARGON2ID || encryption.cipher != CIPHER_CHACHA20POLY1305 {
+        return Err(Error::Did(format!(
+            "Unsupported key encryption: kdf '{}', cipher '{}'",
+            encryption.kdf, encryption.cipher
+        )));
+    }
+
+    let salt = decode_hex("salt", &encryption.salt)?;
+    let nonce = decode_hex("nonce", &encryption.nonce)?;
+    if nonce.len() != 12 {
+        return Err(Error::Did(format!(
+            "Invalid key file nonce length: {}",
+            nonce.len()
+        )));
+    }
+    let params = Params::new(
+        encryption.m_cost,
+        encryption.t_cost,
+        encryption.p_cost,
+        Some(32),
+    )
+    .map_err(|e| Error::Did(format!("Invalid key derivation parameters: {}", e)))?;
+    let key = derive_key(passphrase, &salt, params)?;
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext)
+        .map_err(|_| Error::Did("Failed to decrypt node key: wrong passphrase?".to_string()))
+}
+
+#[cfg(unix)]
+fn warn_if_insecure_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        let mode = metadata.permissions().mode();
+        if mode & 0o077 != 0 {
+            tracing::warn!(
+                "Key file {} is accessible by other users (mode {:o}); run `chmod 600` on it",
+                path.display(),
+                mode & 0o777
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_insecure_permissions(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // ========== TDD Tests: Key File Lifecycle ==========
+
+    #[test]
+    fn test_load_or_generate_creates_key_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("keys").join("node.key");
+
+        let identity = NodeIdentity::load_or_generate(&path, None).unwrap();
+
+        assert!(path.exists(), "Key file should be created");
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("\"key_type\": \"ed25519\""));
+        assert!(!contents.contains("encryption"));
+        assert!(contents.contains(&hex::encode(identity.ed25519_keypair().secret().as_ref())));
+    }
+
+    #[test]
+    fn test_load_or_generate_is_stable_across_restarts() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+
+        let first = NodeIdentity::load_or_generate(&path, None).unwrap();
+        let second = NodeIdentity::load_or_generate(&path, None).unwrap();
+
+        assert_eq!(first.peer_id(), second.peer_id());
+        assert_eq!(first.public_key(), second.public_key());
+    }
+
+    #[test]
+    fn test_save_refuses_to_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        NodeIdentity::generate().save(&path, None).unwrap();
+
+        assert!(NodeIdentity::generate().save(&path, None).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        NodeIdentity::generate().save(&path, None).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_load_rejects_malformed_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(
+            NodeIdentity::load(&path, None),
+            Err(Error::Did(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_version() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        fs::write(
+            &path,
+            r#"{"version": 99, "key_type": "ed25519", "secret": "00"}"#,
+        )
+        .unwrap();
+
+        let err = NodeIdentity::load(&path, None).unwrap_err();
+        assert!(err.to_string().contains("version"));
+    }
+
+    // ========== TDD Tests: Passphrase Encryption ==========
+
+    #[test]
+    fn test_encrypted_key_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+
+        let identity = NodeIdentity::load_or_generate(&path, Some("hunter2")).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("argon2id"));
+        assert!(
+            !contents.contains(&hex::encode(identity.ed25519_keypair().secret().as_ref())),
+            "Secret must not be stored in plaintext"
+        );
+
+        let reloaded = NodeIdentity::load(&path, Some("hunter2")).unwrap();
+        assert_eq!(identity.peer_id(), reloaded.peer_id());
+    }
+
+    #[test]
+    fn test_encrypted_key_rejects_wrong_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        NodeIdentity::generate()
+            .save(&path, Some("correct"))
+            .unwrap();
+
+        let err = NodeIdentity::load(&path, Some("wrong")).unwrap_err();
+        assert!(err.to_string().contains("decrypt"));
+    }
+
+    #[test]
+    fn test_encrypted_key_requires_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.key");
+        NodeIdentity::generate()
+            .save(&path, Some("correct"))
+            .unwrap();
+
+        let err = NodeIdentity::load(&path, None).unwrap_err();
+        assert!(err.to_string().contains("passphrase"));
+    }
+
+    // ========== TDD Tests: Derived Identities ==========
+
+    #[test]
+    fn test_libp2p_identity_matches_node_key() {
+        let identity = NodeIdentity::generate();
+        let libp2p_key = identity.libp2p_keypair();
+
+        let public = libp2p_key.public().try_into_ed25519().unwrap();
+        assert_eq!(public, identity.public_key());
+        assert_eq!(libp2p_key.public().to_peer_id(), identity.peer_id());
+    }
+
+    #[test]
+    fn test_default_did_uses_chain_name_and_peer_id() {
+        let identity = NodeIdentity::generate();
+
+        assert_eq!(
+            identity.default_did(84532),
+            format!("did:agoramesh:base-sepolia:{}", identity.peer_id())
+        );
+        assert_eq!(
+            identity.default_did(8453),
+            format!("did:agoramesh:base:{}", identity.peer_id())
+        );
+        assert!(identity.default_did(999_999).contains(":eip155-999999:"));
+    }
+
+    #[test]
+    fn test_did_document_carries_node_key() {
+        let identity = NodeIdentity::generate();
+        let did = identity.default_did(84532);
+
+        let doc = identity
+            .did_document(&did, 84532, Some("https://node.example.com/"))
+            .unwrap();
+
+        assert_eq!(doc.id, did);
+        let method = doc
+            .find_verification_method(&format!("{}#{}", did, NODE_KEY_ID))
+            .expect("node key should be a verification method");
+        assert_eq!(
+            method.public_key_multibase.as_deref(),
+            Some(encode_ed25519_multibase(&identity.public_key()).as_str())
+        );
+        assert_eq!(doc.a2a_endpoint(), Some("https://node.example.com"));
+        assert_eq!(
+            doc.capability_card_url(),
+            Some("https://node.example.com/.well-known/agent.json")
+        );
+
+        // Signatures made with the node key verify against the document
+        let signature = identity.ed25519_keypair().sign(b"hello");
+        method.verify(b"hello", &signature).unwrap();
+    }
+
+    #[test]
+    fn test_envelope_signer_verifies_against_did_document() {
+        let identity = NodeIdentity::generate();
+        let did = identity.default_did(8453);
+        let doc = identity.did_document(&did, 8453, None).unwrap();
+
+        let sealed = identity
+            .envelope_signer(&did)
+            .seal("/agoramesh/discovery/1.0.0", b"{}")
+            .unwrap();
+
+        let envelope: crate::network::SignedEnvelope = serde_json::from_slice(&sealed).unwrap();
+        envelope.verify("/agoramesh/discovery/1.0.0", &doc).unwrap();
+    }
+
+    #[test]
+    fn test_did_document_rejects_invalid_did() {
+        let identity = NodeIdentity::generate();
+
+        assert!(identity.did_document("not-a-did", 84532, None).is_err());
+    }
+}
@@ -12,6 +12,7 @@ pub mod did;
 pub mod discovery;
 pub mod error;
 pub mod events;
+pub mod identity;
 pub mod metrics;
 pub mod multichain;
 pub mod network;
@@ -27,7 +28,8 @@ pub use arbitration::{
     determine_tier, AIArbitrationConfig, AIArbitrationStats, AIArbitrator, AIDispute,
     AIDisputeState, AIRuling, AppealPeriod, DisputeStatus, DisputeTier, Evidence, EvidenceType,
     Juror, JurorPool, JurorPoolConfig, JurorPoolStats, JurorStatus, JurorVote, KlerosClient,
-    KlerosConfig, KlerosDispute, KlerosStats, Ruling, VotingSession, VotingState, TIER_1_MAX_USDC,
+    KlerosConfig, KlerosDispute, KlerosStats, LlmRulingConfig, LlmRulingEngine, Ruling,
+    RulingAudit, RulingEngine, RulingEngineConfig, VotingSession, VotingState, TIER_1_MAX_USDC,
     TIER_2_MAX_USDC, TIER_3_MIN_USDC,
 };
 pub use circuit_breaker::{
@@ -35,24 +37,34 @@ pub use circuit_breaker::{
     CircuitResult, CircuitState, DegradationStrategy, DegradedResult, ResilientCircuitBreaker,
 };
 pub use config::{ApiConfig, NetworkConfig, NodeConfig};
-pub use contract::TrustRegistryClient;
-pub use discovery::{Capability, CapabilityCard, DiscoveryService, Skill};
+pub use contract::{DidHashIndex, TrustRegistryClient};
+pub use discovery::{
+    Capability, CapabilityCard, CardProof, DiscoveryPage, DiscoveryQuery, DiscoveryService,
+    NetworkSearchConfig, QuerySort, SchemaMatchQuery, SchemaValidators, SearchScope, Skill,
+    SkillMatch,
+};
 pub use error::{Error, Result};
 pub use events::{
-    ContractEvent, EventListener, EventListenerConfig, EventListenerStats, ReconnectConfig,
+    ContractEvent, ContractEventSink, ContractEventSinkStats, EventListener, EventListenerConfig,
+    EventListenerStats, ReconnectConfig,
 };
+pub use identity::NodeIdentity;
 pub use metrics::{
     metrics_middleware, InFlightGuard, MetricNames, MetricsConfig, MetricsService, Timer,
 };
 pub use multichain::{ChainConfig, ChainInfo, MultiChainClient, MultiChainConfig};
-pub use network::{validate_network_config, NetworkEvent, NetworkManager, SwarmCommand};
+pub use network::{
+    validate_network_config, DhtRecordStore, MessageHandler, NatConfig, NatState, NetworkEvent,
+    NetworkManager, PrivateMeshConfig, Reachability, SearchRequest, SearchResponse, SwarmCommand,
+};
 pub use persistence::{PersistenceConfig, PersistenceManager};
 pub use rate_limit::{
     headers as rate_limit_headers, RateLimitConfig, RateLimitLayer, RateLimitResult,
     RateLimitService,
 };
 pub use search::{
-    EmbeddingService, EmbeddingServiceConfig, HybridSearch, HybridSearchConfig, SearchResult,
+    EmbeddingService, EmbeddingServiceConfig, HybridSearch, HybridSearchConfig, RankingWeights,
+    SearchConfig, SearchResult, TrustRanker, VectorFilter, VectorIndexConfig,
 };
 pub use trust::TrustService;
 pub use trust_cache::{CachedTrustInfo, TrustCache, TrustCacheConfig, TrustCacheStats};
@@ -6,17 +6,25 @@ use clap::{Parser, Subcommand};
 use std::env;
 use std::path::Path;
 use tokio::signal;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use agoramesh_node::did::{
+    did_document_key, AgoraMeshDidResolver, CompositeDidResolver, DidCacheConfig,
+    DidDocumentRecord, InMemoryDidResolver, KeyDidResolver, WebDidResolver,
+};
+use agoramesh_node::search::{QdrantConfig, VectorIndex};
 use agoramesh_node::{
-    validate_network_config, ApiServer, AppState, DiscoveryService, EmbeddingService, HybridSearch,
-    MetricsConfig, MetricsService, NetworkConfig, NetworkManager, NodeConfig, RateLimitConfig,
-    RateLimitService, Result, TrustService,
+    validate_network_config, AIArbitrationConfig, AIArbitrator, ApiServer, AppState,
+    ContractEventSink, DhtRecordStore, DidHashIndex, DiscoveryService, EmbeddingService,
+    EventListener, EventListenerConfig, HybridSearch, HybridSearchConfig, MessageHandler,
+    MetricsConfig, MetricsService, NatState, NetworkConfig, NetworkManager, NodeConfig,
+    NodeIdentity, PersistenceManager, RateLimitConfig, RateLimitService, Result, SwarmCommand,
+    TrustCache, TrustRanker, TrustRegistryClient, TrustService, VectorIndexConfig,
 };
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Health response from the API.
@@ -186,6 +194,25 @@ fn env_csv(name: &str) -> Option<Vec<String>> {
     }
 }
 
+/// Build a periodic timer whose first tick fires after one full period.
+fn maintenance_timer(period: Option<Duration>) -> Option<tokio::time::Interval> {
+    period.map(|period| {
+        let mut timer = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        timer
+    })
+}
+
+/// Wait for the next tick of an optional timer (forever if disabled).
+async fn next_tick(timer: &mut Option<tokio::time::Interval>) {
+    match timer {
+        Some(timer) => {
+            timer.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
 fn normalize_token(value: Option<String>) -> Option<String> {
     value.and_then(|token| {
         let trimmed = token.trim();
@@ -220,6 +247,24 @@ fn apply_env_overrides(config: &mut NodeConfig) {
     if let Some(bootstrap_peers) = env_csv("AGORAMESH_P2P_BOOTSTRAP") {
         config.network.bootstrap_peers = bootstrap_peers;
     }
+    if let Some(mdns) = env_bool("AGORAMESH_P2P_MDNS") {
+        config.network.mdns = mdns;
+    }
+    if let Some(psk_file) = env_string("AGORAMESH_P2P_PSK_FILE") {
+        config.network.private.psk_file = Some(psk_file);
+    }
+    if let Some(allowed_peers) = env_csv("AGORAMESH_P2P_ALLOWED_PEERS") {
+        config.network.private.allowed_peers = allowed_peers;
+    }
+    if let Some(denied_peers) = env_csv("AGORAMESH_P2P_DENIED_PEERS") {
+        config.network.private.denied_peers = denied_peers;
+    }
+    if let Some(required) = env_bool("AGORAMESH_REQUIRE_SIGNED_MESSAGES") {
+        config.trust.require_signed_messages = required;
+    }
+    if let Some(required) = env_bool("AGORAMESH_REQUIRE_SIGNED_CARDS") {
+        config.trust.require_signed_cards = required;
+    }
 
     if let Some(chain_rpc) = env_string("AGORAMESH_CHAIN_RPC") {
         config.blockchain.rpc_url = chain_rpc;
@@ -233,12 +278,36 @@ fn apply_env_overrides(config: &mut NodeConfig) {
     if let Some(escrow_address) = env_string("AGORAMESH_ESCROW_ADDRESS") {
         config.blockchain.escrow_address = Some(escrow_address);
     }
+    if let Some(ws_url) = env_string("AGORAMESH_CHAIN_WS") {
+        config.blockchain.ws_url = Some(ws_url);
+    }
+
+    if let Some(url) = env_string("AGORAMESH_QDRANT_URL") {
+        match &mut config.search.vector_index {
+            VectorIndexConfig::Qdrant(qdrant) => qdrant.url = url,
+            index => *index = VectorIndexConfig::Qdrant(QdrantConfig::new(url)),
+        }
+    }
+    if let VectorIndexConfig::Qdrant(qdrant) = &mut config.search.vector_index {
+        if let Some(collection) = env_string("AGORAMESH_QDRANT_COLLECTION") {
+            qdrant.collection = collection;
+        }
+        if let Some(api_key) = env_string("AGORAMESH_QDRANT_API_KEY") {
+            qdrant.api_key = Some(api_key);
+        }
+    }
 
     if let Some(data_dir) = env_string("AGORAMESH_DATA_DIR") {
         config.persistence.data_dir = data_dir;
     }
+    if let Some(enabled) = env_bool("AGORAMESH_PERSISTENCE_ENABLED") {
+        config.persistence.enabled = enabled;
+    }
 
     // Node identity overrides
+    if let Some(key_file) = env_string("AGORAMESH_KEY_FILE") {
+        config.identity.key_file = key_file;
+    }
     if let Some(did) = env_string("AGORAMESH_NODE_DID") {
         config.identity.did = Some(did);
     }
@@ -309,6 +378,9 @@ async fn main() -> Result<()> {
                 listen_addresses,
                 bootstrap_peers: config.network.bootstrap_peers.clone(),
                 max_connections: config.network.max_connections,
+                nat: config.network.nat.clone(),
+                mdns: config.network.mdns,
+                private: config.network.private.clone(),
             };
 
             info!(
@@ -318,20 +390,94 @@ async fn main() -> Result<()> {
             info!("API address: {}", api_addr);
             validate_network_config(&network_config)?;
 
-            // 2. Initialize P2P network
+            // 2. Load (or create) the persistent node key and initialize P2P network
+            let passphrase = env_string("AGORAMESH_KEY_PASSPHRASE");
+            let identity =
+                NodeIdentity::load_or_generate(&config.identity.key_file, passphrase.as_deref())?;
+            let node_did = match config.identity.did.clone() {
+                Some(did) => did,
+                None => {
+                    let did = identity.default_did(config.blockchain.chain_id);
+                    config.identity.did = Some(did.clone());
+                    did
+                }
+            };
+            let did_document = identity.did_document(
+                &node_did,
+                config.blockchain.chain_id,
+                config.node_info.url.as_deref(),
+            )?;
+            info!("Node DID: {}", node_did);
+
+            // 3. Open durable storage for cards, embeddings, trust data and
+            // the DHT records this node hosts
+            let persistence = Arc::new(PersistenceManager::new(config.persistence.clone())?);
+
+            let mut record_store = DhtRecordStore::new(identity.peer_id(), &config.persistence);
+            if let Some(dht_store) = persistence.dht_records() {
+                record_store = record_store.with_backing(dht_store);
+            }
+
             info!("Initializing P2P network...");
-            let mut network = NetworkManager::new(network_config)?;
+            let mut network = NetworkManager::with_record_store(
+                network_config,
+                identity.libp2p_keypair(),
+                record_store,
+                Some(identity.envelope_signer(&node_did)),
+            )?;
             info!("Network started with peer ID: {}", network.local_peer_id());
 
-            // 3. Take event receiver for processing network events
+            // 4. Take event receiver for processing network events
             let mut event_rx = network.take_event_receiver();
 
-            // 4. Initialize semantic search if enabled
+            // Maps contract DID hashes back to DIDs for chain event sync
+            let did_index = Arc::new(DidHashIndex::new());
+
+            let mut trust = TrustService::new("https://sepolia.base.org".to_string(), None)
+                .with_did_index(did_index.clone());
+            if let Some(trust_store) = persistence.trust_data() {
+                trust = trust.with_trust_store(trust_store.clone());
+            }
+            let trust = Arc::new(trust);
+
+            // Search results are re-ranked with live trust, looked up in batches
+            let trust_cache = Arc::new(TrustCache::with_defaults());
+            let trust_ranker = TrustRanker::new(trust.clone(), trust_cache.clone());
+
+            // 5. Initialize semantic search if enabled
+            let mut vector_index: Option<Arc<dyn VectorIndex>> = None;
+            if let VectorIndexConfig::Hnsw(hnsw) = &mut config.search.vector_index {
+                if hnsw.snapshot_path.is_none() && config.persistence.enabled {
+                    let path = Path::new(&config.persistence.data_dir).join("hnsw.snapshot");
+                    hnsw.snapshot_path = Some(path.display().to_string());
+                }
+            }
             let hybrid_search = if enable_semantic_search {
                 info!("Initializing semantic search (downloading ~90MB model if needed)...");
                 match EmbeddingService::new() {
                     Ok(embedding_service) => {
-                        let hybrid = HybridSearch::new(embedding_service);
+                        let dimension = embedding_service.dimension();
+                        let hybrid_config = HybridSearchConfig {
+                            ranking: config.search.ranking.clone(),
+                            ..Default::default()
+                        };
+                        let mut hybrid =
+                            HybridSearch::with_config(embedding_service, hybrid_config)
+                                .with_trust_ranker(trust_ranker.clone());
+                        if let Some(embedding_store) = persistence.embeddings() {
+                            hybrid = hybrid.with_embedding_store(embedding_store.clone());
+                        }
+                        match config.search.vector_index.build(dimension).await {
+                            Ok(index) => {
+                                info!("Using {} vector index", index.name());
+                                vector_index = Some(index.clone());
+                                hybrid = hybrid.with_vector_index(index);
+                            }
+                            Err(e) => {
+                                warn!("Failed to open configured vector index: {}", e);
+                                warn!("Falling back to in-memory vector index");
+                            }
+                        }
                         info!("Semantic search initialized successfully");
                         Some(Arc::new(RwLock::new(hybrid)))
                     }
@@ -345,23 +491,96 @@ async fn main() -> Result<()> {
                 None
             };
 
-            // 5. Create shared state for API server with DHT-enabled discovery
+            // Resolve DIDs: own document first, then DHT/TrustRegistry, did:key and did:web
+            let local_dids = Arc::new(InMemoryDidResolver::new());
+            local_dids.insert(did_document.clone())?;
+            let mut agoramesh_resolver = AgoraMeshDidResolver::new()
+                .with_local_documents(local_dids)
+                .with_network(network.command_channel())
+                .with_chain_id(config.blockchain.chain_id);
+            if let Some(ref address) = config.blockchain.trust_registry_address {
+                match TrustRegistryClient::new(config.blockchain.rpc_url.clone(), address) {
+                    Ok(registry) => {
+                        agoramesh_resolver =
+                            agoramesh_resolver.with_trust_registry(Arc::new(registry))
+                    }
+                    Err(e) => warn!("On-chain DID lookups disabled: {}", e),
+                }
+            }
+            let mut did_resolver = CompositeDidResolver::new(DidCacheConfig::default())
+                .with_agoramesh(agoramesh_resolver)
+                .with_method("key", Arc::new(KeyDidResolver::new()));
+            match WebDidResolver::new() {
+                Ok(web) => did_resolver = did_resolver.with_method("web", Arc::new(web)),
+                Err(e) => warn!("did:web resolution disabled: {}", e),
+            }
+            let did_resolver = Arc::new(did_resolver);
+
+            // 6. Create shared state for API server with DHT-enabled discovery
             let peer_count = Arc::new(AtomicU64::new(0));
-            let discovery = match hybrid_search {
+            let metrics = Arc::new(MetricsService::new(MetricsConfig::default()));
+            let nat_state = Arc::new(RwLock::new(NatState::default()));
+            let mut discovery = match hybrid_search {
                 Some(hs) => {
                     DiscoveryService::with_network_and_shared_search(network.command_channel(), hs)
                 }
                 None => DiscoveryService::with_network(network.command_channel()),
             };
+            if let Some(card_store) = persistence.capability_cards() {
+                discovery = discovery.with_card_store(card_store.clone());
+            }
+            discovery = discovery.with_did_index(did_index.clone());
+            discovery = discovery.with_trust_ranker(trust_ranker, config.search.ranking.clone());
+            discovery = discovery.with_network_search(config.search.network.clone());
+            discovery = discovery
+                .with_did_resolver(did_resolver.clone())
+                .with_required_card_signatures(config.trust.require_signed_cards);
             // Get the shared hybrid search reference from discovery so both
             // the API semantic-search handler and discovery indexing use the
             // same instance.
             let shared_hybrid_search = discovery.hybrid_search();
             let discovery = Arc::new(discovery);
-            let trust = Arc::new(TrustService::new(
-                "https://sepolia.base.org".to_string(),
-                None,
-            ));
+
+            // Rehydrate cache and search index from the previous run
+            match discovery.rehydrate().await {
+                Ok(count) if count > 0 => info!("Restored {} capability card(s) from disk", count),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to restore capability cards: {}", e),
+            }
+            match trust.rehydrate() {
+                Ok(count) if count > 0 => info!("Restored trust data for {} agent(s)", count),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to restore trust data: {}", e),
+            }
+
+            // Publish our DID Document so peers can resolve it from the DHT,
+            // signed with the key our peer ID is derived from
+            let did_record =
+                DidDocumentRecord::seal(&did_document, &identity.envelope_signer(&node_did))?;
+            if let Err(e) = network
+                .command_channel()
+                .send(SwarmCommand::PutRecord {
+                    key: did_document_key(&node_did),
+                    value: did_record,
+                })
+                .await
+            {
+                warn!("Failed to publish DID Document: {}", e);
+            }
+
+            // One arbitrator shared by gossip, contract events and the API
+            let arbitrator = Arc::new(AIArbitrator::new(AIArbitrationConfig::default())?);
+
+            // Route gossip messages to discovery, trust and disputes, verifying signed envelopes
+            let message_handler = Arc::new(
+                MessageHandler::with_services(
+                    discovery.clone(),
+                    Some(trust.clone()),
+                    Some(arbitrator.clone()),
+                )
+                .with_did_resolver(did_resolver.clone())
+                .with_required_signatures(config.trust.require_signed_messages),
+            );
 
             let app_state = AppState {
                 discovery: discovery.clone(),
@@ -370,12 +589,17 @@ async fn main() -> Result<()> {
                 peer_count: peer_count.clone(),
                 node_info: config.get_node_info(),
                 rate_limiter: Arc::new(RateLimitService::new(RateLimitConfig::default())),
-                metrics: Arc::new(MetricsService::new(MetricsConfig::default())),
+                metrics: metrics.clone(),
                 hybrid_search: shared_hybrid_search,
                 api_token: config.api.admin_token.clone(),
+                message_handler: Some(message_handler.clone()),
+                did_document: Some(did_document),
+                did_resolver: Some(did_resolver),
+                nat_state: nat_state.clone(),
+                arbitrator: Some(arbitrator.clone()),
             };
 
-            // 6. Start HTTP API server in background with shared state
+            // 7. Start HTTP API server in background with shared state
             let api_config = agoramesh_node::ApiConfig {
                 listen_address: api_addr.clone(),
                 cors_enabled: config.api.cors_enabled,
@@ -392,7 +616,7 @@ async fn main() -> Result<()> {
                 }
             });
 
-            // 6b. Seed agents from AGORAMESH_SEED_AGENTS env var
+            // 7b. Seed agents from AGORAMESH_SEED_AGENTS env var
             //
             // Format: JSON array of capability card objects, or a URL to fetch.
             // Example: AGORAMESH_SEED_AGENTS='[{"name":"Bridge","description":"...","url":"https://bridge.agoramesh.ai","x-agoramesh":{"did":"did:agoramesh:base-sepolia:agent-001","payment_methods":["x402"]}}]'
@@ -423,14 +647,28 @@ async fn main() -> Result<()> {
                 }
             }
 
-            // 6c. Seed trust data from AGORAMESH_SEED_TRUST env var
+            // 7c. Seed trust data from AGORAMESH_SEED_TRUST env var
             //
             // Format: JSON array of objects with did, stake_amount, successful_txs, failed_txs, endorsement_count
             // Example: AGORAMESH_SEED_TRUST='[{"did":"did:agoramesh:base-sepolia:agent-001","stake_amount":7225000000,"successful_txs":184,"failed_txs":16,"endorsement_count":5}]'
+            //
+            // Agents that already have persisted trust data keep it, so
+            // restarts never reset accumulated reputation.
             if let Some(seed_trust_json) = env_string("AGORAMESH_SEED_TRUST") {
                 match serde_json::from_str::<Vec<SeedTrustEntry>>(&seed_trust_json) {
                     Ok(entries) => {
                         for entry in &entries {
+                            match trust.has_trust_data(&entry.did) {
+                                Ok(false) => {}
+                                Ok(true) => {
+                                    debug!("Keeping persisted trust data for {}", entry.did);
+                                    continue;
+                                }
+                                Err(e) => {
+                                    warn!("Not seeding trust data for {}: {}", entry.did, e);
+                                    continue;
+                                }
+                            }
                             trust.seed_trust_data(
                                 &entry.did,
                                 entry.stake_amount,
@@ -441,13 +679,15 @@ async fn main() -> Result<()> {
 
                             // Create endorser trust data and add endorsements
                             for endorser in &entry.endorsers {
-                                trust.seed_trust_data(
-                                    &endorser.did,
-                                    0,
-                                    endorser.successful_txs,
-                                    endorser.failed_txs,
-                                    0,
-                                );
+                                if !trust.has_trust_data(&endorser.did).unwrap_or(true) {
+                                    trust.seed_trust_data(
+                                        &endorser.did,
+                                        0,
+                                        endorser.successful_txs,
+                                        endorser.failed_txs,
+                                        0,
+                                    );
+                                }
                                 if let Err(e) = trust
                                     .add_endorsement_with_hop(
                                         &endorser.did,
@@ -479,10 +719,49 @@ async fn main() -> Result<()> {
                 }
             }
 
+            // 7d. Sync trust, discovery and disputes with contract events
+            if let Some(ws_url) = config.blockchain.ws_url.clone() {
+                let mut events_config = EventListenerConfig::new(ws_url);
+                if let Some(ref address) = config.blockchain.trust_registry_address {
+                    events_config = events_config.with_trust_registry(address.clone());
+                }
+                if let Some(ref address) = config.blockchain.escrow_address {
+                    events_config = events_config.with_escrow(address.clone());
+                }
+                match EventListener::new(events_config) {
+                    Ok((listener, contract_events)) => {
+                        let mut sink = ContractEventSink::new()
+                            .with_trust_service(trust.clone())
+                            .with_trust_cache(trust_cache.clone())
+                            .with_discovery(discovery.clone())
+                            .with_arbitrator(arbitrator.clone())
+                            .with_did_index(did_index.clone());
+                        let listener = match persistence.chain_events() {
+                            Some(store) => {
+                                sink = sink.with_event_store(store.clone());
+                                listener.with_checkpoint_store(store)
+                            }
+                            None => listener,
+                        };
+
+                        tokio::spawn(async move {
+                            if let Err(e) = listener.run().await {
+                                error!("Contract event listener stopped: {}", e);
+                            }
+                        });
+                        tokio::spawn(async move { sink.run(contract_events).await });
+                        info!("Contract event sync enabled");
+                    }
+                    Err(e) => warn!("Contract event sync disabled: {}", e),
+                }
+            }
+
             info!("AgoraMesh node started successfully");
             info!("Press Ctrl+C to stop");
 
-            // 7. Run event loop - process network events and handle shutdown
+            // 8. Run event loop - process network events, storage maintenance and shutdown
+            let mut flush_timer = maintenance_timer(config.persistence.flush_interval());
+            let mut compaction_timer = maintenance_timer(config.persistence.compaction_interval());
             loop {
                 tokio::select! {
                     // Handle network events
@@ -505,13 +784,22 @@ async fn main() -> Result<()> {
                             agoramesh_node::NetworkEvent::PeerDiscovered(peer_id) => {
                                 info!("Peer discovered via mDNS: {}", peer_id);
                             }
-                            agoramesh_node::NetworkEvent::Message { topic, source, data, .. } => {
+                            agoramesh_node::NetworkEvent::ConnectionDenied { remote_addr, reason } => {
+                                debug!("Refused connection from {}: {}", remote_addr, reason);
+                                metrics.p2p_connection_denied(reason.as_str());
+                            }
+                            agoramesh_node::NetworkEvent::NatStatusChanged(state) => {
                                 info!(
-                                    "Message on {}: {} bytes from {:?}",
-                                    topic,
-                                    data.len(),
-                                    source
+                                    "Reachability: {:?} (relay addresses: {})",
+                                    state.reachability,
+                                    state.relay_addresses.len()
                                 );
+                                *nat_state.write().await = state;
+                            }
+                            event @ agoramesh_node::NetworkEvent::Message { .. } => {
+                                if let Err(e) = message_handler.handle_event(&event).await {
+                                    debug!("Failed to handle gossip message: {}", e);
+                                }
                             }
                             agoramesh_node::NetworkEvent::BootstrapComplete => {
                                 info!("DHT bootstrap complete");
@@ -526,15 +814,72 @@ async fn main() -> Result<()> {
                             agoramesh_node::NetworkEvent::RecordStored { key } => {
                                 info!("DHT record stored: key={} bytes", key.len());
                             }
+                            agoramesh_node::NetworkEvent::CardRecordReceived { source, card, record } => {
+                                // Verify the card proof off the event loop; DID lookups may hit the DHT
+                                let discovery = discovery.clone();
+                                let network_tx = network.command_channel();
+                                tokio::spawn(async move {
+                                    match discovery.verify_card(&card).await {
+                                        Ok(()) => {
+                                            let _ = network_tx.send(SwarmCommand::StoreRecord { record }).await;
+                                        }
+                                        Err(e) => debug!("Rejected DHT card record from {}: {}", source, e),
+                                    }
+                                });
+                            }
+                            agoramesh_node::NetworkEvent::SearchRequest { peer, request_id, request } => {
+                                // Answer off the event loop; peers' queries run against the local cache
+                                let discovery = discovery.clone();
+                                let network_tx = network.command_channel();
+                                tokio::spawn(async move {
+                                    let response = discovery.serve_peer_query(&peer, request).await;
+                                    if let Err(e) = network_tx
+                                        .send(SwarmCommand::RespondSearch { request_id, response })
+                                        .await
+                                    {
+                                        debug!("Failed to answer search from {}: {}", peer, e);
+                                    }
+                                });
+                            }
                         }
                     }
 
+                    // Periodically flush memtables to disk
+                    _ = next_tick(&mut flush_timer) => {
+                        if let Err(e) = persistence.flush() {
+                            warn!("Persistence flush failed: {}", e);
+                        }
+                        if let Some(ref index) = vector_index {
+                            if let Err(e) = index.flush().await {
+                                warn!("Vector index flush failed: {}", e);
+                            }
+                        }
+                    }
+
+                    // Periodically compact stores off the async runtime
+                    _ = next_tick(&mut compaction_timer) => {
+                        let persistence = persistence.clone();
+                        tokio::task::spawn_blocking(move || {
+                            if let Err(e) = persistence.compact() {
+                                warn!("Persistence compaction failed: {}", e);
+                            }
+                        });
+                    }
+
                     // Handle shutdown signal
                     _ = signal::ctrl_c() => {
                         info!("Received shutdown signal");
                         if let Err(e) = network.shutdown().await {
                             warn!("Error during shutdown: {}", e);
                         }
+                        if let Err(e) = persistence.flush() {
+                            warn!("Failed to flush persistent storage: {}", e);
+                        }
+                        if let Some(ref index) = vector_index {
+                            if let Err(e) = index.flush().await {
+                                warn!("Failed to flush vector index: {}", e);
+                            }
+                        }
                         info!("Node stopped");
                         break;
                     }
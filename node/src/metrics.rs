@@ -28,6 +28,8 @@
 //! - `agoramesh_discovery_queries_total` - Discovery queries (counter)
 //! - `agoramesh_trust_lookups_total` - Trust score lookups (counter)
 //! - `agoramesh_p2p_peers_connected` - Connected peers (gauge)
+//! - `agoramesh_p2p_connections_denied_total` - Inbound connections refused, by reason (counter)
+//! - `agoramesh_message_handler_messages_total` - Gossip messages handled, by kind (counter)
 
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
@@ -35,6 +37,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
+use crate::network::MessageHandlerStats;
+
 /// Metrics configuration.
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
@@ -70,6 +74,8 @@ pub struct MetricNames {
     pub p2p_peers_connected: String,
     pub p2p_messages_received: String,
     pub p2p_messages_sent: String,
+    pub p2p_connections_denied: String,
+    pub message_handler_messages: String,
 }
 
 impl MetricNames {
@@ -87,6 +93,8 @@ impl MetricNames {
             p2p_peers_connected: format!("{}_p2p_peers_connected", prefix),
             p2p_messages_received: format!("{}_p2p_messages_received_total", prefix),
             p2p_messages_sent: format!("{}_p2p_messages_sent_total", prefix),
+            p2p_connections_denied: format!("{}_p2p_connections_denied_total", prefix),
+            message_handler_messages: format!("{}_message_handler_messages_total", prefix),
         }
     }
 }
@@ -216,6 +224,14 @@ impl MetricsService {
                 self.names.p2p_messages_sent.clone(),
                 "Total P2P messages sent"
             );
+            describe_counter!(
+                self.names.p2p_connections_denied.clone(),
+                "Total inbound P2P connections refused, by reason"
+            );
+            describe_counter!(
+                self.names.message_handler_messages.clone(),
+                "Total gossip messages handled, by kind"
+            );
         }
     }
 
@@ -326,6 +342,40 @@ impl MetricsService {
             counter!(self.names.p2p_messages_sent.clone(), &labels).increment(1);
         }
     }
+
+    /// Record an inbound P2P connection refused by the connection gate.
+    pub fn p2p_connection_denied(&self, reason: &str) {
+        if self.config.enable_p2p_metrics {
+            let labels = [("reason", reason.to_string())];
+            counter!(self.names.p2p_connections_denied.clone(), &labels).increment(1);
+        }
+    }
+
+    /// Publish gossip message handler statistics.
+    ///
+    /// The handler keeps its own running totals, so the counters are set to
+    /// absolute values (typically at scrape time).
+    pub fn message_handler_stats(&self, stats: &MessageHandlerStats) {
+        if !self.config.enable_p2p_metrics {
+            return;
+        }
+
+        let kinds = [
+            ("received", stats.messages_received),
+            ("processed", stats.messages_processed),
+            ("parse_error", stats.parse_errors),
+            ("discovery", stats.discovery_messages),
+            ("trust", stats.trust_messages),
+            ("dispute", stats.dispute_messages),
+            ("unknown_topic", stats.unknown_topic_messages),
+            ("rejected_signature", stats.rejected_signatures),
+            ("replayed", stats.replayed_messages),
+        ];
+        for (kind, value) in kinds {
+            let labels = [("kind", kind.to_string())];
+            counter!(self.names.message_handler_messages.clone(), &labels).absolute(value);
+        }
+    }
 }
 
 /// Guard for tracking in-flight requests.
@@ -478,6 +528,8 @@ mod tests {
         assert!(names.p2p_peers_connected.starts_with(prefix));
         assert!(names.p2p_messages_received.starts_with(prefix));
         assert!(names.p2p_messages_sent.starts_with(prefix));
+        assert!(names.p2p_connections_denied.starts_with(prefix));
+        assert!(names.message_handler_messages.starts_with(prefix));
     }
 
     // ========== RED Phase: MetricsService Creation Tests ==========
@@ -693,6 +745,30 @@ mod tests {
         service.p2p_message_sent("trust");
     }
 
+    #[test]
+    fn test_p2p_connection_denied_can_be_recorded() {
+        let service = MetricsService::disabled();
+
+        // Should not panic
+        service.p2p_connection_denied("subnet_16");
+        service.p2p_connection_denied("rate_limit");
+    }
+
+    #[test]
+    fn test_message_handler_stats_can_be_recorded() {
+        let service = MetricsService::disabled();
+        let stats = MessageHandlerStats {
+            messages_received: 10,
+            messages_processed: 8,
+            parse_errors: 2,
+            ..Default::default()
+        };
+
+        // Should not panic
+        service.message_handler_stats(&stats);
+        service.message_handler_stats(&MessageHandlerStats::default());
+    }
+
     #[test]
     fn test_p2p_metrics_disabled_when_config_false() {
         let config = MetricsConfig {
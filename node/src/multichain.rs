@@ -243,7 +243,7 @@ impl MultiChainConfig {
             .iter()
             .filter(|c| c.enabled && c.has_trust_registry())
             .collect();
-        chains.sort_by(|a, b| b.priority.cmp(&a.priority));
+        chains.sort_by_key(|c| std::cmp::Reverse(c.priority));
         chains
     }
 }
@@ -331,8 +331,8 @@ impl MultiChainClient {
         }
 
         // If we got at least one score, return the weighted average
-        if total_weight > 0 {
-            Ok(total_score / total_weight)
+        if let Some(score) = total_score.checked_div(total_weight) {
+            Ok(score)
         } else if !errors.is_empty() {
             // All chains failed, return the first error
             Err(errors
@@ -1,30 +1,54 @@
 //! P2P network layer using libp2p.
 //!
 //! This module handles:
-//! - Peer connections and management
+//! - Peer connections over TCP and QUIC
 //! - GossipSub for pub/sub messaging
 //! - Kademlia DHT for distributed storage
-//! - mDNS for local network discovery
+//! - mDNS for local network discovery (optional)
+//! - Request-response search across peers' discovery caches
 //! - Message routing and handling
-//! - Security (Sybil/Eclipse attack protection)
+//! - Security (Sybil/Eclipse attack protection, enforced by a connection gate)
+//! - NAT traversal (AutoNAT, circuit relay v2, DCUtR hole punching)
+//! - Private mesh mode (pre-shared key, peer allowlist and denylist)
 
 pub mod behaviour;
+pub mod connection_gate;
+pub mod envelope;
 pub mod message_handler;
+pub mod nat;
+pub mod private_mesh;
+pub mod record_store;
+pub mod search;
 pub mod security;
 pub mod swarm;
 pub mod transport;
 
 // Re-export main types for convenience
-pub use behaviour::{topics, AgoraMeshBehaviour, AgoraMeshEvent, PROTOCOL_VERSION};
+pub use behaviour::{
+    check_inbound_record, topics, AgoraMeshBehaviour, AgoraMeshEvent, RecordCheck, DHT_RECORD_TTL,
+    PROTOCOL_VERSION,
+};
+pub use connection_gate::{ConnectionDenial, ConnectionGate, DenialReason};
+pub use envelope::{
+    EnvelopeSigner, ReplayGuard, SignedEnvelope, ENVELOPE_VERSION, MAX_ENVELOPE_AGE_SECS,
+};
 pub use message_handler::{DiscoveryMessage, MessageHandler, MessageHandlerStats, TrustMessage};
+pub use nat::{NatConfig, NatState, Reachability};
+pub use private_mesh::{PeerFilter, PrivateMeshConfig};
+pub use record_store::DhtRecordStore;
+pub use search::{SearchRequest, SearchResponse, SEARCH_PROTOCOL, SEARCH_REQUEST_TIMEOUT};
 pub use security::{
-    validate_bootstrap_peers, validate_network_config, ConnectionRateLimiter, ConnectionTracker,
-    GlobalConnectionRateLimiter, SecurityConfig, Subnet16Tracker, SubnetTracker,
+    validate_bootstrap_peers, validate_listen_address, validate_network_config,
+    validate_private_mesh, ConnectionRateLimiter, ConnectionTracker, GlobalConnectionRateLimiter,
+    SecurityConfig, Subnet16Tracker, SubnetTracker, DEFAULT_MAX_CONNECTIONS,
     DEFAULT_MAX_CONNECTIONS_PER_MINUTE, MAX_PEERS_PER_SUBNET_16, MAX_PEERS_PER_SUBNET_24,
     MIN_BOOTSTRAP_PEERS,
 };
 pub use swarm::{NetworkEvent, SwarmCommand, SwarmManager};
-pub use transport::{build_transport, BoxedTransport};
+pub use transport::{
+    build_private_transport, build_transport, build_transport_with_relay, is_quic, prefer_quic,
+    BoxedTransport,
+};
 
 use libp2p::{Multiaddr, PeerId};
 use tokio::sync::{mpsc, oneshot};
@@ -91,6 +115,38 @@ impl NetworkManager {
     /// * `keypair` - The node's identity keypair
     pub fn with_keypair(config: NetworkConfig, keypair: libp2p::identity::Keypair) -> Result<Self> {
         let (manager, command_tx, event_rx) = SwarmManager::with_keypair(&config, keypair)?;
+        Ok(Self::spawn(config, manager, command_tx, event_rx))
+    }
+
+    /// Create a network manager whose DHT records live in `record_store`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Network configuration
+    /// * `keypair` - The node's identity keypair
+    /// * `record_store` - Store for DHT records hosted by this node
+    /// * `envelope_signer` - Signs every published GossipSub message, if set
+    pub fn with_record_store(
+        config: NetworkConfig,
+        keypair: libp2p::identity::Keypair,
+        record_store: DhtRecordStore,
+        envelope_signer: Option<EnvelopeSigner>,
+    ) -> Result<Self> {
+        let (mut manager, command_tx, event_rx) =
+            SwarmManager::with_record_store(&config, keypair, record_store)?;
+        if let Some(signer) = envelope_signer {
+            manager = manager.with_envelope_signer(signer);
+        }
+        Ok(Self::spawn(config, manager, command_tx, event_rx))
+    }
+
+    /// Run `manager` in a background task.
+    fn spawn(
+        config: NetworkConfig,
+        manager: SwarmManager,
+        command_tx: mpsc::Sender<SwarmCommand>,
+        event_rx: mpsc::Receiver<NetworkEvent>,
+    ) -> Self {
         let local_peer_id = manager.local_peer_id();
 
         info!("Created NetworkManager with peer ID: {}", local_peer_id);
@@ -102,12 +158,12 @@ impl NetworkManager {
             }
         });
 
-        Ok(Self {
+        Self {
             local_peer_id,
             config,
             command_tx,
             event_rx: Some(event_rx),
-        })
+        }
     }
 
     /// Get the local peer ID.
@@ -286,6 +342,9 @@ mod tests {
             listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
             bootstrap_peers: vec![],
             max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         }
     }
 
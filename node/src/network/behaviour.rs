@@ -5,14 +5,24 @@
 //! - GossipSub for pub/sub messaging
 //! - Identify protocol for peer information exchange
 //! - mDNS for local network discovery (optional)
+//! - Request-response search for querying peers' discovery caches
+//! - A connection gate enforcing subnet, rate and connection limits
+//! - NAT traversal: AutoNAT, circuit relay v2 and DCUtR hole punching
+//!   (optional, see [`AgoraMeshBehaviour::with_nat_traversal`])
+//!
+//! Kademlia hands records from peers to the swarm manager instead of
+//! storing them; see [`check_inbound_record`]. Records this node hosts are
+//! kept in a [`DhtRecordStore`], which may persist them across restarts.
 
 use libp2p::{
+    autonat, dcutr,
     gossipsub::{self, MessageAuthenticity, MessageId, ValidationMode},
     identify,
-    kad::{self, store::MemoryStore, Mode},
-    mdns,
-    swarm::NetworkBehaviour,
-    PeerId,
+    kad::{self, store::RecordStore, Mode, Quorum},
+    mdns, relay,
+    request_response::{self, ProtocolSupport},
+    swarm::{behaviour::toggle::Toggle, NetworkBehaviour},
+    PeerId, StreamProtocol,
 };
 use std::{
     collections::hash_map::DefaultHasher,
@@ -20,9 +30,22 @@ use std::{
     time::Duration,
 };
 
+use super::connection_gate::{ConnectionDenial, ConnectionGate};
+use super::nat::{NatConfig, Reachability};
+use super::record_store::DhtRecordStore;
+use super::search::{SearchRequest, SearchResponse, SEARCH_PROTOCOL, SEARCH_REQUEST_TIMEOUT};
+use super::security::SecurityConfig;
+use crate::did::{DidDocumentRecord, DID_DOCUMENT_KEY_PREFIX};
+use crate::discovery::{check_card_update, CapabilityCard};
+use crate::error::Error;
+use crate::persistence::PersistenceConfig;
+
 /// AgoraMesh protocol version string.
 pub const PROTOCOL_VERSION: &str = "/agoramesh/1.0.0";
 
+/// How long DHT records are kept before they must be republished.
+pub const DHT_RECORD_TTL: Duration = Duration::from_secs(3600);
+
 /// GossipSub topics for AgoraMesh.
 pub mod topics {
     /// Topic for agent discovery announcements.
@@ -46,21 +69,44 @@ pub mod topics {
 /// - `gossipsub`: Pub/sub messaging for broadcasting agent updates
 /// - `kademlia`: DHT for distributed storage and peer discovery
 /// - `identify`: Protocol to exchange peer info on connection
-/// - `mdns`: Local network discovery (for development/testing)
+/// - `mdns`: Local network discovery, unless disabled with
+///   [`AgoraMeshBehaviour::without_mdns`]
+/// - `search`: Discovery queries answered from peers' local caches
+/// - `gate`: Admission control for inbound connections
+/// - `autonat`, `relay`, `relay_client`, `dcutr`: NAT traversal, each
+///   disabled unless enabled with [`AgoraMeshBehaviour::with_nat_traversal`]
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "AgoraMeshEvent")]
 pub struct AgoraMeshBehaviour {
+    /// Connection gate; first, so refused connections reach no protocol.
+    pub gate: ConnectionGate,
+
     /// GossipSub for pub/sub messaging.
     pub gossipsub: gossipsub::Behaviour,
 
     /// Kademlia DHT for distributed discovery.
-    pub kademlia: kad::Behaviour<MemoryStore>,
+    pub kademlia: kad::Behaviour<DhtRecordStore>,
 
     /// Identify protocol for peer information.
     pub identify: identify::Behaviour,
 
     /// mDNS for local network discovery.
-    pub mdns: mdns::tokio::Behaviour,
+    pub mdns: Toggle<mdns::tokio::Behaviour>,
+
+    /// Request-response protocol for peer discovery search.
+    pub search: request_response::json::Behaviour<SearchRequest, SearchResponse>,
+
+    /// AutoNAT reachability detection.
+    pub autonat: Toggle<autonat::v1::Behaviour>,
+
+    /// Circuit relay v2 server, for public nodes.
+    pub relay: Toggle<relay::Behaviour>,
+
+    /// Circuit relay v2 client, for reservations on relays.
+    pub relay_client: Toggle<relay::client::Behaviour>,
+
+    /// DCUtR hole punching of relayed connections.
+    pub dcutr: Toggle<dcutr::Behaviour>,
 }
 
 /// Events emitted by the AgoraMesh behaviour.
@@ -74,6 +120,24 @@ pub enum AgoraMeshEvent {
     Identify(Box<identify::Event>),
     /// mDNS event.
     Mdns(mdns::Event),
+    /// Peer search event (boxed to reduce enum size).
+    Search(Box<request_response::Event<SearchRequest, SearchResponse>>),
+    /// An inbound connection was refused by the gate.
+    Gate(ConnectionDenial),
+    /// AutoNAT event.
+    Autonat(autonat::v1::Event),
+    /// Relay server event (boxed to reduce enum size).
+    Relay(Box<relay::Event>),
+    /// Relay client event (boxed to reduce enum size).
+    RelayClient(Box<relay::client::Event>),
+    /// DCUtR hole punching event.
+    Dcutr(dcutr::Event),
+}
+
+impl From<ConnectionDenial> for AgoraMeshEvent {
+    fn from(event: ConnectionDenial) -> Self {
+        AgoraMeshEvent::Gate(event)
+    }
 }
 
 impl From<gossipsub::Event> for AgoraMeshEvent {
@@ -100,6 +164,36 @@ impl From<mdns::Event> for AgoraMeshEvent {
     }
 }
 
+impl From<request_response::Event<SearchRequest, SearchResponse>> for AgoraMeshEvent {
+    fn from(event: request_response::Event<SearchRequest, SearchResponse>) -> Self {
+        AgoraMeshEvent::Search(Box::new(event))
+    }
+}
+
+impl From<autonat::v1::Event> for AgoraMeshEvent {
+    fn from(event: autonat::v1::Event) -> Self {
+        AgoraMeshEvent::Autonat(event)
+    }
+}
+
+impl From<relay::Event> for AgoraMeshEvent {
+    fn from(event: relay::Event) -> Self {
+        AgoraMeshEvent::Relay(Box::new(event))
+    }
+}
+
+impl From<relay::client::Event> for AgoraMeshEvent {
+    fn from(event: relay::client::Event) -> Self {
+        AgoraMeshEvent::RelayClient(Box::new(event))
+    }
+}
+
+impl From<dcutr::Event> for AgoraMeshEvent {
+    fn from(event: dcutr::Event) -> Self {
+        AgoraMeshEvent::Dcutr(event)
+    }
+}
+
 impl AgoraMeshBehaviour {
     /// Create a new AgoraMesh behaviour.
     ///
@@ -118,12 +212,28 @@ impl AgoraMeshBehaviour {
     pub fn new(
         local_peer_id: PeerId,
         keypair: &libp2p::identity::Keypair,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let record_store = DhtRecordStore::new(local_peer_id, &PersistenceConfig::default());
+        Self::with_record_store(local_peer_id, keypair, record_store)
+    }
+
+    /// Create a behaviour whose Kademlia DHT uses the given record store.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_peer_id` - The local peer ID
+    /// * `keypair` - The node's identity keypair
+    /// * `record_store` - Store for DHT records hosted by this node
+    pub fn with_record_store(
+        local_peer_id: PeerId,
+        keypair: &libp2p::identity::Keypair,
+        record_store: DhtRecordStore,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         // Configure GossipSub with custom message ID function
         let gossipsub = build_gossipsub(keypair)?;
 
         // Configure Kademlia DHT
-        let kademlia = build_kademlia(local_peer_id);
+        let kademlia = build_kademlia(local_peer_id, record_store);
 
         // Configure Identify protocol
         let identify = build_identify(keypair.public());
@@ -131,14 +241,61 @@ impl AgoraMeshBehaviour {
         // Configure mDNS for local discovery
         let mdns = mdns::tokio::Behaviour::new(mdns::Config::default(), local_peer_id)?;
 
+        // Configure peer search
+        let search = build_search();
+
         Ok(Self {
+            gate: ConnectionGate::new(&SecurityConfig::default()),
             gossipsub,
             kademlia,
             identify,
-            mdns,
+            mdns: Toggle::from(Some(mdns)),
+            search,
+            autonat: Toggle::from(None),
+            relay: Toggle::from(None),
+            relay_client: Toggle::from(None),
+            dcutr: Toggle::from(None),
         })
     }
 
+    /// Replace the connection gate, e.g. to apply the node's own limits.
+    pub fn with_connection_gate(mut self, gate: ConnectionGate) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    /// Disable mDNS, so the node neither announces itself nor discovers
+    /// peers on the local network.
+    pub fn without_mdns(mut self) -> Self {
+        self.mdns = Toggle::from(None);
+        self
+    }
+
+    /// Enable NAT traversal as configured.
+    ///
+    /// `relay_client` is the behaviour half of the transport passed to
+    /// [`super::transport::build_transport_with_relay`]. Without it the
+    /// node cannot reserve relay slots, and hole punching stays off.
+    pub fn with_nat_traversal(
+        mut self,
+        local_peer_id: PeerId,
+        config: &NatConfig,
+        relay_client: Option<relay::client::Behaviour>,
+    ) -> Self {
+        self.autonat = Toggle::from(config.autonat.then(|| config.autonat(local_peer_id)));
+        self.relay = Toggle::from(
+            config
+                .relay_server
+                .then(|| config.relay_server(local_peer_id)),
+        );
+        self.dcutr = Toggle::from(
+            (config.hole_punching && relay_client.is_some())
+                .then(|| dcutr::Behaviour::new(local_peer_id)),
+        );
+        self.relay_client = Toggle::from(relay_client);
+        self
+    }
+
     /// Subscribe to all AgoraMesh topics.
     ///
     /// Subscribes to discovery, capability, trust, and disputes topics.
@@ -198,6 +355,154 @@ impl AgoraMeshBehaviour {
     pub fn set_client_mode(&mut self) {
         self.kademlia.set_mode(Some(Mode::Client));
     }
+
+    /// Switch Kademlia mode to match the detected reachability.
+    ///
+    /// While reachability is unknown, Kademlia picks its mode from the
+    /// node's confirmed external addresses.
+    pub fn apply_reachability(&mut self, reachability: Reachability) {
+        match reachability {
+            Reachability::Public => self.set_server_mode(),
+            Reachability::Private => self.set_client_mode(),
+            Reachability::Unknown => self.kademlia.set_mode(None),
+        }
+    }
+
+    /// Push every stored record and provided key back into the DHT.
+    ///
+    /// Records published by this node and provided keys are republished as
+    /// our own; records hosted for other publishers are replicated to the
+    /// closest known peers, keeping their publisher and expiry. Used after
+    /// boot, when records restored from disk have not been announced yet.
+    ///
+    /// Returns the number of records and keys sent out.
+    pub fn republish_stored_records(&mut self, local_peer_id: &PeerId) -> usize {
+        let store = self.kademlia.store_mut();
+        let records: Vec<kad::Record> = store.records().map(|r| r.into_owned()).collect();
+        let provided: Vec<kad::RecordKey> = store.provided().map(|r| r.key.clone()).collect();
+
+        let mut sent = 0;
+        for record in records {
+            if record.publisher.as_ref() == Some(local_peer_id) {
+                if self.kademlia.put_record(record, Quorum::One).is_ok() {
+                    sent += 1;
+                }
+                continue;
+            }
+
+            let target = kad::KBucketKey::new(record.key.clone());
+            let peers: Vec<PeerId> = self
+                .kademlia
+                .get_closest_local_peers(&target)
+                .take(kad::K_VALUE.get())
+                .map(|key| *key.preimage())
+                .collect();
+            if !peers.is_empty() {
+                self.kademlia
+                    .put_record_to(record, peers.into_iter(), Quorum::One);
+                sent += 1;
+            }
+        }
+
+        for key in provided {
+            if self.kademlia.start_providing(key).is_ok() {
+                sent += 1;
+            }
+        }
+        sent
+    }
+}
+
+/// Outcome of [`check_inbound_record`].
+#[derive(Debug)]
+pub enum RecordCheck {
+    /// An authenticated DID Document; store it as is.
+    Accept,
+    /// A well-formed capability card, to be stored once its proof has
+    /// been verified.
+    VerifyCard(Box<CapabilityCard>),
+}
+
+/// Check a record a peer asked this node to store.
+///
+/// Records keyed by a DID hold that agent's capability card. They must
+/// parse, describe the DID they are stored under and pass
+/// [`check_card_update`] against the `stored` copy, so stale cards and
+/// unsigned replacements of signed cards are refused.
+///
+/// Records under [`DID_DOCUMENT_KEY_PREFIX`] must be a
+/// [`DidDocumentRecord`] signed by the DID's peer ID key or, for other
+/// DIDs, by a key of the `stored` document, and no older than it. The
+/// on-chain owner cannot be checked here, so the first document of a DID
+/// that is not a peer ID is refused. Records under any other key are
+/// refused, since nodes only publish cards and DID Documents.
+///
+/// # Errors
+///
+/// Returns a validation error describing why the record was refused.
+pub fn check_inbound_record(
+    record: &kad::Record,
+    stored: Option<&kad::Record>,
+) -> crate::error::Result<RecordCheck> {
+    if let Some(did) = record
+        .key
+        .as_ref()
+        .strip_prefix(DID_DOCUMENT_KEY_PREFIX.as_bytes())
+    {
+        check_did_document_record(did, record, stored)?;
+        return Ok(RecordCheck::Accept);
+    }
+    if !record.key.as_ref().starts_with(b"did:") {
+        return Err(Error::Validation(
+            "Record key is neither a DID nor a DID Document key".to_string(),
+        ));
+    }
+
+    let card: CapabilityCard = serde_json::from_slice(&record.value)
+        .map_err(|e| Error::Validation(format!("Card record is not a capability card: {}", e)))?;
+    let did = card.agoramesh.as_ref().map(|ext| ext.did.as_bytes());
+    if did != Some(record.key.as_ref()) {
+        return Err(Error::Validation(
+            "Card record does not describe the DID it is stored under".to_string(),
+        ));
+    }
+
+    // A stored record that no longer parses cannot hold the real card
+    if let Some(current) =
+        stored.and_then(|stored| serde_json::from_slice::<CapabilityCard>(&stored.value).ok())
+    {
+        check_card_update(&current, &card)?;
+    }
+
+    Ok(RecordCheck::VerifyCard(Box::new(card)))
+}
+
+/// Authenticate a DID Document record against the `stored` one.
+fn check_did_document_record(
+    did: &[u8],
+    record: &kad::Record,
+    stored: Option<&kad::Record>,
+) -> crate::error::Result<()> {
+    let did = std::str::from_utf8(did)
+        .map_err(|e| Error::Validation(format!("DID Document key is not UTF-8: {}", e)))?;
+    let invalid = |e: Error| Error::Validation(format!("Refused DID Document record: {}", e));
+
+    let incoming = DidDocumentRecord::parse(did, &record.value).map_err(invalid)?;
+    // A stored record that no longer parses carries no authority
+    let current = stored.and_then(|stored| DidDocumentRecord::parse(did, &stored.value).ok());
+    incoming
+        .verify(current.as_ref().map(|current| &current.document))
+        .map_err(invalid)?;
+
+    if let Some(current) = current {
+        if incoming.timestamp() < current.timestamp() {
+            return Err(Error::Validation(format!(
+                "DID Document record for {} is older than the stored one",
+                did
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// Build GossipSub behaviour with AgoraMesh configuration.
@@ -303,19 +608,18 @@ fn build_peer_score_params() -> gossipsub::PeerScoreParams {
 }
 
 /// Build Kademlia DHT behaviour with AgoraMesh configuration.
-fn build_kademlia(local_peer_id: PeerId) -> kad::Behaviour<MemoryStore> {
-    // Create memory store for DHT records
-    let store = MemoryStore::new(local_peer_id);
-
+fn build_kademlia(local_peer_id: PeerId, store: DhtRecordStore) -> kad::Behaviour<DhtRecordStore> {
     // Kademlia configuration
     let mut config = kad::Config::new(
         libp2p::StreamProtocol::try_from_owned(format!("{}/kad", PROTOCOL_VERSION))
             .expect("valid protocol"),
     );
     config.set_query_timeout(Duration::from_secs(60));
-    config.set_record_ttl(Some(Duration::from_secs(3600))); // 1 hour TTL
+    config.set_record_ttl(Some(DHT_RECORD_TTL));
     config.set_publication_interval(Some(Duration::from_secs(600))); // Republish every 10 min
     config.set_provider_record_ttl(Some(Duration::from_secs(3600)));
+    // Records from peers are validated before the swarm manager stores them
+    config.set_record_filtering(kad::StoreInserts::FilterBoth);
 
     kad::Behaviour::with_config(local_peer_id, store, config)
 }
@@ -329,10 +633,29 @@ fn build_identify(public_key: libp2p::identity::PublicKey) -> identify::Behaviou
     identify::Behaviour::new(config)
 }
 
+/// Build the request-response behaviour for peer search.
+fn build_search() -> request_response::json::Behaviour<SearchRequest, SearchResponse> {
+    let config = request_response::Config::default().with_request_timeout(SEARCH_REQUEST_TIMEOUT);
+
+    request_response::json::Behaviour::new(
+        [(StreamProtocol::new(SEARCH_PROTOCOL), ProtocolSupport::Full)],
+        config,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use libp2p::identity::Keypair;
+    use crate::network::nat::is_relayed;
+    use futures::StreamExt;
+    use libp2p::{
+        core::{transport::MemoryTransport, upgrade::Version},
+        identity::Keypair,
+        multiaddr::Protocol,
+        noise,
+        swarm::SwarmEvent,
+        yamux, Multiaddr, Swarm, Transport,
+    };
 
     #[tokio::test]
     async fn test_create_behaviour() {
@@ -342,6 +665,30 @@ mod tests {
         assert!(behaviour.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_republish_stored_records_announces_own_records_and_keys() {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = PeerId::from(keypair.public());
+        let mut store = DhtRecordStore::new(peer_id, &PersistenceConfig::default());
+        let mut own = kad::Record::new(kad::RecordKey::new(&"did:own"), b"card".to_vec());
+        own.publisher = Some(peer_id);
+        store.put(own).unwrap();
+        let foreign = kad::Record::new(kad::RecordKey::new(&"did:foreign"), b"card".to_vec());
+        store.put(foreign).unwrap();
+        store
+            .add_provider(kad::ProviderRecord::new(
+                kad::RecordKey::new(&"/agoramesh/skill/translate"),
+                peer_id,
+                vec![],
+            ))
+            .unwrap();
+        let mut behaviour =
+            AgoraMeshBehaviour::with_record_store(peer_id, &keypair, store).unwrap();
+
+        // The foreign record has no known peers to be replicated to yet
+        assert_eq!(behaviour.republish_stored_records(&peer_id), 2);
+    }
+
     #[test]
     fn test_topic_names() {
         assert_eq!(topics::DISCOVERY, "/agoramesh/discovery/1.0.0");
@@ -393,6 +740,288 @@ mod tests {
         assert!(result.is_err(), "Should fail to publish without peers");
     }
 
+    // ========== TDD Tests: NAT traversal ==========
+
+    /// A node on the in-process memory transport, with a relay client.
+    fn memory_swarm(config: &NatConfig) -> Swarm<AgoraMeshBehaviour> {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        let (relay_transport, relay_client) = relay::client::new(peer_id);
+        let transport = relay_transport
+            .or_transport(MemoryTransport::default())
+            .upgrade(Version::V1)
+            .authenticate(noise::Config::new(&keypair).unwrap())
+            .multiplex(yamux::Config::default())
+            .boxed();
+        let behaviour = AgoraMeshBehaviour::new(peer_id, &keypair)
+            .unwrap()
+            .with_nat_traversal(peer_id, config, Some(relay_client));
+        Swarm::new(
+            transport,
+            behaviour,
+            peer_id,
+            libp2p::swarm::Config::with_tokio_executor()
+                .with_idle_connection_timeout(Duration::from_secs(60)),
+        )
+    }
+
+    /// A relay listening on a memory address; returns its circuit address.
+    async fn memory_relay(relay: &mut Swarm<AgoraMeshBehaviour>) -> Multiaddr {
+        relay.listen_on(Protocol::Memory(0).into()).unwrap();
+        let addr = loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = relay.select_next_some().await {
+                break address;
+            }
+        };
+        relay.add_external_address(addr.clone());
+        addr.with(Protocol::P2p(*relay.local_peer_id()))
+            .with(Protocol::P2pCircuit)
+    }
+
+    fn relay_config() -> NatConfig {
+        NatConfig {
+            autonat: false,
+            relay_server: true,
+            ..Default::default()
+        }
+    }
+
+    fn client_config() -> NatConfig {
+        NatConfig {
+            autonat: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nat_traversal_is_disabled_by_default() {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = PeerId::from(keypair.public());
+        let behaviour = AgoraMeshBehaviour::new(peer_id, &keypair).unwrap();
+
+        assert!(!behaviour.autonat.is_enabled());
+        assert!(!behaviour.relay.is_enabled());
+        assert!(!behaviour.relay_client.is_enabled());
+        assert!(!behaviour.dcutr.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_mdns_can_be_disabled() {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = PeerId::from(keypair.public());
+        let behaviour = AgoraMeshBehaviour::new(peer_id, &keypair).unwrap();
+
+        assert!(behaviour.mdns.is_enabled());
+        assert!(!behaviour.without_mdns().mdns.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_apply_reachability_switches_kademlia_mode() {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = PeerId::from(keypair.public());
+        let mut behaviour = AgoraMeshBehaviour::new(peer_id, &keypair).unwrap();
+
+        behaviour.apply_reachability(Reachability::Public);
+        assert_eq!(behaviour.kademlia.mode(), Mode::Server);
+
+        behaviour.apply_reachability(Reachability::Private);
+        assert_eq!(behaviour.kademlia.mode(), Mode::Client);
+    }
+
+    #[tokio::test]
+    async fn test_private_node_reserves_slot_on_relay() {
+        let mut relay = memory_swarm(&relay_config());
+        let mut private = memory_swarm(&client_config());
+        let relay_id = *relay.local_peer_id();
+
+        let circuit = memory_relay(&mut relay).await;
+        private.listen_on(circuit).unwrap();
+
+        let reserved_on = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                tokio::select! {
+                    _ = relay.select_next_some() => {}
+                    event = private.select_next_some() => {
+                        if let SwarmEvent::Behaviour(AgoraMeshEvent::RelayClient(event)) = event {
+                            if let relay::client::Event::ReservationReqAccepted { relay_peer_id, .. } = *event {
+                                return relay_peer_id;
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Relay should accept the reservation");
+
+        assert_eq!(reserved_on, relay_id);
+    }
+
+    #[tokio::test]
+    async fn test_peer_reaches_private_node_through_relay() {
+        let mut relay = memory_swarm(&relay_config());
+        let mut private = memory_swarm(&client_config());
+        let mut dialer = memory_swarm(&client_config());
+        let private_id = *private.local_peer_id();
+
+        let circuit = memory_relay(&mut relay).await;
+        private.listen_on(circuit).unwrap();
+
+        let remote_addr = tokio::time::timeout(Duration::from_secs(10), async {
+            let mut dialed = false;
+            loop {
+                tokio::select! {
+                    _ = relay.select_next_some() => {}
+                    event = private.select_next_some() => {
+                        // The relayed address appears once the reservation is made
+                        if let SwarmEvent::NewListenAddr { address, .. } = event {
+                            if !dialed {
+                                dialed = true;
+                                dialer.dial(address).unwrap();
+                            }
+                        }
+                    }
+                    event = dialer.select_next_some() => {
+                        if let SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } = event {
+                            if peer_id == private_id {
+                                return endpoint.get_remote_address().clone();
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Dialer should reach the private node");
+
+        assert!(is_relayed(&remote_addr));
+    }
+
+    // ========== TDD Tests: check_inbound_record() ==========
+
+    fn card_record(did: &str, version: u64) -> kad::Record {
+        let card = serde_json::json!({
+            "name": "Agent",
+            "description": "An agent",
+            "url": "https://agent.example.com",
+            "provider": null,
+            "skills": [],
+            "authentication": null,
+            "x-agoramesh": { "did": did, "payment_methods": [], "version": version }
+        });
+        kad::Record::new(
+            kad::RecordKey::new(&did),
+            serde_json::to_vec(&card).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_inbound_record_refuses_unknown_keys() {
+        let card = card_record("did:agoramesh:base:a", 1);
+        let record = kad::Record::new(
+            kad::RecordKey::new(&"/agoramesh/providers/skill"),
+            card.value,
+        );
+
+        let result = check_inbound_record(&record, None);
+
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    fn did_document_record(
+        identity: &crate::identity::NodeIdentity,
+        did: &str,
+        signer_did: &str,
+    ) -> kad::Record {
+        let document = identity.did_document(did, 84532, None).unwrap();
+        let value =
+            DidDocumentRecord::seal(&document, &identity.envelope_signer(signer_did)).unwrap();
+        kad::Record::new(
+            kad::RecordKey::new(&crate::did::did_document_key(did)),
+            value,
+        )
+    }
+
+    #[test]
+    fn test_inbound_record_accepts_did_document_signed_by_peer_key() {
+        let identity = crate::identity::NodeIdentity::generate();
+        let did = identity.default_did(84532);
+
+        let check = check_inbound_record(&did_document_record(&identity, &did, &did), None);
+
+        assert!(matches!(check, Ok(RecordCheck::Accept)));
+    }
+
+    #[test]
+    fn test_inbound_record_rejects_forged_did_document() {
+        let victim = crate::identity::NodeIdentity::generate();
+        let attacker = crate::identity::NodeIdentity::generate();
+        let did = victim.default_did(84532);
+
+        // Attacker's key published under the victim's peer-ID DID
+        let forged = did_document_record(&attacker, &did, &did);
+        assert!(check_inbound_record(&forged, None).is_err());
+
+        // Unsigned document
+        let mut unsigned = forged.clone();
+        unsigned.value = victim
+            .did_document(&did, 84532, None)
+            .unwrap()
+            .to_json()
+            .unwrap()
+            .into_bytes();
+        assert!(check_inbound_record(&unsigned, None).is_err());
+    }
+
+    #[test]
+    fn test_inbound_record_did_document_needs_previous_controller() {
+        let owner = crate::identity::NodeIdentity::generate();
+        let attacker = crate::identity::NodeIdentity::generate();
+        let did = "did:agoramesh:base:named-agent";
+
+        // No authority for a DID that is not a peer ID
+        let first = did_document_record(&owner, did, did);
+        assert!(check_inbound_record(&first, None).is_err());
+
+        // Updates must be signed by the stored controller
+        let update = did_document_record(&owner, did, did);
+        assert!(matches!(
+            check_inbound_record(&update, Some(&first)),
+            Ok(RecordCheck::Accept)
+        ));
+        let takeover = did_document_record(&attacker, did, did);
+        assert!(check_inbound_record(&takeover, Some(&first)).is_err());
+    }
+
+    #[test]
+    fn test_inbound_record_verifies_newer_card() {
+        let stored = card_record("did:agoramesh:base:a", 1);
+
+        let check =
+            check_inbound_record(&card_record("did:agoramesh:base:a", 2), Some(&stored)).unwrap();
+
+        assert!(matches!(check, RecordCheck::VerifyCard(card) if card.revision().0 == 2));
+    }
+
+    #[test]
+    fn test_inbound_record_rejects_stale_card() {
+        let stored = card_record("did:agoramesh:base:a", 2);
+
+        let result = check_inbound_record(&card_record("did:agoramesh:base:a", 1), Some(&stored));
+
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn test_inbound_record_rejects_card_under_foreign_did() {
+        let mut record = card_record("did:agoramesh:base:attacker", 1);
+        record.key = kad::RecordKey::new(&"did:agoramesh:base:victim");
+
+        assert!(check_inbound_record(&record, None).is_err());
+        record.value = b"garbage".to_vec();
+        assert!(check_inbound_record(&record, None).is_err());
+    }
+
     #[test]
     fn test_peer_score_params_configured_for_all_topics() {
         let params = build_peer_score_params();
@@ -0,0 +1,610 @@
+//! Connection admission control.
+//!
+//! [`ConnectionGate`] is a [`NetworkBehaviour`] that applies the policies in
+//! [`super::security`] to inbound connections before any other protocol
+//! sees them:
+//! - IPs backing off after failed handshakes are refused
+//! - The node-wide connection limit and per-/24 and per-/16 subnet limits
+//!   are enforced
+//! - New connections are rate limited across all IPs
+//!
+//! Every refusal is reported as a [`ConnectionDenial`] so it can be counted.
+//! Outbound connections are the node's own choice and are only checked
+//! against the [`PeerFilter`] of a private mesh, like inbound ones.
+
+use libp2p::{
+    core::{transport::PortUse, Endpoint},
+    multiaddr::Protocol,
+    swarm::{
+        dummy, ConnectionClosed, ConnectionDenied, ConnectionId, FromSwarm, ListenError,
+        ListenFailure, NetworkBehaviour, THandler, THandlerInEvent, THandlerOutEvent, ToSwarm,
+    },
+    Multiaddr, PeerId,
+};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::net::IpAddr;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use super::private_mesh::PeerFilter;
+use super::security::{
+    ConnectionRateLimiter, ConnectionTracker, GlobalConnectionRateLimiter, SecurityConfig,
+    Subnet16Tracker, SubnetTracker,
+};
+
+/// How long a failing IP's backoff state is remembered.
+const BACKOFF_RETENTION: Duration = Duration::from_secs(3600);
+
+/// Why an inbound connection was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenialReason {
+    /// The IP is backing off after failed handshakes.
+    Backoff,
+    /// The node is at its connection limit.
+    MaxConnections,
+    /// The remote /24 subnet is at its limit.
+    Subnet24,
+    /// The remote /16 subnet is at its limit.
+    Subnet16,
+    /// Too many new connections in the current minute.
+    RateLimit,
+    /// The peer is not on the private mesh allowlist.
+    PeerNotAllowed,
+    /// The peer is on the denylist.
+    PeerDenied,
+}
+
+impl DenialReason {
+    /// Metric label for this reason.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DenialReason::Backoff => "backoff",
+            DenialReason::MaxConnections => "max_connections",
+            DenialReason::Subnet24 => "subnet_24",
+            DenialReason::Subnet16 => "subnet_16",
+            DenialReason::RateLimit => "rate_limit",
+            DenialReason::PeerNotAllowed => "peer_not_allowed",
+            DenialReason::PeerDenied => "peer_denied",
+        }
+    }
+}
+
+impl fmt::Display for DenialReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "connection denied: {}", self.as_str())
+    }
+}
+
+impl std::error::Error for DenialReason {}
+
+/// A connection refused by the gate.
+#[derive(Debug, Clone)]
+pub struct ConnectionDenial {
+    /// Address the connection came from.
+    pub remote_addr: Multiaddr,
+    /// Why it was refused.
+    pub reason: DenialReason,
+}
+
+/// Behaviour enforcing [`SecurityConfig`] on inbound connections.
+pub struct ConnectionGate {
+    subnets_24: SubnetTracker,
+    subnets_16: Subnet16Tracker,
+    connections: ConnectionTracker,
+    rate: GlobalConnectionRateLimiter,
+    backoff: ConnectionRateLimiter,
+    limit_private_subnets: bool,
+    peers: PeerFilter,
+    /// Established inbound connections and their remote IPs.
+    inbound: HashMap<ConnectionId, IpAddr>,
+    last_cleanup: Instant,
+    denials: VecDeque<ConnectionDenial>,
+}
+
+impl ConnectionGate {
+    /// Create a gate enforcing the limits in `config`.
+    pub fn new(config: &SecurityConfig) -> Self {
+        Self {
+            subnets_24: SubnetTracker::with_limit(config.max_peers_per_subnet),
+            subnets_16: Subnet16Tracker::with_limit(config.max_peers_per_subnet_16),
+            connections: ConnectionTracker::new(config.max_connections),
+            rate: GlobalConnectionRateLimiter::new(config.max_connections_per_minute),
+            backoff: ConnectionRateLimiter::with_config(
+                config.rate_limit_base_delay,
+                config.rate_limit_max_delay,
+                config.rate_limit_max_failures,
+            ),
+            limit_private_subnets: config.limit_private_subnets,
+            peers: PeerFilter::default(),
+            inbound: HashMap::new(),
+            last_cleanup: Instant::now(),
+            denials: VecDeque::new(),
+        }
+    }
+
+    /// Only keep connections to peers `filter` permits.
+    pub fn with_peer_filter(mut self, filter: PeerFilter) -> Self {
+        self.peers = filter;
+        self
+    }
+
+    /// Number of established inbound connections being tracked.
+    pub fn inbound_connections(&self) -> usize {
+        self.inbound.len()
+    }
+
+    fn subnet_limited(&self, ip: &IpAddr) -> bool {
+        self.limit_private_subnets || !is_private(ip)
+    }
+
+    /// Check a new connection from `ip` against every limit.
+    fn admit(&mut self, ip: &IpAddr) -> Result<(), DenialReason> {
+        if !self.backoff.can_attempt(ip) {
+            return Err(DenialReason::Backoff);
+        }
+        if !self.connections.can_accept_connection() && !self.connections.has_connection(ip) {
+            return Err(DenialReason::MaxConnections);
+        }
+        if self.subnet_limited(ip) {
+            if !self.subnets_24.can_accept_connection(ip) {
+                return Err(DenialReason::Subnet24);
+            }
+            if !self.subnets_16.can_accept_connection(ip) {
+                return Err(DenialReason::Subnet16);
+            }
+        }
+        // Last, so refused connections do not use up the rate budget
+        if !self.rate.record_new_connection() {
+            return Err(DenialReason::RateLimit);
+        }
+        Ok(())
+    }
+
+    /// Count an established connection from `ip`.
+    ///
+    /// Limits are checked again: several connections may have passed
+    /// [`Self::admit`] before any of them was established.
+    fn track(&mut self, connection_id: ConnectionId, ip: IpAddr) -> Result<(), DenialReason> {
+        if self.connections.add_connection(&ip).is_err() {
+            return Err(DenialReason::MaxConnections);
+        }
+        if self.subnet_limited(&ip) {
+            if self.subnets_24.add_connection(&ip).is_err() {
+                self.release_ip(&ip);
+                return Err(DenialReason::Subnet24);
+            }
+            if self.subnets_16.add_connection(&ip).is_err() {
+                self.subnets_24.remove_connection(&ip);
+                self.release_ip(&ip);
+                return Err(DenialReason::Subnet16);
+            }
+        }
+        self.inbound.insert(connection_id, ip);
+        self.backoff.record_success(&ip);
+        Ok(())
+    }
+
+    fn untrack(&mut self, connection_id: &ConnectionId) {
+        let Some(ip) = self.inbound.remove(connection_id) else {
+            return;
+        };
+        if self.subnet_limited(&ip) {
+            self.subnets_24.remove_connection(&ip);
+            self.subnets_16.remove_connection(&ip);
+        }
+        self.release_ip(&ip);
+    }
+
+    /// Free the connection slot of `ip` once none of its connections remain.
+    fn release_ip(&mut self, ip: &IpAddr) {
+        if !self.inbound.values().any(|other| other == ip) {
+            self.connections.remove_connection(ip);
+        }
+    }
+
+    fn deny(&mut self, remote_addr: &Multiaddr, reason: DenialReason) -> ConnectionDenied {
+        self.denials.push_back(ConnectionDenial {
+            remote_addr: remote_addr.clone(),
+            reason,
+        });
+        ConnectionDenied::new(reason)
+    }
+}
+
+/// The IP address a multiaddr points at, if any.
+///
+/// Relayed (`/p2p-circuit`) addresses have none: the IP they contain is the
+/// relay's, and limiting it would cap how many peers one relay can carry.
+pub fn multiaddr_ip(addr: &Multiaddr) -> Option<IpAddr> {
+    if addr.iter().any(|p| matches!(p, Protocol::P2pCircuit)) {
+        return None;
+    }
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+/// Loopback, private and link-local addresses.
+fn is_private(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback(),
+    }
+}
+
+impl NetworkBehaviour for ConnectionGate {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = ConnectionDenial;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        let Some(ip) = multiaddr_ip(remote_addr) else {
+            return Ok(());
+        };
+        if self.last_cleanup.elapsed() >= BACKOFF_RETENTION {
+            self.backoff.cleanup(BACKOFF_RETENTION);
+            self.last_cleanup = Instant::now();
+        }
+
+        match self.admit(&ip) {
+            Ok(()) => {
+                self.backoff.record_attempt(ip);
+                Ok(())
+            }
+            Err(reason) => Err(self.deny(remote_addr, reason)),
+        }
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        peer: PeerId,
+        _local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        if let Err(reason) = self.peers.check(&peer) {
+            return Err(self.deny(remote_addr, reason));
+        }
+        if let Some(ip) = multiaddr_ip(remote_addr) {
+            if let Err(reason) = self.track(connection_id, ip) {
+                return Err(self.deny(remote_addr, reason));
+            }
+        }
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        _role_override: Endpoint,
+        _port_use: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        if let Err(reason) = self.peers.check(&peer) {
+            return Err(self.deny(addr, reason));
+        }
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        match event {
+            FromSwarm::ConnectionClosed(ConnectionClosed { connection_id, .. }) => {
+                self.untrack(&connection_id);
+            }
+            FromSwarm::ListenFailure(ListenFailure {
+                send_back_addr,
+                error,
+                connection_id,
+                ..
+            }) => {
+                // Another behaviour may have refused a connection we counted
+                self.untrack(&connection_id);
+
+                // Failed handshakes push the IP into exponential backoff
+                if matches!(
+                    error,
+                    ListenError::Transport(_) | ListenError::WrongPeerId { .. }
+                ) {
+                    if let Some(ip) = multiaddr_ip(send_back_addr) {
+                        self.backoff.record_failure(ip);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _peer_id: PeerId,
+        _connection_id: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        match self.denials.pop_front() {
+            Some(denial) => Poll::Ready(ToSwarm::GenerateEvent(denial)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::PrivateMeshConfig;
+    use libp2p::core::ConnectedPoint;
+
+    fn addr(ip: &str) -> Multiaddr {
+        format!("/ip4/{}/tcp/4001", ip).parse().unwrap()
+    }
+
+    fn local() -> Multiaddr {
+        addr("10.0.0.1")
+    }
+
+    fn peer() -> PeerId {
+        PeerId::from(libp2p::identity::Keypair::generate_ed25519().public())
+    }
+
+    /// Run a connection from `ip` through both admission steps.
+    fn connect(gate: &mut ConnectionGate, id: usize, ip: &str) -> Result<(), ConnectionDenied> {
+        let connection_id = ConnectionId::new_unchecked(id);
+        gate.handle_pending_inbound_connection(connection_id, &local(), &addr(ip))?;
+        gate.handle_established_inbound_connection(connection_id, peer(), &local(), &addr(ip))
+            .map(|_| ())
+    }
+
+    fn close(gate: &mut ConnectionGate, id: usize, ip: &str) {
+        let endpoint = ConnectedPoint::Listener {
+            local_addr: local(),
+            send_back_addr: addr(ip),
+        };
+        gate.on_swarm_event(FromSwarm::ConnectionClosed(ConnectionClosed {
+            peer_id: peer(),
+            connection_id: ConnectionId::new_unchecked(id),
+            endpoint: &endpoint,
+            cause: None,
+            remaining_established: 0,
+        }));
+    }
+
+    fn reason(denied: ConnectionDenied) -> DenialReason {
+        denied.downcast::<DenialReason>().unwrap()
+    }
+
+    fn generous() -> SecurityConfig {
+        SecurityConfig {
+            max_connections_per_minute: 1_000,
+            ..Default::default()
+        }
+    }
+
+    // ========== TDD Tests: Subnet and connection limits ==========
+
+    #[test]
+    fn test_gate_denies_connections_over_subnet_16_limit() {
+        let mut gate = ConnectionGate::new(&generous());
+
+        for (i, ip) in ["8.8.1.1", "8.8.2.1", "8.8.3.1"].iter().enumerate() {
+            assert!(connect(&mut gate, i, ip).is_ok());
+        }
+        let denied = connect(&mut gate, 3, "8.8.4.1").unwrap_err();
+
+        assert_eq!(reason(denied), DenialReason::Subnet16);
+        assert!(connect(&mut gate, 4, "9.9.1.1").is_ok());
+    }
+
+    #[test]
+    fn test_gate_denies_connections_over_subnet_24_limit() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            max_peers_per_subnet: 2,
+            ..generous()
+        });
+
+        assert!(connect(&mut gate, 1, "8.8.8.1").is_ok());
+        assert!(connect(&mut gate, 2, "8.8.8.2").is_ok());
+
+        assert_eq!(
+            reason(connect(&mut gate, 3, "8.8.8.3").unwrap_err()),
+            DenialReason::Subnet24
+        );
+    }
+
+    #[test]
+    fn test_gate_enforces_max_connections() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            max_connections: 2,
+            ..generous()
+        });
+
+        assert!(connect(&mut gate, 1, "1.1.1.1").is_ok());
+        assert!(connect(&mut gate, 2, "2.2.2.2").is_ok());
+
+        assert_eq!(
+            reason(connect(&mut gate, 3, "3.3.3.3").unwrap_err()),
+            DenialReason::MaxConnections
+        );
+        // A second connection from a connected IP does not take a new slot
+        assert!(connect(&mut gate, 4, "1.1.1.1").is_ok());
+    }
+
+    #[test]
+    fn test_closing_a_connection_frees_its_slot() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            max_connections: 1,
+            ..generous()
+        });
+        connect(&mut gate, 1, "1.1.1.1").unwrap();
+
+        close(&mut gate, 1, "1.1.1.1");
+
+        assert_eq!(gate.inbound_connections(), 0);
+        assert!(connect(&mut gate, 2, "2.2.2.2").is_ok());
+    }
+
+    #[test]
+    fn test_private_addresses_skip_subnet_limits_by_default() {
+        let mut gate = ConnectionGate::new(&generous());
+
+        for i in 1..=10 {
+            assert!(connect(&mut gate, i, &format!("127.0.0.{}", i)).is_ok());
+        }
+
+        let mut strict = ConnectionGate::new(&SecurityConfig {
+            limit_private_subnets: true,
+            ..generous()
+        });
+        for i in 1..=3 {
+            connect(&mut strict, i, &format!("192.168.1.{}", i)).unwrap();
+        }
+        assert!(connect(&mut strict, 4, "192.168.1.4").is_err());
+    }
+
+    // ========== TDD Tests: Rate limiting and backoff ==========
+
+    #[test]
+    fn test_gate_rate_limits_new_connections() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            max_connections_per_minute: 2,
+            ..Default::default()
+        });
+
+        assert!(connect(&mut gate, 1, "1.1.1.1").is_ok());
+        assert!(connect(&mut gate, 2, "2.2.2.2").is_ok());
+
+        assert_eq!(
+            reason(connect(&mut gate, 3, "3.3.3.3").unwrap_err()),
+            DenialReason::RateLimit
+        );
+    }
+
+    #[test]
+    fn test_failed_handshakes_back_off_the_ip() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            rate_limit_base_delay: Duration::from_secs(60),
+            ..generous()
+        });
+        let error = ListenError::WrongPeerId {
+            obtained: peer(),
+            endpoint: ConnectedPoint::Listener {
+                local_addr: local(),
+                send_back_addr: addr("6.6.6.6"),
+            },
+        };
+
+        gate.on_swarm_event(FromSwarm::ListenFailure(ListenFailure {
+            local_addr: &local(),
+            send_back_addr: &addr("6.6.6.6"),
+            error: &error,
+            connection_id: ConnectionId::new_unchecked(1),
+            peer_id: None,
+        }));
+
+        assert_eq!(
+            reason(connect(&mut gate, 2, "6.6.6.6").unwrap_err()),
+            DenialReason::Backoff
+        );
+        assert!(connect(&mut gate, 3, "7.7.7.7").is_ok());
+    }
+
+    #[test]
+    fn test_denials_are_reported_as_events() {
+        let mut gate = ConnectionGate::new(&SecurityConfig {
+            max_connections: 0,
+            ..generous()
+        });
+        let _ = connect(&mut gate, 1, "1.1.1.1");
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        match gate.poll(&mut cx) {
+            Poll::Ready(ToSwarm::GenerateEvent(denial)) => {
+                assert_eq!(denial.reason, DenialReason::MaxConnections);
+                assert_eq!(denial.remote_addr, addr("1.1.1.1"));
+            }
+            _ => panic!("expected a denial event"),
+        }
+        assert!(gate.poll(&mut cx).is_pending());
+    }
+
+    // ========== TDD Tests: Peer allowlist ==========
+
+    #[test]
+    fn test_gate_only_keeps_connections_to_allowed_peers() {
+        let member = peer();
+        let filter = PrivateMeshConfig {
+            allowed_peers: vec![member.to_string()],
+            ..Default::default()
+        }
+        .peer_filter()
+        .unwrap();
+        let mut gate = ConnectionGate::new(&generous()).with_peer_filter(filter);
+        let connection_id = ConnectionId::new_unchecked(1);
+
+        let inbound = gate
+            .handle_established_inbound_connection(
+                connection_id,
+                peer(),
+                &local(),
+                &addr("1.1.1.1"),
+            )
+            .map(|_| ())
+            .unwrap_err();
+        let outbound = gate
+            .handle_established_outbound_connection(
+                connection_id,
+                peer(),
+                &addr("2.2.2.2"),
+                Endpoint::Dialer,
+                PortUse::New,
+            )
+            .map(|_| ())
+            .unwrap_err();
+
+        assert_eq!(reason(inbound), DenialReason::PeerNotAllowed);
+        assert_eq!(reason(outbound), DenialReason::PeerNotAllowed);
+        assert_eq!(gate.inbound_connections(), 0);
+        assert!(gate
+            .handle_established_inbound_connection(
+                connection_id,
+                member,
+                &local(),
+                &addr("1.1.1.1")
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_multiaddr_ip_extracts_address() {
+        assert_eq!(
+            multiaddr_ip(&addr("1.2.3.4")),
+            Some("1.2.3.4".parse().unwrap())
+        );
+        assert_eq!(
+            multiaddr_ip(&"/ip6/::1/udp/4001/quic-v1".parse().unwrap()),
+            Some("::1".parse().unwrap())
+        );
+        assert_eq!(
+            multiaddr_ip(&"/dns4/example.com/tcp/1".parse().unwrap()),
+            None
+        );
+        assert_eq!(
+            multiaddr_ip(&"/ip4/1.2.3.4/tcp/1/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN/p2p-circuit".parse().unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,491 @@
+//! Signed envelopes for GossipSub messages.
+//!
+//! A [`SignedEnvelope`] wraps a discovery, trust or dispute message and binds
+//! it to a verification method in the signer's DID document:
+//! - Ed25519 keys (`Ed25519VerificationKey2020`)
+//! - Ethereum accounts (`EcdsaSecp256k1RecoveryMethod2020`, EIP-191 signatures)
+//!
+//! The signature covers the topic, signer, verification method, nonce,
+//! timestamp and payload, so an envelope cannot be replayed on another topic
+//! or re-attributed. [`ReplayGuard`] rejects stale envelopes and nonces that
+//! have already been seen from the same signer.
+
+use std::collections::HashMap;
+
+use alloy::primitives::hex;
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use libp2p::identity::ed25519;
+use serde::{Deserialize, Serialize};
+
+use crate::did::DIDDocument;
+use crate::error::{Error, Result};
+
+/// Current envelope format version.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Maximum clock skew (in either direction) accepted for envelope timestamps.
+pub const MAX_ENVELOPE_AGE_SECS: u64 = 300;
+
+/// Domain separator included in every signed message.
+const SIGNING_DOMAIN: &str = "agoramesh-gossip-envelope";
+
+/// A signed, versioned wrapper around a GossipSub message payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEnvelope {
+    /// Envelope format version.
+    pub version: u8,
+    /// Serialized inner message (JSON).
+    pub payload: String,
+    /// DID of the signer.
+    pub signer: String,
+    /// Full ID of the verification method used (`did:...#key-1`).
+    pub verification_method: String,
+    /// Hex-encoded signature over the signing bytes.
+    pub signature: String,
+    /// Random nonce, unique per signer.
+    pub nonce: String,
+    /// Creation time (Unix seconds).
+    pub timestamp: u64,
+}
+
+impl SignedEnvelope {
+    /// Sign a payload with an Ed25519 key.
+    pub fn sign_ed25519(
+        topic: &str,
+        payload: String,
+        signer: &str,
+        verification_method: &str,
+        keypair: &ed25519::Keypair,
+    ) -> Result<Self> {
+        let mut envelope = Self::unsigned(payload, signer, verification_method)?;
+        let signature = keypair.sign(&envelope.signing_bytes(topic)?);
+        envelope.signature = hex::encode(signature);
+        Ok(envelope)
+    }
+
+    /// Sign a payload with an Ethereum account (EIP-191 `personal_sign`).
+    pub fn sign_ethereum(
+        topic: &str,
+        payload: String,
+        signer: &str,
+        verification_method: &str,
+        wallet: &PrivateKeySigner,
+    ) -> Result<Self> {
+        let mut envelope = Self::unsigned(payload, signer, verification_method)?;
+        let signature = wallet
+            .sign_message_sync(&envelope.signing_bytes(topic)?)
+            .map_err(|e| Error::Did(format!("Failed to sign envelope: {}", e)))?;
+        envelope.signature = hex::encode(signature.as_bytes());
+        Ok(envelope)
+    }
+
+    fn unsigned(payload: String, signer: &str, verification_method: &str) -> Result<Self> {
+        Ok(Self {
+            version: ENVELOPE_VERSION,
+            payload,
+            signer: signer.to_string(),
+            verification_method: verification_method.to_string(),
+            signature: String::new(),
+            nonce: uuid::Uuid::new_v4().to_string(),
+            timestamp: current_timestamp()?,
+        })
+    }
+
+    /// Bytes covered by the signature.
+    ///
+    /// Encoded as a JSON array so field boundaries are unambiguous.
+    pub fn signing_bytes(&self, topic: &str) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&(
+            SIGNING_DOMAIN,
+            self.version,
+            topic,
+            &self.signer,
+            &self.verification_method,
+            &self.nonce,
+            self.timestamp,
+            &self.payload,
+        ))?)
+    }
+
+    /// Verify the envelope signature against the signer's DID document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is unsupported, the document does not
+    /// belong to the signer, the verification method is unknown, or the
+    /// signature does not match.
+    pub fn verify(&self, topic: &str, document: &DIDDocument) -> Result<()> {
+        if self.version != ENVELOPE_VERSION {
+            return Err(Error::Validation(format!(
+                "Unsupported envelope version: {}",
+                self.version
+            )));
+        }
+        if document.id != self.signer {
+            return Err(Error::Validation(format!(
+                "DID document '{}' does not match signer '{}'",
+                document.id, self.signer
+            )));
+        }
+
+        let method = document
+            .find_verification_method(&self.verification_method)
+            .filter(|m| m.controller == self.signer)
+            .ok_or_else(|| {
+                Error::Validation(format!(
+                    "Verification method '{}' not found for {}",
+                    self.verification_method, self.signer
+                ))
+            })?;
+
+        let signature = hex::decode(&self.signature)
+            .map_err(|e| Error::Validation(format!("Invalid signature encoding: {}", e)))?;
+        method
+            .verify(&self.signing_bytes(topic)?, &signature)
+            .map_err(|e| Error::Validation(format!("Invalid envelope signature: {}", e)))
+    }
+}
+
+/// Signs outbound GossipSub messages as one DID.
+///
+/// The swarm wraps every publish in a [`SignedEnvelope`] when a signer is
+/// configured, so peers can attribute the message to the node's DID.
+#[derive(Clone)]
+pub struct EnvelopeSigner {
+    did: String,
+    verification_method: String,
+    keypair: ed25519::Keypair,
+}
+
+impl std::fmt::Debug for EnvelopeSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvelopeSigner")
+            .field("did", &self.did)
+            .field("verification_method", &self.verification_method)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EnvelopeSigner {
+    /// Sign as `did` with the Ed25519 key behind `verification_method`.
+    pub fn new(did: &str, verification_method: &str, keypair: ed25519::Keypair) -> Self {
+        Self {
+            did: did.to_string(),
+            verification_method: verification_method.to_string(),
+            keypair,
+        }
+    }
+
+    /// The signer DID.
+    pub fn did(&self) -> &str {
+        &self.did
+    }
+
+    /// Wrap a JSON message for `topic` in a serialized signed envelope.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is not UTF-8 or the envelope cannot be
+    /// serialized.
+    pub fn seal(&self, topic: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let payload = String::from_utf8(data.to_vec())
+            .map_err(|e| Error::Validation(format!("Gossip payload is not UTF-8: {}", e)))?;
+        let envelope = SignedEnvelope::sign_ed25519(
+            topic,
+            payload,
+            &self.did,
+            &self.verification_method,
+            &self.keypair,
+        )?;
+        serde_json::to_vec(&envelope)
+            .map_err(|e| Error::Internal(format!("Failed to serialize envelope: {}", e)))
+    }
+}
+
+/// Replay protection for signed envelopes.
+///
+/// Tracks `(signer, nonce)` pairs for the acceptance window and rejects
+/// envelopes whose timestamp falls outside it.
+#[derive(Debug)]
+pub struct ReplayGuard {
+    max_age_secs: u64,
+    seen: HashMap<(String, String), u64>,
+    last_pruned: u64,
+}
+
+impl Default for ReplayGuard {
+    fn default() -> Self {
+        Self::new(MAX_ENVELOPE_AGE_SECS)
+    }
+}
+
+impl ReplayGuard {
+    /// Create a guard accepting timestamps within `max_age_secs` of now.
+    pub fn new(max_age_secs: u64) -> Self {
+        Self {
+            max_age_secs,
+            seen: HashMap::new(),
+            last_pruned: 0,
+        }
+    }
+
+    /// Check an envelope at time `now` (Unix seconds) and record its nonce.
+    ///
+    /// Only call this for envelopes whose signature has been verified, so
+    /// forged envelopes cannot fill the nonce table.
+    pub fn check(&mut self, envelope: &SignedEnvelope, now: u64) -> Result<()> {
+        if envelope.timestamp.saturating_add(self.max_age_secs) < now {
+            return Err(Error::Validation(format!(
+                "Envelope timestamp {} is too old",
+                envelope.timestamp
+            )));
+        }
+        if envelope.timestamp > now.saturating_add(self.max_age_secs) {
+            return Err(Error::Validation(format!(
+                "Envelope timestamp {} is in the future",
+                envelope.timestamp
+            )));
+        }
+
+        // Forget nonces that can no longer pass the timestamp check (at most once a second)
+        if now > self.last_pruned {
+            let max_age_secs = self.max_age_secs;
+            self.seen
+                .retain(|_, timestamp| timestamp.saturating_add(max_age_secs) >= now);
+            self.last_pruned = now;
+        }
+
+        let key = (envelope.signer.clone(), envelope.nonce.clone());
+        if self.seen.contains_key(&key) {
+            return Err(Error::Validation(format!(
+                "Replayed envelope nonce {} from {}",
+                envelope.nonce, envelope.signer
+            )));
+        }
+        self.seen.insert(key, envelope.timestamp);
+        Ok(())
+    }
+
+    /// Number of nonces currently tracked.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Whether no nonces are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+/// Current Unix timestamp in seconds.
+pub(crate) fn current_timestamp() -> Result<u64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| Error::Internal(format!("System clock error: {}", e)))?
+        .as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::did::{encode_ed25519_multibase, DIDDocumentBuilder};
+
+    const TOPIC: &str = "/agoramesh/trust/1.0.0";
+    const SIGNER: &str = "did:agoramesh:base:signer";
+    const KEY_ID: &str = "did:agoramesh:base:signer#key-1";
+
+    fn ed25519_identity() -> (ed25519::Keypair, DIDDocument) {
+        let keypair = ed25519::Keypair::generate();
+        let doc = DIDDocumentBuilder::new("base", "signer")
+            .add_ed25519_key("key-1", &encode_ed25519_multibase(&keypair.public()))
+            .build()
+            .unwrap();
+        (keypair, doc)
+    }
+
+    // ========== TDD Tests: Signing and verification ==========
+
+    #[test]
+    fn test_ed25519_envelope_verifies() {
+        let (keypair, doc) = ed25519_identity();
+
+        let envelope =
+            SignedEnvelope::sign_ed25519(TOPIC, "{}".to_string(), SIGNER, KEY_ID, &keypair)
+                .unwrap();
+
+        assert_eq!(envelope.version, ENVELOPE_VERSION);
+        assert!(envelope.verify(TOPIC, &doc).is_ok());
+    }
+
+    #[test]
+    fn test_ethereum_envelope_verifies() {
+        let wallet = PrivateKeySigner::random();
+        let doc = DIDDocumentBuilder::new("base", "signer")
+            .add_ethereum_account("eth", &wallet.address().to_string(), 8453)
+            .build()
+            .unwrap();
+
+        let envelope = SignedEnvelope::sign_ethereum(
+            TOPIC,
+            "{}".to_string(),
+            SIGNER,
+            "did:agoramesh:base:signer#eth",
+            &wallet,
+        )
+        .unwrap();
+
+        assert!(envelope.verify(TOPIC, &doc).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_payload_is_rejected() {
+        let (keypair, doc) = ed25519_identity();
+        let mut envelope =
+            SignedEnvelope::sign_ed25519(TOPIC, "{}".to_string(), SIGNER, KEY_ID, &keypair)
+                .unwrap();
+
+        envelope.payload = r#"{"forged":true}"#.to_string();
+
+        assert!(envelope.verify(TOPIC, &doc).is_err());
+    }
+
+    #[test]
+    fn test_envelope_is_bound_to_topic() {
+        let (keypair, doc) = ed25519_identity();
+        let envelope =
+            SignedEnvelope::sign_ed25519(TOPIC, "{}".to_string(), SIGNER, KEY_ID, &keypair)
+                .unwrap();
+
+        assert!(envelope.verify("/agoramesh/discovery/1.0.0", &doc).is_err());
+    }
+
+    #[test]
+    fn test_envelope_rejects_foreign_key() {
+        let (_, doc) = ed25519_identity();
+        let other = ed25519::Keypair::generate();
+        let envelope =
+            SignedEnvelope::sign_ed25519(TOPIC, "{}".to_string(), SIGNER, KEY_ID, &other).unwrap();
+
+        assert!(envelope.verify(TOPIC, &doc).is_err());
+    }
+
+    #[test]
+    fn test_envelope_rejects_unknown_verification_method() {
+        let (keypair, doc) = ed25519_identity();
+        let envelope = SignedEnvelope::sign_ed25519(
+            TOPIC,
+            "{}".to_string(),
+            SIGNER,
+            "did:agoramesh:base:signer#key-2",
+            &keypair,
+        )
+        .unwrap();
+
+        let err = envelope.verify(TOPIC, &doc).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_envelope_rejects_mismatched_document() {
+        let (keypair, doc) = ed25519_identity();
+        let envelope = SignedEnvelope::sign_ed25519(
+            TOPIC,
+            "{}".to_string(),
+            "did:agoramesh:base:impostor",
+            KEY_ID,
+            &keypair,
+        )
+        .unwrap();
+
+        assert!(envelope.verify(TOPIC, &doc).is_err());
+    }
+
+    #[test]
+    fn test_envelope_rejects_unknown_version() {
+        let (keypair, doc) = ed25519_identity();
+        let mut envelope =
+            SignedEnvelope::sign_ed25519(TOPIC, "{}".to_string(), SIGNER, KEY_ID, &keypair)
+                .unwrap();
+
+        envelope.version = ENVELOPE_VERSION + 1;
+
+        assert!(envelope.verify(TOPIC, &doc).is_err());
+    }
+
+    // ========== TDD Tests: EnvelopeSigner ==========
+
+    #[test]
+    fn test_signer_seals_verifiable_envelope() {
+        let (keypair, doc) = ed25519_identity();
+        let signer = EnvelopeSigner::new(SIGNER, KEY_ID, keypair);
+
+        let sealed = signer.seal(TOPIC, br#"{"type":"trust_update"}"#).unwrap();
+
+        let envelope: SignedEnvelope = serde_json::from_slice(&sealed).unwrap();
+        assert_eq!(envelope.signer, SIGNER);
+        assert_eq!(envelope.payload, r#"{"type":"trust_update"}"#);
+        assert!(envelope.verify(TOPIC, &doc).is_ok());
+    }
+
+    #[test]
+    fn test_signer_rejects_binary_payload() {
+        let (keypair, _) = ed25519_identity();
+        let signer = EnvelopeSigner::new(SIGNER, KEY_ID, keypair);
+
+        assert!(signer.seal(TOPIC, &[0xff, 0xfe]).is_err());
+    }
+
+    // ========== TDD Tests: ReplayGuard ==========
+
+    fn envelope_at(timestamp: u64, nonce: &str) -> SignedEnvelope {
+        SignedEnvelope {
+            version: ENVELOPE_VERSION,
+            payload: "{}".to_string(),
+            signer: SIGNER.to_string(),
+            verification_method: KEY_ID.to_string(),
+            signature: String::new(),
+            nonce: nonce.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_replay_guard_rejects_duplicate_nonce() {
+        let mut guard = ReplayGuard::default();
+        let envelope = envelope_at(1_000, "n1");
+
+        assert!(guard.check(&envelope, 1_000).is_ok());
+        assert!(guard.check(&envelope, 1_001).is_err());
+    }
+
+    #[test]
+    fn test_replay_guard_rejects_stale_and_future_timestamps() {
+        let mut guard = ReplayGuard::new(300);
+
+        assert!(guard.check(&envelope_at(1_000, "old"), 1_301).is_err());
+        assert!(guard.check(&envelope_at(1_302, "new"), 1_001).is_err());
+        assert!(guard.check(&envelope_at(1_000, "ok"), 1_300).is_ok());
+    }
+
+    #[test]
+    fn test_replay_guard_prunes_expired_nonces() {
+        let mut guard = ReplayGuard::new(300);
+        guard.check(&envelope_at(1_000, "n1"), 1_000).unwrap();
+        assert_eq!(guard.len(), 1);
+
+        guard.check(&envelope_at(2_000, "n2"), 2_000).unwrap();
+
+        assert_eq!(guard.len(), 1, "Expired nonce should be pruned");
+    }
+
+    #[test]
+    fn test_same_nonce_from_different_signers_is_allowed() {
+        let mut guard = ReplayGuard::default();
+        let first = envelope_at(1_000, "shared");
+        let mut second = first.clone();
+        second.signer = "did:agoramesh:base:other".to_string();
+
+        assert!(guard.check(&first, 1_000).is_ok());
+        assert!(guard.check(&second, 1_000).is_ok());
+    }
+}
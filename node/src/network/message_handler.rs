@@ -4,18 +4,22 @@
 //! - Parsing incoming GossipSub messages by topic
 //! - Routing messages to appropriate handlers
 //! - Processing discovery, capability, and trust messages
+//! - Verifying signed envelopes and rejecting replays
 
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::arbitration::{AIArbitrator, Evidence, EvidenceType};
+use crate::did::DidResolver;
 use crate::discovery::{CapabilityCard, DiscoveryService};
 use crate::error::{Error, Result};
 use crate::trust::TrustService;
 
 use super::behaviour::topics;
+use super::envelope::{current_timestamp, ReplayGuard, SignedEnvelope};
 use super::NetworkEvent;
 
 /// Maximum allowed length for evidence title (256 characters).
@@ -61,6 +65,11 @@ pub enum TrustMessage {
     ReputationEvent {
         /// The agent's DID.
         did: String,
+        /// DID of the other party to the transaction, who reports it.
+        ///
+        /// Signed events must be signed by this DID.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        counterparty_did: Option<String>,
         /// Whether the transaction was successful.
         success: bool,
         /// Transaction amount in USDC (6 decimals).
@@ -129,6 +138,10 @@ pub struct MessageHandlerStats {
     pub dispute_messages: u64,
     /// Unknown topic messages received.
     pub unknown_topic_messages: u64,
+    /// Messages rejected for a missing, invalid or unauthorized signature.
+    pub rejected_signatures: u64,
+    /// Signed messages rejected as replays (reused nonce or stale timestamp).
+    pub replayed_messages: u64,
 }
 
 impl MessageHandlerStats {
@@ -166,6 +179,16 @@ impl MessageHandlerStats {
     pub fn record_unknown_topic(&mut self) {
         self.unknown_topic_messages += 1;
     }
+
+    /// Record a rejected signature.
+    pub fn record_rejected_signature(&mut self) {
+        self.rejected_signatures += 1;
+    }
+
+    /// Record a replayed message.
+    pub fn record_replay(&mut self) {
+        self.replayed_messages += 1;
+    }
 }
 
 /// Handler for incoming network messages.
@@ -181,6 +204,15 @@ pub struct MessageHandler {
     /// Optional arbitrator for handling disputes.
     arbitrator: Option<Arc<AIArbitrator>>,
 
+    /// Optional resolver for verifying signed envelopes.
+    did_resolver: Option<Arc<dyn DidResolver>>,
+
+    /// Whether unsigned (or unverifiable) messages are rejected.
+    require_signatures: bool,
+
+    /// Nonce tracking for signed envelopes.
+    replay_guard: Mutex<ReplayGuard>,
+
     /// Handler statistics.
     stats: RwLock<MessageHandlerStats>,
 }
@@ -192,6 +224,9 @@ impl MessageHandler {
             discovery_service,
             trust_service: None,
             arbitrator: None,
+            did_resolver: None,
+            require_signatures: false,
+            replay_guard: Mutex::new(ReplayGuard::default()),
             stats: RwLock::new(MessageHandlerStats::default()),
         }
     }
@@ -205,6 +240,9 @@ impl MessageHandler {
             discovery_service,
             trust_service,
             arbitrator: None,
+            did_resolver: None,
+            require_signatures: false,
+            replay_guard: Mutex::new(ReplayGuard::default()),
             stats: RwLock::new(MessageHandlerStats::default()),
         }
     }
@@ -219,10 +257,32 @@ impl MessageHandler {
             discovery_service,
             trust_service,
             arbitrator,
+            did_resolver: None,
+            require_signatures: false,
+            replay_guard: Mutex::new(ReplayGuard::default()),
             stats: RwLock::new(MessageHandlerStats::default()),
         }
     }
 
+    /// Verify signed envelopes against DID documents from `resolver`.
+    ///
+    /// Without a resolver, envelopes cannot be verified and are treated
+    /// like unsigned messages.
+    pub fn with_did_resolver(mut self, resolver: Arc<dyn DidResolver>) -> Self {
+        self.did_resolver = Some(resolver);
+        self
+    }
+
+    /// Require every message to be a verified signed envelope.
+    ///
+    /// Defaults to `false` so unsigned discovery messages from older peers are
+    /// accepted. Reputation events and dispute changes always need a verified
+    /// signer, whatever this is set to.
+    pub fn with_required_signatures(mut self, required: bool) -> Self {
+        self.require_signatures = required;
+        self
+    }
+
     /// Handle an incoming network event.
     ///
     /// Routes the message to the appropriate handler based on topic.
@@ -259,17 +319,127 @@ impl MessageHandler {
         data: &[u8],
         source: Option<&libp2p::PeerId>,
     ) -> Result<()> {
+        if !matches!(
+            topic,
+            topics::DISCOVERY | topics::CAPABILITY | topics::TRUST | topics::DISPUTES
+        ) {
+            self.stats.write().await.record_unknown_topic();
+            warn!("Received message on unknown topic: {}", topic);
+            return Ok(());
+        }
+
+        let (payload, signer) = self.open_envelope(topic, data).await?;
+        let signer = signer.as_deref();
+
         match topic {
-            topics::DISCOVERY => self.handle_discovery_message(data, source).await,
-            topics::CAPABILITY => self.handle_capability_message(data, source).await,
-            topics::TRUST => self.handle_trust_message(data, source).await,
-            topics::DISPUTES => self.handle_dispute_message(data, source).await,
-            _ => {
-                self.stats.write().await.record_unknown_topic();
-                warn!("Received message on unknown topic: {}", topic);
-                Ok(())
+            topics::DISCOVERY => {
+                self.handle_discovery_message(&payload, source, signer)
+                    .await
+            }
+            topics::CAPABILITY => {
+                self.handle_capability_message(&payload, source, signer)
+                    .await
+            }
+            topics::TRUST => self.handle_trust_message(&payload, source, signer).await,
+            _ => self.handle_dispute_message(&payload, source, signer).await,
+        }
+    }
+
+    /// Unwrap a signed envelope, verifying its signature and freshness.
+    ///
+    /// Returns the inner payload and the authenticated signer DID. Unsigned
+    /// messages pass through without a signer unless signatures are required;
+    /// handlers that change trust or dispute state reject them on their own.
+    async fn open_envelope<'a>(
+        &self,
+        topic: &str,
+        data: &'a [u8],
+    ) -> Result<(Cow<'a, [u8]>, Option<String>)> {
+        let Ok(envelope) = serde_json::from_slice::<SignedEnvelope>(data) else {
+            if self.require_signatures {
+                return Err(self.reject_signature("unsigned message".to_string()).await);
+            }
+            return Ok((Cow::Borrowed(data), None));
+        };
+
+        let Some(ref resolver) = self.did_resolver else {
+            if self.require_signatures {
+                return Err(self
+                    .reject_signature("no DID resolver configured to verify signatures".to_string())
+                    .await);
             }
+            // Cannot verify: treat like an unsigned message
+            return Ok((Cow::Owned(envelope.payload.into_bytes()), None));
+        };
+
+        let verified = match resolver.resolve(&envelope.signer).await {
+            Ok(Some(document)) => envelope.verify(topic, &document),
+            Ok(None) if !self.require_signatures => {
+                // Unknown signer: no better than an unsigned message
+                debug!(
+                    "Unknown signer DID {}, treating as unsigned",
+                    envelope.signer
+                );
+                return Ok((Cow::Owned(envelope.payload.into_bytes()), None));
+            }
+            Ok(None) => Err(Error::Validation(format!(
+                "Unknown signer DID: {}",
+                envelope.signer
+            ))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = verified {
+            return Err(self
+                .reject_signature(format!("{} (signer {})", e, envelope.signer))
+                .await);
+        }
+
+        let replay = self
+            .replay_guard
+            .lock()
+            .map_err(|e| Error::Internal(format!("Failed to acquire replay guard lock: {}", e)))?
+            .check(&envelope, current_timestamp()?);
+        if let Err(e) = replay {
+            self.stats.write().await.record_replay();
+            warn!("Rejecting replayed message: {}", e);
+            return Err(e);
         }
+
+        Ok((
+            Cow::Owned(envelope.payload.into_bytes()),
+            Some(envelope.signer),
+        ))
+    }
+
+    /// Record a signature rejection and build the corresponding error.
+    async fn reject_signature(&self, reason: String) -> Error {
+        self.stats.write().await.record_rejected_signature();
+        warn!("Rejecting message signature: {}", reason);
+        Error::Validation(format!("Signature rejected: {}", reason))
+    }
+
+    /// Check that a card may be announced by the envelope signer.
+    ///
+    /// Nodes relay the cards of the agents they host, so the signer may
+    /// differ from the card's DID when the card carries its own proof
+    /// (checked by [`DiscoveryService::verify_card`] on ingest). A foreign
+    /// card without a proof is as unauthenticated as an unsigned message
+    /// and only passes when signatures are optional.
+    async fn authorize_card(&self, card: &CapabilityCard, signer: Option<&str>) -> Result<()> {
+        let Some(signer) = signer else {
+            return Ok(());
+        };
+        let card_did = card.agoramesh.as_ref().map(|ext| ext.did.as_str());
+        if card_did != Some(signer) && !card.is_signed() && self.require_signatures {
+            return Err(self
+                .reject_signature(format!(
+                    "{} cannot announce card for {}",
+                    signer,
+                    card_did.unwrap_or("unknown")
+                ))
+                .await);
+        }
+        Ok(())
     }
 
     /// Handle a message on the discovery topic.
@@ -277,13 +447,15 @@ impl MessageHandler {
         &self,
         data: &[u8],
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         self.stats.write().await.record_discovery();
 
         // Try to parse as DiscoveryMessage first
         match serde_json::from_slice::<DiscoveryMessage>(data) {
             Ok(message) => {
-                self.process_discovery_message(message, source).await?;
+                self.process_discovery_message(message, source, signer)
+                    .await?;
                 self.stats.write().await.record_processed();
                 Ok(())
             }
@@ -291,7 +463,7 @@ impl MessageHandler {
                 // Try parsing as raw CapabilityCard (backward compatibility)
                 match serde_json::from_slice::<CapabilityCard>(data) {
                     Ok(card) => {
-                        self.process_card_announcement(card, source).await?;
+                        self.process_card_announcement(card, source, signer).await?;
                         self.stats.write().await.record_processed();
                         Ok(())
                     }
@@ -313,10 +485,11 @@ impl MessageHandler {
         &self,
         message: DiscoveryMessage,
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         match message {
             DiscoveryMessage::CardAnnouncement { card } => {
-                self.process_card_announcement(*card, source).await
+                self.process_card_announcement(*card, source, signer).await
             }
             DiscoveryMessage::DiscoveryRequest { timestamp } => {
                 self.process_discovery_request(timestamp, source).await
@@ -329,7 +502,10 @@ impl MessageHandler {
         &self,
         card: CapabilityCard,
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
+        self.authorize_card(&card, signer).await?;
+
         let did = card
             .agoramesh
             .as_ref()
@@ -338,15 +514,18 @@ impl MessageHandler {
 
         info!("Received card announcement for {} from {:?}", did, source);
 
-        // Register the card in our local discovery service
+        // Ingest the card into our local discovery service without re-publishing
         // Note: This will also index in HybridSearch if available
-        self.discovery_service.register(&card).await?;
+        self.discovery_service.ingest(&card).await?;
 
         debug!("Cached card for {}", did);
         Ok(())
     }
 
     /// Process a discovery request (request for registry broadcast).
+    ///
+    /// Answers by re-announcing the cards registered on this node. The
+    /// discovery service rate limits re-announcements.
     async fn process_discovery_request(
         &self,
         timestamp: u64,
@@ -357,9 +536,11 @@ impl MessageHandler {
             timestamp, source
         );
 
-        // The response is handled at a higher level - we just log the request here
-        // The NetworkManager or main loop should decide whether to re-announce agents
-        debug!("Discovery request processed (response handled by caller)");
+        let announced = self.discovery_service.reannounce_local_cards().await?;
+        debug!(
+            "Discovery request answered: re-announced {} local card(s)",
+            announced
+        );
         Ok(())
     }
 
@@ -368,13 +549,15 @@ impl MessageHandler {
         &self,
         data: &[u8],
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         // Capability messages are similar to discovery card announcements
         // but specifically for capability updates
         match serde_json::from_slice::<CapabilityCard>(data) {
             Ok(card) => {
+                self.authorize_card(&card, signer).await?;
                 info!("Received capability update from {:?}", source);
-                self.discovery_service.register(&card).await?;
+                self.discovery_service.ingest(&card).await?;
                 self.stats.write().await.record_processed();
                 Ok(())
             }
@@ -394,12 +577,13 @@ impl MessageHandler {
         &self,
         data: &[u8],
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         self.stats.write().await.record_trust();
 
         match serde_json::from_slice::<TrustMessage>(data) {
             Ok(message) => {
-                self.process_trust_message(message, source).await?;
+                self.process_trust_message(message, source, signer).await?;
                 self.stats.write().await.record_processed();
                 Ok(())
             }
@@ -419,6 +603,7 @@ impl MessageHandler {
         &self,
         message: TrustMessage,
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         match message {
             TrustMessage::TrustUpdate {
@@ -511,6 +696,7 @@ impl MessageHandler {
             }
             TrustMessage::ReputationEvent {
                 did,
+                counterparty_did,
                 success,
                 amount,
                 timestamp,
@@ -521,6 +707,27 @@ impl MessageHandler {
                     return Err(Error::Validation(format!("Invalid DID format: {}", did)));
                 }
 
+                // Only the other party to a transaction may report it, and
+                // only once it has proven who it is
+                let Some(signer) = signer else {
+                    return Err(self
+                        .reject_signature(format!("reputation event for {} is not signed", did))
+                        .await);
+                };
+                if signer == did {
+                    return Err(self
+                        .reject_signature(format!("{} cannot report its own reputation", did))
+                        .await);
+                }
+                if counterparty_did.as_deref() != Some(signer) {
+                    return Err(self
+                        .reject_signature(format!(
+                            "{} is not the counterparty of this transaction",
+                            signer
+                        ))
+                        .await);
+                }
+
                 // Validate timestamp is not in the future
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
@@ -570,6 +777,7 @@ impl MessageHandler {
         &self,
         data: &[u8],
         source: Option<&libp2p::PeerId>,
+        signer: Option<&str>,
     ) -> Result<()> {
         // Record dispute message
         self.stats.write().await.record_dispute();
@@ -582,6 +790,35 @@ impl MessageHandler {
 
         info!("Received dispute message from {:?}: {:?}", source, message);
 
+        // Only authenticated dispute parties may open disputes or submit evidence
+        let authorized = match (&message, signer) {
+            (DisputeMessage::DisputeStatus { .. }, _) => true,
+            (_, None) => {
+                return Err(self
+                    .reject_signature("dispute message is not signed".to_string())
+                    .await);
+            }
+            (
+                DisputeMessage::CreateDispute {
+                    client_did,
+                    provider_did,
+                    ..
+                },
+                Some(signer),
+            ) => signer == client_did || signer == provider_did,
+            (DisputeMessage::SubmitEvidence { submitter_did, .. }, Some(signer)) => {
+                signer == submitter_did
+            }
+        };
+        if !authorized {
+            return Err(self
+                .reject_signature(format!(
+                    "{} is not a party to this dispute message",
+                    signer.unwrap_or_default()
+                ))
+                .await);
+        }
+
         // Validate and process based on message type
         match message {
             DisputeMessage::CreateDispute {
@@ -825,6 +1062,8 @@ impl MessageHandler {
             trust_messages: stats.trust_messages,
             dispute_messages: stats.dispute_messages,
             unknown_topic_messages: stats.unknown_topic_messages,
+            rejected_signatures: stats.rejected_signatures,
+            replayed_messages: stats.replayed_messages,
         }
     }
 }
@@ -849,6 +1088,7 @@ mod tests {
                 id: "test".to_string(),
                 name: "Test Skill".to_string(),
                 description: Some("A test skill".to_string()),
+                tags: vec![],
                 input_schema: None,
                 output_schema: None,
             }],
@@ -863,12 +1103,15 @@ mod tests {
                     model: PricingModel::PerRequest,
                 }),
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         }
     }
 
     fn discovery_service() -> Arc<DiscoveryService> {
-        Arc::new(DiscoveryService::new())
+        Arc::new(DiscoveryService::new().with_required_card_signatures(false))
     }
 
     // ========== TDD Tests: MessageHandler creation ==========
@@ -962,6 +1205,73 @@ mod tests {
         assert!(result.is_ok(), "Should handle discovery request");
     }
 
+    #[tokio::test]
+    async fn test_discovery_request_reannounces_local_cards() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange: a node with one locally registered agent
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service =
+            Arc::new(DiscoveryService::with_network(tx).with_required_card_signatures(false));
+        service
+            .register(&sample_card("did:agoramesh:base:local"))
+            .await
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+        let handler = MessageHandler::new(service);
+
+        let data = serde_json::to_vec(&DiscoveryMessage::DiscoveryRequest {
+            timestamp: 1704067200,
+        })
+        .unwrap();
+        let event = NetworkEvent::Message {
+            topic: topics::DISCOVERY.to_string(),
+            source: Some(PeerId::random()),
+            data,
+            message_id: MessageId::new(b"test-id"),
+        };
+
+        // Act
+        handler.handle_event(&event).await.unwrap();
+
+        // Assert
+        match rx.try_recv().expect("Should re-announce local card") {
+            SwarmCommand::Publish { topic, .. } => assert_eq!(topic, topics::DISCOVERY),
+            cmd => panic!("Expected Publish command, got {:?}", cmd),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_card_announcement_is_not_republished() {
+        use crate::network::SwarmCommand;
+        use tokio::sync::mpsc;
+
+        // Arrange
+        let (tx, mut rx) = mpsc::channel::<SwarmCommand>(10);
+        let service =
+            Arc::new(DiscoveryService::with_network(tx).with_required_card_signatures(false));
+        let handler = MessageHandler::new(service.clone());
+
+        let data = serde_json::to_vec(&DiscoveryMessage::CardAnnouncement {
+            card: Box::new(sample_card("did:agoramesh:base:remote")),
+        })
+        .unwrap();
+        let event = NetworkEvent::Message {
+            topic: topics::DISCOVERY.to_string(),
+            source: Some(PeerId::random()),
+            data,
+            message_id: MessageId::new(b"test-id"),
+        };
+
+        // Act
+        handler.handle_event(&event).await.unwrap();
+
+        // Assert
+        assert_eq!(service.cache_size(), 1);
+        assert!(rx.try_recv().is_err(), "Remote cards must not be echoed");
+    }
+
     #[tokio::test]
     async fn test_invalid_discovery_message_returns_error() {
         let service = discovery_service();
@@ -1015,22 +1325,17 @@ mod tests {
     #[tokio::test]
     async fn test_handle_reputation_event() {
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:rep-test".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000, // 1 USDC
             timestamp: 1704067200,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1138,7 +1443,8 @@ mod tests {
     async fn test_handle_dispute_message_without_arbitrator() {
         // Without arbitrator, dispute messages should be rejected
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1152,14 +1458,7 @@ mod tests {
             amount_usdc: 50_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"dispute-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1307,22 +1606,17 @@ mod tests {
     #[tokio::test]
     async fn test_reputation_event_validates_did_format() {
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let message = TrustMessage::ReputationEvent {
             did: "not-a-valid-did".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000,
             timestamp: 1704067200,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1335,22 +1629,17 @@ mod tests {
     #[tokio::test]
     async fn test_reputation_event_validates_timestamp() {
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:test".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000,
             timestamp: 4102444800, // Year 2100 - future
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1363,7 +1652,8 @@ mod tests {
     #[tokio::test]
     async fn test_reputation_event_valid_success_accepted() {
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1372,18 +1662,12 @@ mod tests {
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:test-agent".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1396,7 +1680,8 @@ mod tests {
     #[tokio::test]
     async fn test_reputation_event_valid_failure_accepted() {
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1405,18 +1690,12 @@ mod tests {
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:test-agent".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: false,
             amount: 500_000,
             timestamp: now - 120,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         let result = handler.handle_event(&event).await;
 
@@ -1496,6 +1775,7 @@ mod tests {
     fn test_trust_message_serialization_reputation_event() {
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:test".to_string(),
+            counterparty_did: None,
             success: true,
             amount: 1_000_000,
             timestamp: 1704067200,
@@ -1531,7 +1811,9 @@ mod tests {
     async fn test_reputation_event_records_success_in_trust_service() {
         let discovery = discovery_service();
         let trust = test_trust_service();
-        let handler = MessageHandler::with_trust_service(discovery, Some(trust.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_trust_service(discovery, Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1540,18 +1822,12 @@ mod tests {
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:recording-test".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         handler.handle_event(&event).await.unwrap();
 
@@ -1570,7 +1846,9 @@ mod tests {
     async fn test_reputation_event_records_failure_in_trust_service() {
         let discovery = discovery_service();
         let trust = test_trust_service();
-        let handler = MessageHandler::with_trust_service(discovery, Some(trust.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_trust_service(discovery, Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1579,18 +1857,12 @@ mod tests {
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:failure-test".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: false,
             amount: 500_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         handler.handle_event(&event).await.unwrap();
 
@@ -1609,7 +1881,8 @@ mod tests {
     async fn test_handler_without_trust_service_still_validates() {
         // Handler without TrustService should still validate but not record
         let service = discovery_service();
-        let handler = MessageHandler::new(service);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(service).with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1618,18 +1891,12 @@ mod tests {
 
         let message = TrustMessage::ReputationEvent {
             did: "did:agoramesh:base:no-trust".to_string(),
+            counterparty_did: Some("did:agoramesh:base:client".to_string()),
             success: true,
             amount: 1_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::TRUST.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"test-id"),
-        };
+        let event = signed_as(&resolver, "client", topics::TRUST, &message);
 
         // Should still succeed (just won't record)
         let result = handler.handle_event(&event).await;
@@ -1651,7 +1918,9 @@ mod tests {
     async fn test_create_dispute_message_creates_dispute_in_arbitrator() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1665,14 +1934,7 @@ mod tests {
             amount_usdc: 50_000_000, // $50 USDC - Tier 2
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"dispute-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_ok(), "Should handle create dispute message");
@@ -1689,7 +1951,9 @@ mod tests {
     async fn test_create_dispute_validates_amount_tier() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1704,14 +1968,7 @@ mod tests {
             amount_usdc: 5_000_000, // $5 USDC - Tier 1
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"tier1-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         // Tier 1 disputes are rejected by AIArbitrator
@@ -1722,7 +1979,9 @@ mod tests {
     async fn test_submit_evidence_adds_to_dispute() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         // First create a dispute
         let dispute_id = arbitrator
@@ -1747,14 +2006,7 @@ mod tests {
             description: "The provider failed to deliver the service as agreed.".to_string(),
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"evidence-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_ok(), "Should handle submit evidence message");
@@ -1768,7 +2020,9 @@ mod tests {
     async fn test_dispute_validates_did_format() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1783,14 +2037,7 @@ mod tests {
             amount_usdc: 50_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"invalid-did-msg"),
-        };
+        let event = signed_as(&resolver, "provider", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_err(), "Should reject invalid DID format");
@@ -1800,7 +2047,9 @@ mod tests {
     async fn test_dispute_validates_timestamp() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         // Future timestamp
         let message = DisputeMessage::CreateDispute {
@@ -1810,14 +2059,7 @@ mod tests {
             amount_usdc: 50_000_000,
             timestamp: 4102444800, // Year 2100
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"future-ts-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_err(), "Should reject future timestamp");
@@ -1856,7 +2098,8 @@ mod tests {
     async fn test_handler_without_arbitrator_rejects_disputes() {
         let discovery = discovery_service();
         // Handler without arbitrator
-        let handler = MessageHandler::new(discovery);
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(discovery).with_did_resolver(resolver.clone());
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1870,14 +2113,7 @@ mod tests {
             amount_usdc: 50_000_000,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"no-arb-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         // Without arbitrator, should fail gracefully
@@ -2063,7 +2299,9 @@ mod tests {
     async fn test_evidence_rejects_title_too_long() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         // Create a dispute first
         let dispute_id = arbitrator
@@ -2089,14 +2327,7 @@ mod tests {
             description: "Valid description".to_string(),
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"long-title-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(
@@ -2110,7 +2341,9 @@ mod tests {
     async fn test_evidence_rejects_description_too_long() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         // Create a dispute first
         let dispute_id = arbitrator
@@ -2136,14 +2369,7 @@ mod tests {
             description: long_desc,
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"long-desc-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(
@@ -2157,7 +2383,9 @@ mod tests {
     async fn test_evidence_rejects_empty_title() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let dispute_id = arbitrator
             .create_dispute(
@@ -2180,14 +2408,7 @@ mod tests {
             description: "Some description".to_string(),
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"empty-title-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_err(), "Should reject evidence with empty title");
@@ -2197,7 +2418,9 @@ mod tests {
     async fn test_evidence_rejects_empty_description() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let dispute_id = arbitrator
             .create_dispute(
@@ -2220,14 +2443,7 @@ mod tests {
             description: "".to_string(), // Empty description
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"empty-desc-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(
@@ -2240,7 +2456,9 @@ mod tests {
     async fn test_evidence_accepts_valid_submission() {
         let discovery = discovery_service();
         let arbitrator = test_arbitrator();
-        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()));
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::with_services(discovery, None, Some(arbitrator.clone()))
+            .with_did_resolver(resolver.clone());
 
         let dispute_id = arbitrator
             .create_dispute(
@@ -2263,14 +2481,7 @@ mod tests {
             description: "This is valid evidence describing the transaction.".to_string(),
             timestamp: now - 60,
         };
-        let data = serde_json::to_vec(&message).unwrap();
-
-        let event = NetworkEvent::Message {
-            topic: topics::DISPUTES.to_string(),
-            source: Some(PeerId::random()),
-            data,
-            message_id: MessageId::new(b"valid-evidence-msg"),
-        };
+        let event = signed_as(&resolver, "client", topics::DISPUTES, &message);
 
         let result = handler.handle_event(&event).await;
         assert!(result.is_ok(), "Should accept valid evidence submission");
@@ -2282,4 +2493,398 @@ mod tests {
             "Evidence should be recorded"
         );
     }
+
+    // ========== TDD Tests: Signed envelopes ==========
+
+    fn signing_identity(
+        resolver: &crate::did::InMemoryDidResolver,
+        identifier: &str,
+    ) -> (libp2p::identity::ed25519::Keypair, String) {
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let doc = crate::did::DIDDocumentBuilder::new("base", identifier)
+            .add_ed25519_key(
+                "key-1",
+                &crate::did::encode_ed25519_multibase(&keypair.public()),
+            )
+            .build()
+            .unwrap();
+        let did = doc.id.clone();
+        resolver.insert(doc).unwrap();
+        (keypair, did)
+    }
+
+    fn signed_event(
+        topic: &str,
+        payload: &impl Serialize,
+        keypair: &libp2p::identity::ed25519::Keypair,
+        did: &str,
+    ) -> (NetworkEvent, SignedEnvelope) {
+        let envelope = SignedEnvelope::sign_ed25519(
+            topic,
+            serde_json::to_string(payload).unwrap(),
+            did,
+            &format!("{}#key-1", did),
+            keypair,
+        )
+        .unwrap();
+        let event = NetworkEvent::Message {
+            topic: topic.to_string(),
+            source: Some(PeerId::random()),
+            data: serde_json::to_vec(&envelope).unwrap(),
+            message_id: MessageId::new(b"test-id"),
+        };
+        (event, envelope)
+    }
+
+    /// Sign `payload` as `did:agoramesh:base:{identifier}`, registering the
+    /// signer's DID document with `resolver`.
+    fn signed_as(
+        resolver: &crate::did::InMemoryDidResolver,
+        identifier: &str,
+        topic: &str,
+        payload: &impl Serialize,
+    ) -> NetworkEvent {
+        let (keypair, did) = signing_identity(resolver, identifier);
+        signed_event(topic, payload, &keypair, &did).0
+    }
+
+    fn reputation_event(did: &str, counterparty: &str) -> TrustMessage {
+        TrustMessage::ReputationEvent {
+            did: did.to_string(),
+            counterparty_did: Some(counterparty.to_string()),
+            success: true,
+            amount: 1_000_000,
+            timestamp: current_timestamp().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signed_reputation_event_is_processed() {
+        // Arrange
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone())
+            .with_required_signatures(true);
+        let (keypair, reporter) = signing_identity(&resolver, "reporter-ok");
+        let subject = "did:agoramesh:base:subject-ok";
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event(subject, &reporter),
+            &keypair,
+            &reporter,
+        );
+
+        // Act
+        handler.handle_event(&event).await.unwrap();
+
+        // Assert
+        let info = trust.get_trust(subject).await.unwrap();
+        assert_eq!(info.successful_transactions, 1);
+        assert_eq!(handler.stats().await.rejected_signatures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_envelope_is_rejected_and_counted() {
+        // Arrange
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
+        let (keypair, reporter) = signing_identity(&resolver, "reporter-tampered");
+        let (_, mut envelope) = signed_event(
+            topics::TRUST,
+            &reputation_event("did:agoramesh:base:honest", &reporter),
+            &keypair,
+            &reporter,
+        );
+        envelope.payload =
+            serde_json::to_string(&reputation_event("did:agoramesh:base:victim", &reporter))
+                .unwrap();
+        let event = NetworkEvent::Message {
+            topic: topics::TRUST.to_string(),
+            source: Some(PeerId::random()),
+            data: serde_json::to_vec(&envelope).unwrap(),
+            message_id: MessageId::new(b"test-id"),
+        };
+
+        // Act
+        let result = handler.handle_event(&event).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+        let info = trust.get_trust("did:agoramesh:base:victim").await.unwrap();
+        assert_eq!(info.successful_transactions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_signer_is_rejected_when_signatures_required() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(discovery_service())
+            .with_did_resolver(resolver.clone())
+            .with_required_signatures(true);
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event("did:agoramesh:base:subject", "did:agoramesh:base:nobody"),
+            &keypair,
+            "did:agoramesh:base:nobody",
+        );
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_signer_cannot_report_reputation_when_optional() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let subject = "did:agoramesh:base:unknown-signer-subject";
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event(subject, "did:agoramesh:base:nobody"),
+            &keypair,
+            "did:agoramesh:base:nobody",
+        );
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+        assert_eq!(
+            trust
+                .get_trust(subject)
+                .await
+                .unwrap()
+                .successful_transactions,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_reputation_event_rejected_when_signatures_optional() {
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()));
+        let subject = "did:agoramesh:base:unsigned-subject";
+        let event = NetworkEvent::Message {
+            topic: topics::TRUST.to_string(),
+            source: Some(PeerId::random()),
+            data: serde_json::to_vec(&reputation_event(subject, "did:agoramesh:base:client"))
+                .unwrap(),
+            message_id: MessageId::new(b"test-id"),
+        };
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+        assert_eq!(
+            trust
+                .get_trust(subject)
+                .await
+                .unwrap()
+                .successful_transactions,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_dispute_rejected_when_signatures_optional() {
+        let arbitrator = test_arbitrator();
+        let handler =
+            MessageHandler::with_services(discovery_service(), None, Some(arbitrator.clone()));
+        let message = DisputeMessage::CreateDispute {
+            escrow_id: "escrow-unsigned".to_string(),
+            client_did: "did:agoramesh:base:client".to_string(),
+            provider_did: "did:agoramesh:base:provider".to_string(),
+            amount_usdc: 50_000_000,
+            timestamp: current_timestamp().unwrap(),
+        };
+        let event = NetworkEvent::Message {
+            topic: topics::DISPUTES.to_string(),
+            source: Some(PeerId::random()),
+            data: serde_json::to_vec(&message).unwrap(),
+            message_id: MessageId::new(b"unsigned-dispute"),
+        };
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+        assert!(arbitrator
+            .get_disputes_by_party("did:agoramesh:base:client")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_envelope_is_rejected() {
+        // Arrange
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
+        let (keypair, reporter) = signing_identity(&resolver, "reporter-replay");
+        let subject = "did:agoramesh:base:subject-replay";
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event(subject, &reporter),
+            &keypair,
+            &reporter,
+        );
+
+        // Act
+        handler.handle_event(&event).await.unwrap();
+        let replay = handler.handle_event(&event).await;
+
+        // Assert
+        assert!(replay.is_err());
+        assert_eq!(handler.stats().await.replayed_messages, 1);
+        let info = trust.get_trust(subject).await.unwrap();
+        assert_eq!(
+            info.successful_transactions, 1,
+            "Replay must not count twice"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_message_rejected_when_signatures_required() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let handler = MessageHandler::new(discovery_service())
+            .with_did_resolver(resolver.clone())
+            .with_required_signatures(true);
+        let event = NetworkEvent::Message {
+            topic: topics::TRUST.to_string(),
+            source: Some(PeerId::random()),
+            data: serde_json::to_vec(&reputation_event(
+                "did:agoramesh:base:x",
+                "did:agoramesh:base:y",
+            ))
+            .unwrap(),
+            message_id: MessageId::new(b"test-id"),
+        };
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_card_signed_by_another_did_is_rejected() {
+        // Arrange
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let discovery = discovery_service();
+        let handler = MessageHandler::new(discovery.clone())
+            .with_did_resolver(resolver.clone())
+            .with_required_signatures(true);
+        let (keypair, impostor) = signing_identity(&resolver, "card-impostor");
+        let message = DiscoveryMessage::CardAnnouncement {
+            card: Box::new(sample_card("did:agoramesh:base:card-victim")),
+        };
+        let (event, _) = signed_event(topics::DISCOVERY, &message, &keypair, &impostor);
+
+        // Act
+        let result = handler.handle_event(&event).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(discovery.cache_size(), 0);
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_card_signed_by_its_own_did_is_accepted() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let discovery = discovery_service();
+        let handler = MessageHandler::new(discovery.clone()).with_did_resolver(resolver.clone());
+        let (keypair, did) = signing_identity(&resolver, "card-owner");
+        let message = DiscoveryMessage::CardAnnouncement {
+            card: Box::new(sample_card(&did)),
+        };
+        let (event, _) = signed_event(topics::DISCOVERY, &message, &keypair, &did);
+
+        handler.handle_event(&event).await.unwrap();
+
+        assert_eq!(discovery.cache_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_self_reported_reputation_is_rejected() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
+        let (keypair, did) = signing_identity(&resolver, "self-reporter");
+        let (event, _) = signed_event(topics::TRUST, &reputation_event(&did, &did), &keypair, &did);
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(
+            trust.get_trust(&did).await.unwrap().successful_transactions,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reputation_signed_by_non_counterparty_is_rejected() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()))
+            .with_did_resolver(resolver.clone());
+        let (keypair, bystander) = signing_identity(&resolver, "bystander");
+        let subject = "did:agoramesh:base:rep-victim";
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event(subject, "did:agoramesh:base:real-client"),
+            &keypair,
+            &bystander,
+        );
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(handler.stats().await.rejected_signatures, 1);
+        assert_eq!(
+            trust
+                .get_trust(subject)
+                .await
+                .unwrap()
+                .successful_transactions,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_node_may_relay_hosted_card_when_signatures_optional() {
+        let resolver = Arc::new(crate::did::InMemoryDidResolver::new());
+        let discovery = discovery_service();
+        let handler = MessageHandler::new(discovery.clone()).with_did_resolver(resolver.clone());
+        let (keypair, node) = signing_identity(&resolver, "hosting-node");
+        let message = DiscoveryMessage::CardAnnouncement {
+            card: Box::new(sample_card("did:agoramesh:base:hosted-agent")),
+        };
+        let (event, _) = signed_event(topics::DISCOVERY, &message, &keypair, &node);
+
+        handler.handle_event(&event).await.unwrap();
+
+        assert_eq!(discovery.cache_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_envelope_without_resolver_cannot_report_reputation() {
+        let trust = test_trust_service();
+        let handler = MessageHandler::with_trust_service(discovery_service(), Some(trust.clone()));
+        let keypair = libp2p::identity::ed25519::Keypair::generate();
+        let subject = "did:agoramesh:base:no-resolver";
+        let (event, _) = signed_event(
+            topics::TRUST,
+            &reputation_event(subject, "did:agoramesh:base:unverified"),
+            &keypair,
+            "did:agoramesh:base:unverified",
+        );
+
+        assert!(handler.handle_event(&event).await.is_err());
+        assert_eq!(
+            trust
+                .get_trust(subject)
+                .await
+                .unwrap()
+                .successful_transactions,
+            0
+        );
+    }
 }
@@ -0,0 +1,287 @@
+//! NAT traversal.
+//!
+//! Nodes behind NAT become reachable in three steps:
+//! - AutoNAT asks connected peers to dial back and reports whether this
+//!   node is publicly reachable
+//! - A node found to be private reserves a slot on a circuit relay v2
+//!   server and is reachable through it at a `/p2p-circuit` address
+//! - DCUtR upgrades relayed connections to direct ones by hole punching
+//!
+//! Public nodes may run the relay server for others. Kademlia follows the
+//! detected reachability: public nodes answer DHT queries, private nodes
+//! only issue them.
+
+use libp2p::{autonat, kad::Mode, multiaddr::Protocol, relay, Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+
+/// Default number of relays a private node reserves a slot on.
+pub const DEFAULT_MAX_RELAY_RESERVATIONS: usize = 2;
+
+/// Default number of reservations a relay server accepts.
+pub const DEFAULT_RELAY_MAX_RESERVATIONS: usize = 128;
+
+/// Default number of circuits a relay server carries at once.
+pub const DEFAULT_RELAY_MAX_CIRCUITS: usize = 16;
+
+/// NAT traversal settings (`[network.nat]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NatConfig {
+    /// Detect reachability with AutoNAT.
+    pub autonat: bool,
+
+    /// Only trust AutoNAT results for public IPs; disable on LANs.
+    pub only_global_ips: bool,
+
+    /// Act as a circuit relay for private peers. Enable on public nodes.
+    pub relay_server: bool,
+
+    /// Reservations accepted when acting as a relay.
+    pub relay_max_reservations: usize,
+
+    /// Relayed circuits carried at once when acting as a relay.
+    pub relay_max_circuits: usize,
+
+    /// Relays to use while private, as `/…/p2p/<peer-id>` multiaddrs.
+    /// Connected peers that offer relaying are used as well.
+    pub relays: Vec<String>,
+
+    /// Relays to hold a reservation on at once.
+    pub max_relay_reservations: usize,
+
+    /// Upgrade relayed connections to direct ones by hole punching.
+    pub hole_punching: bool,
+}
+
+impl Default for NatConfig {
+    fn default() -> Self {
+        Self {
+            autonat: true,
+            only_global_ips: true,
+            relay_server: false,
+            relay_max_reservations: DEFAULT_RELAY_MAX_RESERVATIONS,
+            relay_max_circuits: DEFAULT_RELAY_MAX_CIRCUITS,
+            relays: vec![],
+            max_relay_reservations: DEFAULT_MAX_RELAY_RESERVATIONS,
+            hole_punching: true,
+        }
+    }
+}
+
+impl NatConfig {
+    /// AutoNAT client and server behaviour for this configuration.
+    pub fn autonat(&self, local_peer_id: PeerId) -> autonat::v1::Behaviour {
+        let config = autonat::v1::Config {
+            only_global_ips: self.only_global_ips,
+            ..Default::default()
+        };
+        autonat::v1::Behaviour::new(local_peer_id, config)
+    }
+
+    /// Circuit relay server behaviour for this configuration.
+    pub fn relay_server(&self, local_peer_id: PeerId) -> relay::Behaviour {
+        let config = relay::Config {
+            max_reservations: self.relay_max_reservations,
+            max_circuits: self.relay_max_circuits,
+            ..Default::default()
+        };
+        relay::Behaviour::new(local_peer_id, config)
+    }
+
+    /// The configured relay addresses that parse and name the relay's peer.
+    pub fn relay_addresses(&self) -> Vec<Multiaddr> {
+        self.relays
+            .iter()
+            .filter_map(|addr| addr.parse::<Multiaddr>().ok())
+            .filter(|addr| matches!(addr.iter().last(), Some(Protocol::P2p(_))))
+            .collect()
+    }
+}
+
+/// Whether this node can be dialed from the internet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reachability {
+    /// Not yet determined.
+    #[default]
+    Unknown,
+    /// Peers can dial this node directly.
+    Public,
+    /// This node is behind NAT or a firewall.
+    Private,
+}
+
+impl Reachability {
+    /// The Kademlia mode for this reachability.
+    ///
+    /// `None` leaves the choice to Kademlia, which serves once an external
+    /// address is confirmed.
+    pub fn kademlia_mode(&self) -> Option<Mode> {
+        match self {
+            Reachability::Unknown => None,
+            Reachability::Public => Some(Mode::Server),
+            Reachability::Private => Some(Mode::Client),
+        }
+    }
+}
+
+impl From<&autonat::v1::NatStatus> for Reachability {
+    fn from(status: &autonat::v1::NatStatus) -> Self {
+        match status {
+            autonat::v1::NatStatus::Unknown => Reachability::Unknown,
+            autonat::v1::NatStatus::Public(_) => Reachability::Public,
+            autonat::v1::NatStatus::Private => Reachability::Private,
+        }
+    }
+}
+
+/// Snapshot of the node's NAT traversal state, as reported by `/health`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NatState {
+    /// Reachability detected by AutoNAT.
+    pub reachability: Reachability,
+
+    /// Address AutoNAT confirmed peers can dial, when public.
+    pub public_address: Option<String>,
+
+    /// Addresses this node is reachable at through relays.
+    pub relay_addresses: Vec<String>,
+}
+
+impl NatState {
+    /// Apply an AutoNAT status. Returns whether anything changed.
+    pub fn set_status(&mut self, status: &autonat::v1::NatStatus) -> bool {
+        let public_address = match status {
+            autonat::v1::NatStatus::Public(addr) => Some(addr.to_string()),
+            _ => None,
+        };
+        let reachability = Reachability::from(status);
+        let changed = reachability != self.reachability || public_address != self.public_address;
+        self.reachability = reachability;
+        self.public_address = public_address;
+        changed
+    }
+
+    /// Record a relayed listen address. Returns whether it was new.
+    pub fn add_relay_address(&mut self, addr: &Multiaddr) -> bool {
+        let addr = addr.to_string();
+        if self.relay_addresses.contains(&addr) {
+            return false;
+        }
+        self.relay_addresses.push(addr);
+        true
+    }
+
+    /// Forget a relayed listen address. Returns whether it was known.
+    pub fn remove_relay_address(&mut self, addr: &Multiaddr) -> bool {
+        let addr = addr.to_string();
+        let before = self.relay_addresses.len();
+        self.relay_addresses.retain(|a| *a != addr);
+        self.relay_addresses.len() != before
+    }
+}
+
+/// Whether `addr` goes through a relay.
+pub fn is_relayed(addr: &Multiaddr) -> bool {
+    addr.iter().any(|p| matches!(p, Protocol::P2pCircuit))
+}
+
+/// The address to listen on to be reachable through the relay at
+/// `relay_addr`, which must end in the relay's `/p2p/<peer-id>`.
+pub fn relay_listen_address(relay_addr: &Multiaddr) -> Multiaddr {
+    relay_addr.clone().with(Protocol::P2pCircuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> Multiaddr {
+        s.parse().unwrap()
+    }
+
+    // ========== TDD Tests: Reachability ==========
+
+    #[test]
+    fn test_reachability_selects_kademlia_mode() {
+        assert_eq!(Reachability::Public.kademlia_mode(), Some(Mode::Server));
+        assert_eq!(Reachability::Private.kademlia_mode(), Some(Mode::Client));
+        assert_eq!(Reachability::Unknown.kademlia_mode(), None);
+    }
+
+    #[test]
+    fn test_nat_state_tracks_status_changes() {
+        let mut state = NatState::default();
+        let public = addr("/ip4/203.0.113.7/tcp/9000");
+
+        assert!(state.set_status(&autonat::v1::NatStatus::Public(public.clone())));
+        assert_eq!(state.reachability, Reachability::Public);
+        assert_eq!(state.public_address, Some(public.to_string()));
+        assert!(!state.set_status(&autonat::v1::NatStatus::Public(public)));
+
+        assert!(state.set_status(&autonat::v1::NatStatus::Private));
+        assert_eq!(state.reachability, Reachability::Private);
+        assert_eq!(state.public_address, None);
+    }
+
+    #[test]
+    fn test_nat_state_tracks_relay_addresses() {
+        let mut state = NatState::default();
+        let relayed = addr("/ip4/203.0.113.7/tcp/9000/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN/p2p-circuit");
+
+        assert!(state.add_relay_address(&relayed));
+        assert!(!state.add_relay_address(&relayed));
+        assert_eq!(state.relay_addresses.len(), 1);
+        assert!(state.remove_relay_address(&relayed));
+        assert!(state.relay_addresses.is_empty());
+    }
+
+    #[test]
+    fn test_nat_state_serializes_for_health() {
+        let state = NatState {
+            reachability: Reachability::Private,
+            public_address: None,
+            relay_addresses: vec![],
+        };
+
+        let json = serde_json::to_value(&state).unwrap();
+
+        assert_eq!(json["reachability"], "private");
+    }
+
+    // ========== TDD Tests: NatConfig ==========
+
+    #[test]
+    fn test_relay_addresses_require_relay_peer_id() {
+        let config = NatConfig {
+            relays: vec![
+                "/ip4/203.0.113.7/tcp/9000/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"
+                    .to_string(),
+                "/ip4/203.0.113.8/tcp/9000".to_string(),
+                "garbage".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        let relays = config.relay_addresses();
+
+        assert_eq!(relays.len(), 1);
+        assert!(relay_listen_address(&relays[0])
+            .to_string()
+            .ends_with("/p2p-circuit"));
+        assert!(is_relayed(&relay_listen_address(&relays[0])));
+    }
+
+    #[test]
+    fn test_nat_config_parses_from_toml_with_defaults() {
+        let config: NatConfig = toml::from_str("relay_server = true").unwrap();
+
+        assert!(config.relay_server);
+        assert!(config.autonat);
+        assert!(config.hole_punching);
+        assert_eq!(
+            config.max_relay_reservations,
+            DEFAULT_MAX_RELAY_RESERVATIONS
+        );
+    }
+}
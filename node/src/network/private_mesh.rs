@@ -0,0 +1,207 @@
+//! Private mesh mode.
+//!
+//! A consortium keeps its nodes to itself with two independent controls:
+//! - A pre-shared key (the `/key/swarm/psk/1.0.0/` file format) wraps every
+//!   TCP connection in libp2p pnet encryption, so nodes without the key
+//!   cannot complete a handshake. QUIC brings its own encryption and cannot
+//!   be wrapped: a node with a key only uses TCP
+//! - A peer allowlist and denylist, checked by the connection gate in both
+//!   directions once a connection's peer ID is known
+//!
+//! Disabling mDNS (`network.mdns = false`) keeps the node from announcing
+//! itself on the local network.
+
+use libp2p::{pnet::PreSharedKey, PeerId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::connection_gate::DenialReason;
+use crate::error::{Error, Result};
+
+/// Private mesh settings (`[network.private]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivateMeshConfig {
+    /// Pre-shared key file shared by every node of the mesh.
+    pub psk_file: Option<String>,
+
+    /// When non-empty, only these peer IDs may connect.
+    pub allowed_peers: Vec<String>,
+
+    /// Peer IDs that may never connect, even when allowed.
+    pub denied_peers: Vec<String>,
+}
+
+impl PrivateMeshConfig {
+    /// Read the pre-shared key, if one is configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or holds no valid key.
+    pub fn load_psk(&self) -> Result<Option<PreSharedKey>> {
+        let Some(path) = &self.psk_file else {
+            return Ok(None);
+        };
+
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read pre-shared key file {}: {}",
+                path, e
+            ))
+        })?;
+        contents
+            .parse::<PreSharedKey>()
+            .map(Some)
+            .map_err(|e| Error::Config(format!("Invalid pre-shared key in {}: {}", path, e)))
+    }
+
+    /// The peer allowlist and denylist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any entry is not a valid peer ID.
+    pub fn peer_filter(&self) -> Result<PeerFilter> {
+        let allowed = if self.allowed_peers.is_empty() {
+            None
+        } else {
+            Some(parse_peer_ids(&self.allowed_peers)?)
+        };
+
+        Ok(PeerFilter {
+            allowed,
+            denied: parse_peer_ids(&self.denied_peers)?,
+        })
+    }
+}
+
+fn parse_peer_ids(peers: &[String]) -> Result<HashSet<PeerId>> {
+    peers
+        .iter()
+        .map(|peer| {
+            peer.parse::<PeerId>()
+                .map_err(|e| Error::Config(format!("Invalid peer ID '{}': {}", peer, e)))
+        })
+        .collect()
+}
+
+/// Which peers may hold a connection to this node.
+///
+/// The default filter admits every peer.
+#[derive(Debug, Clone, Default)]
+pub struct PeerFilter {
+    /// `None` admits every peer that is not denied.
+    allowed: Option<HashSet<PeerId>>,
+    denied: HashSet<PeerId>,
+}
+
+impl PeerFilter {
+    /// Check `peer_id` against the denylist, then the allowlist.
+    pub fn check(&self, peer_id: &PeerId) -> std::result::Result<(), DenialReason> {
+        if self.denied.contains(peer_id) {
+            return Err(DenialReason::PeerDenied);
+        }
+        match &self.allowed {
+            Some(allowed) if !allowed.contains(peer_id) => Err(DenialReason::PeerNotAllowed),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `peer_id` may connect.
+    pub fn permits(&self, peer_id: &PeerId) -> bool {
+        self.check(peer_id).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const PSK: &str = "/key/swarm/psk/1.0.0/\n/base16/\n\
+        6189c5cf0b87fb800c1a9feeda73c6ab5e998db48fb9e6a978575c770ceef683\n";
+
+    fn peer() -> PeerId {
+        PeerId::from(libp2p::identity::Keypair::generate_ed25519().public())
+    }
+
+    // ========== TDD Tests: Peer filter ==========
+
+    #[test]
+    fn test_default_filter_admits_every_peer() {
+        let filter = PrivateMeshConfig::default().peer_filter().unwrap();
+
+        assert!(filter.permits(&peer()));
+    }
+
+    #[test]
+    fn test_allowlist_admits_only_listed_peers() {
+        let member = peer();
+        let config = PrivateMeshConfig {
+            allowed_peers: vec![member.to_string()],
+            ..Default::default()
+        };
+
+        let filter = config.peer_filter().unwrap();
+
+        assert!(filter.permits(&member));
+        assert_eq!(filter.check(&peer()), Err(DenialReason::PeerNotAllowed));
+    }
+
+    #[test]
+    fn test_denylist_overrides_allowlist() {
+        let member = peer();
+        let config = PrivateMeshConfig {
+            allowed_peers: vec![member.to_string()],
+            denied_peers: vec![member.to_string()],
+            ..Default::default()
+        };
+
+        let filter = config.peer_filter().unwrap();
+
+        assert_eq!(filter.check(&member), Err(DenialReason::PeerDenied));
+    }
+
+    #[test]
+    fn test_invalid_peer_id_is_rejected() {
+        let config = PrivateMeshConfig {
+            denied_peers: vec!["not-a-peer-id".to_string()],
+            ..Default::default()
+        };
+
+        assert!(matches!(config.peer_filter(), Err(Error::Config(_))));
+    }
+
+    // ========== TDD Tests: Pre-shared key ==========
+
+    #[test]
+    fn test_load_psk_reads_swarm_key_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(PSK.as_bytes()).unwrap();
+        let config = PrivateMeshConfig {
+            psk_file: Some(file.path().to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+
+        let psk = config.load_psk().unwrap();
+
+        assert_eq!(psk, Some(PSK.parse().unwrap()));
+        assert_eq!(PrivateMeshConfig::default().load_psk().unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_psk_rejects_missing_or_malformed_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"not a key").unwrap();
+
+        for path in [
+            file.path().to_string_lossy().into_owned(),
+            "/nonexistent/swarm.key".to_string(),
+        ] {
+            let config = PrivateMeshConfig {
+                psk_file: Some(path),
+                ..Default::default()
+            };
+            assert!(matches!(config.load_psk(), Err(Error::Config(_))));
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! Durable Kademlia record store.
+//!
+//! [`DhtRecordStore`] keeps the records and provider records this node
+//! hosts in a [`kad::store::MemoryStore`], which enforces the size and count
+//! limits from [`PersistenceConfig`]. With a backing [`Store`] (enabled by
+//! `persistence.dht_records`), every change is written through so the
+//! records survive a restart:
+//! - Records are stored under `record/{hex key}`
+//! - Provider records under `provider/{hex key}/{peer id}`
+//! - Expiry deadlines are kept as Unix timestamps; expired entries are
+//!   dropped on load
+//!
+//! Restored records are pushed back into the network once the node has
+//! bootstrapped; see [`AgoraMeshBehaviour::republish_stored_records`].
+//!
+//! [`AgoraMeshBehaviour::republish_stored_records`]: super::AgoraMeshBehaviour::republish_stored_records
+
+use alloy::primitives::hex;
+use libp2p::{
+    kad::{
+        self,
+        store::{MemoryStore, MemoryStoreConfig, RecordStore},
+        ProviderRecord, Record, RecordKey,
+    },
+    Multiaddr, PeerId,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::persistence::{PersistenceConfig, Store};
+
+/// Backing store key prefix for value records.
+const RECORD_PREFIX: &str = "record/";
+
+/// Backing store key prefix for provider records.
+const PROVIDER_PREFIX: &str = "provider/";
+
+/// A value record as written to the backing store.
+#[derive(Serialize, Deserialize)]
+struct PersistedRecord {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    publisher: Option<Vec<u8>>,
+    expires_at: Option<u64>,
+}
+
+/// A provider record as written to the backing store.
+#[derive(Serialize, Deserialize)]
+struct PersistedProvider {
+    key: Vec<u8>,
+    provider: Vec<u8>,
+    addresses: Vec<Vec<u8>>,
+    expires_at: Option<u64>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Convert a monotonic deadline into a Unix timestamp.
+fn to_unix(expires: Option<Instant>) -> Option<u64> {
+    expires.map(|t| unix_now() + t.saturating_duration_since(Instant::now()).as_secs())
+}
+
+/// Convert a Unix timestamp back into a monotonic deadline.
+///
+/// Returns `Err(())` if the deadline has already passed.
+fn to_instant(expires_at: Option<u64>) -> std::result::Result<Option<Instant>, ()> {
+    match expires_at {
+        None => Ok(None),
+        Some(at) => match at.checked_sub(unix_now()) {
+            Some(remaining) if remaining > 0 => {
+                Ok(Some(Instant::now() + Duration::from_secs(remaining)))
+            }
+            _ => Err(()),
+        },
+    }
+}
+
+fn record_key(key: &RecordKey) -> String {
+    format!("{}{}", RECORD_PREFIX, hex::encode(key.as_ref()))
+}
+
+fn providers_prefix(key: &RecordKey) -> String {
+    format!("{}{}/", PROVIDER_PREFIX, hex::encode(key.as_ref()))
+}
+
+fn provider_key(key: &RecordKey, provider: &PeerId) -> String {
+    format!("{}{}", providers_prefix(key), provider)
+}
+
+fn encode_record(record: &Record) -> Option<Vec<u8>> {
+    bincode::serialize(&PersistedRecord {
+        key: record.key.to_vec(),
+        value: record.value.clone(),
+        publisher: record.publisher.map(|p| p.to_bytes()),
+        expires_at: to_unix(record.expires),
+    })
+    .ok()
+}
+
+fn decode_record(bytes: &[u8]) -> Option<PersistedRecord> {
+    bincode::deserialize(bytes).ok()
+}
+
+fn encode_provider(record: &ProviderRecord) -> Option<Vec<u8>> {
+    bincode::serialize(&PersistedProvider {
+        key: record.key.to_vec(),
+        provider: record.provider.to_bytes(),
+        addresses: record.addresses.iter().map(|a| a.to_vec()).collect(),
+        expires_at: to_unix(record.expires),
+    })
+    .ok()
+}
+
+fn decode_provider(bytes: &[u8]) -> Option<PersistedProvider> {
+    bincode::deserialize(bytes).ok()
+}
+
+/// Kademlia record store with optional write-through persistence.
+pub struct DhtRecordStore {
+    records: MemoryStore,
+    backing: Option<Arc<dyn Store>>,
+}
+
+impl DhtRecordStore {
+    /// Create an in-memory store with the DHT limits from `config`.
+    pub fn new(local_peer_id: PeerId, config: &PersistenceConfig) -> Self {
+        let limits = MemoryStoreConfig {
+            max_records: config.dht_max_records,
+            max_value_bytes: config.dht_max_record_bytes,
+            max_provided_keys: config.dht_max_provided_keys,
+            max_providers_per_key: kad::K_VALUE.get(),
+        };
+
+        Self {
+            records: MemoryStore::with_config(local_peer_id, limits),
+            backing: None,
+        }
+    }
+
+    /// Write records through to `backing`, first loading the unexpired
+    /// records it already holds.
+    ///
+    /// Entries that are expired, unreadable or over the configured limits
+    /// are removed from the backing store.
+    pub fn with_backing(mut self, backing: Arc<dyn Store>) -> Self {
+        let records = self.load_records(backing.as_ref());
+        let providers = self.load_providers(backing.as_ref());
+        info!(
+            "Restored {} DHT records and {} provider records",
+            records, providers
+        );
+
+        self.backing = Some(backing);
+        self
+    }
+
+    /// Whether records are written to durable storage.
+    pub fn is_persistent(&self) -> bool {
+        self.backing.is_some()
+    }
+
+    fn load_records(&mut self, backing: &dyn Store) -> usize {
+        let entries = backing.iter_prefix(RECORD_PREFIX).unwrap_or_else(|e| {
+            warn!("Failed to read persisted DHT records: {}", e);
+            Vec::new()
+        });
+
+        let mut loaded = 0;
+        for (store_key, bytes) in entries {
+            let restored = decode_record(&bytes).and_then(|p| {
+                let expires = to_instant(p.expires_at).ok()?;
+                let publisher = match p.publisher {
+                    Some(bytes) => Some(PeerId::from_bytes(&bytes).ok()?),
+                    None => None,
+                };
+                Some(Record {
+                    key: RecordKey::from(p.key),
+                    value: p.value,
+                    publisher,
+                    expires,
+                })
+            });
+
+            match restored.map(|record| self.records.put(record)) {
+                Some(Ok(())) => loaded += 1,
+                Some(Err(e)) => {
+                    warn!("Dropping persisted DHT record {}: {}", store_key, e);
+                    delete_quietly(backing, &store_key);
+                }
+                None => delete_quietly(backing, &store_key),
+            }
+        }
+        loaded
+    }
+
+    fn load_providers(&mut self, backing: &dyn Store) -> usize {
+        let entries = backing.iter_prefix(PROVIDER_PREFIX).unwrap_or_else(|e| {
+            warn!("Failed to read persisted provider records: {}", e);
+            Vec::new()
+        });
+
+        let mut loaded = 0;
+        for (store_key, bytes) in entries {
+            let restored = decode_provider(&bytes).and_then(|p| {
+                let expires = to_instant(p.expires_at).ok()?;
+                Some(ProviderRecord {
+                    key: RecordKey::from(p.key),
+                    provider: PeerId::from_bytes(&p.provider).ok()?,
+                    expires,
+                    addresses: p
+                        .addresses
+                        .into_iter()
+                        .filter_map(|a| Multiaddr::try_from(a).ok())
+                        .collect(),
+                })
+            });
+
+            match restored.map(|record| self.records.add_provider(record)) {
+                Some(Ok(())) => loaded += 1,
+                Some(Err(e)) => {
+                    warn!("Dropping persisted provider record {}: {}", store_key, e);
+                    delete_quietly(backing, &store_key);
+                }
+                None => delete_quietly(backing, &store_key),
+            }
+        }
+        loaded
+    }
+
+    /// Rewrite the persisted providers of `key` to match memory, which may
+    /// have evicted a provider to stay within the per-key limit.
+    fn persist_providers(&self, key: &RecordKey) {
+        let Some(backing) = &self.backing else {
+            return;
+        };
+        let current = self.records.providers(key);
+
+        match backing.iter_prefix(&providers_prefix(key)) {
+            Ok(entries) => {
+                for (store_key, _) in entries {
+                    if !current
+                        .iter()
+                        .any(|r| provider_key(key, &r.provider) == store_key)
+                    {
+                        delete_quietly(backing.as_ref(), &store_key);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to read persisted provider records: {}", e),
+        }
+
+        for record in &current {
+            if let Some(bytes) = encode_provider(record) {
+                put_quietly(
+                    backing.as_ref(),
+                    &provider_key(key, &record.provider),
+                    &bytes,
+                );
+            }
+        }
+    }
+}
+
+/// Backing store failures are logged: Kademlia has no way to report them,
+/// and the in-memory copy stays authoritative until restart.
+fn put_quietly(backing: &dyn Store, key: &str, value: &[u8]) {
+    if let Err(e) = backing.put(key, value) {
+        warn!("Failed to persist DHT entry {}: {}", key, e);
+    }
+}
+
+fn delete_quietly(backing: &dyn Store, key: &str) {
+    if let Err(e) = backing.delete(key) {
+        warn!("Failed to delete persisted DHT entry {}: {}", key, e);
+    }
+}
+
+impl RecordStore for DhtRecordStore {
+    type RecordsIter<'a> = <MemoryStore as RecordStore>::RecordsIter<'a>;
+    type ProvidedIter<'a> = <MemoryStore as RecordStore>::ProvidedIter<'a>;
+
+    fn get(&self, k: &RecordKey) -> Option<Cow<'_, Record>> {
+        self.records.get(k)
+    }
+
+    fn put(&mut self, r: Record) -> kad::store::Result<()> {
+        let Some(backing) = &self.backing else {
+            return self.records.put(r);
+        };
+        let bytes = encode_record(&r);
+        let key = record_key(&r.key);
+
+        self.records.put(r)?;
+        if let Some(bytes) = bytes {
+            put_quietly(backing.as_ref(), &key, &bytes);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, k: &RecordKey) {
+        self.records.remove(k);
+        if let Some(backing) = &self.backing {
+            delete_quietly(backing.as_ref(), &record_key(k));
+        }
+    }
+
+    fn records(&self) -> Self::RecordsIter<'_> {
+        self.records.records()
+    }
+
+    fn add_provider(&mut self, record: ProviderRecord) -> kad::store::Result<()> {
+        let key = record.key.clone();
+        self.records.add_provider(record)?;
+        self.persist_providers(&key);
+        Ok(())
+    }
+
+    fn providers(&self, key: &RecordKey) -> Vec<ProviderRecord> {
+        self.records.providers(key)
+    }
+
+    fn provided(&self) -> Self::ProvidedIter<'_> {
+        self.records.provided()
+    }
+
+    fn remove_provider(&mut self, k: &RecordKey, p: &PeerId) {
+        self.records.remove_provider(k, p);
+        if let Some(backing) = &self.backing {
+            delete_quietly(backing.as_ref(), &provider_key(k, p));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::MemoryStore as BackingStore;
+
+    fn peer() -> PeerId {
+        PeerId::from(libp2p::identity::Keypair::generate_ed25519().public())
+    }
+
+    fn limits(max_records: usize, max_record_bytes: usize) -> PersistenceConfig {
+        PersistenceConfig {
+            dht_max_records: max_records,
+            dht_max_record_bytes: max_record_bytes,
+            ..Default::default()
+        }
+    }
+
+    fn record(key: &str, value: &[u8], publisher: Option<PeerId>) -> Record {
+        let mut record = Record::new(RecordKey::new(&key), value.to_vec());
+        record.publisher = publisher;
+        record.expires = Some(Instant::now() + Duration::from_secs(3600));
+        record
+    }
+
+    fn reopen(local: PeerId, backing: &Arc<BackingStore>) -> DhtRecordStore {
+        DhtRecordStore::new(local, &PersistenceConfig::default()).with_backing(backing.clone())
+    }
+
+    // ========== TDD Tests: Persistence across restarts ==========
+
+    #[test]
+    fn test_records_survive_reopen() {
+        let (local, publisher) = (peer(), peer());
+        let backing = Arc::new(BackingStore::new());
+        let mut store = reopen(local, &backing);
+
+        store
+            .put(record("did:a", b"card", Some(publisher)))
+            .unwrap();
+        drop(store);
+        let store = reopen(local, &backing);
+
+        let restored = store.get(&RecordKey::new(&"did:a")).unwrap();
+        assert_eq!(restored.value, b"card".to_vec());
+        assert_eq!(restored.publisher, Some(publisher));
+        assert!(restored.expires.is_some());
+        assert!(store.is_persistent());
+    }
+
+    #[test]
+    fn test_removed_and_expired_records_are_not_restored() {
+        let local = peer();
+        let backing = Arc::new(BackingStore::new());
+        let mut store = reopen(local, &backing);
+
+        store.put(record("did:removed", b"x", None)).unwrap();
+        store.remove(&RecordKey::new(&"did:removed"));
+        let mut expired = record("did:expired", b"x", None);
+        expired.expires = Some(Instant::now());
+        store.put(expired).unwrap();
+        drop(store);
+        let store = reopen(local, &backing);
+
+        assert_eq!(store.records().count(), 0);
+        assert!(backing.iter_prefix(RECORD_PREFIX).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_provided_keys_survive_reopen() {
+        let local = peer();
+        let backing = Arc::new(BackingStore::new());
+        let mut store = reopen(local, &backing);
+        let key = RecordKey::new(&"/agoramesh/skill/translate");
+
+        store
+            .add_provider(ProviderRecord::new(key.clone(), local, vec![]))
+            .unwrap();
+        drop(store);
+        let store = reopen(local, &backing);
+
+        let provided: Vec<_> = store.provided().map(|r| r.key.clone()).collect();
+        assert_eq!(provided, vec![key]);
+    }
+
+    #[test]
+    fn test_unreadable_entries_are_dropped() {
+        let backing = Arc::new(BackingStore::new());
+        backing.put("record/00", b"not bincode").unwrap();
+
+        let store = reopen(peer(), &backing);
+
+        assert_eq!(store.records().count(), 0);
+        assert!(!backing.contains("record/00").unwrap());
+    }
+
+    // ========== TDD Tests: Configured limits ==========
+
+    #[test]
+    fn test_limits_come_from_config() {
+        let mut store = DhtRecordStore::new(peer(), &limits(1, 4));
+
+        assert!(matches!(
+            store.put(record("did:big", b"too large", None)),
+            Err(kad::store::Error::ValueTooLarge)
+        ));
+        store.put(record("did:a", b"ok", None)).unwrap();
+        assert!(matches!(
+            store.put(record("did:b", b"ok", None)),
+            Err(kad::store::Error::MaxRecords)
+        ));
+        assert!(!store.is_persistent());
+    }
+
+    #[test]
+    fn test_records_over_lowered_limit_are_dropped_on_load() {
+        let local = peer();
+        let backing = Arc::new(BackingStore::new());
+        let mut store = reopen(local, &backing);
+        for key in ["did:a", "did:b", "did:c"] {
+            store.put(record(key, b"x", None)).unwrap();
+        }
+        drop(store);
+
+        let store = DhtRecordStore::new(local, &limits(2, 1024)).with_backing(backing.clone());
+
+        assert_eq!(store.records().count(), 2);
+        assert_eq!(backing.iter_prefix(RECORD_PREFIX).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,63 @@
+//! Peer-to-peer discovery search protocol.
+//!
+//! A request-response protocol over which a node asks a peer to run a
+//! [`DiscoveryQuery`] against the peer's local cache. Messages are JSON
+//! encoded; responses carry the peer's matching cards in ranked order.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::discovery::{CapabilityCard, DiscoveryQuery};
+
+/// Protocol name for peer discovery search.
+pub const SEARCH_PROTOCOL: &str = "/agoramesh/search/1.0.0";
+
+/// How long an outbound search request may take before it fails.
+pub const SEARCH_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A discovery query sent to a peer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchRequest {
+    /// The query to run against the peer's local cache.
+    pub query: DiscoveryQuery,
+}
+
+/// A peer's answer to a [`SearchRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SearchResponse {
+    /// Matching cards, best first.
+    Results {
+        /// The peer's ranked results.
+        cards: Vec<CapabilityCard>,
+    },
+
+    /// The requester exceeded its query budget on this peer.
+    RateLimited,
+}
+
+impl SearchResponse {
+    /// Get the returned cards (empty when rate limited).
+    pub fn into_cards(self) -> Vec<CapabilityCard> {
+        match self {
+            SearchResponse::Results { cards } => cards,
+            SearchResponse::RateLimited => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_response_round_trips_as_tagged_json() {
+        let json = serde_json::to_value(SearchResponse::RateLimited).unwrap();
+        assert_eq!(json, serde_json::json!({ "status": "rate_limited" }));
+
+        let parsed: SearchResponse =
+            serde_json::from_value(serde_json::json!({ "status": "results", "cards": [] }))
+                .unwrap();
+        assert!(parsed.into_cards().is_empty());
+    }
+}
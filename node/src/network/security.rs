@@ -6,6 +6,7 @@
 //! - Connection rate limiting with exponential backoff
 //! - Max connection enforcement
 
+use libp2p::multiaddr::{Multiaddr, Protocol};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::time::{Duration, Instant};
@@ -25,6 +26,9 @@ pub const MIN_BOOTSTRAP_PEERS: usize = 3;
 /// Default maximum new connections per minute (global rate limit).
 pub const DEFAULT_MAX_CONNECTIONS_PER_MINUTE: usize = 10;
 
+/// Default maximum inbound connections.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 50;
+
 /// Default idle connection timeout in seconds.
 pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
 
@@ -525,6 +529,14 @@ pub fn extract_ip_from_multiaddr(addr: &str) -> Option<IpAddr> {
 pub struct SecurityConfig {
     /// Maximum peers per /24 subnet.
     pub max_peers_per_subnet: usize,
+    /// Maximum peers per /16 subnet.
+    pub max_peers_per_subnet_16: usize,
+    /// Maximum inbound connections, counted by remote IP.
+    pub max_connections: usize,
+    /// Maximum new inbound connections per minute across all IPs.
+    pub max_connections_per_minute: usize,
+    /// Apply subnet limits to loopback and private (LAN) addresses too.
+    pub limit_private_subnets: bool,
     /// Idle connection timeout.
     pub idle_timeout: Duration,
     /// Enable bootstrap peer validation.
@@ -533,22 +545,72 @@ pub struct SecurityConfig {
     pub rate_limit_base_delay: Duration,
     /// Rate limiting max delay.
     pub rate_limit_max_delay: Duration,
+    /// Failed handshakes after which an IP is refused for the session.
+    pub rate_limit_max_failures: u32,
 }
 
 impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             max_peers_per_subnet: MAX_PEERS_PER_SUBNET_24,
+            max_peers_per_subnet_16: MAX_PEERS_PER_SUBNET_16,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            max_connections_per_minute: DEFAULT_MAX_CONNECTIONS_PER_MINUTE,
+            limit_private_subnets: false,
             idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
             validate_bootstrap_peers: true,
             rate_limit_base_delay: Duration::from_secs(1),
             rate_limit_max_delay: Duration::from_secs(300),
+            rate_limit_max_failures: 10,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Default security settings with the connection limit from `config`.
+    pub fn for_network(config: &NetworkConfig) -> Self {
+        Self {
+            max_connections: config.max_connections as usize,
+            ..Self::default()
         }
     }
 }
 
+/// Validate a P2P listen address.
+///
+/// Accepts an IP address followed by `/tcp/<port>` or `/udp/<port>/quic-v1`.
+pub fn validate_listen_address(addr: &str) -> Result<()> {
+    let parsed: Multiaddr = addr
+        .parse()
+        .map_err(|e| Error::Config(format!("Invalid listen address '{}': {}", addr, e)))?;
+
+    let protocols: Vec<Protocol<'_>> = parsed.iter().collect();
+    let supported = matches!(
+        protocols.as_slice(),
+        [Protocol::Ip4(_) | Protocol::Ip6(_), Protocol::Tcp(_)]
+            | [
+                Protocol::Ip4(_) | Protocol::Ip6(_),
+                Protocol::Udp(_),
+                Protocol::QuicV1
+            ]
+    );
+
+    if !supported {
+        return Err(Error::Config(format!(
+            "Unsupported listen address '{}': expected /ip4|ip6/<ip>/tcp/<port> or /ip4|ip6/<ip>/udp/<port>/quic-v1",
+            addr
+        )));
+    }
+
+    Ok(())
+}
+
 /// Validate network configuration with security checks.
 pub fn validate_network_config(config: &NetworkConfig) -> Result<()> {
+    for addr in &config.listen_addresses {
+        validate_listen_address(addr)?;
+    }
+
     // Validate bootstrap peers if any are configured
     if !config.bootstrap_peers.is_empty() {
         validate_bootstrap_peers(&config.bootstrap_peers)?;
@@ -561,12 +623,39 @@ pub fn validate_network_config(config: &NetworkConfig) -> Result<()> {
         ));
     }
 
+    validate_private_mesh(config)
+}
+
+/// Validate private mesh settings.
+///
+/// Peer IDs must parse and a configured pre-shared key must load. A
+/// bootstrap peer whose address names a peer ID the peer lists refuse
+/// would be disconnected on every attempt, so it is rejected up front.
+pub fn validate_private_mesh(config: &NetworkConfig) -> Result<()> {
+    let peers = config.private.peer_filter()?;
+    config.private.load_psk()?;
+
+    for addr in &config.bootstrap_peers {
+        let Ok(addr) = addr.parse::<Multiaddr>() else {
+            continue;
+        };
+        if let Some(Protocol::P2p(peer_id)) = addr.iter().last() {
+            if !peers.permits(&peer_id) {
+                return Err(Error::Config(format!(
+                    "Bootstrap peer {} is refused by network.private peer lists",
+                    peer_id
+                )));
+            }
+        }
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::network::PrivateMeshConfig;
 
     // ================================================================
     // Sybil Attack Protection Tests - Subnet Limits
@@ -890,6 +979,9 @@ mod tests {
             listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
             bootstrap_peers: vec![],
             max_connections: 10,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         };
 
         // Test that max_connections of 0 is rejected
@@ -897,6 +989,9 @@ mod tests {
             listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
             bootstrap_peers: vec![],
             max_connections: 0,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         };
 
         let result = validate_network_config(&invalid_config);
@@ -950,6 +1045,23 @@ mod tests {
         assert_eq!(config.idle_timeout, Duration::from_secs(60));
     }
 
+    #[test]
+    fn test_security_config_takes_max_connections_from_network_config() {
+        let network = NetworkConfig {
+            listen_addresses: vec![],
+            bootstrap_peers: vec![],
+            max_connections: 7,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
+        };
+
+        let config = SecurityConfig::for_network(&network);
+
+        assert_eq!(config.max_connections, 7);
+        assert_eq!(config.max_peers_per_subnet_16, MAX_PEERS_PER_SUBNET_16);
+    }
+
     // ================================================================
     // IP Extraction Tests
     // ================================================================
@@ -973,6 +1085,75 @@ mod tests {
     // Integration Tests - Full Config Validation
     // ================================================================
 
+    #[test]
+    fn test_validate_listen_address_accepts_tcp_and_quic() {
+        for addr in [
+            "/ip4/0.0.0.0/tcp/9000",
+            "/ip6/::/tcp/9000",
+            "/ip4/0.0.0.0/udp/9000/quic-v1",
+            "/ip6/::/udp/9000/quic-v1",
+        ] {
+            assert!(
+                validate_listen_address(addr).is_ok(),
+                "{} should be accepted",
+                addr
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_listen_address_rejects_unsupported_transports() {
+        for addr in [
+            "not-a-multiaddr",
+            "/ip4/0.0.0.0/udp/9000",
+            "/ip4/0.0.0.0/udp/9000/quic",
+            "/ip4/0.0.0.0/tcp/9000/ws",
+            "/dns4/example.com/tcp/9000",
+        ] {
+            assert!(
+                validate_listen_address(addr).is_err(),
+                "{} should be rejected",
+                addr
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_network_config_with_quic_listen_and_bootstrap() {
+        let config = NetworkConfig {
+            listen_addresses: vec![
+                "/ip4/0.0.0.0/tcp/9000".to_string(),
+                "/ip6/::/udp/9000/quic-v1".to_string(),
+            ],
+            bootstrap_peers: vec![
+                "/ip4/192.168.1.1/udp/9000/quic-v1/p2p/12D3KooWTest1".to_string(),
+                "/ip4/10.0.1.1/udp/9000/quic-v1/p2p/12D3KooWTest2".to_string(),
+                "/ip4/172.16.1.1/tcp/9000/p2p/12D3KooWTest3".to_string(),
+            ],
+            max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
+        };
+
+        let result = validate_network_config(&config);
+        assert!(result.is_ok(), "QUIC addresses should pass: {:?}", result);
+    }
+
+    #[test]
+    fn test_validate_network_config_rejects_bad_listen_address() {
+        let config = NetworkConfig {
+            listen_addresses: vec!["/ip4/0.0.0.0/udp/9000".to_string()],
+            bootstrap_peers: vec![],
+            max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
+        };
+
+        assert!(validate_network_config(&config).is_err());
+    }
+
     #[test]
     fn test_validate_network_config_empty_bootstrap_ok() {
         // Empty bootstrap peers is OK for local development
@@ -980,6 +1161,9 @@ mod tests {
             listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
             bootstrap_peers: vec![],
             max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         };
 
         let result = validate_network_config(&config);
@@ -1000,6 +1184,9 @@ mod tests {
                 "/ip4/172.16.1.1/tcp/9000/p2p/12D3KooWTest3".to_string(),
             ],
             max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         };
 
         let result = validate_network_config(&config);
@@ -1020,12 +1207,69 @@ mod tests {
                 "/ip4/192.168.3.1/tcp/9000/p2p/12D3KooWTest3".to_string(),
             ],
             max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         };
 
         let result = validate_network_config(&config);
         assert!(result.is_err(), "Non-diverse bootstrap peers should fail");
     }
 
+    #[test]
+    fn test_validate_private_mesh_rejects_bootstrap_peer_outside_allowlist() {
+        let member = "12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN";
+        let outsider = libp2p::PeerId::random();
+        let mut config = NetworkConfig {
+            listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
+            bootstrap_peers: vec![format!("/ip4/10.0.0.1/tcp/9000/p2p/{}", member)],
+            max_connections: 50,
+            nat: Default::default(),
+            mdns: false,
+            private: PrivateMeshConfig {
+                allowed_peers: vec![member.to_string()],
+                ..Default::default()
+            },
+        };
+        assert!(validate_private_mesh(&config).is_ok());
+
+        config
+            .bootstrap_peers
+            .push(format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", outsider));
+
+        assert!(validate_private_mesh(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_private_mesh_rejects_bad_peer_ids_and_missing_key() {
+        let base = NetworkConfig {
+            listen_addresses: vec!["/ip4/0.0.0.0/tcp/9000".to_string()],
+            bootstrap_peers: vec![],
+            max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
+        };
+
+        let bad_peer = NetworkConfig {
+            private: PrivateMeshConfig {
+                denied_peers: vec!["12D3KooWTest1".to_string()],
+                ..Default::default()
+            },
+            ..base.clone()
+        };
+        let missing_key = NetworkConfig {
+            private: PrivateMeshConfig {
+                psk_file: Some("/nonexistent/swarm.key".to_string()),
+                ..Default::default()
+            },
+            ..base
+        };
+
+        assert!(validate_network_config(&bad_peer).is_err());
+        assert!(validate_network_config(&missing_key).is_err());
+    }
+
     // ================================================================
     // RED PHASE: /16 Subnet Limits for Sybil Attack Protection
     // ================================================================
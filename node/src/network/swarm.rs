@@ -4,19 +4,37 @@
 
 use futures::StreamExt;
 use libp2p::{
+    autonat,
+    core::transport::ListenerId,
+    dcutr,
     gossipsub::{self, MessageId},
-    identify, kad, mdns,
-    swarm::{dial_opts::DialOpts, SwarmEvent},
+    identify,
+    kad::{self, store::RecordStore},
+    mdns,
+    multiaddr::Protocol,
+    relay,
+    request_response::{self, InboundRequestId, OutboundRequestId, ResponseChannel},
+    swarm::{dial_opts::DialOpts, ListenError, SwarmEvent},
     Multiaddr, PeerId, Swarm,
 };
 use std::collections::{HashMap, HashSet};
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, info, warn};
 
-use super::behaviour::{topics, AgoraMeshBehaviour, AgoraMeshEvent};
-use super::transport::build_transport;
+use super::behaviour::{
+    check_inbound_record, topics, AgoraMeshBehaviour, AgoraMeshEvent, RecordCheck,
+};
+use super::connection_gate::{ConnectionDenial, ConnectionGate, DenialReason};
+use super::envelope::EnvelopeSigner;
+use super::nat::{is_relayed, relay_listen_address, NatState, Reachability};
+use super::record_store::DhtRecordStore;
+use super::search::{SearchRequest, SearchResponse};
+use super::security::SecurityConfig;
+use super::transport::{build_transport_with_relay, prefer_quic};
 use crate::config::NetworkConfig;
+use crate::discovery::CapabilityCard;
 use crate::error::{Error, Result};
+use crate::persistence::PersistenceConfig;
 
 /// Commands that can be sent to the swarm manager.
 #[derive(Debug)]
@@ -43,6 +61,44 @@ pub enum SwarmCommand {
         /// Channel to send the result.
         response_tx: tokio::sync::oneshot::Sender<Option<Vec<u8>>>,
     },
+    /// Store a card record from a peer whose proof has been verified.
+    StoreRecord {
+        /// The record, from [`NetworkEvent::CardRecordReceived`].
+        record: kad::Record,
+    },
+    /// Announce this node as a provider for a DHT key.
+    StartProviding {
+        /// The provider key.
+        key: Vec<u8>,
+    },
+    /// Stop announcing this node as a provider for a DHT key.
+    StopProviding {
+        /// The provider key.
+        key: Vec<u8>,
+    },
+    /// Find the nodes providing a DHT key.
+    GetProviders {
+        /// The provider key.
+        key: Vec<u8>,
+        /// Channel to send the providers (excluding this node).
+        response_tx: tokio::sync::oneshot::Sender<Vec<PeerId>>,
+    },
+    /// Send a discovery search request to a peer.
+    SearchPeer {
+        /// The peer to query.
+        peer: PeerId,
+        /// The search request.
+        request: SearchRequest,
+        /// Channel to send the response (None if the request failed).
+        response_tx: tokio::sync::oneshot::Sender<Option<SearchResponse>>,
+    },
+    /// Answer a search request received from a peer.
+    RespondSearch {
+        /// ID of the inbound request, from [`NetworkEvent::SearchRequest`].
+        request_id: InboundRequestId,
+        /// The response to send.
+        response: SearchResponse,
+    },
     /// Shutdown the swarm.
     Shutdown,
 }
@@ -81,6 +137,38 @@ pub enum NetworkEvent {
         /// The record key.
         key: Vec<u8>,
     },
+    /// A peer asked this node to store a capability card record.
+    ///
+    /// The card passed [`check_inbound_record`]; verify its proof and send
+    /// [`SwarmCommand::StoreRecord`] to store it.
+    CardRecordReceived {
+        /// The peer that sent the record.
+        source: PeerId,
+        /// The card held by the record.
+        card: Box<CapabilityCard>,
+        /// The record to store.
+        record: kad::Record,
+    },
+    /// An inbound connection was refused by the connection gate.
+    ConnectionDenied {
+        /// Address the connection came from.
+        remote_addr: Multiaddr,
+        /// Which limit refused it.
+        reason: DenialReason,
+    },
+    /// A peer sent a discovery search request.
+    ///
+    /// Answer with [`SwarmCommand::RespondSearch`] before the request times out.
+    SearchRequest {
+        /// The requesting peer.
+        peer: PeerId,
+        /// ID to pass back with the response.
+        request_id: InboundRequestId,
+        /// The search request.
+        request: SearchRequest,
+    },
+    /// Reachability or relay addresses changed.
+    NatStatusChanged(NatState),
 }
 
 /// Manager for the libp2p swarm.
@@ -90,6 +178,8 @@ pub enum NetworkEvent {
 /// - GossipSub message publishing and subscription
 /// - Kademlia DHT operations
 /// - mDNS local discovery
+/// - Peer search requests and responses
+/// - NAT traversal: relay reservations while private
 pub struct SwarmManager {
     /// The libp2p swarm.
     swarm: Swarm<AgoraMeshBehaviour>,
@@ -111,6 +201,38 @@ pub struct SwarmManager {
 
     /// Pending GetRecord queries (query_id -> response_tx).
     pending_get_queries: HashMap<kad::QueryId, oneshot::Sender<Option<Vec<u8>>>>,
+
+    /// Pending GetProviders queries (query_id -> (providers so far, response_tx)).
+    pending_provider_queries:
+        HashMap<kad::QueryId, (HashSet<PeerId>, oneshot::Sender<Vec<PeerId>>)>,
+
+    /// Pending outbound search requests (request_id -> response_tx).
+    pending_search_requests: HashMap<OutboundRequestId, oneshot::Sender<Option<SearchResponse>>>,
+
+    /// Inbound search requests awaiting an answer from the application.
+    pending_search_responses: HashMap<InboundRequestId, ResponseChannel<SearchResponse>>,
+
+    /// Whether stored DHT records have been republished since boot.
+    records_republished: bool,
+
+    /// Reachability and relay addresses, reported on change.
+    nat: NatState,
+
+    /// Relays usable while private: configured ones, then peers seen
+    /// offering relaying. Each ends in the relay's `/p2p/<peer-id>`.
+    relay_candidates: Vec<Multiaddr>,
+
+    /// Open relay listeners (listener_id -> relay).
+    relay_listeners: HashMap<ListenerId, PeerId>,
+
+    /// Relays whose reservation failed since reachability last changed.
+    failed_relays: HashSet<PeerId>,
+
+    /// Relays to hold a reservation on at once.
+    max_relay_reservations: usize,
+
+    /// Signs every outbound GossipSub message, when set.
+    envelope_signer: Option<EnvelopeSigner>,
 }
 
 impl SwarmManager {
@@ -147,14 +269,53 @@ impl SwarmManager {
         Self,
         mpsc::Sender<SwarmCommand>,
         mpsc::Receiver<NetworkEvent>,
+    )> {
+        let local_peer_id = PeerId::from(keypair.public());
+        let record_store = DhtRecordStore::new(local_peer_id, &PersistenceConfig::default());
+        Self::with_record_store(config, keypair, record_store)
+    }
+
+    /// Create a swarm manager whose DHT records live in `record_store`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Network configuration
+    /// * `keypair` - The node's identity keypair
+    /// * `record_store` - Store for DHT records hosted by this node
+    pub fn with_record_store(
+        config: &NetworkConfig,
+        keypair: libp2p::identity::Keypair,
+        record_store: DhtRecordStore,
+    ) -> Result<(
+        Self,
+        mpsc::Sender<SwarmCommand>,
+        mpsc::Receiver<NetworkEvent>,
     )> {
         let local_peer_id = PeerId::from(keypair.public());
         info!("Local peer ID: {}", local_peer_id);
 
-        let transport = build_transport(&keypair)?;
+        // A pre-shared key confines the node to its private mesh
+        let psk = config.private.load_psk()?;
+        if let Some(psk) = &psk {
+            info!(
+                "Private mesh: pre-shared key {}, TCP only",
+                psk.fingerprint()
+            );
+        }
+
+        let (relay_transport, relay_client) = relay::client::new(local_peer_id);
+        let transport = build_transport_with_relay(&keypair, relay_transport, psk)?;
 
-        let behaviour = AgoraMeshBehaviour::new(local_peer_id, &keypair)
-            .map_err(|e| Error::Network(format!("Failed to create behaviour: {}", e)))?;
+        let gate = ConnectionGate::new(&SecurityConfig::for_network(config))
+            .with_peer_filter(config.private.peer_filter()?);
+        let mut behaviour =
+            AgoraMeshBehaviour::with_record_store(local_peer_id, &keypair, record_store)
+                .map_err(|e| Error::Network(format!("Failed to create behaviour: {}", e)))?
+                .with_connection_gate(gate)
+                .with_nat_traversal(local_peer_id, &config.nat, Some(relay_client));
+        if !config.mdns {
+            behaviour = behaviour.without_mdns();
+        }
 
         let swarm = Swarm::new(
             transport,
@@ -188,11 +349,28 @@ impl SwarmManager {
             connected_peers: HashSet::new(),
             bootstrap_peers,
             pending_get_queries: HashMap::new(),
+            pending_provider_queries: HashMap::new(),
+            pending_search_requests: HashMap::new(),
+            pending_search_responses: HashMap::new(),
+            records_republished: false,
+            nat: NatState::default(),
+            relay_candidates: config.nat.relay_addresses(),
+            relay_listeners: HashMap::new(),
+            failed_relays: HashSet::new(),
+            max_relay_reservations: config.nat.max_relay_reservations,
+            envelope_signer: None,
         };
 
         Ok((manager, command_tx, event_rx))
     }
 
+    /// Wrap every outbound GossipSub message in an envelope signed by
+    /// `signer`.
+    pub fn with_envelope_signer(mut self, signer: EnvelopeSigner) -> Self {
+        self.envelope_signer = Some(signer);
+        self
+    }
+
     /// Get the local peer ID.
     pub fn local_peer_id(&self) -> PeerId {
         self.local_peer_id
@@ -204,17 +382,28 @@ impl SwarmManager {
     ///
     /// * `listen_addresses` - Addresses to listen on
     pub async fn run(mut self, listen_addresses: &[String]) -> Result<()> {
-        // Start listening on configured addresses
+        // Start listening on configured addresses. A dual-stack list may
+        // include IPv6 addresses on hosts without IPv6, so only fail when
+        // no address could be bound at all.
+        let mut listening = 0;
         for addr_str in listen_addresses {
             let addr: Multiaddr = addr_str.parse().map_err(|e| {
                 Error::Network(format!("Invalid listen address '{}': {}", addr_str, e))
             })?;
 
-            self.swarm
-                .listen_on(addr.clone())
-                .map_err(|e| Error::Network(format!("Failed to listen on {}: {}", addr, e)))?;
-
-            info!("Listening on {}", addr);
+            match self.swarm.listen_on(addr.clone()) {
+                Ok(_) => {
+                    info!("Listening on {}", addr);
+                    listening += 1;
+                }
+                Err(e) => warn!("Failed to listen on {}: {}", addr, e),
+            }
+        }
+        if listening == 0 && !listen_addresses.is_empty() {
+            return Err(Error::Network(format!(
+                "Failed to listen on any of {}",
+                listen_addresses.join(", ")
+            )));
         }
 
         // Subscribe to GossipSub topics
@@ -224,7 +413,8 @@ impl SwarmManager {
             .map_err(|e| Error::Network(format!("Failed to subscribe to topics: {}", e)))?;
         info!("Subscribed to topics: {:?}", topics::all());
 
-        // Add bootstrap peers to Kademlia and dial them
+        // Add bootstrap peers to Kademlia, grouping each peer's addresses
+        let mut bootstrap_addrs: Vec<(PeerId, Vec<Multiaddr>)> = Vec::new();
         for addr in self.bootstrap_peers.clone() {
             if let Some(peer_id) = extract_peer_id(&addr) {
                 self.swarm
@@ -232,13 +422,22 @@ impl SwarmManager {
                     .add_address(&peer_id, addr.clone());
                 debug!("Added bootstrap peer {} to Kademlia", peer_id);
 
-                // Dial the bootstrap peer
-                if let Err(e) = self.swarm.dial(addr.clone()) {
-                    warn!("Failed to dial bootstrap peer {}: {}", addr, e);
+                match bootstrap_addrs.iter_mut().find(|(id, _)| *id == peer_id) {
+                    Some((_, addrs)) => addrs.push(addr),
+                    None => bootstrap_addrs.push((peer_id, vec![addr])),
                 }
             }
         }
 
+        // Dial each bootstrap peer once, over QUIC where it offers it
+        for (peer_id, mut addrs) in bootstrap_addrs {
+            prefer_quic(&mut addrs);
+            let opts = DialOpts::peer_id(peer_id).addresses(addrs).build();
+            if let Err(e) = self.swarm.dial(opts) {
+                warn!("Failed to dial bootstrap peer {}: {}", peer_id, e);
+            }
+        }
+
         // Bootstrap Kademlia if we have peers
         if !self.bootstrap_peers.is_empty() {
             match self.swarm.behaviour_mut().bootstrap() {
@@ -320,11 +519,37 @@ impl SwarmManager {
             }
             SwarmEvent::NewListenAddr { address, .. } => {
                 info!("Listening on {}/p2p/{}", address, self.local_peer_id);
+                if is_relayed(&address) && self.nat.add_relay_address(&address) {
+                    self.report_nat_state().await;
+                }
+            }
+            SwarmEvent::ExpiredListenAddr { address, .. } => {
+                debug!("No longer listening on {}", address);
+                if self.nat.remove_relay_address(&address) {
+                    self.report_nat_state().await;
+                }
             }
             SwarmEvent::ListenerClosed {
-                addresses, reason, ..
+                listener_id,
+                addresses,
+                reason,
             } => {
-                warn!("Listener closed for {:?}: {:?}", addresses, reason);
+                let mut changed = false;
+                for address in &addresses {
+                    changed |= self.nat.remove_relay_address(address);
+                }
+                if let Some(relay_peer) = self.relay_listeners.remove(&listener_id) {
+                    if let Err(e) = &reason {
+                        debug!("Relay reservation on {} failed: {}", relay_peer, e);
+                        self.failed_relays.insert(relay_peer);
+                    }
+                    self.reserve_relays();
+                } else {
+                    warn!("Listener closed for {:?}: {:?}", addresses, reason);
+                }
+                if changed {
+                    self.report_nat_state().await;
+                }
             }
             SwarmEvent::ListenerError { error, .. } => {
                 error!("Listener error: {}", error);
@@ -342,6 +567,14 @@ impl SwarmManager {
             SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
                 warn!("Outgoing connection error to {:?}: {}", peer_id, error);
             }
+            SwarmEvent::IncomingConnectionError {
+                send_back_addr,
+                error: ListenError::Denied { cause },
+                ..
+            } => {
+                // Counted via the gate's ConnectionDenied event
+                debug!("Refused connection from {}: {}", send_back_addr, cause);
+            }
             SwarmEvent::IncomingConnectionError {
                 local_addr,
                 send_back_addr,
@@ -399,11 +632,26 @@ impl SwarmManager {
             }
 
             AgoraMeshEvent::Kademlia(kad::Event::OutboundQueryProgressed {
-                id, result, ..
+                id,
+                result,
+                step,
+                ..
             }) => {
                 match result {
                     kad::QueryResult::Bootstrap(Ok(_)) => {
                         info!("Kademlia bootstrap completed");
+                        if !self.records_republished {
+                            // Records restored from disk are unknown to the
+                            // network until announced again
+                            self.records_republished = true;
+                            let sent = self
+                                .swarm
+                                .behaviour_mut()
+                                .republish_stored_records(&self.local_peer_id);
+                            if sent > 0 {
+                                info!("Republished {} stored DHT records and keys", sent);
+                            }
+                        }
                         let _ = self.event_tx.send(NetworkEvent::BootstrapComplete).await;
                     }
                     kad::QueryResult::Bootstrap(Err(e)) => {
@@ -456,9 +704,81 @@ impl SwarmManager {
                     kad::QueryResult::PutRecord(Err(e)) => {
                         warn!("PutRecord failed: {:?}", e);
                     }
+                    kad::QueryResult::StartProviding(Ok(kad::AddProviderOk { key })) => {
+                        debug!("Announced as provider for key {:?}", key);
+                    }
+                    kad::QueryResult::StartProviding(Err(e)) => {
+                        warn!("StartProviding failed: {:?}", e);
+                    }
+                    kad::QueryResult::GetProviders(Ok(kad::GetProvidersOk::FoundProviders {
+                        providers,
+                        ..
+                    })) => {
+                        if let Some((found, _)) = self.pending_provider_queries.get_mut(&id) {
+                            found.extend(providers);
+                        }
+                        if step.last {
+                            self.finish_provider_query(id);
+                        }
+                    }
+                    kad::QueryResult::GetProviders(Ok(
+                        kad::GetProvidersOk::FinishedWithNoAdditionalRecord { .. },
+                    )) => {
+                        self.finish_provider_query(id);
+                    }
+                    kad::QueryResult::GetProviders(Err(e)) => {
+                        debug!("GetProviders failed: {:?}", e);
+                        self.finish_provider_query(id);
+                    }
                     _ => {}
                 }
             }
+            AgoraMeshEvent::Kademlia(kad::Event::InboundRequest {
+                request:
+                    kad::InboundRequest::PutRecord {
+                        source,
+                        record: Some(record),
+                        ..
+                    },
+            }) => {
+                let stored = self
+                    .swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .store_mut()
+                    .get(&record.key)
+                    .map(|stored| stored.into_owned());
+                match check_inbound_record(&record, stored.as_ref()) {
+                    Ok(RecordCheck::Accept) => self.store_record(record),
+                    Ok(RecordCheck::VerifyCard(card)) => {
+                        let _ = self
+                            .event_tx
+                            .send(NetworkEvent::CardRecordReceived {
+                                source,
+                                card,
+                                record,
+                            })
+                            .await;
+                    }
+                    Err(e) => debug!("Rejected DHT record from {}: {}", source, e),
+                }
+            }
+            AgoraMeshEvent::Kademlia(kad::Event::InboundRequest {
+                request:
+                    kad::InboundRequest::AddProvider {
+                        record: Some(record),
+                    },
+            }) => {
+                if let Err(e) = self
+                    .swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .store_mut()
+                    .add_provider(record)
+                {
+                    debug!("Provider record not stored: {:?}", e);
+                }
+            }
             AgoraMeshEvent::Kademlia(kad::Event::RoutingUpdated {
                 peer, addresses, ..
             }) => {
@@ -480,6 +800,10 @@ impl SwarmManager {
                         info.listen_addrs.len()
                     );
 
+                    if info.protocols.contains(&relay::HOP_PROTOCOL_NAME) {
+                        self.add_relay_candidate(peer_id, &info.listen_addrs);
+                    }
+
                     // Add discovered addresses to Kademlia
                     for addr in info.listen_addrs {
                         self.swarm.behaviour_mut().add_address(&peer_id, addr);
@@ -497,17 +821,17 @@ impl SwarmManager {
             },
 
             AgoraMeshEvent::Mdns(mdns::Event::Discovered(peers)) => {
+                let mut to_dial: Vec<(PeerId, Vec<Multiaddr>)> = Vec::new();
                 for (peer_id, addr) in peers {
                     debug!("mDNS discovered peer {} at {}", peer_id, addr);
                     self.swarm
                         .behaviour_mut()
                         .add_address(&peer_id, addr.clone());
 
-                    // Dial discovered peers
                     if !self.connected_peers.contains(&peer_id) {
-                        let opts = DialOpts::peer_id(peer_id).addresses(vec![addr]).build();
-                        if let Err(e) = self.swarm.dial(opts) {
-                            debug!("Failed to dial mDNS peer {}: {}", peer_id, e);
+                        match to_dial.iter_mut().find(|(id, _)| *id == peer_id) {
+                            Some((_, addrs)) => addrs.push(addr),
+                            None => to_dial.push((peer_id, vec![addr])),
                         }
                     }
 
@@ -516,12 +840,237 @@ impl SwarmManager {
                         .send(NetworkEvent::PeerDiscovered(peer_id))
                         .await;
                 }
+
+                // Dial discovered peers once each, over QUIC where offered
+                for (peer_id, mut addrs) in to_dial {
+                    prefer_quic(&mut addrs);
+                    let opts = DialOpts::peer_id(peer_id).addresses(addrs).build();
+                    if let Err(e) = self.swarm.dial(opts) {
+                        debug!("Failed to dial mDNS peer {}: {}", peer_id, e);
+                    }
+                }
             }
             AgoraMeshEvent::Mdns(mdns::Event::Expired(peers)) => {
                 for (peer_id, addr) in peers {
                     debug!("mDNS peer {} at {} expired", peer_id, addr);
                 }
             }
+
+            AgoraMeshEvent::Search(event) => match *event {
+                request_response::Event::Message { peer, message, .. } => match message {
+                    request_response::Message::Request {
+                        request_id,
+                        request,
+                        channel,
+                    } => {
+                        debug!("Received search request {} from {}", request_id, peer);
+                        self.pending_search_responses.insert(request_id, channel);
+
+                        let _ = self
+                            .event_tx
+                            .send(NetworkEvent::SearchRequest {
+                                peer,
+                                request_id,
+                                request,
+                            })
+                            .await;
+                    }
+                    request_response::Message::Response {
+                        request_id,
+                        response,
+                    } => {
+                        debug!("Received search response {} from {}", request_id, peer);
+                        if let Some(tx) = self.pending_search_requests.remove(&request_id) {
+                            let _ = tx.send(Some(response));
+                        }
+                    }
+                },
+                request_response::Event::OutboundFailure {
+                    peer,
+                    request_id,
+                    error,
+                    ..
+                } => {
+                    debug!(
+                        "Search request {} to {} failed: {}",
+                        request_id, peer, error
+                    );
+                    if let Some(tx) = self.pending_search_requests.remove(&request_id) {
+                        let _ = tx.send(None);
+                    }
+                }
+                request_response::Event::InboundFailure {
+                    peer,
+                    request_id,
+                    error,
+                    ..
+                } => {
+                    debug!(
+                        "Search request {} from {} failed: {}",
+                        request_id, peer, error
+                    );
+                    self.pending_search_responses.remove(&request_id);
+                }
+                request_response::Event::ResponseSent {
+                    peer, request_id, ..
+                } => {
+                    debug!("Sent search response {} to {}", request_id, peer);
+                }
+            },
+
+            AgoraMeshEvent::Autonat(autonat::v1::Event::StatusChanged { old, new }) => {
+                info!("NAT status changed from {:?} to {:?}", old, new);
+                if self.nat.set_status(&new) {
+                    let reachability = self.nat.reachability;
+                    self.swarm.behaviour_mut().apply_reachability(reachability);
+                    self.failed_relays.clear();
+                    if reachability == Reachability::Public {
+                        self.release_relays();
+                    }
+                    self.reserve_relays();
+                    self.report_nat_state().await;
+                }
+            }
+            AgoraMeshEvent::Autonat(event) => {
+                debug!("AutoNAT: {:?}", event);
+            }
+            AgoraMeshEvent::RelayClient(event) => match *event {
+                relay::client::Event::ReservationReqAccepted {
+                    relay_peer_id,
+                    renewal,
+                    ..
+                } => {
+                    if !renewal {
+                        info!("Reserved a relay slot on {}", relay_peer_id);
+                    }
+                }
+                event => debug!("Relay client: {:?}", event),
+            },
+            AgoraMeshEvent::Relay(event) => {
+                debug!("Relay: {:?}", event);
+            }
+            AgoraMeshEvent::Dcutr(dcutr::Event {
+                remote_peer_id,
+                result,
+            }) => match result {
+                Ok(_) => info!("Hole punched a direct connection to {}", remote_peer_id),
+                Err(e) => debug!("Hole punching to {} failed: {}", remote_peer_id, e),
+            },
+
+            AgoraMeshEvent::Gate(ConnectionDenial {
+                remote_addr,
+                reason,
+            }) => {
+                let _ = self
+                    .event_tx
+                    .send(NetworkEvent::ConnectionDenied {
+                        remote_addr,
+                        reason,
+                    })
+                    .await;
+            }
+        }
+    }
+
+    /// Put a validated record into the local Kademlia store.
+    fn store_record(&mut self, record: kad::Record) {
+        let key = record.key.clone();
+        match self.swarm.behaviour_mut().kademlia.store_mut().put(record) {
+            Ok(()) => debug!("Stored DHT record for key {:?}", key),
+            Err(e) => debug!("DHT record for key {:?} not stored: {:?}", key, e),
+        }
+    }
+
+    /// Send the providers collected for a finished GetProviders query.
+    fn finish_provider_query(&mut self, id: kad::QueryId) {
+        if let Some((mut found, tx)) = self.pending_provider_queries.remove(&id) {
+            found.remove(&self.local_peer_id);
+            let _ = tx.send(found.into_iter().collect());
+        }
+    }
+
+    /// Send the current NAT state to the application.
+    async fn report_nat_state(&mut self) {
+        let _ = self
+            .event_tx
+            .send(NetworkEvent::NatStatusChanged(self.nat.clone()))
+            .await;
+    }
+
+    /// Remember a peer that offers relaying, at one of its listen addresses.
+    fn add_relay_candidate(&mut self, peer_id: PeerId, listen_addrs: &[Multiaddr]) {
+        if self
+            .relay_candidates
+            .iter()
+            .any(|addr| extract_peer_id(addr) == Some(peer_id))
+        {
+            return;
+        }
+
+        let mut addrs: Vec<Multiaddr> = listen_addrs
+            .iter()
+            .filter(|addr| !is_relayed(addr))
+            .cloned()
+            .collect();
+        prefer_quic(&mut addrs);
+        let Some(addr) = addrs.into_iter().next() else {
+            return;
+        };
+        let addr = match extract_peer_id(&addr) {
+            Some(_) => addr,
+            None => addr.with(Protocol::P2p(peer_id)),
+        };
+
+        debug!("Peer {} offers relaying at {}", peer_id, addr);
+        self.relay_candidates.push(addr);
+        self.reserve_relays();
+    }
+
+    /// While private, reserve slots on relays until enough are held.
+    ///
+    /// Each reservation is a listener on the relay's `/p2p-circuit`
+    /// address; relays that failed are skipped until reachability changes.
+    fn reserve_relays(&mut self) {
+        if self.nat.reachability != Reachability::Private {
+            return;
+        }
+
+        for addr in self.relay_candidates.clone() {
+            if self.relay_listeners.len() >= self.max_relay_reservations {
+                break;
+            }
+            let Some(relay_peer) = extract_peer_id(&addr) else {
+                continue;
+            };
+            if self.failed_relays.contains(&relay_peer)
+                || self
+                    .relay_listeners
+                    .values()
+                    .any(|peer| *peer == relay_peer)
+            {
+                continue;
+            }
+
+            match self.swarm.listen_on(relay_listen_address(&addr)) {
+                Ok(listener_id) => {
+                    debug!("Requesting a relay reservation on {}", addr);
+                    self.relay_listeners.insert(listener_id, relay_peer);
+                }
+                Err(e) => {
+                    debug!("Cannot listen through relay {}: {}", addr, e);
+                    self.failed_relays.insert(relay_peer);
+                }
+            }
+        }
+    }
+
+    /// Give up relay reservations once the node is directly reachable.
+    ///
+    /// The listeners are forgotten when the swarm reports them closed.
+    fn release_relays(&mut self) {
+        let listeners: Vec<ListenerId> = self.relay_listeners.keys().copied().collect();
+        for listener_id in listeners {
+            self.swarm.remove_listener(listener_id);
         }
     }
 
@@ -535,6 +1084,16 @@ impl SwarmManager {
                 }
             }
             SwarmCommand::Publish { topic, data } => {
+                let data = match self.envelope_signer {
+                    Some(ref signer) => match signer.seal(&topic, &data) {
+                        Ok(sealed) => sealed,
+                        Err(e) => {
+                            error!("Failed to sign message for {}: {}", topic, e);
+                            return;
+                        }
+                    },
+                    None => data,
+                };
                 match self.swarm.behaviour_mut().publish(&topic, data) {
                     Ok(msg_id) => {
                         debug!("Published message {} to topic {}", msg_id, topic);
@@ -579,6 +1138,75 @@ impl SwarmManager {
                 self.pending_get_queries.insert(query_id, response_tx);
                 debug!("Started GetRecord for key {:?}", key);
             }
+            SwarmCommand::StoreRecord { record } => {
+                // Re-check: a newer copy may have been stored while the proof was verified
+                let stored = self
+                    .swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .store_mut()
+                    .get(&record.key)
+                    .map(|stored| stored.into_owned());
+                match check_inbound_record(&record, stored.as_ref()) {
+                    Ok(_) => self.store_record(record),
+                    Err(e) => debug!("Dropped verified DHT record: {}", e),
+                }
+            }
+            SwarmCommand::StartProviding { key } => {
+                match self
+                    .swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .start_providing(kad::RecordKey::new(&key))
+                {
+                    Ok(_) => debug!("Started providing key {:?}", key),
+                    Err(e) => error!("Failed to start providing DHT key: {:?}", e),
+                }
+            }
+            SwarmCommand::StopProviding { key } => {
+                self.swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .stop_providing(&kad::RecordKey::new(&key));
+                debug!("Stopped providing key {:?}", key);
+            }
+            SwarmCommand::GetProviders { key, response_tx } => {
+                let kad_key = kad::RecordKey::new(&key);
+                let query_id = self.swarm.behaviour_mut().kademlia.get_providers(kad_key);
+                self.pending_provider_queries
+                    .insert(query_id, (HashSet::new(), response_tx));
+                debug!("Started GetProviders for key {:?}", key);
+            }
+            SwarmCommand::SearchPeer {
+                peer,
+                request,
+                response_tx,
+            } => {
+                let request_id = self
+                    .swarm
+                    .behaviour_mut()
+                    .search
+                    .send_request(&peer, request);
+                self.pending_search_requests.insert(request_id, response_tx);
+                debug!("Sent search request {} to {}", request_id, peer);
+            }
+            SwarmCommand::RespondSearch {
+                request_id,
+                response,
+            } => match self.pending_search_responses.remove(&request_id) {
+                Some(channel) => {
+                    if self
+                        .swarm
+                        .behaviour_mut()
+                        .search
+                        .send_response(channel, response)
+                        .is_err()
+                    {
+                        debug!("Search request {} closed before responding", request_id);
+                    }
+                }
+                None => debug!("No pending search request {}", request_id),
+            },
             SwarmCommand::Shutdown => {
                 // Handled in run_event_loop
             }
@@ -607,6 +1235,9 @@ mod tests {
             listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
             bootstrap_peers: vec![],
             max_connections: 50,
+            nat: Default::default(),
+            mdns: true,
+            private: Default::default(),
         }
     }
 
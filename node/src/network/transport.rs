@@ -1,14 +1,24 @@
 //! libp2p transport configuration.
 //!
 //! Configures the transport stack with:
+//! - QUIC (`/udp/<port>/quic-v1`), with TLS 1.3 and native stream multiplexing
 //! - TCP with Noise encryption and Yamux multiplexing
-//! - QUIC transport (when available)
-//! - DNS resolution layer
+//!
+//! Both run side by side, so a node can listen on and dial either kind of
+//! address. QUIC is preferred: see [`prefer_quic`]. Nodes behind NAT add a
+//! circuit relay client with [`build_transport_with_relay`].
+//!
+//! A private mesh uses [`build_private_transport`] instead: TCP only, with
+//! every connection wrapped in pnet encryption under the mesh's key.
 
 use libp2p::{
     core::{muxing::StreamMuxerBox, transport::Boxed, upgrade},
+    futures::future::Either,
     identity::Keypair,
-    noise, tcp, yamux, PeerId, Transport,
+    multiaddr::Protocol,
+    noise,
+    pnet::{PnetConfig, PreSharedKey},
+    quic, relay, tcp, yamux, Multiaddr, PeerId, Transport,
 };
 use std::time::Duration;
 
@@ -20,7 +30,7 @@ const TCP_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Build the libp2p transport stack.
 ///
-/// Creates a TCP transport with:
+/// Creates a QUIC transport alongside a TCP transport with:
 /// - Noise protocol for encryption
 /// - Yamux for multiplexing
 ///
@@ -46,17 +56,110 @@ pub fn build_transport(keypair: &Keypair) -> std::io::Result<BoxedTransport> {
     // Configure Yamux for stream multiplexing
     let yamux_config = yamux::Config::default();
 
-    // Build the full transport stack
-    let transport = tcp_transport
+    let tcp_transport = tcp_transport
         .upgrade(upgrade::Version::V1Lazy)
         .authenticate(noise_config)
         .multiplex(yamux_config)
+        .timeout(TCP_TIMEOUT);
+
+    // QUIC brings its own encryption and multiplexing
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(keypair));
+
+    // Each address is handled by whichever transport supports it
+    let transport = quic_transport
+        .or_transport(tcp_transport)
+        .map(|output, _| match output {
+            Either::Left((peer_id, connection)) => (peer_id, StreamMuxerBox::new(connection)),
+            Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+        })
+        .boxed();
+
+    Ok(transport)
+}
+
+/// Build the transport stack of a private mesh.
+///
+/// TCP connections are wrapped in pnet encryption keyed by `psk` before
+/// the Noise handshake, so only peers holding the same key can connect.
+/// QUIC cannot be wrapped and is left out: QUIC addresses are not
+/// supported by this transport.
+///
+/// # Errors
+///
+/// Returns an error if transport creation fails.
+pub fn build_private_transport(
+    keypair: &Keypair,
+    psk: PreSharedKey,
+) -> std::io::Result<BoxedTransport> {
+    let tcp_transport = tcp::tokio::Transport::new(tcp::Config::default().nodelay(true));
+    let noise_config = noise::Config::new(keypair).map_err(std::io::Error::other)?;
+
+    let transport = tcp_transport
+        .and_then(move |socket, _| PnetConfig::new(psk).handshake(socket))
+        .upgrade(upgrade::Version::V1Lazy)
+        .authenticate(noise_config)
+        .multiplex(yamux::Config::default())
         .timeout(TCP_TIMEOUT)
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed();
+
+    Ok(transport)
+}
+
+/// Build the transport stack with a circuit relay client.
+///
+/// `relay_transport` comes from [`relay::client::new`]; its behaviour half
+/// must be part of the swarm's behaviour. Relayed connections are
+/// encrypted and multiplexed end to end with Noise and Yamux. With a `psk`,
+/// direct connections use [`build_private_transport`]; circuits then only
+/// run through relays of the same private mesh.
+///
+/// # Errors
+///
+/// Returns an error if transport creation fails.
+pub fn build_transport_with_relay(
+    keypair: &Keypair,
+    relay_transport: relay::client::Transport,
+    psk: Option<PreSharedKey>,
+) -> std::io::Result<BoxedTransport> {
+    let noise_config = noise::Config::new(keypair).map_err(std::io::Error::other)?;
+    let relay_transport = relay_transport
+        .upgrade(upgrade::Version::V1Lazy)
+        .authenticate(noise_config)
+        .multiplex(yamux::Config::default());
+
+    let direct_transport = match psk {
+        Some(psk) => build_private_transport(keypair, psk)?,
+        None => build_transport(keypair)?,
+    };
+
+    // `/p2p-circuit` addresses go to the relay client, all others are direct
+    let transport = relay_transport
+        .or_transport(direct_transport)
+        .map(|output, _| match output {
+            Either::Left((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+            Either::Right((peer_id, muxer)) => (peer_id, muxer),
+        })
         .boxed();
 
     Ok(transport)
 }
 
+/// Whether `addr` is a QUIC (`/quic-v1`) address.
+pub fn is_quic(addr: &Multiaddr) -> bool {
+    addr.iter().any(|p| matches!(p, Protocol::QuicV1))
+}
+
+/// Order dial addresses so QUIC comes first, keeping the order otherwise.
+///
+/// The swarm dials a peer's addresses concurrently in this order, so the
+/// QUIC attempt starts first and, with its one round-trip handshake,
+/// usually wins. TCP remains the fallback for peers or networks without
+/// UDP.
+pub fn prefer_quic(addrs: &mut [Multiaddr]) {
+    addrs.sort_by_key(|addr| !is_quic(addr));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,4 +170,109 @@ mod tests {
         let transport = build_transport(&keypair);
         assert!(transport.is_ok());
     }
+
+    #[test]
+    fn test_build_transport_with_relay() {
+        let keypair = Keypair::generate_ed25519();
+        let (relay_transport, _relay_client) = relay::client::new(keypair.public().to_peer_id());
+
+        assert!(build_transport_with_relay(&keypair, relay_transport, None).is_ok());
+    }
+
+    fn private_swarm(psk: PreSharedKey) -> libp2p::Swarm<libp2p::swarm::dummy::Behaviour> {
+        let keypair = Keypair::generate_ed25519();
+        libp2p::Swarm::new(
+            build_private_transport(&keypair, psk).unwrap(),
+            libp2p::swarm::dummy::Behaviour,
+            keypair.public().to_peer_id(),
+            libp2p::swarm::Config::with_tokio_executor(),
+        )
+    }
+
+    /// Whether a node holding the `dialer` key reaches one holding `listener`.
+    async fn private_nodes_connect(listener: PreSharedKey, dialer: PreSharedKey) -> bool {
+        use futures::StreamExt;
+        use libp2p::swarm::SwarmEvent;
+
+        let mut listener = private_swarm(listener);
+        let mut dialer = private_swarm(dialer);
+        listener
+            .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .unwrap();
+        let addr = loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = listener.select_next_some().await {
+                break address;
+            }
+        };
+
+        dialer.dial(addr).unwrap();
+        // A wrong key garbles the handshake, which may then wait for the
+        // transport timeout instead of failing outright
+        let connect = async {
+            loop {
+                tokio::select! {
+                    event = dialer.select_next_some() => match event {
+                        SwarmEvent::ConnectionEstablished { .. } => return true,
+                        SwarmEvent::OutgoingConnectionError { .. } => return false,
+                        _ => {}
+                    },
+                    _ = listener.select_next_some() => {}
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), connect)
+            .await
+            .unwrap_or(false)
+    }
+
+    #[tokio::test]
+    async fn test_private_transport_requires_the_same_key() {
+        let key = PreSharedKey::new([7; 32]);
+        let other_key = PreSharedKey::new([8; 32]);
+
+        assert!(private_nodes_connect(key, key).await);
+        assert!(!private_nodes_connect(key, other_key).await);
+    }
+
+    #[tokio::test]
+    async fn test_private_transport_does_not_listen_on_quic() {
+        let keypair = Keypair::generate_ed25519();
+        let mut transport = build_private_transport(&keypair, PreSharedKey::new([7; 32])).unwrap();
+
+        let result = transport.listen_on(
+            libp2p::core::transport::ListenerId::next(),
+            "/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap(),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transport_listens_on_tcp_and_quic() {
+        let keypair = Keypair::generate_ed25519();
+        let mut transport = build_transport(&keypair).unwrap();
+
+        for addr in ["/ip4/127.0.0.1/tcp/0", "/ip4/127.0.0.1/udp/0/quic-v1"] {
+            let result = transport.listen_on(
+                libp2p::core::transport::ListenerId::next(),
+                addr.parse().unwrap(),
+            );
+            assert!(result.is_ok(), "Should listen on {}: {:?}", addr, result);
+        }
+    }
+
+    #[test]
+    fn test_prefer_quic_orders_quic_addresses_first() {
+        let mut addrs: Vec<Multiaddr> = vec![
+            "/ip4/10.0.0.1/tcp/9000".parse().unwrap(),
+            "/ip6/::1/tcp/9000".parse().unwrap(),
+            "/ip4/10.0.0.1/udp/9000/quic-v1".parse().unwrap(),
+        ];
+
+        prefer_quic(&mut addrs);
+
+        assert!(is_quic(&addrs[0]));
+        assert_eq!(addrs[1], "/ip4/10.0.0.1/tcp/9000".parse().unwrap());
+        assert_eq!(addrs[2], "/ip6/::1/tcp/9000".parse().unwrap());
+    }
 }
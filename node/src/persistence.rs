@@ -3,6 +3,7 @@
 //! Provides durable storage for:
 //! - Capability cards (agent metadata)
 //! - Trust data (reputation, stake, endorsements)
+//! - Search embeddings (keyed by card text hash and model)
 //! - DHT records (optional)
 //!
 //! Uses RocksDB as the underlying key-value store for high performance
@@ -10,7 +11,7 @@
 
 use crate::discovery::CapabilityCard;
 use crate::error::{Error, Result};
-use rocksdb::{Options, DB};
+use rocksdb::{ColumnFamily, Options, WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
@@ -31,7 +32,7 @@ pub struct PersistenceConfig {
     #[serde(default = "default_data_dir")]
     pub data_dir: String,
 
-    /// Whether to persist capability cards.
+    /// Whether to persist capability cards and their search embeddings.
     #[serde(default = "default_true")]
     pub capability_cards: bool,
 
@@ -42,6 +43,30 @@ pub struct PersistenceConfig {
     /// Whether to persist DHT records.
     #[serde(default = "default_false")]
     pub dht_records: bool,
+
+    /// Maximum number of DHT records held by this node.
+    #[serde(default = "default_dht_max_records")]
+    pub dht_max_records: usize,
+
+    /// Maximum size of a single DHT record value in bytes.
+    #[serde(default = "default_dht_max_record_bytes")]
+    pub dht_max_record_bytes: usize,
+
+    /// Maximum number of keys this node announces itself as provider for.
+    #[serde(default = "default_dht_max_provided_keys")]
+    pub dht_max_provided_keys: usize,
+
+    /// Interval between memtable flushes in seconds (0 = only on shutdown).
+    #[serde(default = "default_flush_interval_secs")]
+    pub flush_interval_secs: u64,
+
+    /// Interval between full compactions in seconds (0 = disabled).
+    #[serde(default = "default_compaction_interval_secs")]
+    pub compaction_interval_secs: u64,
+
+    /// Whether every write is synced to disk before returning.
+    #[serde(default = "default_false")]
+    pub sync_writes: bool,
 }
 
 fn default_enabled() -> bool {
@@ -60,6 +85,26 @@ fn default_false() -> bool {
     false
 }
 
+fn default_dht_max_records() -> usize {
+    1024
+}
+
+fn default_dht_max_record_bytes() -> usize {
+    65 * 1024
+}
+
+fn default_dht_max_provided_keys() -> usize {
+    1024
+}
+
+fn default_flush_interval_secs() -> u64 {
+    60
+}
+
+fn default_compaction_interval_secs() -> u64 {
+    24 * 60 * 60
+}
+
 impl Default for PersistenceConfig {
     fn default() -> Self {
         Self {
@@ -68,10 +113,30 @@ impl Default for PersistenceConfig {
             capability_cards: true,
             trust_data: true,
             dht_records: false,
+            dht_max_records: default_dht_max_records(),
+            dht_max_record_bytes: default_dht_max_record_bytes(),
+            dht_max_provided_keys: default_dht_max_provided_keys(),
+            flush_interval_secs: default_flush_interval_secs(),
+            compaction_interval_secs: default_compaction_interval_secs(),
+            sync_writes: false,
         }
     }
 }
 
+impl PersistenceConfig {
+    /// Interval between periodic flushes, if enabled.
+    pub fn flush_interval(&self) -> Option<std::time::Duration> {
+        (self.flush_interval_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.flush_interval_secs))
+    }
+
+    /// Interval between periodic compactions, if enabled.
+    pub fn compaction_interval(&self) -> Option<std::time::Duration> {
+        (self.compaction_interval_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.compaction_interval_secs))
+    }
+}
+
 /// Trust data stored for each agent.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TrustData {
@@ -85,8 +150,44 @@ pub struct TrustData {
     pub endorsement_count: u64,
     /// Total volume in USDC (6 decimals).
     pub total_volume: u64,
-    /// Last activity timestamp (Unix seconds).
+    /// Last activity timestamp (Unix seconds), used for reputation decay.
     pub last_activity: u64,
+    /// Endorsements received, with their web-of-trust hop distances.
+    pub endorsements: Vec<Endorsement>,
+}
+
+/// An endorsement received from another agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Endorsement {
+    /// DID of the endorser.
+    pub endorser_did: String,
+    /// Hop distance from trust root (1 = direct, 2 = one intermediary, etc.).
+    pub hop_distance: u32,
+}
+
+/// Trust data layout written before endorsements were persisted.
+#[derive(Deserialize)]
+struct LegacyTrustData {
+    stake_amount: u64,
+    successful_transactions: u64,
+    failed_transactions: u64,
+    endorsement_count: u64,
+    total_volume: u64,
+    last_activity: u64,
+}
+
+impl From<LegacyTrustData> for TrustData {
+    fn from(legacy: LegacyTrustData) -> Self {
+        Self {
+            stake_amount: legacy.stake_amount,
+            successful_transactions: legacy.successful_transactions,
+            failed_transactions: legacy.failed_transactions,
+            endorsement_count: legacy.endorsement_count,
+            total_volume: legacy.total_volume,
+            last_activity: legacy.last_activity,
+            endorsements: Vec::new(),
+        }
+    }
 }
 
 // =============================================================================
@@ -112,6 +213,16 @@ pub trait Store: Send + Sync {
 
     /// Get all keys.
     fn keys(&self) -> Result<Vec<String>>;
+
+    /// Flush buffered writes to durable storage.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Compact the underlying storage to reclaim space.
+    fn compact(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -119,47 +230,122 @@ pub trait Store: Send + Sync {
 // =============================================================================
 
 /// RocksDB-backed key-value store.
+///
+/// Each store reads and writes one column family of its database; see
+/// [`RocksStore::column_family`] for keeping several stores in one
+/// database, sharing its write-ahead log.
 pub struct RocksStore {
-    db: DB,
+    db: Arc<DB>,
     name: String,
+    column_family: String,
+    sync_writes: bool,
+    write_opts: WriteOptions,
 }
 
 impl RocksStore {
     /// Open or create a RocksDB store at the given path.
     pub fn open<P: AsRef<Path>>(path: P, name: &str) -> Result<Self> {
+        Self::open_with_column_families(path, name, &[])
+    }
+
+    /// Open or create a RocksDB store at the given path, creating the
+    /// named column families if they are missing.
+    ///
+    /// The returned store uses the default column family.
+    pub fn open_with_column_families<P: AsRef<Path>>(
+        path: P,
+        name: &str,
+        column_families: &[&str],
+    ) -> Result<Self> {
         let mut opts = Options::default();
         opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
         opts.set_max_open_files(256);
         opts.set_keep_log_file_num(3);
         opts.set_max_log_file_size(1024 * 1024); // 1MB
 
-        let db = DB::open(&opts, path.as_ref())
+        // Every existing column family must be opened as well
+        let mut names: Vec<String> = DB::list_cf(&opts, path.as_ref()).unwrap_or_default();
+        for cf in column_families
+            .iter()
+            .copied()
+            .chain([DEFAULT_COLUMN_FAMILY_NAME])
+        {
+            if !names.iter().any(|existing| existing == cf) {
+                names.push(cf.to_string());
+            }
+        }
+
+        let db = DB::open_cf(&opts, path.as_ref(), &names)
             .map_err(|e| Error::Persistence(format!("Failed to open RocksDB {}: {}", name, e)))?;
 
         info!("Opened RocksDB store: {} at {:?}", name, path.as_ref());
 
         Ok(Self {
-            db,
+            db: Arc::new(db),
             name: name.to_string(),
+            column_family: DEFAULT_COLUMN_FAMILY_NAME.to_string(),
+            sync_writes: false,
+            write_opts: WriteOptions::default(),
         })
     }
 
+    /// Get a store for another column family of the same database.
+    ///
+    /// The column family must have been named when the database was
+    /// opened. The new store inherits this store's write options.
+    pub fn column_family(&self, column_family: &str) -> Result<Self> {
+        if self.db.cf_handle(column_family).is_none() {
+            return Err(Error::Persistence(format!(
+                "RocksDB {} has no column family {}",
+                self.name, column_family
+            )));
+        }
+
+        let mut write_opts = WriteOptions::default();
+        write_opts.set_sync(self.sync_writes);
+        Ok(Self {
+            db: self.db.clone(),
+            name: format!("{}/{}", self.name, column_family),
+            column_family: column_family.to_string(),
+            sync_writes: self.sync_writes,
+            write_opts,
+        })
+    }
+
+    /// Sync every write to disk before returning (slower, but no data loss
+    /// on power failure).
+    pub fn with_sync_writes(mut self, sync: bool) -> Self {
+        self.sync_writes = sync;
+        self.write_opts.set_sync(sync);
+        self
+    }
+
     /// Get the store name.
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    fn cf(&self) -> Result<&ColumnFamily> {
+        self.db.cf_handle(&self.column_family).ok_or_else(|| {
+            Error::Persistence(format!(
+                "RocksDB {} has no column family {}",
+                self.name, self.column_family
+            ))
+        })
+    }
 }
 
 impl Store for RocksStore {
     fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
         self.db
-            .get(key.as_bytes())
+            .get_cf(self.cf()?, key.as_bytes())
             .map_err(|e| Error::Persistence(format!("RocksDB get error: {}", e)))
     }
 
     fn put(&self, key: &str, value: &[u8]) -> Result<()> {
         self.db
-            .put(key.as_bytes(), value)
+            .put_cf_opt(self.cf()?, key.as_bytes(), value, &self.write_opts)
             .map_err(|e| Error::Persistence(format!("RocksDB put error: {}", e)))?;
         debug!("Persisted key: {} ({} bytes)", key, value.len());
         Ok(())
@@ -167,7 +353,7 @@ impl Store for RocksStore {
 
     fn delete(&self, key: &str) -> Result<()> {
         self.db
-            .delete(key.as_bytes())
+            .delete_cf_opt(self.cf()?, key.as_bytes(), &self.write_opts)
             .map_err(|e| Error::Persistence(format!("RocksDB delete error: {}", e)))?;
         debug!("Deleted key: {}", key);
         Ok(())
@@ -175,13 +361,13 @@ impl Store for RocksStore {
 
     fn contains(&self, key: &str) -> Result<bool> {
         self.db
-            .get_pinned(key.as_bytes())
+            .get_pinned_cf(self.cf()?, key.as_bytes())
             .map(|opt| opt.is_some())
             .map_err(|e| Error::Persistence(format!("RocksDB contains error: {}", e)))
     }
 
     fn iter_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
-        let iter = self.db.prefix_iterator(prefix.as_bytes());
+        let iter = self.db.prefix_iterator_cf(self.cf()?, prefix.as_bytes());
         let mut results = Vec::new();
 
         for item in iter {
@@ -206,7 +392,9 @@ impl Store for RocksStore {
     }
 
     fn keys(&self) -> Result<Vec<String>> {
-        let iter = self.db.iterator(rocksdb::IteratorMode::Start);
+        let iter = self
+            .db
+            .iterator_cf(self.cf()?, rocksdb::IteratorMode::Start);
         let mut keys = Vec::new();
 
         for item in iter {
@@ -225,6 +413,21 @@ impl Store for RocksStore {
 
         Ok(keys)
     }
+
+    fn flush(&self) -> Result<()> {
+        self.db
+            .flush_cf(self.cf()?)
+            .map_err(|e| Error::Persistence(format!("RocksDB flush error: {}", e)))?;
+        debug!("Flushed RocksDB store: {}", self.name);
+        Ok(())
+    }
+
+    fn compact(&self) -> Result<()> {
+        self.db
+            .compact_range_cf::<&[u8], &[u8]>(self.cf()?, None, None);
+        debug!("Compacted RocksDB store: {}", self.name);
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -304,7 +507,28 @@ impl Store for MemoryStore {
 // Typed Stores
 // =============================================================================
 
+/// A capability card as written to the card store.
+#[derive(Deserialize)]
+struct StoredCard {
+    card: CapabilityCard,
+    /// Whether the card was registered on this node.
+    #[serde(default)]
+    local: bool,
+    /// Whether the agent was deactivated on-chain.
+    #[serde(default)]
+    inactive: bool,
+}
+
+#[derive(Serialize)]
+struct StoredCardRef<'a> {
+    card: &'a CapabilityCard,
+    local: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    inactive: bool,
+}
+
 /// Store for capability cards with JSON serialization.
+#[derive(Clone)]
 pub struct CapabilityCardStore {
     store: Arc<dyn Store>,
 }
@@ -317,24 +541,88 @@ impl CapabilityCardStore {
 
     /// Get a capability card by DID.
     pub fn get(&self, did: &str) -> Result<Option<CapabilityCard>> {
+        Ok(self.get_stored(did)?.map(|stored| stored.card))
+    }
+
+    fn get_stored(&self, did: &str) -> Result<Option<StoredCard>> {
         match self.store.get(did)? {
             Some(data) => {
-                let card: CapabilityCard = serde_json::from_slice(&data).map_err(|e| {
-                    Error::Persistence(format!("Failed to deserialize card: {}", e))
-                })?;
-                Ok(Some(card))
+                let stored = serde_json::from_slice::<StoredCard>(&data)
+                    .or_else(|e| {
+                        // Fall back to bare cards written before the local flag
+                        serde_json::from_slice::<CapabilityCard>(&data)
+                            .map(|card| StoredCard {
+                                card,
+                                local: false,
+                                inactive: false,
+                            })
+                            .map_err(|_| e)
+                    })
+                    .map_err(|e| {
+                        Error::Persistence(format!("Failed to deserialize card: {}", e))
+                    })?;
+                Ok(Some(stored))
             }
             None => Ok(None),
         }
     }
 
-    /// Store a capability card.
+    /// Store a capability card learned from the network.
     pub fn put(&self, did: &str, card: &CapabilityCard) -> Result<()> {
-        let data = serde_json::to_vec(card)
-            .map_err(|e| Error::Persistence(format!("Failed to serialize card: {}", e)))?;
+        self.put_stored(did, card, false)
+    }
+
+    /// Store a capability card registered on this node.
+    pub fn put_local(&self, did: &str, card: &CapabilityCard) -> Result<()> {
+        self.put_stored(did, card, true)
+    }
+
+    fn put_stored(&self, did: &str, card: &CapabilityCard, local: bool) -> Result<()> {
+        self.write_stored(did, card, local, false)
+    }
+
+    fn write_stored(
+        &self,
+        did: &str,
+        card: &CapabilityCard,
+        local: bool,
+        inactive: bool,
+    ) -> Result<()> {
+        let data = serde_json::to_vec(&StoredCardRef {
+            card,
+            local,
+            inactive,
+        })
+        .map_err(|e| Error::Persistence(format!("Failed to serialize card: {}", e)))?;
         self.store.put(did, &data)
     }
 
+    /// Mark a stored card's agent as deactivated (or active again).
+    ///
+    /// The card itself is kept. Returns `false` if no card is stored for `did`.
+    pub fn set_inactive(&self, did: &str, inactive: bool) -> Result<bool> {
+        match self.get_stored(did)? {
+            Some(stored) => {
+                self.write_stored(did, &stored.card, stored.local, inactive)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// DIDs of stored cards whose agents are deactivated.
+    pub fn inactive(&self) -> Result<Vec<String>> {
+        let mut dids = Vec::new();
+
+        for key in self.store.keys()? {
+            if self.get_stored(&key)?.is_some_and(|stored| stored.inactive) {
+                dids.push(key);
+            }
+        }
+
+        Ok(dids)
+    }
+
     /// Delete a capability card.
     pub fn delete(&self, did: &str) -> Result<()> {
         self.store.delete(did)
@@ -359,6 +647,21 @@ impl CapabilityCardStore {
         Ok(cards)
     }
 
+    /// Get all capability cards registered on this node.
+    pub fn local(&self) -> Result<Vec<(String, CapabilityCard)>> {
+        let mut cards = Vec::new();
+
+        for key in self.store.keys()? {
+            if let Some(stored) = self.get_stored(&key)? {
+                if stored.local {
+                    cards.push((key, stored.card));
+                }
+            }
+        }
+
+        Ok(cards)
+    }
+
     /// Get the number of stored cards.
     pub fn len(&self) -> Result<usize> {
         Ok(self.store.keys()?.len())
@@ -368,9 +671,20 @@ impl CapabilityCardStore {
     pub fn is_empty(&self) -> Result<bool> {
         Ok(self.len()? == 0)
     }
+
+    /// Flush buffered writes to disk.
+    pub fn flush(&self) -> Result<()> {
+        self.store.flush()
+    }
+
+    /// Compact the underlying store.
+    pub fn compact(&self) -> Result<()> {
+        self.store.compact()
+    }
 }
 
 /// Store for trust data with bincode serialization.
+#[derive(Clone)]
 pub struct TrustDataStore {
     store: Arc<dyn Store>,
 }
@@ -385,9 +699,16 @@ impl TrustDataStore {
     pub fn get(&self, did: &str) -> Result<Option<TrustData>> {
         match self.store.get(did)? {
             Some(data) => {
-                let trust: TrustData = bincode::deserialize(&data).map_err(|e| {
-                    Error::Persistence(format!("Failed to deserialize trust data: {}", e))
-                })?;
+                let trust = bincode::deserialize::<TrustData>(&data)
+                    .or_else(|e| {
+                        // Fall back to the pre-endorsement layout
+                        bincode::deserialize::<LegacyTrustData>(&data)
+                            .map(TrustData::from)
+                            .map_err(|_| e)
+                    })
+                    .map_err(|e| {
+                        Error::Persistence(format!("Failed to deserialize trust data: {}", e))
+                    })?;
                 Ok(Some(trust))
             }
             None => Ok(None),
@@ -422,6 +743,20 @@ impl TrustDataStore {
         Ok(trust)
     }
 
+    /// Get all trust data entries.
+    pub fn all(&self) -> Result<Vec<(String, TrustData)>> {
+        let keys = self.store.keys()?;
+        let mut entries = Vec::new();
+
+        for key in keys {
+            if let Some(trust) = self.get(&key)? {
+                entries.push((key, trust));
+            }
+        }
+
+        Ok(entries)
+    }
+
     /// Get the number of stored entries.
     pub fn len(&self) -> Result<usize> {
         Ok(self.store.keys()?.len())
@@ -431,6 +766,97 @@ impl TrustDataStore {
     pub fn is_empty(&self) -> Result<bool> {
         Ok(self.len()? == 0)
     }
+
+    /// Flush buffered writes to disk.
+    pub fn flush(&self) -> Result<()> {
+        self.store.flush()
+    }
+
+    /// Compact the underlying store.
+    pub fn compact(&self) -> Result<()> {
+        self.store.compact()
+    }
+}
+
+/// Column family of the capability card database holding search embeddings.
+pub const EMBEDDINGS_COLUMN_FAMILY: &str = "embeddings";
+
+/// Store for search embeddings, encoded as little-endian `f32` bytes.
+///
+/// Keys are content hashes (see `EmbeddingService::storage_key`), so a
+/// vector is reused for as long as the card text and model are unchanged.
+#[derive(Clone)]
+pub struct EmbeddingStore {
+    store: Arc<dyn Store>,
+}
+
+impl EmbeddingStore {
+    /// Create a new embedding store.
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self { store }
+    }
+
+    /// Get an embedding by key.
+    pub fn get(&self, key: &str) -> Result<Option<Vec<f32>>> {
+        match self.store.get(key)? {
+            Some(data) => {
+                if data.len() % 4 != 0 {
+                    return Err(Error::Persistence(format!(
+                        "Corrupt embedding for {}: {} bytes",
+                        key,
+                        data.len()
+                    )));
+                }
+                let embedding = data
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                Ok(Some(embedding))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Store an embedding.
+    pub fn put(&self, key: &str, embedding: &[f32]) -> Result<()> {
+        let data: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.store.put(key, &data)
+    }
+
+    /// Delete an embedding.
+    pub fn delete(&self, key: &str) -> Result<()> {
+        self.store.delete(key)
+    }
+
+    /// Check if an embedding exists.
+    pub fn contains(&self, key: &str) -> Result<bool> {
+        self.store.contains(key)
+    }
+
+    /// Get all stored keys.
+    pub fn keys(&self) -> Result<Vec<String>> {
+        self.store.keys()
+    }
+
+    /// Get the number of stored embeddings.
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.store.keys()?.len())
+    }
+
+    /// Check if the store is empty.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Flush buffered writes to disk.
+    pub fn flush(&self) -> Result<()> {
+        self.store.flush()
+    }
+
+    /// Compact the underlying store.
+    pub fn compact(&self) -> Result<()> {
+        self.store.compact()
+    }
 }
 
 // =============================================================================
@@ -442,6 +868,9 @@ pub struct PersistenceManager {
     config: PersistenceConfig,
     capability_store: Option<CapabilityCardStore>,
     trust_store: Option<TrustDataStore>,
+    embedding_store: Option<EmbeddingStore>,
+    chain_events_store: Option<Arc<dyn Store>>,
+    dht_store: Option<Arc<dyn Store>>,
 }
 
 impl PersistenceManager {
@@ -453,6 +882,9 @@ impl PersistenceManager {
                 config,
                 capability_store: None,
                 trust_store: None,
+                embedding_store: None,
+                chain_events_store: None,
+                dht_store: None,
             });
         }
 
@@ -464,34 +896,65 @@ impl PersistenceManager {
             ))
         })?;
 
-        // Open capability card store
-        let capability_store = if config.capability_cards {
+        // Open capability cards, with their search embeddings in a column
+        // family of the same database
+        let (capability_store, embedding_store) = if config.capability_cards {
             let path = Path::new(&config.data_dir).join("capability_cards");
-            let store = Arc::new(RocksStore::open(&path, "capability_cards")?);
-            Some(CapabilityCardStore::new(store))
+            let cards = RocksStore::open_with_column_families(
+                &path,
+                "capability_cards",
+                &[EMBEDDINGS_COLUMN_FAMILY],
+            )?
+            .with_sync_writes(config.sync_writes);
+            let embeddings = cards.column_family(EMBEDDINGS_COLUMN_FAMILY)?;
+            (
+                Some(CapabilityCardStore::new(Arc::new(cards))),
+                Some(EmbeddingStore::new(Arc::new(embeddings))),
+            )
         } else {
-            None
+            (None, None)
         };
 
         // Open trust data store
         let trust_store = if config.trust_data {
             let path = Path::new(&config.data_dir).join("trust_data");
-            let store = Arc::new(RocksStore::open(&path, "trust_data")?);
+            let store = Arc::new(
+                RocksStore::open(&path, "trust_data")?.with_sync_writes(config.sync_writes),
+            );
             Some(TrustDataStore::new(store))
         } else {
             None
         };
 
+        // Open contract event listener checkpoints (always small)
+        let path = Path::new(&config.data_dir).join("chain_events");
+        let chain_events_store: Arc<dyn Store> =
+            Arc::new(RocksStore::open(&path, "chain_events")?.with_sync_writes(config.sync_writes));
+
+        // Open Kademlia records hosted by this node
+        let dht_store: Option<Arc<dyn Store>> = if config.dht_records {
+            let path = Path::new(&config.data_dir).join("dht_records");
+            Some(Arc::new(
+                RocksStore::open(&path, "dht_records")?.with_sync_writes(config.sync_writes),
+            ))
+        } else {
+            None
+        };
+
         info!(
-            "Persistence manager initialized: capability_cards={}, trust_data={}",
+            "Persistence manager initialized: capability_cards={}, trust_data={}, dht_records={}",
             capability_store.is_some(),
-            trust_store.is_some()
+            trust_store.is_some(),
+            dht_store.is_some()
         );
 
         Ok(Self {
             config,
             capability_store,
             trust_store,
+            embedding_store,
+            chain_events_store: Some(chain_events_store),
+            dht_store,
         })
     }
 
@@ -504,6 +967,9 @@ impl PersistenceManager {
             config: PersistenceConfig::default(),
             capability_store: Some(capability_store),
             trust_store: Some(trust_store),
+            embedding_store: Some(EmbeddingStore::new(Arc::new(MemoryStore::new()))),
+            chain_events_store: Some(Arc::new(MemoryStore::new())),
+            dht_store: Some(Arc::new(MemoryStore::new())),
         }
     }
 
@@ -522,10 +988,65 @@ impl PersistenceManager {
         self.trust_store.as_ref()
     }
 
+    /// Get the search embedding store.
+    pub fn embeddings(&self) -> Option<&EmbeddingStore> {
+        self.embedding_store.as_ref()
+    }
+
+    /// Get the store for contract event listener checkpoints.
+    pub fn chain_events(&self) -> Option<Arc<dyn Store>> {
+        self.chain_events_store.clone()
+    }
+
+    /// Get the store for Kademlia records, if DHT persistence is enabled.
+    pub fn dht_records(&self) -> Option<Arc<dyn Store>> {
+        self.dht_store.clone()
+    }
+
     /// Check if persistence is enabled.
     pub fn is_enabled(&self) -> bool {
         self.config.enabled
     }
+
+    /// Flush all open stores to disk.
+    pub fn flush(&self) -> Result<()> {
+        if let Some(store) = &self.capability_store {
+            store.flush()?;
+        }
+        if let Some(store) = &self.trust_store {
+            store.flush()?;
+        }
+        if let Some(store) = &self.embedding_store {
+            store.flush()?;
+        }
+        if let Some(store) = &self.chain_events_store {
+            store.flush()?;
+        }
+        if let Some(store) = &self.dht_store {
+            store.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Compact all open stores.
+    pub fn compact(&self) -> Result<()> {
+        if let Some(store) = &self.capability_store {
+            store.compact()?;
+        }
+        if let Some(store) = &self.trust_store {
+            store.compact()?;
+        }
+        if let Some(store) = &self.embedding_store {
+            store.compact()?;
+        }
+        if let Some(store) = &self.chain_events_store {
+            store.compact()?;
+        }
+        if let Some(store) = &self.dht_store {
+            store.compact()?;
+        }
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -566,6 +1087,29 @@ mod tests {
         assert!(!store.contains("key1").unwrap());
     }
 
+    #[test]
+    fn test_rocks_store_column_families_share_one_database() {
+        let tmp_dir = TempDir::new().unwrap();
+        {
+            let store =
+                RocksStore::open_with_column_families(tmp_dir.path(), "test", &["extra"]).unwrap();
+            let extra = store.column_family("extra").unwrap();
+            store.put("key", b"default").unwrap();
+            extra.put("key", b"extra").unwrap();
+            extra.put("other", b"extra").unwrap();
+
+            assert_eq!(store.keys().unwrap(), vec!["key"]);
+            assert_eq!(extra.get("key").unwrap(), Some(b"extra".to_vec()));
+            assert!(store.column_family("missing").is_err());
+        }
+
+        // Existing column families are opened even when not requested
+        let store = RocksStore::open(tmp_dir.path(), "test").unwrap();
+        let extra = store.column_family("extra").unwrap();
+        assert_eq!(store.get("key").unwrap(), Some(b"default".to_vec()));
+        assert_eq!(extra.keys().unwrap(), vec!["key", "other"]);
+    }
+
     #[test]
     fn test_rocks_store() {
         let tmp_dir = TempDir::new().unwrap();
@@ -606,6 +1150,43 @@ mod tests {
         assert!(!store.contains("did:test:1").unwrap());
     }
 
+    #[test]
+    fn test_capability_card_store_remembers_local_cards() {
+        let raw = Arc::new(MemoryStore::new());
+        let store = CapabilityCardStore::new(raw.clone());
+        let card = create_test_card();
+
+        store.put_local("did:test:local", &card).unwrap();
+        store.put("did:test:remote", &card).unwrap();
+        // Card written before the local flag existed
+        raw.put("did:test:legacy", &serde_json::to_vec(&card).unwrap())
+            .unwrap();
+
+        let local = store.local().unwrap();
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].0, "did:test:local");
+        assert_eq!(store.all().unwrap().len(), 3);
+        assert!(store.get("did:test:legacy").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_capability_card_store_keeps_inactive_cards() {
+        let store = CapabilityCardStore::new(Arc::new(MemoryStore::new()));
+        let card = create_test_card();
+        store.put_local("did:test:deactivated", &card).unwrap();
+        store.put("did:test:active", &card).unwrap();
+
+        assert!(store.set_inactive("did:test:deactivated", true).unwrap());
+        assert!(!store.set_inactive("did:test:unknown", true).unwrap());
+
+        assert_eq!(store.inactive().unwrap(), vec!["did:test:deactivated"]);
+        assert!(store.get("did:test:deactivated").unwrap().is_some());
+        assert_eq!(store.local().unwrap().len(), 1);
+
+        store.set_inactive("did:test:deactivated", false).unwrap();
+        assert!(store.inactive().unwrap().is_empty());
+    }
+
     #[test]
     fn test_trust_data_store() {
         let store = TrustDataStore::new(Arc::new(MemoryStore::new()));
@@ -627,6 +1208,62 @@ mod tests {
         assert_eq!(trust.total_volume, 1000);
     }
 
+    #[test]
+    fn test_trust_data_store_roundtrips_endorsements() {
+        let store = TrustDataStore::new(Arc::new(MemoryStore::new()));
+        let trust = TrustData {
+            endorsement_count: 1,
+            endorsements: vec![Endorsement {
+                endorser_did: "did:test:endorser".to_string(),
+                hop_distance: 2,
+            }],
+            ..Default::default()
+        };
+
+        store.put("did:test:1", &trust).unwrap();
+
+        let retrieved = store.get("did:test:1").unwrap().unwrap();
+        assert_eq!(retrieved.endorsements, trust.endorsements);
+    }
+
+    #[test]
+    fn test_trust_data_store_reads_legacy_records() {
+        let backend = Arc::new(MemoryStore::new());
+        // Six u64 fields, as written before endorsements were persisted
+        let legacy = bincode::serialize(&(5u64, 10u64, 1u64, 2u64, 1000u64, 42u64)).unwrap();
+        backend.put("did:test:legacy", &legacy).unwrap();
+        let store = TrustDataStore::new(backend);
+
+        let trust = store.get("did:test:legacy").unwrap().unwrap();
+
+        assert_eq!(trust.stake_amount, 5);
+        assert_eq!(trust.successful_transactions, 10);
+        assert_eq!(trust.endorsement_count, 2);
+        assert_eq!(trust.last_activity, 42);
+        assert!(trust.endorsements.is_empty());
+    }
+
+    #[test]
+    fn test_embedding_store_roundtrips_vectors() {
+        let store = EmbeddingStore::new(Arc::new(MemoryStore::new()));
+        let embedding = vec![0.25, -1.5, f32::MIN_POSITIVE, 3.0];
+
+        store.put("model:abc", &embedding).unwrap();
+
+        assert_eq!(store.get("model:abc").unwrap(), Some(embedding));
+        assert_eq!(store.get("model:missing").unwrap(), None);
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_embedding_store_rejects_truncated_vectors() {
+        let backend = Arc::new(MemoryStore::new());
+        backend.put("model:abc", &[0, 0, 128]).unwrap();
+        let store = EmbeddingStore::new(backend);
+
+        assert!(store.get("model:abc").is_err());
+    }
+
     #[test]
     fn test_persistence_manager_in_memory() {
         let manager = PersistenceManager::in_memory();
@@ -634,6 +1271,7 @@ mod tests {
         assert!(manager.is_enabled());
         assert!(manager.capability_cards().is_some());
         assert!(manager.trust_data().is_some());
+        assert!(manager.embeddings().is_some());
     }
 
     #[test]
@@ -647,6 +1285,34 @@ mod tests {
         assert!(!manager.is_enabled());
         assert!(manager.capability_cards().is_none());
         assert!(manager.trust_data().is_none());
+        assert!(manager.embeddings().is_none());
+        assert!(manager.chain_events().is_none());
+        assert!(manager.dht_records().is_none());
+    }
+
+    #[test]
+    fn test_persistence_manager_opens_dht_store_when_enabled() {
+        let tmp_dir = TempDir::new().unwrap();
+        let data_dir = tmp_dir.path().to_string_lossy().to_string();
+
+        let disabled = PersistenceManager::new(PersistenceConfig {
+            data_dir: data_dir.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(disabled.dht_records().is_none());
+        drop(disabled);
+
+        let enabled = PersistenceManager::new(PersistenceConfig {
+            data_dir,
+            dht_records: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let store = enabled.dht_records().unwrap();
+        store.put("record/00", b"value").unwrap();
+        assert!(enabled.flush().is_ok());
+        assert_eq!(store.get("record/00").unwrap(), Some(b"value".to_vec()));
     }
 
     #[test]
@@ -658,6 +1324,7 @@ mod tests {
             capability_cards: true,
             trust_data: true,
             dht_records: false,
+            ..Default::default()
         };
 
         let manager = PersistenceManager::new(config).unwrap();
@@ -695,4 +1362,94 @@ mod tests {
             .unwrap();
         assert_eq!(trust.successful_transactions, 5);
     }
+
+    #[test]
+    fn test_persistence_manager_survives_reopen() {
+        let tmp_dir = TempDir::new().unwrap();
+        let config = PersistenceConfig {
+            data_dir: tmp_dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        {
+            let manager = PersistenceManager::new(config.clone()).unwrap();
+            manager
+                .capability_cards()
+                .unwrap()
+                .put("did:test:1", &create_test_card())
+                .unwrap();
+            manager
+                .trust_data()
+                .unwrap()
+                .update("did:test:1", |t| t.failed_transactions = 2)
+                .unwrap();
+            manager
+                .embeddings()
+                .unwrap()
+                .put("model:abc", &[1.0, 2.0])
+                .unwrap();
+            manager.flush().unwrap();
+            manager.compact().unwrap();
+        }
+
+        let manager = PersistenceManager::new(config).unwrap();
+        let cards = manager.capability_cards().unwrap().all().unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].0, "did:test:1");
+
+        let trust = manager.trust_data().unwrap().all().unwrap();
+        assert_eq!(trust.len(), 1);
+        assert_eq!(trust[0].1.failed_transactions, 2);
+
+        let embedding = manager.embeddings().unwrap().get("model:abc").unwrap();
+        assert_eq!(embedding, Some(vec![1.0, 2.0]));
+        assert!(
+            !tmp_dir.path().join("embeddings").exists(),
+            "Embeddings should live in the capability card database"
+        );
+    }
+
+    #[test]
+    fn test_chain_events_store_survives_reopen() {
+        let tmp_dir = TempDir::new().unwrap();
+        let config = PersistenceConfig {
+            data_dir: tmp_dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        {
+            let manager = PersistenceManager::new(config.clone()).unwrap();
+            let store = manager.chain_events().unwrap();
+            store
+                .put("last_processed_block", &42u64.to_be_bytes())
+                .unwrap();
+            manager.flush().unwrap();
+        }
+
+        let manager = PersistenceManager::new(config).unwrap();
+        let block = manager
+            .chain_events()
+            .unwrap()
+            .get("last_processed_block")
+            .unwrap();
+        assert_eq!(block, Some(42u64.to_be_bytes().to_vec()));
+    }
+
+    #[test]
+    fn test_maintenance_intervals_from_config() {
+        let config = PersistenceConfig::default();
+        assert_eq!(
+            config.flush_interval(),
+            Some(std::time::Duration::from_secs(60))
+        );
+        assert!(config.compaction_interval().is_some());
+
+        let config = PersistenceConfig {
+            flush_interval_secs: 0,
+            compaction_interval_secs: 0,
+            ..Default::default()
+        };
+        assert!(config.flush_interval().is_none());
+        assert!(config.compaction_interval().is_none());
+    }
 }
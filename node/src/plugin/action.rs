@@ -277,11 +277,7 @@ impl TrackedAction {
 
         ActionStats {
             execution_count,
-            average_duration_ms: if execution_count > 0 {
-                total_duration_ms / execution_count
-            } else {
-                0
-            },
+            average_duration_ms: total_duration_ms.checked_div(execution_count).unwrap_or(0),
             error_count,
             success_rate: if execution_count > 0 {
                 ((execution_count - error_count) as f64 / execution_count as f64) * 100.0
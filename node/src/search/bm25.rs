@@ -0,0 +1,461 @@
+//! BM25 keyword scoring over an inverted index of capability cards.
+//!
+//! Card fields are tokenised (lowercased, split on non-alphanumerics,
+//! stop-words dropped, lightly stemmed) and indexed with per-field boosts,
+//! so a term in the card name counts more than one in a skill description.
+//! Scores use the Okapi BM25 IDF and document length normalisation over the
+//! boosted term frequencies (BM25F-style).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::discovery::CapabilityCard;
+
+/// Words too common to carry meaning in a query.
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "any", "are", "as", "at", "be", "by", "can", "do", "does", "for",
+    "from", "has", "have", "how", "i", "in", "into", "is", "it", "its", "me", "my", "of", "on",
+    "or", "our", "so", "some", "that", "the", "their", "this", "to", "us", "was", "we", "what",
+    "which", "who", "will", "with", "you", "your",
+];
+
+/// Suffix rewrites applied by [`stem`], longest first within each family.
+const SUFFIX_RULES: &[(&str, &str)] = &[
+    ("ational", "ate"),
+    ("ization", "ize"),
+    ("fulness", "ful"),
+    ("iveness", "ive"),
+    ("ousness", "ous"),
+    ("ations", "ate"),
+    ("ation", "ate"),
+    ("sses", "ss"),
+    ("ies", "y"),
+    ("ied", "y"),
+    ("ings", ""),
+    ("ing", ""),
+    ("edly", ""),
+    ("ed", ""),
+    ("ers", ""),
+    ("er", ""),
+    ("ions", ""),
+    ("ion", ""),
+    ("ly", ""),
+    ("es", ""),
+    ("s", ""),
+];
+
+/// Shortest stem a suffix rule may leave behind.
+const MIN_STEM_LEN: usize = 3;
+
+/// BM25 parameters and field boosts.
+#[derive(Debug, Clone)]
+pub struct Bm25Config {
+    /// Term frequency saturation (default: 1.2)
+    pub k1: f32,
+
+    /// Length normalisation strength, 0.0 - 1.0 (default: 0.75)
+    pub b: f32,
+
+    /// Boost for the card name
+    pub name_boost: f32,
+
+    /// Boost for the card description
+    pub description_boost: f32,
+
+    /// Boost for skill names
+    pub skill_name_boost: f32,
+
+    /// Boost for skill IDs
+    pub skill_id_boost: f32,
+
+    /// Boost for skill descriptions
+    pub skill_description_boost: f32,
+}
+
+impl Default for Bm25Config {
+    fn default() -> Self {
+        Self {
+            k1: 1.2,
+            b: 0.75,
+            name_boost: 3.0,
+            description_boost: 1.0,
+            skill_name_boost: 2.0,
+            skill_id_boost: 2.0,
+            skill_description_boost: 1.0,
+        }
+    }
+}
+
+/// A keyword search hit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordMatch {
+    /// Agent DID.
+    pub did: String,
+
+    /// BM25 score (unbounded, higher is better).
+    pub score: f32,
+}
+
+/// Per-document statistics needed for scoring and removal.
+#[derive(Debug, Clone)]
+struct Document {
+    /// Boosted token count.
+    length: f32,
+
+    /// Distinct terms, for removing postings.
+    terms: Vec<String>,
+}
+
+/// Inverted index scoring capability cards with BM25.
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    config: Bm25Config,
+
+    /// Term -> DID -> boosted term frequency.
+    postings: HashMap<String, HashMap<String, f32>>,
+
+    /// Indexed documents by DID.
+    documents: HashMap<String, Document>,
+
+    /// Sum of all document lengths.
+    total_length: f32,
+}
+
+/// Split text into normalised, stemmed search terms.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect()
+}
+
+/// Reduce a lowercased word to its stem.
+///
+/// A light suffix stripper rather than a full Porter stemmer: it folds
+/// plurals, tenses and `-ation` nouns ("translates", "translating",
+/// "translation" -> "translat") but keeps agent nouns like "translator"
+/// distinct.
+pub fn stem(word: &str) -> String {
+    if word.len() <= MIN_STEM_LEN || !word.is_ascii() {
+        return word.to_string();
+    }
+
+    let mut stemmed = word.to_string();
+    for (suffix, replacement) in SUFFIX_RULES {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() < MIN_STEM_LEN {
+                continue;
+            }
+            // Leave "ss", "us" and "is" endings ("access", "status", "analysis")
+            if *suffix == "s" && (base.ends_with('s') || base.ends_with('u') || base.ends_with('i'))
+            {
+                break;
+            }
+            stemmed = format!("{}{}", base, replacement);
+            break;
+        }
+    }
+
+    if stemmed.len() > MIN_STEM_LEN && stemmed.ends_with('e') {
+        stemmed.pop();
+    }
+    stemmed
+}
+
+impl Bm25Index {
+    /// Create an empty index with default parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty index with custom parameters.
+    pub fn with_config(config: Bm25Config) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Index a card under `did`, replacing any previous version.
+    pub fn index_card(&mut self, did: &str, card: &CapabilityCard) {
+        self.remove(did);
+
+        let config = &self.config;
+        let mut fields: Vec<(&str, f32)> = vec![
+            (&card.name, config.name_boost),
+            (&card.description, config.description_boost),
+        ];
+        for skill in &card.skills {
+            fields.push((&skill.name, config.skill_name_boost));
+            fields.push((&skill.id, config.skill_id_boost));
+            if let Some(description) = &skill.description {
+                fields.push((description, config.skill_description_boost));
+            }
+        }
+
+        let mut frequencies: HashMap<String, f32> = HashMap::new();
+        let mut length = 0.0;
+        for (text, boost) in fields {
+            for term in tokenize(text) {
+                *frequencies.entry(term).or_default() += boost;
+                length += boost;
+            }
+        }
+
+        let terms: Vec<String> = frequencies.keys().cloned().collect();
+        for (term, frequency) in frequencies {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(did.to_string(), frequency);
+        }
+        self.total_length += length;
+        self.documents
+            .insert(did.to_string(), Document { length, terms });
+    }
+
+    /// Remove a card. Returns whether it was indexed.
+    pub fn remove(&mut self, did: &str) -> bool {
+        let Some(document) = self.documents.remove(did) else {
+            return false;
+        };
+
+        for term in &document.terms {
+            if let Some(posting) = self.postings.get_mut(term) {
+                posting.remove(did);
+                if posting.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_length -= document.length;
+        if self.documents.is_empty() {
+            self.total_length = 0.0;
+        }
+        true
+    }
+
+    /// Remove every card.
+    pub fn clear(&mut self) {
+        self.postings.clear();
+        self.documents.clear();
+        self.total_length = 0.0;
+    }
+
+    /// Number of indexed cards.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Check whether no cards are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Check whether a DID is indexed.
+    pub fn contains(&self, did: &str) -> bool {
+        self.documents.contains_key(did)
+    }
+
+    /// Score every card containing at least one query term, best first.
+    pub fn search(&self, query: &str) -> Vec<KeywordMatch> {
+        if self.documents.is_empty() {
+            return vec![];
+        }
+
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let count = self.documents.len() as f32;
+        let average_length = (self.total_length / count).max(f32::EPSILON);
+        let Bm25Config { k1, b, .. } = self.config;
+
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+        for term in &terms {
+            let Some(posting) = self.postings.get(term) else {
+                continue;
+            };
+            let frequency = posting.len() as f32;
+            let idf = (1.0 + (count - frequency + 0.5) / (frequency + 0.5)).ln();
+
+            for (did, tf) in posting {
+                let length = self.documents[did].length;
+                let norm = k1 * (1.0 - b + b * length / average_length);
+                *scores.entry(did.as_str()).or_default() += idf * tf * (k1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut matches: Vec<KeywordMatch> = scores
+            .into_iter()
+            .map(|(did, score)| KeywordMatch {
+                did: did.to_string(),
+                score,
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.did.cmp(&b.did))
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::Skill;
+
+    fn card(name: &str, description: &str, skills: &[(&str, &str, &str)]) -> CapabilityCard {
+        CapabilityCard {
+            name: name.to_string(),
+            description: description.to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills: skills
+                .iter()
+                .map(|(id, name, description)| Skill {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    tags: vec![],
+                    input_schema: None,
+                    output_schema: None,
+                })
+                .collect(),
+            authentication: None,
+            agoramesh: None,
+        }
+    }
+
+    fn dids(matches: &[KeywordMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.did.as_str()).collect()
+    }
+
+    // ========== TDD Tests: tokenize() ==========
+
+    #[test]
+    fn test_tokenize_lowercases_splits_and_drops_stop_words() {
+        assert_eq!(
+            tokenize("Review THE code-quality of my repo"),
+            vec!["review", "cod", "quality", "repo"]
+        );
+    }
+
+    #[test]
+    fn test_stem_folds_inflections() {
+        for word in [
+            "translate",
+            "translates",
+            "translating",
+            "translated",
+            "translation",
+        ] {
+            assert_eq!(stem(word), "translat", "stemming {}", word);
+        }
+        assert_eq!(stem("agents"), "agent");
+        assert_eq!(stem("queries"), "query");
+        assert_eq!(stem("analysis"), "analysis");
+        assert_eq!(stem("access"), "access");
+        assert_ne!(stem("translator"), stem("translation"));
+    }
+
+    // ========== TDD Tests: Bm25Index ==========
+
+    #[test]
+    fn test_search_matches_stemmed_terms_across_fields() {
+        let mut index = Bm25Index::new();
+        index.index_card(
+            "did:a",
+            &card("Linguist", "Translates documents", &[("docs", "Docs", "")]),
+        );
+        index.index_card(
+            "did:b",
+            &card("Weather", "Forecasts", &[("weather", "Weather", "")]),
+        );
+
+        let matches = index.search("translation");
+
+        assert_eq!(dids(&matches), vec!["did:a"]);
+        assert!(matches[0].score > 0.0);
+    }
+
+    #[test]
+    fn test_search_indexes_skill_ids() {
+        let mut index = Bm25Index::new();
+        index.index_card(
+            "did:a",
+            &card("Agent", "Does things", &[("ocr-extract", "Reader", "")]),
+        );
+
+        assert_eq!(dids(&index.search("ocr")), vec!["did:a"]);
+    }
+
+    #[test]
+    fn test_name_match_outranks_description_match() {
+        let mut index = Bm25Index::new();
+        index.index_card("did:name", &card("Summarizer", "Handles text", &[]));
+        index.index_card("did:desc", &card("Helper", "Summarizer for text", &[]));
+
+        assert_eq!(
+            dids(&index.search("summarizer")),
+            vec!["did:name", "did:desc"]
+        );
+    }
+
+    #[test]
+    fn test_rare_terms_weigh_more_than_common_ones() {
+        let mut index = Bm25Index::new();
+        index.index_card("did:common", &card("Code Agent", "General code", &[]));
+        index.index_card("did:rare", &card("Rust Helper", "Systems work", &[]));
+        index.index_card("did:other", &card("Data Agent", "More data", &[]));
+
+        // "agent" appears in two cards, "rust" in one
+        let matches = index.search("rust agent");
+
+        assert_eq!(matches[0].did, "did:rare");
+    }
+
+    #[test]
+    fn test_shorter_documents_score_higher_for_same_term() {
+        let mut index = Bm25Index::new();
+        index.index_card("did:short", &card("Translator", "", &[]));
+        index.index_card(
+            "did:long",
+            &card(
+                "Translator",
+                "Also weather, finance, poetry and images",
+                &[],
+            ),
+        );
+
+        assert_eq!(
+            dids(&index.search("translator")),
+            vec!["did:short", "did:long"]
+        );
+    }
+
+    #[test]
+    fn test_reindex_replaces_and_remove_deletes() {
+        let mut index = Bm25Index::new();
+        index.index_card("did:a", &card("Translator", "", &[]));
+        index.index_card("did:a", &card("Weather", "", &[]));
+
+        assert!(index.search("translator").is_empty());
+        assert_eq!(dids(&index.search("weather")), vec!["did:a"]);
+        assert_eq!(index.len(), 1);
+
+        assert!(index.remove("did:a"));
+        assert!(!index.remove("did:a"));
+        assert!(index.is_empty());
+        assert!(index.search("weather").is_empty());
+    }
+
+    #[test]
+    fn test_stop_word_only_query_matches_nothing() {
+        let mut index = Bm25Index::new();
+        index.index_card("did:a", &card("The Agent", "", &[]));
+
+        assert!(index.search("the of and").is_empty());
+    }
+}
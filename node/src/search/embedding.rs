@@ -4,6 +4,7 @@
 //! for semantic similarity search.
 
 use crate::error::{Error, Result};
+use alloy::primitives::keccak256;
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -168,6 +169,20 @@ impl EmbeddingService {
             .collect()
     }
 
+    /// Get the configured model name.
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Get the persistent storage key for `text` embedded with `model`.
+    ///
+    /// The key changes whenever either the text or the model does, so stale
+    /// vectors are never reused after a card edit or model switch.
+    pub fn storage_key(model: &str, text: &str) -> String {
+        let hash = keccak256(text.as_bytes());
+        format!("{}:{}", model, alloy::hex::encode(hash))
+    }
+
     /// Get the embedding dimension (384 for all-MiniLM-L6-v2).
     pub fn dimension(&self) -> usize {
         match self.config.model.as_str() {
@@ -474,6 +489,31 @@ mod tests {
         assert!(text.contains("Description"));
     }
 
+    // ========== TDD Tests: storage_key() ==========
+
+    #[test]
+    fn test_storage_key_is_stable_for_same_text_and_model() {
+        let a = EmbeddingService::storage_key("all-MiniLM-L6-v2", "Agent: translates");
+        let b = EmbeddingService::storage_key("all-MiniLM-L6-v2", "Agent: translates");
+
+        assert_eq!(a, b);
+        assert!(a.starts_with("all-MiniLM-L6-v2:"));
+    }
+
+    #[test]
+    fn test_storage_key_changes_with_text_or_model() {
+        let base = EmbeddingService::storage_key("all-MiniLM-L6-v2", "Agent: translates");
+
+        assert_ne!(
+            base,
+            EmbeddingService::storage_key("all-MiniLM-L6-v2", "Agent: reviews code")
+        );
+        assert_ne!(
+            base,
+            EmbeddingService::storage_key("BGEBaseENV15", "Agent: translates")
+        );
+    }
+
     // ========== TDD Tests: caching ==========
 
     #[tokio::test]
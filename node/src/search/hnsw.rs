@@ -0,0 +1,993 @@
+//! Embedded HNSW (Hierarchical Navigable Small World) vector index.
+//!
+//! Approximate nearest-neighbour search over card embeddings without an
+//! external service. Vectors are unit-normalized on insert so cosine
+//! distance reduces to `1 - dot`. Inserts and removals are incremental, and
+//! the whole graph can be snapshotted to disk so a restart does not have to
+//! rebuild it.
+//!
+//! Based on Malkov & Yashunin, "Efficient and robust approximate nearest
+//! neighbor search using Hierarchical Navigable Small World graphs" (2016).
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::embedding::Embedding;
+use super::vector_index::{VectorFilter, VectorIndex, VectorMatch, VectorPayload, VectorPoint};
+use crate::error::{Error, Result};
+
+/// Snapshot format version; bumped on incompatible layout changes.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Upper bound on node levels, far above what realistic sizes reach.
+const MAX_LEVEL: usize = 16;
+
+/// HNSW index tunables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswConfig {
+    /// Links per node on upper layers (layer 0 keeps `2 * m`).
+    #[serde(default = "default_m")]
+    pub m: usize,
+
+    /// Candidate list size while inserting; higher builds a better graph.
+    #[serde(default = "default_ef_construction")]
+    pub ef_construction: usize,
+
+    /// Candidate list size while searching; higher trades latency for recall.
+    #[serde(default = "default_ef_search")]
+    pub ef_search: usize,
+
+    /// File the graph is snapshotted to (None = not persisted).
+    #[serde(default)]
+    pub snapshot_path: Option<String>,
+}
+
+fn default_m() -> usize {
+    16
+}
+
+fn default_ef_construction() -> usize {
+    200
+}
+
+fn default_ef_search() -> usize {
+    64
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: default_m(),
+            ef_construction: default_ef_construction(),
+            ef_search: default_ef_search(),
+            snapshot_path: None,
+        }
+    }
+}
+
+/// Distance and node ID, ordered by distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored(f32, u32);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    did: String,
+    /// Unit-normalized embedding.
+    vector: Vec<f32>,
+    payload: VectorPayload,
+    /// Neighbour lists, one per layer from 0 up to the node's level.
+    links: Vec<Vec<u32>>,
+    /// Nodes linking to this one, per layer. Links are not symmetric after
+    /// pruning, so removal needs these to find every inbound link. Rebuilt
+    /// from `links` when a snapshot is loaded.
+    #[serde(skip)]
+    inbound: Vec<Vec<u32>>,
+}
+
+/// The layered proximity graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Graph {
+    version: u32,
+    dimension: usize,
+    m: usize,
+    ef_construction: usize,
+    /// Node slots; removed nodes leave `None` until the slot is reused.
+    nodes: Vec<Option<Node>>,
+    free: Vec<u32>,
+    ids: HashMap<String, u32>,
+    entry: Option<u32>,
+    rng_state: u64,
+}
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    1.0 - a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>()
+}
+
+impl Graph {
+    fn new(dimension: usize, m: usize, ef_construction: usize) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            dimension,
+            m: m.max(2),
+            ef_construction: ef_construction.max(1),
+            nodes: vec![],
+            free: vec![],
+            ids: HashMap::new(),
+            entry: None,
+            rng_state: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn node(&self, id: u32) -> &Node {
+        self.nodes[id as usize]
+            .as_ref()
+            .expect("graph links only point at live nodes")
+    }
+
+    fn node_mut(&mut self, id: u32) -> &mut Node {
+        self.nodes[id as usize]
+            .as_mut()
+            .expect("graph links only point at live nodes")
+    }
+
+    fn level_of(&self, id: u32) -> usize {
+        self.node(id).links.len() - 1
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    /// SplitMix64; deterministic so snapshots and tests are reproducible.
+    fn next_random(&mut self) -> u64 {
+        self.rng_state = self.rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn random_level(&mut self) -> usize {
+        let uniform = (self.next_random() >> 11) as f64 / (1u64 << 53) as f64;
+        let ml = 1.0 / (self.m as f64).ln();
+        ((-(1.0 - uniform).ln() * ml).floor() as usize).min(MAX_LEVEL)
+    }
+
+    /// Best-first search of one layer, returning up to `ef` nodes by
+    /// ascending distance.
+    fn search_layer(&self, query: &[f32], entry: &[u32], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entry.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        let mut results: BinaryHeap<Scored> = BinaryHeap::new();
+
+        for &id in entry {
+            let scored = Scored(distance(query, &self.node(id).vector), id);
+            candidates.push(Reverse(scored));
+            results.push(scored);
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let furthest = results.peek().map(|s| s.0).unwrap_or(f32::INFINITY);
+            if current.0 > furthest && results.len() >= ef {
+                break;
+            }
+
+            let Some(links) = self.node(current.1).links.get(layer) else {
+                continue;
+            };
+            for &neighbor in links {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let d = distance(query, &self.node(neighbor).vector);
+                let furthest = results.peek().map(|s| s.0).unwrap_or(f32::INFINITY);
+                if results.len() < ef || d < furthest {
+                    candidates.push(Reverse(Scored(d, neighbor)));
+                    results.push(Scored(d, neighbor));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    /// Descend greedily from the entry point to `layer`, returning the
+    /// closest node found.
+    fn descend(&self, query: &[f32], entry: u32, layer: usize) -> u32 {
+        let mut current = entry;
+        for l in (layer + 1..=self.level_of(entry)).rev() {
+            if let Some(best) = self.search_layer(query, &[current], 1, l).first() {
+                current = best.1;
+            }
+        }
+        current
+    }
+
+    /// Neighbour selection heuristic: prefer candidates that are closer to
+    /// the base than to any already-selected neighbour, then fill up with
+    /// the closest pruned ones.
+    fn select_neighbors(&self, candidates: &[Scored], m: usize) -> Vec<u32> {
+        let mut selected: Vec<Scored> = Vec::with_capacity(m);
+        let mut pruned: Vec<Scored> = vec![];
+
+        for &candidate in candidates {
+            if selected.len() >= m {
+                break;
+            }
+            let vector = &self.node(candidate.1).vector;
+            let diverse = selected
+                .iter()
+                .all(|s| distance(vector, &self.node(s.1).vector) > candidate.0);
+            if diverse {
+                selected.push(candidate);
+            } else {
+                pruned.push(candidate);
+            }
+        }
+        for candidate in pruned {
+            if selected.len() >= m {
+                break;
+            }
+            selected.push(candidate);
+        }
+
+        selected.into_iter().map(|s| s.1).collect()
+    }
+
+    /// Replace the links of `id` on `layer`, keeping inbound lists in step.
+    fn set_links(&mut self, id: u32, layer: usize, links: Vec<u32>) {
+        let old = std::mem::replace(&mut self.node_mut(id).links[layer], links.clone());
+        for target in old.iter().filter(|t| !links.contains(*t)) {
+            let inbound = &mut self.node_mut(*target).inbound[layer];
+            if let Some(pos) = inbound.iter().position(|&l| l == id) {
+                inbound.swap_remove(pos);
+            }
+        }
+        for target in links.into_iter().filter(|t| !old.contains(t)) {
+            self.node_mut(target).inbound[layer].push(id);
+        }
+    }
+
+    /// Recompute every inbound list from the outbound links.
+    fn rebuild_inbound(&mut self) {
+        for node in self.nodes.iter_mut().flatten() {
+            node.inbound = vec![vec![]; node.links.len()];
+        }
+        for source in 0..self.nodes.len() as u32 {
+            let Some(node) = self.nodes[source as usize].as_ref() else {
+                continue;
+            };
+            for (layer, links) in node.links.clone().into_iter().enumerate() {
+                for target in links {
+                    self.node_mut(target).inbound[layer].push(source);
+                }
+            }
+        }
+    }
+
+    /// Re-select the links of `id` on `layer` from `candidates`.
+    fn relink(&mut self, id: u32, layer: usize, candidates: impl IntoIterator<Item = u32>) {
+        let vector = self.node(id).vector.clone();
+        let mut scored: Vec<Scored> = candidates
+            .into_iter()
+            .collect::<HashSet<u32>>()
+            .into_iter()
+            .filter(|&c| c != id && self.nodes[c as usize].is_some())
+            .map(|c| Scored(distance(&vector, &self.node(c).vector), c))
+            .collect();
+        scored.sort();
+
+        let links = self.select_neighbors(&scored, self.max_links(layer));
+        self.set_links(id, layer, links);
+    }
+
+    fn insert(&mut self, did: String, vector: Vec<f32>, payload: VectorPayload) {
+        let vector = normalize(&vector);
+
+        if let Some(&id) = self.ids.get(&did) {
+            let node = self.node_mut(id);
+            if node.vector == vector {
+                node.payload = payload;
+                return;
+            }
+            self.remove(&did);
+        }
+
+        let level = self.random_level();
+        let node = Node {
+            did: did.clone(),
+            vector: vector.clone(),
+            payload,
+            links: vec![vec![]; level + 1],
+            inbound: vec![vec![]; level + 1],
+        };
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id as usize] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                (self.nodes.len() - 1) as u32
+            }
+        };
+        self.ids.insert(did, id);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return;
+        };
+        let top = self.level_of(entry);
+
+        let mut nearest = vec![self.descend(&vector, entry, level.min(top))];
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&vector, &nearest, self.ef_construction, layer);
+            let neighbors = self.select_neighbors(&found, self.m);
+            self.set_links(id, layer, neighbors.clone());
+
+            let max_links = self.max_links(layer);
+            for neighbor in neighbors {
+                let mut links = self.node(neighbor).links[layer].clone();
+                links.push(id);
+                if links.len() > max_links {
+                    self.relink(neighbor, layer, links);
+                } else {
+                    self.set_links(neighbor, layer, links);
+                }
+            }
+            nearest = found.into_iter().map(|s| s.1).collect();
+        }
+
+        if level > top {
+            self.entry = Some(id);
+        }
+    }
+
+    fn remove(&mut self, did: &str) -> bool {
+        let Some(id) = self.ids.remove(did) else {
+            return false;
+        };
+        let removed = self.nodes[id as usize]
+            .take()
+            .expect("id map only holds live nodes");
+        self.free.push(id);
+
+        for (layer, links) in removed.links.iter().enumerate() {
+            for &target in links {
+                let inbound = &mut self.node_mut(target).inbound[layer];
+                if let Some(pos) = inbound.iter().position(|&l| l == id) {
+                    inbound.swap_remove(pos);
+                }
+            }
+        }
+
+        // Repair the nodes that linked here using the removed node's
+        // neighbourhood
+        for (layer, sources) in removed.inbound.iter().enumerate() {
+            for &source in sources {
+                let links = &mut self.node_mut(source).links[layer];
+                if let Some(pos) = links.iter().position(|&l| l == id) {
+                    links.swap_remove(pos);
+                }
+                let mut candidates = links.clone();
+                candidates.extend(&removed.links[layer]);
+                self.relink(source, layer, candidates);
+            }
+        }
+
+        // Only removing the entry point needs a scan for the new top node
+        if self.entry == Some(id) {
+            self.entry = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter_map(|(i, n)| n.as_ref().map(|n| (n.links.len(), i as u32)))
+                .max()
+                .map(|(_, i)| i);
+        }
+
+        true
+    }
+
+    fn search(
+        &self,
+        query: &[f32],
+        filter: &VectorFilter,
+        limit: usize,
+        ef_search: usize,
+    ) -> Vec<VectorMatch> {
+        let Some(entry) = self.entry else {
+            return vec![];
+        };
+        if limit == 0 {
+            return vec![];
+        }
+
+        let query = normalize(query);
+        let start = self.descend(&query, entry, 0);
+        let mut ef = ef_search.max(limit);
+
+        loop {
+            let found = self.search_layer(&query, &[start], ef, 0);
+            let matches: Vec<VectorMatch> = found
+                .iter()
+                .filter(|s| filter.matches(&self.node(s.1).payload))
+                .take(limit)
+                .map(|s| VectorMatch {
+                    did: self.node(s.1).did.clone(),
+                    similarity: 1.0 - s.0,
+                })
+                .collect();
+
+            // Restrictive filters widen the beam until enough nodes pass
+            if matches.len() >= limit || filter.is_empty() || ef >= self.len() {
+                return matches;
+            }
+            ef = (ef * 2).min(self.len());
+        }
+    }
+}
+
+/// Vector index backed by an in-process HNSW graph.
+pub struct HnswIndex {
+    graph: RwLock<Graph>,
+    ef_search: usize,
+    snapshot_path: Option<PathBuf>,
+    dirty: AtomicBool,
+}
+
+fn lock_poisoned<E: std::fmt::Display>(e: E) -> Error {
+    Error::Search(format!("HNSW index lock poisoned: {}", e))
+}
+
+impl HnswIndex {
+    /// Create an empty index for vectors of `dimension`.
+    pub fn new(config: &HnswConfig, dimension: usize) -> Self {
+        Self {
+            graph: RwLock::new(Graph::new(dimension, config.m, config.ef_construction)),
+            ef_search: config.ef_search.max(1),
+            snapshot_path: config.snapshot_path.as_ref().map(PathBuf::from),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Create an index, restoring the configured snapshot if it exists and
+    /// was built with the same dimension and `m`.
+    pub fn open(config: &HnswConfig, dimension: usize) -> Result<Self> {
+        let index = Self::new(config, dimension);
+        let Some(path) = index.snapshot_path.clone() else {
+            return Ok(index);
+        };
+        if !path.exists() {
+            return Ok(index);
+        }
+
+        match Self::read_snapshot(&path) {
+            Ok(graph) if graph.dimension == dimension && graph.m == config.m.max(2) => {
+                info!(
+                    "Restored HNSW snapshot with {} vectors from {}",
+                    graph.len(),
+                    path.display()
+                );
+                *index.graph.write().map_err(lock_poisoned)? = graph;
+            }
+            Ok(graph) => warn!(
+                "Ignoring HNSW snapshot {} built for dimension {} / m {}",
+                path.display(),
+                graph.dimension,
+                graph.m
+            ),
+            Err(e) => warn!("Ignoring unreadable HNSW snapshot: {}", e),
+        }
+
+        Ok(index)
+    }
+
+    fn read_snapshot(path: &Path) -> Result<Graph> {
+        let data = std::fs::read(path)?;
+        let mut graph: Graph = bincode::deserialize(&data)
+            .map_err(|e| Error::Persistence(format!("Failed to decode HNSW snapshot: {}", e)))?;
+        if graph.version != SNAPSHOT_VERSION {
+            return Err(Error::Persistence(format!(
+                "Unsupported HNSW snapshot version {}",
+                graph.version
+            )));
+        }
+        graph.rebuild_inbound();
+        Ok(graph)
+    }
+
+    /// Write the graph to `path`, replacing it atomically.
+    pub fn save_snapshot(&self, path: &Path) -> Result<()> {
+        let data = {
+            let graph = self.graph.read().map_err(lock_poisoned)?;
+            bincode::serialize(&*graph)
+                .map_err(|e| Error::Persistence(format!("Failed to encode HNSW snapshot: {}", e)))?
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Get the configured snapshot path.
+    pub fn snapshot_path(&self) -> Option<&Path> {
+        self.snapshot_path.as_deref()
+    }
+
+    fn mark_dirty(&self) {
+        self.dirty.store(true, AtomicOrdering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl VectorIndex for HnswIndex {
+    fn name(&self) -> &str {
+        "hnsw"
+    }
+
+    async fn upsert(&self, points: Vec<VectorPoint>) -> Result<()> {
+        let mut graph = self.graph.write().map_err(lock_poisoned)?;
+        for point in points {
+            if point.embedding.len() != graph.dimension {
+                return Err(Error::Search(format!(
+                    "Embedding for {} has dimension {}, index expects {}",
+                    point.did,
+                    point.embedding.len(),
+                    graph.dimension
+                )));
+            }
+            graph.insert(point.did, point.embedding, point.payload);
+        }
+        self.mark_dirty();
+        Ok(())
+    }
+
+    async fn remove(&self, did: &str) -> Result<bool> {
+        let removed = self.graph.write().map_err(lock_poisoned)?.remove(did);
+        if removed {
+            self.mark_dirty();
+        }
+        Ok(removed)
+    }
+
+    async fn search(
+        &self,
+        query: &Embedding,
+        filter: &VectorFilter,
+        limit: usize,
+    ) -> Result<Vec<VectorMatch>> {
+        let graph = self.graph.read().map_err(lock_poisoned)?;
+        Ok(graph.search(query, filter, limit, self.ef_search))
+    }
+
+    async fn len(&self) -> Result<usize> {
+        Ok(self.graph.read().map_err(lock_poisoned)?.len())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        let mut graph = self.graph.write().map_err(lock_poisoned)?;
+        *graph = Graph::new(graph.dimension, graph.m, graph.ef_construction);
+        self.mark_dirty();
+        Ok(())
+    }
+
+    async fn retain(&self, dids: &HashSet<String>) -> Result<usize> {
+        let mut graph = self.graph.write().map_err(lock_poisoned)?;
+        let stale: Vec<String> = graph
+            .ids
+            .keys()
+            .filter(|did| !dids.contains(*did))
+            .cloned()
+            .collect();
+        for did in &stale {
+            graph.remove(did);
+        }
+        if !stale.is_empty() {
+            self.mark_dirty();
+        }
+        Ok(stale.len())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let Some(path) = &self.snapshot_path else {
+            return Ok(());
+        };
+        if self.dirty.swap(false, AtomicOrdering::Relaxed) {
+            if let Err(e) = self.save_snapshot(path) {
+                self.mark_dirty();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::PricingModel;
+    use crate::search::{EmbeddingService, InMemoryVectorIndex};
+    use tempfile::TempDir;
+
+    /// Deterministic pseudo-random unit vectors.
+    fn vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut graph = Graph::new(dimension, 16, 1);
+        graph.rng_state = seed;
+        (0..count)
+            .map(|_| {
+                let v: Vec<f32> = (0..dimension)
+                    .map(|_| (graph.next_random() >> 40) as f32 / (1u64 << 24) as f32 - 0.5)
+                    .collect();
+                normalize(&v)
+            })
+            .collect()
+    }
+
+    fn points(vectors: &[Vec<f32>]) -> Vec<VectorPoint> {
+        vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| VectorPoint {
+                did: format!("did:test:{}", i),
+                embedding: v.clone(),
+                payload: VectorPayload::default(),
+            })
+            .collect()
+    }
+
+    fn small_config() -> HnswConfig {
+        HnswConfig {
+            m: 8,
+            ef_construction: 64,
+            ef_search: 32,
+            snapshot_path: None,
+        }
+    }
+
+    // ========== TDD Tests: search ==========
+
+    #[tokio::test]
+    async fn test_empty_index_returns_no_matches() {
+        let index = HnswIndex::new(&small_config(), 4);
+
+        let matches = index
+            .search(&vec![1.0, 0.0, 0.0, 0.0], &VectorFilter::default(), 5)
+            .await
+            .unwrap();
+
+        assert!(matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_finds_exact_vector_first() {
+        let data = vectors(500, 16, 7);
+        let index = HnswIndex::new(&small_config(), 16);
+        index.upsert(points(&data)).await.unwrap();
+
+        let matches = index
+            .search(&data[123], &VectorFilter::default(), 3)
+            .await
+            .unwrap();
+
+        assert_eq!(matches[0].did, "did:test:123");
+        assert!((matches[0].similarity - 1.0).abs() < 1e-5);
+        assert_eq!(matches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_recall_against_brute_force() {
+        let data = vectors(1000, 32, 11);
+        let queries = vectors(50, 32, 99);
+        let hnsw = HnswIndex::new(&HnswConfig::default(), 32);
+        let exact = InMemoryVectorIndex::new();
+        hnsw.upsert(points(&data)).await.unwrap();
+        exact.upsert(points(&data)).await.unwrap();
+
+        let k = 10;
+        let mut hits = 0;
+        for query in &queries {
+            let truth: HashSet<String> = exact
+                .search(query, &VectorFilter::default(), k)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|m| m.did)
+                .collect();
+            hits += hnsw
+                .search(query, &VectorFilter::default(), k)
+                .await
+                .unwrap()
+                .iter()
+                .filter(|m| truth.contains(&m.did))
+                .count();
+        }
+
+        let recall = hits as f64 / (queries.len() * k) as f64;
+        assert!(recall >= 0.9, "recall@10 too low: {}", recall);
+    }
+
+    #[tokio::test]
+    async fn test_similarity_matches_cosine() {
+        let data = vectors(50, 8, 3);
+        let index = HnswIndex::new(&small_config(), 8);
+        index.upsert(points(&data)).await.unwrap();
+        let query = vectors(1, 8, 42).remove(0);
+
+        let matches = index
+            .search(&query, &VectorFilter::default(), 5)
+            .await
+            .unwrap();
+
+        for m in matches {
+            let i: usize = m.did.trim_start_matches("did:test:").parse().unwrap();
+            let expected = EmbeddingService::cosine_similarity(&query, &data[i]);
+            assert!((m.similarity - expected).abs() < 1e-5);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_widens_search_until_enough_matches() {
+        let data = vectors(300, 8, 5);
+        let index = HnswIndex::new(&small_config(), 8);
+        let mut pts = points(&data);
+        // Only every 50th card is priced per token
+        for (i, point) in pts.iter_mut().enumerate() {
+            if i % 50 == 0 {
+                point.payload.pricing_model = Some(PricingModel::PerToken);
+            }
+        }
+        index.upsert(pts).await.unwrap();
+        let filter = VectorFilter {
+            pricing_models: vec![PricingModel::PerToken],
+            ..Default::default()
+        };
+
+        let matches = index.search(&data[1], &filter, 6).await.unwrap();
+
+        assert_eq!(matches.len(), 6);
+        for m in matches {
+            let i: usize = m.did.trim_start_matches("did:test:").parse().unwrap();
+            assert_eq!(i % 50, 0);
+        }
+    }
+
+    // ========== TDD Tests: incremental updates ==========
+
+    #[tokio::test]
+    async fn test_remove_drops_node_and_keeps_graph_searchable() {
+        let data = vectors(400, 16, 13);
+        let index = HnswIndex::new(&small_config(), 16);
+        index.upsert(points(&data)).await.unwrap();
+
+        for i in (0..400).step_by(2) {
+            assert!(index.remove(&format!("did:test:{}", i)).await.unwrap());
+        }
+        assert!(!index.remove("did:test:0").await.unwrap());
+        assert_eq!(index.len().await.unwrap(), 200);
+
+        for i in (1..400).step_by(40) {
+            let matches = index
+                .search(&data[i], &VectorFilter::default(), 1)
+                .await
+                .unwrap();
+            assert_eq!(matches[0].did, format!("did:test:{}", i));
+        }
+    }
+
+    /// Assert every inbound list mirrors the outbound links exactly.
+    fn assert_inbound_consistent(graph: &Graph) {
+        let mut expected: HashSet<(u32, usize, u32)> = HashSet::new();
+        for (source, node) in graph.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            for (layer, links) in node.links.iter().enumerate() {
+                expected.extend(links.iter().map(|&t| (t, layer, source as u32)));
+            }
+        }
+        let mut actual: HashSet<(u32, usize, u32)> = HashSet::new();
+        for (target, node) in graph.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            assert_eq!(node.inbound.len(), node.links.len());
+            for (layer, sources) in node.inbound.iter().enumerate() {
+                for &source in sources {
+                    assert!(actual.insert((target as u32, layer, source)));
+                }
+            }
+        }
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_inbound_links_track_inserts_and_removals() {
+        let data = vectors(300, 8, 5);
+        let mut graph = Graph::new(8, 6, 32);
+        for (i, v) in data.iter().enumerate() {
+            graph.insert(
+                format!("did:test:{}", i),
+                v.clone(),
+                VectorPayload::default(),
+            );
+        }
+        assert_inbound_consistent(&graph);
+
+        for i in (0..300).step_by(3) {
+            assert!(graph.remove(&format!("did:test:{}", i)));
+        }
+        assert_inbound_consistent(&graph);
+
+        // Reused slots and the rebuilt snapshot state agree too
+        for i in (0..300).step_by(6) {
+            graph.insert(
+                format!("did:new:{}", i),
+                data[i].clone(),
+                VectorPayload::default(),
+            );
+        }
+        assert_inbound_consistent(&graph);
+        let mut restored: Graph =
+            bincode::deserialize(&bincode::serialize(&graph).unwrap()).unwrap();
+        restored.rebuild_inbound();
+        assert_inbound_consistent(&restored);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_replaces_vector_for_same_did() {
+        let index = HnswIndex::new(&small_config(), 2);
+        index
+            .upsert(vec![
+                VectorPoint {
+                    did: "did:a".to_string(),
+                    embedding: vec![1.0, 0.0],
+                    payload: VectorPayload::default(),
+                },
+                VectorPoint {
+                    did: "did:b".to_string(),
+                    embedding: vec![0.0, 1.0],
+                    payload: VectorPayload::default(),
+                },
+            ])
+            .await
+            .unwrap();
+
+        index
+            .upsert(vec![VectorPoint {
+                did: "did:a".to_string(),
+                embedding: vec![0.0, -1.0],
+                payload: VectorPayload::default(),
+            }])
+            .await
+            .unwrap();
+
+        assert_eq!(index.len().await.unwrap(), 2);
+        let matches = index
+            .search(&vec![0.0, -1.0], &VectorFilter::default(), 1)
+            .await
+            .unwrap();
+        assert_eq!(matches[0].did, "did:a");
+    }
+
+    #[tokio::test]
+    async fn test_upsert_rejects_wrong_dimension() {
+        let index = HnswIndex::new(&small_config(), 4);
+
+        let result = index.upsert(points(&[vec![1.0, 0.0]])).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retain_removes_unlisted_dids() {
+        let index = HnswIndex::new(&small_config(), 8);
+        index.upsert(points(&vectors(20, 8, 1))).await.unwrap();
+        let keep: HashSet<String> = (0..5).map(|i| format!("did:test:{}", i)).collect();
+
+        let removed = index.retain(&keep).await.unwrap();
+
+        assert_eq!(removed, 15);
+        assert_eq!(index.len().await.unwrap(), 5);
+    }
+
+    // ========== TDD Tests: snapshots ==========
+
+    #[tokio::test]
+    async fn test_snapshot_roundtrip_restores_graph() {
+        let dir = TempDir::new().unwrap();
+        let config = HnswConfig {
+            snapshot_path: Some(dir.path().join("hnsw.snapshot").display().to_string()),
+            ..small_config()
+        };
+        let data = vectors(200, 8, 21);
+
+        let index = HnswIndex::open(&config, 8).unwrap();
+        index.upsert(points(&data)).await.unwrap();
+        index.flush().await.unwrap();
+        let before = index
+            .search(&data[17], &VectorFilter::default(), 5)
+            .await
+            .unwrap();
+
+        let restored = HnswIndex::open(&config, 8).unwrap();
+
+        assert_eq!(restored.len().await.unwrap(), 200);
+        let after = restored
+            .search(&data[17], &VectorFilter::default(), 5)
+            .await
+            .unwrap();
+        assert_eq!(before, after);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_with_other_dimension_is_ignored() {
+        let dir = TempDir::new().unwrap();
+        let config = HnswConfig {
+            snapshot_path: Some(dir.path().join("hnsw.snapshot").display().to_string()),
+            ..small_config()
+        };
+        let index = HnswIndex::open(&config, 8).unwrap();
+        index.upsert(points(&vectors(10, 8, 2))).await.unwrap();
+        index.flush().await.unwrap();
+
+        let reopened = HnswIndex::open(&config, 16).unwrap();
+
+        assert_eq!(reopened.len().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_without_changes_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hnsw.snapshot");
+        let config = HnswConfig {
+            snapshot_path: Some(path.display().to_string()),
+            ..small_config()
+        };
+
+        let index = HnswIndex::open(&config, 8).unwrap();
+        index.flush().await.unwrap();
+
+        assert!(!path.exists());
+    }
+}
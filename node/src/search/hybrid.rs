@@ -1,14 +1,33 @@
 //! Hybrid search combining keyword (BM25) and vector similarity.
 //!
 //! Uses Reciprocal Rank Fusion (RRF) to combine results from:
-//! - BM25 keyword matching (exact term matches)
+//! - BM25 keyword matching over a tokenised [`Bm25Index`]
 //! - Vector cosine similarity (semantic meaning)
+//!
+//! Nearest-neighbour lookups go through a pluggable [`VectorIndex`]. Card
+//! embeddings can be persisted in an [`EmbeddingStore`] so that a restart
+//! only recomputes vectors whose card text or model has changed.
+//!
+//! With a [`TrustRanker`] attached, relevance is blended with live trust
+//! signals according to [`HybridSearchConfig::ranking`].
 
-use crate::discovery::CapabilityCard;
+use crate::discovery::{CapabilityCard, DiscoveryPage, DiscoveryQuery};
 use crate::error::{Error, Result};
-use std::collections::HashMap;
+use crate::persistence::EmbeddingStore;
+use crate::trust::TrustInfo;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, warn};
 
+use super::bm25::{Bm25Config, Bm25Index};
 use super::embedding::{Embedding, EmbeddingService};
+use super::ranking::{RankingWeights, TrustRanker};
+use super::vector_index::{
+    InMemoryVectorIndex, VectorFilter, VectorIndex, VectorPayload, VectorPoint,
+};
+
+/// Number of cards embedded per model invocation when bulk indexing.
+const INDEX_BATCH_SIZE: usize = 256;
 
 /// Configuration for hybrid search.
 #[derive(Debug, Clone)]
@@ -27,6 +46,16 @@ pub struct HybridSearchConfig {
 
     /// Minimum score threshold (0.0 - 1.0)
     pub min_score: f32,
+
+    /// Number of nearest neighbours fetched from the vector index per query
+    pub vector_candidates: usize,
+
+    /// BM25 parameters and field boosts for keyword scoring
+    pub bm25: Bm25Config,
+
+    /// Weights for blending relevance with trust signals (used when a
+    /// trust ranker is attached)
+    pub ranking: RankingWeights,
 }
 
 impl Default for HybridSearchConfig {
@@ -37,6 +66,9 @@ impl Default for HybridSearchConfig {
             rrf_k: 60.0,
             max_results: 20,
             min_score: 0.1,
+            vector_candidates: 100,
+            bm25: Bm25Config::default(),
+            ranking: RankingWeights::default(),
         }
     }
 }
@@ -47,17 +79,24 @@ pub struct SearchResult {
     /// Agent DID
     pub did: String,
 
-    /// Combined hybrid score (0.0 - 1.0)
+    /// Ranking score (0.0 - 1.0); equals `relevance_score` unless a trust
+    /// ranker is attached
     pub score: f32,
 
+    /// Combined vector and keyword relevance (0.0 - 1.0)
+    pub relevance_score: f32,
+
     /// Vector similarity score
     pub vector_score: f32,
 
-    /// Keyword match score
+    /// BM25 keyword score relative to the best keyword match (0.0 - 1.0)
     pub keyword_score: f32,
 
     /// The capability card
     pub card: CapabilityCard,
+
+    /// Live trust data used for ranking, if a trust ranker is attached
+    pub trust: Option<TrustInfo>,
 }
 
 /// Hybrid search combining BM25 keyword matching and vector similarity.
@@ -65,8 +104,20 @@ pub struct HybridSearch {
     /// Embedding service for vector generation
     embedding_service: EmbeddingService,
 
-    /// In-memory index of agent embeddings (DID -> embedding)
-    index: HashMap<String, (CapabilityCard, Embedding)>,
+    /// Indexed cards (DID -> card)
+    cards: HashMap<String, CapabilityCard>,
+
+    /// Vector index holding card embeddings
+    vector_index: Arc<dyn VectorIndex>,
+
+    /// Inverted index for BM25 keyword scoring
+    keyword_index: Bm25Index,
+
+    /// Persistent embeddings keyed by card text hash and model
+    embedding_store: Option<EmbeddingStore>,
+
+    /// Optional re-ranker blending relevance with live trust data
+    trust_ranker: Option<TrustRanker>,
 
     /// Configuration
     config: HybridSearchConfig,
@@ -82,64 +133,357 @@ impl HybridSearch {
     pub fn with_config(embedding_service: EmbeddingService, config: HybridSearchConfig) -> Self {
         Self {
             embedding_service,
-            index: HashMap::new(),
+            cards: HashMap::new(),
+            vector_index: Arc::new(InMemoryVectorIndex::new()),
+            keyword_index: Bm25Index::with_config(config.bm25.clone()),
+            embedding_store: None,
+            trust_ranker: None,
             config,
         }
     }
 
+    /// Use `index` for nearest-neighbour lookups instead of the in-memory scan.
+    pub fn with_vector_index(mut self, index: Arc<dyn VectorIndex>) -> Self {
+        self.vector_index = index;
+        self
+    }
+
+    /// Persist card embeddings in `store` and reuse them across restarts.
+    pub fn with_embedding_store(mut self, store: EmbeddingStore) -> Self {
+        self.embedding_store = Some(store);
+        self
+    }
+
+    /// Re-rank results with live trust data from `ranker`, weighted by
+    /// [`HybridSearchConfig::ranking`].
+    pub fn with_trust_ranker(mut self, ranker: TrustRanker) -> Self {
+        self.trust_ranker = Some(ranker);
+        self
+    }
+
     /// Index a capability card for search.
     ///
-    /// Generates and stores embedding for the card.
+    /// Reuses a persisted embedding when the card text is unchanged,
+    /// otherwise generates and stores a new one.
     pub async fn index_card(&mut self, card: &CapabilityCard) -> Result<()> {
-        let did = card
-            .agoramesh
+        let did = Self::card_did(card)?;
+        let text = Self::card_text(card);
+        let key = EmbeddingService::storage_key(self.embedding_service.model(), &text);
+
+        let embedding = match self.load_embedding(&key) {
+            Some(embedding) => embedding,
+            None => {
+                let embedding = self.embedding_service.embed(&text).await?;
+                self.save_embedding(&key, &embedding);
+                embedding
+            }
+        };
+
+        self.vector_index
+            .upsert(vec![Self::vector_point(&did, card, embedding)])
+            .await?;
+        self.insert_card(&did, card);
+
+        Ok(())
+    }
+
+    /// Index many capability cards, embedding only those without a
+    /// persisted vector.
+    ///
+    /// Cards without a DID are skipped. Returns the number of cards
+    /// that had to be embedded.
+    pub async fn index_cards(&mut self, cards: &[CapabilityCard]) -> Result<usize> {
+        let mut fresh: Vec<(String, Embedding, &CapabilityCard)> = vec![];
+        let mut stale: Vec<(String, String, String, &CapabilityCard)> = vec![];
+
+        for card in cards {
+            let Ok(did) = Self::card_did(card) else {
+                warn!("Skipping card without DID: {}", card.name);
+                continue;
+            };
+            let text = Self::card_text(card);
+            let key = EmbeddingService::storage_key(self.embedding_service.model(), &text);
+
+            match self.load_embedding(&key) {
+                Some(embedding) => fresh.push((did, embedding, card)),
+                None => stale.push((did, key, text, card)),
+            }
+        }
+
+        for chunk in fresh.chunks(INDEX_BATCH_SIZE) {
+            let points = chunk
+                .iter()
+                .map(|(did, embedding, card)| Self::vector_point(did, card, embedding.clone()))
+                .collect();
+            self.vector_index.upsert(points).await?;
+            for (did, _, card) in chunk {
+                self.insert_card(did, card);
+            }
+        }
+
+        debug!(
+            "Indexing {} cards, {} need embedding",
+            cards.len(),
+            stale.len()
+        );
+
+        for chunk in stale.chunks(INDEX_BATCH_SIZE) {
+            let texts: Vec<String> = chunk.iter().map(|(_, _, text, _)| text.clone()).collect();
+            let embeddings = self.embedding_service.embed_batch(&texts).await?;
+
+            let mut points = Vec::with_capacity(chunk.len());
+            for ((did, key, _, card), embedding) in chunk.iter().zip(embeddings) {
+                self.save_embedding(key, &embedding);
+                points.push(Self::vector_point(did, card, embedding));
+            }
+            self.vector_index.upsert(points).await?;
+            for (did, _, _, card) in chunk {
+                self.insert_card(did, card);
+            }
+        }
+
+        Ok(stale.len())
+    }
+
+    /// Delete persisted embeddings that no indexed card refers to.
+    ///
+    /// Call after the index has been fully rebuilt, e.g. on startup, to
+    /// drop vectors for edited cards and previous models. Returns the
+    /// number of embeddings removed.
+    pub fn prune_embeddings(&self) -> Result<usize> {
+        let Some(store) = &self.embedding_store else {
+            return Ok(0);
+        };
+
+        let live: HashSet<String> = self
+            .cards
+            .values()
+            .map(|card| {
+                EmbeddingService::storage_key(
+                    self.embedding_service.model(),
+                    &Self::card_text(card),
+                )
+            })
+            .collect();
+
+        let mut removed = 0;
+        for key in store.keys()? {
+            if !live.contains(&key) {
+                store.delete(&key)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Drop vectors for DIDs that are no longer indexed, e.g. points
+    /// restored from an index snapshot whose cards have since gone away.
+    /// Returns the number of vectors removed.
+    pub async fn prune_vector_index(&self) -> Result<usize> {
+        let live: HashSet<String> = self.cards.keys().cloned().collect();
+        self.vector_index.retain(&live).await
+    }
+
+    /// Record an indexed card and its keyword postings.
+    fn insert_card(&mut self, did: &str, card: &CapabilityCard) {
+        self.keyword_index.index_card(did, card);
+        self.cards.insert(did.to_string(), card.clone());
+    }
+
+    /// Build the vector index entry for a card.
+    fn vector_point(did: &str, card: &CapabilityCard, embedding: Embedding) -> VectorPoint {
+        VectorPoint {
+            did: did.to_string(),
+            embedding,
+            payload: VectorPayload::from_card(card),
+        }
+    }
+
+    /// Get the DID a card is indexed under.
+    fn card_did(card: &CapabilityCard) -> Result<String> {
+        card.agoramesh
             .as_ref()
             .map(|ext| ext.did.clone())
-            .ok_or_else(|| Error::Search("Card missing DID".to_string()))?;
+            .ok_or_else(|| Error::Search("Card missing DID".to_string()))
+    }
 
-        // Create text for embedding
+    /// Create the text a card is embedded from.
+    fn card_text(card: &CapabilityCard) -> String {
         let skills: Vec<String> = card.skills.iter().map(|c| c.name.clone()).collect();
+        EmbeddingService::card_to_text(&card.name, &card.description, &skills)
+    }
 
-        let text = EmbeddingService::card_to_text(&card.name, &card.description, &skills);
-
-        // Generate embedding
-        let embedding = self.embedding_service.embed(&text).await?;
-
-        // Store in index
-        self.index.insert(did, (card.clone(), embedding));
+    /// Load a persisted embedding, ignoring unreadable or mis-sized vectors.
+    fn load_embedding(&self, key: &str) -> Option<Embedding> {
+        let store = self.embedding_store.as_ref()?;
+        match store.get(key) {
+            Ok(Some(embedding)) if embedding.len() == self.embedding_service.dimension() => {
+                Some(embedding)
+            }
+            Ok(Some(embedding)) => {
+                warn!(
+                    "Ignoring stored embedding {} with dimension {}",
+                    key,
+                    embedding.len()
+                );
+                None
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Failed to load embedding {}: {}", key, e);
+                None
+            }
+        }
+    }
 
-        Ok(())
+    /// Persist an embedding; failures only cost a recomputation later.
+    fn save_embedding(&self, key: &str, embedding: &Embedding) {
+        if let Some(store) = &self.embedding_store {
+            if let Err(e) = store.put(key, embedding) {
+                warn!("Failed to persist embedding {}: {}", key, e);
+            }
+        }
     }
 
     /// Remove a card from the index.
-    pub fn remove_card(&mut self, did: &str) -> bool {
-        self.index.remove(did).is_some()
+    pub async fn remove_card(&mut self, did: &str) -> bool {
+        if self.cards.remove(did).is_none() {
+            return false;
+        }
+        self.keyword_index.remove(did);
+        if let Err(e) = self.vector_index.remove(did).await {
+            warn!(
+                "Failed to remove {} from {} index: {}",
+                did,
+                self.vector_index.name(),
+                e
+            );
+        }
+        true
     }
 
     /// Search for agents matching the query.
     ///
     /// Combines vector similarity and keyword matching using RRF.
     pub async fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
-        if self.index.is_empty() {
+        self.search_filtered(query, &VectorFilter::default()).await
+    }
+
+    /// Search for agents matching the query and payload filter.
+    ///
+    /// The filter is applied by the vector index, so external backends
+    /// only return eligible candidates.
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        filter: &VectorFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let candidates = self.config.vector_candidates.max(self.config.max_results);
+        let mut results = self.rank(query, filter, candidates).await?;
+        results.truncate(self.config.max_results);
+        Ok(results)
+    }
+
+    /// Run a structured discovery query.
+    ///
+    /// Without free text, every indexed card passing the filters is
+    /// returned with zero scores, so sorting and paging still apply.
+    /// Paging is not capped by `max_results`.
+    pub async fn query(&self, query: &DiscoveryQuery) -> Result<DiscoveryPage<SearchResult>> {
+        let results = self.ranked(query).await?;
+        query.paginate(results)
+    }
+
+    /// Run a structured discovery query without paging it.
+    ///
+    /// Returns every filtered, sorted result that is ranked at least up to
+    /// the end of the requested page; later results may be missing.
+    pub async fn ranked(&self, query: &DiscoveryQuery) -> Result<Vec<SearchResult>> {
+        query.validate()?;
+
+        let text = query.query_text();
+        let mut results: Vec<SearchResult> = if text.is_empty() {
+            let mut results: Vec<SearchResult> = self
+                .cards
+                .iter()
+                .filter(|(_, card)| query.matches(card))
+                .map(|(did, card)| SearchResult {
+                    did: did.clone(),
+                    score: 0.0,
+                    relevance_score: 0.0,
+                    vector_score: 0.0,
+                    keyword_score: 0.0,
+                    card: card.clone(),
+                    trust: None,
+                })
+                .collect();
+            results.sort_by(|a, b| a.did.cmp(&b.did));
+            results
+        } else {
+            let candidates = self.config.vector_candidates.max(query.page_end()?);
+            self.rank(text, &query.vector_filter(), candidates)
+                .await?
+                .into_iter()
+                .filter(|r| query.matches(&r.card))
+                .collect()
+        };
+
+        query.sort(&mut results, |r| &r.card);
+        Ok(results)
+    }
+
+    /// Score every indexed card against `query`, best first.
+    ///
+    /// `candidates` bounds how many nearest neighbours are fetched from the
+    /// vector index; cards outside that set score 0 on vectors. Cards are
+    /// kept by relevance and then re-ranked with trust data, if a trust
+    /// ranker is attached.
+    async fn rank(
+        &self,
+        query: &str,
+        filter: &VectorFilter,
+        candidates: usize,
+    ) -> Result<Vec<SearchResult>> {
+        if self.cards.is_empty() {
             return Ok(vec![]);
         }
 
         // Generate query embedding
         let query_embedding = self.embedding_service.embed(query).await?;
-        let query_lower = query.to_lowercase();
-        let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
+
+        // BM25 scores, normalized against the best keyword match
+        let keyword_matches = self.keyword_index.search(query);
+        let best_keyword = keyword_matches.first().map(|m| m.score).unwrap_or(0.0);
+        let keyword_scores: HashMap<String, f32> = keyword_matches
+            .into_iter()
+            .map(|m| (m.did, m.score / best_keyword))
+            .collect();
+
+        // Nearest neighbours
+        let similarities: HashMap<String, f32> = self
+            .vector_index
+            .search(&query_embedding, filter, candidates)
+            .await?
+            .into_iter()
+            .map(|m| (m.did, m.similarity))
+            .collect();
 
         // Score all indexed cards
         let mut results: Vec<SearchResult> = vec![];
 
-        for (did, (card, embedding)) in &self.index {
-            // Vector similarity score
-            let vector_score = EmbeddingService::cosine_similarity(&query_embedding, embedding);
-            // Normalize to 0-1 range (cosine can be -1 to 1)
-            let vector_score = (vector_score + 1.0) / 2.0;
+        for (did, card) in &self.cards {
+            if !filter.matches_card(card) {
+                continue;
+            }
+
+            // Vector similarity score, normalized to 0-1 range (cosine can be -1 to 1)
+            let vector_score = similarities
+                .get(did)
+                .map(|similarity| (similarity + 1.0) / 2.0)
+                .unwrap_or(0.0);
 
-            // Keyword match score (simple BM25-like)
-            let keyword_score = self.compute_keyword_score(card, &query_terms);
+            let keyword_score = keyword_scores.get(did).copied().unwrap_or(0.0);
 
             // Skip if both scores are too low
             if vector_score < 0.3 && keyword_score < 0.1 {
@@ -154,9 +498,11 @@ impl HybridSearch {
                 results.push(SearchResult {
                     did: did.clone(),
                     score: combined_score,
+                    relevance_score: combined_score,
                     vector_score,
                     keyword_score,
                     card: card.clone(),
+                    trust: None,
                 });
             }
         }
@@ -168,54 +514,51 @@ impl HybridSearch {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        // Limit results
-        results.truncate(self.config.max_results);
-
-        Ok(results)
-    }
-
-    /// Compute keyword match score (simplified BM25-like).
-    fn compute_keyword_score(&self, card: &CapabilityCard, query_terms: &[&str]) -> f32 {
-        if query_terms.is_empty() {
-            return 0.0;
-        }
-
-        let card_text = format!(
-            "{} {} {}",
-            card.name,
-            card.description,
-            card.skills
-                .iter()
-                .map(|c| format!("{} {}", c.name, c.description.as_deref().unwrap_or("")))
-                .collect::<Vec<_>>()
-                .join(" ")
-        )
-        .to_lowercase();
-
-        let mut matches = 0;
-        for term in query_terms {
-            if card_text.contains(term) {
-                matches += 1;
-            }
+        if let Some(ranker) = &self.trust_ranker {
+            let scored: Vec<(f32, SearchResult)> = results
+                .into_iter()
+                .map(|r| (r.relevance_score, r))
+                .collect();
+            results = ranker
+                .rerank(&self.config.ranking, scored, |r| &r.card)
+                .await
+                .into_iter()
+                .map(|ranked| SearchResult {
+                    score: ranked.score,
+                    trust: ranked.trust,
+                    ..ranked.item
+                })
+                .collect();
         }
 
-        // Return ratio of matching terms
-        matches as f32 / query_terms.len() as f32
+        Ok(results)
     }
 
     /// Get the number of indexed cards.
     pub fn index_size(&self) -> usize {
-        self.index.len()
+        self.cards.len()
     }
 
     /// Check if a DID is indexed.
     pub fn is_indexed(&self, did: &str) -> bool {
-        self.index.contains_key(did)
+        self.cards.contains_key(did)
     }
 
     /// Clear the entire index.
-    pub fn clear(&mut self) {
-        self.index.clear();
+    pub async fn clear(&mut self) -> Result<()> {
+        self.cards.clear();
+        self.keyword_index.clear();
+        self.vector_index.clear().await
+    }
+
+    /// Get the vector index backend.
+    pub fn vector_index(&self) -> &Arc<dyn VectorIndex> {
+        &self.vector_index
+    }
+
+    /// Get the BM25 keyword index.
+    pub fn keyword_index(&self) -> &Bm25Index {
+        &self.keyword_index
     }
 
     /// Get configuration.
@@ -252,6 +595,7 @@ mod tests {
                     id: c.to_lowercase().replace(' ', "-"),
                     name: c.to_string(),
                     description: Some(format!("{} skill", c)),
+                    tags: vec![],
                     input_schema: None,
                     output_schema: None,
                 })
@@ -267,6 +611,9 @@ mod tests {
                     model: PricingModel::PerRequest,
                 }),
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         }
     }
@@ -410,7 +757,7 @@ mod tests {
         );
         search.index_card(&card).await.expect("Should index");
 
-        let removed = search.remove_card("did:agoramesh:base:agent1");
+        let removed = search.remove_card("did:agoramesh:base:agent1").await;
 
         assert!(removed, "Should return true when card existed");
         assert_eq!(search.index_size(), 0);
@@ -424,7 +771,7 @@ mod tests {
             return;
         };
 
-        let removed = search.remove_card("did:agoramesh:base:nonexistent");
+        let removed = search.remove_card("did:agoramesh:base:nonexistent").await;
 
         assert!(!removed, "Should return false for nonexistent card");
     }
@@ -469,6 +816,68 @@ mod tests {
         assert!(results[0].keyword_score > 0.0, "Should have keyword match");
     }
 
+    #[tokio::test]
+    async fn test_keyword_score_is_relative_to_best_bm25_match() {
+        let config = HybridSearchConfig {
+            min_score: 0.0,
+            ..Default::default()
+        };
+        let Some(mut search) = try_get_search_with_config(config) else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+
+        let strong = sample_card(
+            "did:agoramesh:base:strong",
+            "Invoice Parser",
+            "Parses invoices",
+            vec!["Invoice Parsing"],
+        );
+        let weak = sample_card(
+            "did:agoramesh:base:weak",
+            "Document Helper",
+            "Handles receipts and the occasional invoice",
+            vec!["Documents"],
+        );
+        search.index_card(&strong).await.unwrap();
+        search.index_card(&weak).await.unwrap();
+
+        let results = search.search("invoices").await.unwrap();
+        let score = |did: &str| {
+            results
+                .iter()
+                .find(|r| r.did == did)
+                .map(|r| r.keyword_score)
+                .unwrap_or(0.0)
+        };
+
+        assert_eq!(score("did:agoramesh:base:strong"), 1.0);
+        let weak_score = score("did:agoramesh:base:weak");
+        assert!(weak_score > 0.0 && weak_score < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_remove_card_drops_keyword_postings() {
+        let Some(mut search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let card = sample_card(
+            "did:agoramesh:base:reviewer",
+            "Code Reviewer",
+            "Reviews code",
+            vec!["Code Review"],
+        );
+        search.index_card(&card).await.unwrap();
+        assert!(search
+            .keyword_index()
+            .contains("did:agoramesh:base:reviewer"));
+
+        search.remove_card("did:agoramesh:base:reviewer").await;
+
+        assert!(search.keyword_index().is_empty());
+    }
+
     #[tokio::test]
     async fn test_search_finds_semantically_similar_agent() {
         let Some(mut search) = try_get_search() else {
@@ -607,6 +1016,201 @@ mod tests {
         );
     }
 
+    // ========== TDD Tests: trust-aware ranking ==========
+
+    #[tokio::test]
+    async fn test_trust_ranker_promotes_trusted_agent() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let trust = Arc::new(crate::trust::TrustService::new(
+            "https://sepolia.base.org".to_string(),
+            None,
+        ));
+        trust.seed_trust_data("did:agoramesh:base:trusted", 10_000_000_000, 100, 0, 0);
+        let mut search = search.with_trust_ranker(TrustRanker::new(
+            trust,
+            Arc::new(crate::trust_cache::TrustCache::with_defaults()),
+        ));
+
+        // Identical cards apart from the DID: relevance ties, trust decides
+        for did in ["did:agoramesh:base:unknown", "did:agoramesh:base:trusted"] {
+            let card = sample_card(did, "Translator", "Translation service", vec!["Translate"]);
+            search.index_card(&card).await.expect("Should index");
+        }
+
+        let results = search.search("translation").await.expect("Search works");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].did, "did:agoramesh:base:trusted");
+        assert!(results[0].trust.as_ref().is_some_and(|t| t.score > 0.5));
+        assert_eq!(results[0].relevance_score, results[1].relevance_score);
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[tokio::test]
+    async fn test_search_without_trust_ranker_scores_by_relevance() {
+        let Some(mut search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let card = sample_card(
+            "did:agoramesh:base:plain",
+            "Translator",
+            "Translation service",
+            vec!["Translate"],
+        );
+        search.index_card(&card).await.expect("Should index");
+
+        let results = search.search("translation").await.expect("Search works");
+
+        assert_eq!(results[0].score, results[0].relevance_score);
+        assert!(results[0].trust.is_none());
+    }
+
+    // ========== TDD Tests: query() ==========
+
+    #[tokio::test]
+    async fn test_query_applies_filters_sort_and_paging() {
+        let config = HybridSearchConfig {
+            min_score: 0.0,
+            max_results: 1,
+            ..Default::default()
+        };
+        let Some(mut search) = try_get_search_with_config(config) else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+
+        for (i, price) in [300u64, 100, 200].into_iter().enumerate() {
+            let mut card = sample_card(
+                &format!("did:agoramesh:base:translator-{}", i),
+                "Translator",
+                "Translates documents",
+                vec!["Translation"],
+            );
+            if let Some(pricing) = card.agoramesh.as_mut().and_then(|e| e.pricing.as_mut()) {
+                pricing.base_price = price;
+            }
+            search.index_card(&card).await.unwrap();
+        }
+        let weather = sample_card(
+            "did:agoramesh:base:weather",
+            "Weather",
+            "Forecasts",
+            vec!["Forecast"],
+        );
+        search.index_card(&weather).await.unwrap();
+
+        let query = DiscoveryQuery {
+            text: Some("translate documents".to_string()),
+            skill_id: Some("translation".to_string()),
+            sort: crate::discovery::QuerySort::Price,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = search.query(&query).await.unwrap();
+
+        // Paging is not capped by max_results
+        assert_eq!(page.total, 3);
+        let dids: Vec<&str> = page.items.iter().map(|r| r.did.as_str()).collect();
+        assert_eq!(
+            dids,
+            vec![
+                "did:agoramesh:base:translator-1",
+                "did:agoramesh:base:translator-2"
+            ]
+        );
+        assert_eq!(page.next_cursor.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_query_without_text_lists_matching_cards() {
+        let Some(mut search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        search
+            .index_card(&sample_card(
+                "did:agoramesh:base:a",
+                "Translator",
+                "Translates",
+                vec!["Translation"],
+            ))
+            .await
+            .unwrap();
+        search
+            .index_card(&sample_card(
+                "did:agoramesh:base:b",
+                "Weather",
+                "Forecasts",
+                vec!["Forecast"],
+            ))
+            .await
+            .unwrap();
+
+        let page = search
+            .query(&DiscoveryQuery {
+                skill_id: Some("forecast".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].did, "did:agoramesh:base:b");
+        assert_eq!(page.items[0].score, 0.0);
+    }
+
+    // ========== TDD Tests: search_filtered() ==========
+
+    #[tokio::test]
+    async fn test_search_filtered_excludes_cards_failing_filter() {
+        let config = HybridSearchConfig {
+            min_score: 0.0,
+            ..Default::default()
+        };
+        let Some(mut search) = try_get_search_with_config(config) else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+
+        let per_request = sample_card(
+            "did:agoramesh:base:per-request",
+            "Translator",
+            "Translates documents",
+            vec!["Translation"],
+        );
+        let mut per_token = sample_card(
+            "did:agoramesh:base:per-token",
+            "Translator Pro",
+            "Translates documents",
+            vec!["Translation"],
+        );
+        if let Some(pricing) = per_token
+            .agoramesh
+            .as_mut()
+            .and_then(|ext| ext.pricing.as_mut())
+        {
+            pricing.model = PricingModel::PerToken;
+        }
+        search.index_card(&per_request).await.expect("Should index");
+        search.index_card(&per_token).await.expect("Should index");
+
+        let filter = VectorFilter {
+            pricing_models: vec![PricingModel::PerToken],
+            ..Default::default()
+        };
+        let results = search
+            .search_filtered("translate documents", &filter)
+            .await
+            .expect("Search should work");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].did, "did:agoramesh:base:per-token");
+    }
+
     // ========== TDD Tests: clear() ==========
 
     #[tokio::test]
@@ -625,8 +1229,178 @@ mod tests {
         search.index_card(&card).await.expect("Should index");
         assert_eq!(search.index_size(), 1);
 
-        search.clear();
+        search.clear().await.expect("Should clear");
 
         assert_eq!(search.index_size(), 0);
     }
+
+    // ========== TDD Tests: embedding store ==========
+
+    fn memory_embedding_store() -> EmbeddingStore {
+        EmbeddingStore::new(std::sync::Arc::new(crate::persistence::MemoryStore::new()))
+    }
+
+    fn stored_key(search: &HybridSearch, card: &CapabilityCard) -> String {
+        EmbeddingService::storage_key(
+            search.embedding_service.model(),
+            &HybridSearch::card_text(card),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_index_card_persists_embedding() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let store = memory_embedding_store();
+        let vectors = Arc::new(InMemoryVectorIndex::new());
+        let mut search = search
+            .with_embedding_store(store.clone())
+            .with_vector_index(vectors.clone());
+        let card = sample_card(
+            "did:agoramesh:base:agent1",
+            "Agent",
+            "Description",
+            vec!["Skill"],
+        );
+
+        search.index_card(&card).await.expect("Should index");
+
+        let stored = store.get(&stored_key(&search, &card)).unwrap();
+        assert_eq!(
+            stored.as_ref(),
+            vectors.embedding("did:agoramesh:base:agent1").as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_card_reuses_stored_embedding() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let store = memory_embedding_store();
+        let vectors = Arc::new(InMemoryVectorIndex::new());
+        let mut search = search
+            .with_embedding_store(store.clone())
+            .with_vector_index(vectors.clone());
+        let card = sample_card(
+            "did:agoramesh:base:agent1",
+            "Agent",
+            "Description",
+            vec!["Skill"],
+        );
+
+        // A sentinel vector proves the model was not run again
+        let sentinel = vec![0.5; search.embedding_service.dimension()];
+        store.put(&stored_key(&search, &card), &sentinel).unwrap();
+
+        search.index_card(&card).await.expect("Should index");
+
+        assert_eq!(
+            vectors.embedding("did:agoramesh:base:agent1").unwrap(),
+            sentinel
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_card_ignores_stored_embedding_with_wrong_dimension() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let store = memory_embedding_store();
+        let vectors = Arc::new(InMemoryVectorIndex::new());
+        let mut search = search
+            .with_embedding_store(store.clone())
+            .with_vector_index(vectors.clone());
+        let card = sample_card(
+            "did:agoramesh:base:agent1",
+            "Agent",
+            "Description",
+            vec!["Skill"],
+        );
+        store.put(&stored_key(&search, &card), &[0.5, 0.5]).unwrap();
+
+        search.index_card(&card).await.expect("Should index");
+
+        let dimension = search.embedding_service.dimension();
+        assert_eq!(
+            vectors
+                .embedding("did:agoramesh:base:agent1")
+                .unwrap()
+                .len(),
+            dimension
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_cards_embeds_only_stale_cards() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let store = memory_embedding_store();
+        let vectors = Arc::new(InMemoryVectorIndex::new());
+        let mut search = search
+            .with_embedding_store(store.clone())
+            .with_vector_index(vectors.clone());
+        let cached = sample_card(
+            "did:agoramesh:base:cached",
+            "Cached",
+            "Already embedded",
+            vec!["Skill"],
+        );
+        let fresh = sample_card(
+            "did:agoramesh:base:fresh",
+            "Fresh",
+            "Not embedded yet",
+            vec!["Skill"],
+        );
+        let mut no_did = fresh.clone();
+        no_did.agoramesh = None;
+
+        let sentinel = vec![0.5; search.embedding_service.dimension()];
+        store.put(&stored_key(&search, &cached), &sentinel).unwrap();
+
+        let embedded = search
+            .index_cards(&[cached, fresh.clone(), no_did])
+            .await
+            .expect("Should index");
+
+        assert_eq!(embedded, 1);
+        assert_eq!(search.index_size(), 2);
+        assert_eq!(
+            vectors.embedding("did:agoramesh:base:cached").unwrap(),
+            sentinel
+        );
+        assert!(store.contains(&stored_key(&search, &fresh)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prune_embeddings_drops_unreferenced_vectors() {
+        let Some(search) = try_get_search() else {
+            eprintln!("Skipping: embedding model not available");
+            return;
+        };
+        let store = memory_embedding_store();
+        let vectors = Arc::new(InMemoryVectorIndex::new());
+        let mut search = search
+            .with_embedding_store(store.clone())
+            .with_vector_index(vectors.clone());
+        let card = sample_card(
+            "did:agoramesh:base:agent1",
+            "Agent",
+            "Description",
+            vec!["Skill"],
+        );
+        search.index_card(&card).await.expect("Should index");
+        store.put("old-model:deadbeef", &[1.0]).unwrap();
+
+        let removed = search.prune_embeddings().unwrap();
+
+        assert_eq!(removed, 1);
+        assert_eq!(store.keys().unwrap(), vec![stored_key(&search, &card)]);
+    }
 }
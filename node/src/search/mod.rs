@@ -2,8 +2,11 @@
 //!
 //! Provides vector-based semantic search using:
 //! - FastEmbed for embedding generation (ONNX-based, lightweight)
-//! - Qdrant for vector storage and similarity search
+//! - A pluggable vector index (in-memory scan, embedded HNSW or Qdrant) for
+//!   similarity search
 //! - Hybrid search combining BM25 keyword matching with vector similarity
+//! - Trust-aware re-ranking blending relevance with live trust, stake,
+//!   price and recency
 //!
 //! # Architecture
 //!
@@ -36,11 +39,26 @@
 //!                        └──────────────┘
 //! ```
 
+mod bm25;
 mod embedding;
+mod hnsw;
 mod hybrid;
+mod qdrant;
+mod ranking;
+mod vector_index;
 
+pub use bm25::{tokenize, Bm25Config, Bm25Index, KeywordMatch};
 pub use embedding::{Embedding, EmbeddingService, EmbeddingServiceConfig};
+pub use hnsw::{HnswConfig, HnswIndex};
 pub use hybrid::{HybridSearch, HybridSearchConfig, SearchResult};
+pub use qdrant::{QdrantBackend, QdrantConfig, QdrantVectorIndex};
+pub use ranking::{
+    Ranked, RankingWeights, TrustRanker, DEFAULT_RECENCY_HALF_LIFE_SECS, DEFAULT_REFERENCE_PRICE,
+};
+pub use vector_index::{
+    InMemoryVectorIndex, SearchConfig, VectorFilter, VectorIndex, VectorIndexConfig, VectorMatch,
+    VectorPayload, VectorPoint,
+};
 
 /// Default embedding model (all-MiniLM-L6-v2 - 384 dimensions, good balance of speed/quality)
 pub const DEFAULT_MODEL: &str = "all-MiniLM-L6-v2";
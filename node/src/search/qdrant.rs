@@ -0,0 +1,841 @@
+//! Qdrant-backed vector index.
+//!
+//! Cards are stored as points in a single cosine-distance collection. Point
+//! IDs are UUIDs derived from the agent DID, and the DID plus the filterable
+//! attributes of [`VectorPayload`] are stored as payload so that
+//! [`VectorFilter`] constraints are evaluated by Qdrant itself.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use alloy::primitives::keccak256;
+use async_trait::async_trait;
+use qdrant_client::qdrant::r#match::MatchValue;
+use qdrant_client::qdrant::value::Kind;
+use qdrant_client::qdrant::vectors_config::Config as VectorsConfig;
+use qdrant_client::qdrant::{
+    Condition, CountPointsBuilder, CreateCollectionBuilder, CreateFieldIndexCollectionBuilder,
+    DeletePointsBuilder, Distance, FieldType, Filter, ListValue, PointId, PointStruct,
+    PointsIdsList, QueryPointsBuilder, Range, RepeatedStrings, ScoredPoint, UpsertPointsBuilder,
+    Value, VectorParamsBuilder,
+};
+use qdrant_client::Qdrant;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use super::embedding::Embedding;
+use super::vector_index::{VectorFilter, VectorIndex, VectorMatch, VectorPayload, VectorPoint};
+use crate::error::{Error, Result};
+
+/// Payload field holding the agent DID.
+pub const DID_FIELD: &str = "did";
+
+/// Payload field holding the snake_case pricing model.
+pub const PRICING_MODEL_FIELD: &str = "pricing_model";
+
+/// Payload field holding the list of payment methods.
+pub const PAYMENT_METHODS_FIELD: &str = "payment_methods";
+
+/// Payload field holding the advertised trust score.
+pub const TRUST_SCORE_FIELD: &str = "trust_score";
+
+/// Qdrant connection settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QdrantConfig {
+    /// gRPC endpoint (e.g. `http://localhost:6334`).
+    pub url: String,
+
+    /// Collection holding card vectors.
+    #[serde(default = "default_collection")]
+    pub collection: String,
+
+    /// API key for Qdrant Cloud or secured deployments.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+fn default_collection() -> String {
+    "agoramesh_cards".to_string()
+}
+
+impl QdrantConfig {
+    /// Create a configuration for `url` with the default collection.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            collection: default_collection(),
+            api_key: None,
+        }
+    }
+}
+
+/// The subset of the Qdrant API used by [`QdrantVectorIndex`].
+///
+/// Implemented for [`qdrant_client::Qdrant`]; tests substitute an
+/// in-process fake.
+#[async_trait]
+pub trait QdrantBackend: Send + Sync {
+    /// Create the collection and its payload indexes unless it exists.
+    ///
+    /// An existing collection must hold `dimension`-sized vectors.
+    async fn ensure_collection(&self, collection: &str, dimension: u64) -> Result<()>;
+
+    /// Drop the collection if it exists.
+    async fn delete_collection(&self, collection: &str) -> Result<()>;
+
+    /// Insert or replace points.
+    async fn upsert(&self, collection: &str, points: Vec<PointStruct>) -> Result<()>;
+
+    /// Delete points by ID.
+    async fn delete(&self, collection: &str, ids: Vec<PointId>) -> Result<()>;
+
+    /// Return the `limit` nearest points to `vector` that pass `filter`.
+    async fn query(
+        &self,
+        collection: &str,
+        vector: Vec<f32>,
+        filter: Option<Filter>,
+        limit: u64,
+    ) -> Result<Vec<ScoredPoint>>;
+
+    /// Count points, optionally restricted to `filter`.
+    async fn count(&self, collection: &str, filter: Option<Filter>) -> Result<u64>;
+}
+
+fn qdrant_error(e: qdrant_client::QdrantError) -> Error {
+    Error::Search(format!("Qdrant request failed: {}", e))
+}
+
+/// Check that an existing collection holds `dimension`-sized vectors.
+fn check_vector_dimension(
+    collection: &str,
+    vectors: Option<&VectorsConfig>,
+    dimension: u64,
+) -> Result<()> {
+    match vectors {
+        Some(VectorsConfig::Params(params)) if params.size == dimension => Ok(()),
+        Some(VectorsConfig::Params(params)) => Err(Error::Search(format!(
+            "Qdrant collection {} holds {}-dimensional vectors, but embeddings have {}",
+            collection, params.size, dimension
+        ))),
+        _ => Err(Error::Search(format!(
+            "Qdrant collection {} does not hold a single unnamed vector",
+            collection
+        ))),
+    }
+}
+
+#[async_trait]
+impl QdrantBackend for Qdrant {
+    async fn ensure_collection(&self, collection: &str, dimension: u64) -> Result<()> {
+        if self
+            .collection_exists(collection)
+            .await
+            .map_err(qdrant_error)?
+        {
+            let info = self
+                .collection_info(collection)
+                .await
+                .map_err(qdrant_error)?;
+            let vectors = info
+                .result
+                .and_then(|info| info.config)
+                .and_then(|config| config.params)
+                .and_then(|params| params.vectors_config)
+                .and_then(|vectors| vectors.config);
+            return check_vector_dimension(collection, vectors.as_ref(), dimension);
+        }
+
+        self.create_collection(
+            CreateCollectionBuilder::new(collection)
+                .vectors_config(VectorParamsBuilder::new(dimension, Distance::Cosine)),
+        )
+        .await
+        .map_err(qdrant_error)?;
+
+        for (field, field_type) in [
+            (DID_FIELD, FieldType::Keyword),
+            (PRICING_MODEL_FIELD, FieldType::Keyword),
+            (PAYMENT_METHODS_FIELD, FieldType::Keyword),
+            (TRUST_SCORE_FIELD, FieldType::Float),
+        ] {
+            self.create_field_index(
+                CreateFieldIndexCollectionBuilder::new(collection, field, field_type).wait(true),
+            )
+            .await
+            .map_err(qdrant_error)?;
+        }
+
+        info!(
+            "Created Qdrant collection {} ({} dims)",
+            collection, dimension
+        );
+        Ok(())
+    }
+
+    async fn delete_collection(&self, collection: &str) -> Result<()> {
+        if self
+            .collection_exists(collection)
+            .await
+            .map_err(qdrant_error)?
+        {
+            Qdrant::delete_collection(self, collection)
+                .await
+                .map_err(qdrant_error)?;
+        }
+        Ok(())
+    }
+
+    async fn upsert(&self, collection: &str, points: Vec<PointStruct>) -> Result<()> {
+        self.upsert_points(UpsertPointsBuilder::new(collection, points).wait(true))
+            .await
+            .map_err(qdrant_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, collection: &str, ids: Vec<PointId>) -> Result<()> {
+        self.delete_points(
+            DeletePointsBuilder::new(collection)
+                .points(PointsIdsList { ids })
+                .wait(true),
+        )
+        .await
+        .map_err(qdrant_error)?;
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        collection: &str,
+        vector: Vec<f32>,
+        filter: Option<Filter>,
+        limit: u64,
+    ) -> Result<Vec<ScoredPoint>> {
+        let mut request = QueryPointsBuilder::new(collection)
+            .query(vector)
+            .limit(limit)
+            .with_payload(true);
+        if let Some(filter) = filter {
+            request = request.filter(filter);
+        }
+
+        let response = Qdrant::query(self, request).await.map_err(qdrant_error)?;
+        Ok(response.result)
+    }
+
+    async fn count(&self, collection: &str, filter: Option<Filter>) -> Result<u64> {
+        let mut request = CountPointsBuilder::new(collection).exact(true);
+        if let Some(filter) = filter {
+            request = request.filter(filter);
+        }
+
+        let response = Qdrant::count(self, request).await.map_err(qdrant_error)?;
+        Ok(response.result.map(|r| r.count).unwrap_or(0))
+    }
+}
+
+/// Vector index stored in a Qdrant collection.
+pub struct QdrantVectorIndex {
+    backend: Arc<dyn QdrantBackend>,
+    collection: String,
+    dimension: usize,
+}
+
+impl QdrantVectorIndex {
+    /// Connect to the configured Qdrant server and ensure the collection exists.
+    pub async fn connect(config: &QdrantConfig, dimension: usize) -> Result<Self> {
+        let client = Qdrant::from_url(&config.url)
+            .api_key(config.api_key.clone())
+            .build()
+            .map_err(qdrant_error)?;
+
+        Self::new(Arc::new(client), config.collection.clone(), dimension).await
+    }
+
+    /// Create an index on top of `backend`, ensuring the collection exists.
+    pub async fn new(
+        backend: Arc<dyn QdrantBackend>,
+        collection: impl Into<String>,
+        dimension: usize,
+    ) -> Result<Self> {
+        let collection = collection.into();
+        backend
+            .ensure_collection(&collection, dimension as u64)
+            .await?;
+
+        Ok(Self {
+            backend,
+            collection,
+            dimension,
+        })
+    }
+
+    /// Get the collection name.
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    /// Derive the stable point ID for a DID.
+    pub fn point_id(did: &str) -> PointId {
+        let hash = keccak256(did.as_bytes());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        PointId::from(Uuid::from_bytes(bytes).to_string())
+    }
+
+    /// Build the Qdrant payload for a point.
+    pub fn payload(did: &str, payload: &VectorPayload) -> HashMap<String, Value> {
+        let mut fields = HashMap::new();
+        fields.insert(DID_FIELD.to_string(), Value::from(did));
+        if let Some(model) = &payload.pricing_model {
+            fields.insert(
+                PRICING_MODEL_FIELD.to_string(),
+                Value::from(pricing_model_keyword(model)),
+            );
+        }
+        fields.insert(
+            PAYMENT_METHODS_FIELD.to_string(),
+            Value {
+                kind: Some(Kind::ListValue(ListValue {
+                    values: payload
+                        .payment_methods
+                        .iter()
+                        .map(|m| Value::from(m.as_str()))
+                        .collect(),
+                })),
+            },
+        );
+        // Missing trust is stored as 0.0 so range filters treat it like the
+        // in-memory index does
+        fields.insert(
+            TRUST_SCORE_FIELD.to_string(),
+            Value::from(payload.trust_score.unwrap_or(0.0)),
+        );
+        fields
+    }
+
+    /// Translate a [`VectorFilter`] into a Qdrant payload filter.
+    pub fn filter(filter: &VectorFilter) -> Option<Filter> {
+        if filter.is_empty() {
+            return None;
+        }
+
+        let mut must = vec![];
+        if !filter.pricing_models.is_empty() {
+            must.push(keywords_condition(
+                PRICING_MODEL_FIELD,
+                filter
+                    .pricing_models
+                    .iter()
+                    .map(pricing_model_keyword)
+                    .collect(),
+            ));
+        }
+        if !filter.payment_methods.is_empty() {
+            must.push(keywords_condition(
+                PAYMENT_METHODS_FIELD,
+                filter.payment_methods.clone(),
+            ));
+        }
+        if let Some(min_trust) = filter.min_trust {
+            must.push(Condition::range(
+                TRUST_SCORE_FIELD,
+                Range {
+                    gte: Some(min_trust),
+                    ..Default::default()
+                },
+            ));
+        }
+
+        Some(Filter::must(must))
+    }
+}
+
+/// Match any of `values` exactly; never treated as full-text.
+fn keywords_condition(field: &str, values: Vec<String>) -> Condition {
+    Condition::matches(
+        field,
+        MatchValue::Keywords(RepeatedStrings { strings: values }),
+    )
+}
+
+fn pricing_model_keyword(model: &crate::discovery::PricingModel) -> String {
+    serde_json::to_value(model)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl VectorIndex for QdrantVectorIndex {
+    fn name(&self) -> &str {
+        "qdrant"
+    }
+
+    async fn upsert(&self, points: Vec<VectorPoint>) -> Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+
+        let points = points
+            .into_iter()
+            .map(|point| {
+                if point.embedding.len() != self.dimension {
+                    return Err(Error::Search(format!(
+                        "Embedding for {} has dimension {}, collection expects {}",
+                        point.did,
+                        point.embedding.len(),
+                        self.dimension
+                    )));
+                }
+                Ok(PointStruct::new(
+                    Self::point_id(&point.did),
+                    point.embedding,
+                    Self::payload(&point.did, &point.payload),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.backend.upsert(&self.collection, points).await
+    }
+
+    async fn remove(&self, did: &str) -> Result<bool> {
+        let id = Self::point_id(did);
+        let existing = self
+            .backend
+            .count(
+                &self.collection,
+                Some(Filter::must([Condition::has_id([id.clone()])])),
+            )
+            .await?;
+        if existing == 0 {
+            return Ok(false);
+        }
+
+        self.backend.delete(&self.collection, vec![id]).await?;
+        Ok(true)
+    }
+
+    async fn search(
+        &self,
+        query: &Embedding,
+        filter: &VectorFilter,
+        limit: usize,
+    ) -> Result<Vec<VectorMatch>> {
+        let points = self
+            .backend
+            .query(
+                &self.collection,
+                query.clone(),
+                Self::filter(filter),
+                limit as u64,
+            )
+            .await?;
+
+        Ok(points
+            .into_iter()
+            .filter_map(|point| {
+                let did = match point.payload.get(DID_FIELD)?.kind.as_ref()? {
+                    Kind::StringValue(did) => did.clone(),
+                    _ => return None,
+                };
+                Some(VectorMatch {
+                    did,
+                    similarity: point.score,
+                })
+            })
+            .collect())
+    }
+
+    async fn len(&self) -> Result<usize> {
+        Ok(self.backend.count(&self.collection, None).await? as usize)
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.backend.delete_collection(&self.collection).await?;
+        self.backend
+            .ensure_collection(&self.collection, self.dimension as u64)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::PricingModel;
+    use crate::search::EmbeddingService;
+    use qdrant_client::qdrant::condition::ConditionOneOf;
+    use qdrant_client::qdrant::point_id::PointIdOptions;
+    use qdrant_client::qdrant::vectors::VectorsOptions;
+    use qdrant_client::qdrant::{vector, Vector};
+    use std::sync::Mutex;
+
+    /// In-process stand-in for a Qdrant server.
+    ///
+    /// Evaluates the keyword, range and has-id conditions produced by
+    /// [`QdrantVectorIndex`] so filter pushdown is exercised end to end.
+    #[derive(Default)]
+    struct FakeQdrant {
+        collections: Mutex<HashMap<String, FakePoints>>,
+        dimensions: Mutex<HashMap<String, u64>>,
+    }
+
+    /// Point ID -> (vector, payload).
+    type FakePoints = HashMap<String, (Vec<f32>, HashMap<String, Value>)>;
+
+    fn id_key(id: &PointId) -> String {
+        match &id.point_id_options {
+            Some(PointIdOptions::Uuid(uuid)) => uuid.clone(),
+            Some(PointIdOptions::Num(num)) => num.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn strings(value: &Value) -> Vec<String> {
+        match &value.kind {
+            Some(Kind::StringValue(s)) => vec![s.clone()],
+            Some(Kind::ListValue(list)) => list.values.iter().flat_map(strings).collect(),
+            _ => vec![],
+        }
+    }
+
+    fn condition_holds(id: &str, payload: &HashMap<String, Value>, condition: &Condition) -> bool {
+        match condition.condition_one_of.as_ref() {
+            Some(ConditionOneOf::HasId(has_id)) => has_id
+                .has_id
+                .iter()
+                .any(|candidate| id_key(candidate) == id),
+            Some(ConditionOneOf::Field(field)) => {
+                let Some(value) = payload.get(&field.key) else {
+                    return false;
+                };
+                if let Some(m) = field.r#match.as_ref() {
+                    let Some(MatchValue::Keywords(wanted)) = m.match_value.as_ref() else {
+                        panic!("unexpected match condition: {:?}", m);
+                    };
+                    if !strings(value).iter().any(|s| wanted.strings.contains(s)) {
+                        return false;
+                    }
+                }
+                if let Some(range) = field.range.as_ref() {
+                    let Some(Kind::DoubleValue(v)) = value.kind else {
+                        return false;
+                    };
+                    if range.gte.is_some_and(|gte| v < gte) {
+                        return false;
+                    }
+                }
+                true
+            }
+            other => panic!("unexpected condition: {:?}", other),
+        }
+    }
+
+    fn filter_holds(id: &str, payload: &HashMap<String, Value>, filter: &Option<Filter>) -> bool {
+        filter.as_ref().is_none_or(|f| {
+            f.must
+                .iter()
+                .all(|condition| condition_holds(id, payload, condition))
+        })
+    }
+
+    #[async_trait]
+    impl QdrantBackend for FakeQdrant {
+        async fn ensure_collection(&self, collection: &str, dimension: u64) -> Result<()> {
+            let existing = *self
+                .dimensions
+                .lock()
+                .unwrap()
+                .entry(collection.to_string())
+                .or_insert(dimension);
+            if existing != dimension {
+                return Err(Error::Search("dimension mismatch".to_string()));
+            }
+            self.collections
+                .lock()
+                .unwrap()
+                .entry(collection.to_string())
+                .or_default();
+            Ok(())
+        }
+
+        async fn delete_collection(&self, collection: &str) -> Result<()> {
+            self.collections.lock().unwrap().remove(collection);
+            self.dimensions.lock().unwrap().remove(collection);
+            Ok(())
+        }
+
+        async fn upsert(&self, collection: &str, points: Vec<PointStruct>) -> Result<()> {
+            let mut collections = self.collections.lock().unwrap();
+            let points_by_id = collections
+                .get_mut(collection)
+                .ok_or_else(|| Error::Search("no such collection".to_string()))?;
+            for point in points {
+                let Some(VectorsOptions::Vector(Vector {
+                    vector: Some(vector::Vector::Dense(dense)),
+                    ..
+                })) = point.vectors.and_then(|v| v.vectors_options)
+                else {
+                    panic!("expected a single dense vector");
+                };
+                points_by_id.insert(
+                    id_key(point.id.as_ref().unwrap()),
+                    (dense.data, point.payload),
+                );
+            }
+            Ok(())
+        }
+
+        async fn delete(&self, collection: &str, ids: Vec<PointId>) -> Result<()> {
+            let mut collections = self.collections.lock().unwrap();
+            if let Some(points) = collections.get_mut(collection) {
+                for id in ids {
+                    points.remove(&id_key(&id));
+                }
+            }
+            Ok(())
+        }
+
+        async fn query(
+            &self,
+            collection: &str,
+            vector: Vec<f32>,
+            filter: Option<Filter>,
+            limit: u64,
+        ) -> Result<Vec<ScoredPoint>> {
+            let collections = self.collections.lock().unwrap();
+            let mut hits: Vec<ScoredPoint> = collections
+                .get(collection)
+                .into_iter()
+                .flatten()
+                .filter(|(id, (_, payload))| filter_holds(id, payload, &filter))
+                .map(|(id, (stored, payload))| ScoredPoint {
+                    id: Some(PointId::from(id.as_str())),
+                    payload: payload.clone(),
+                    score: EmbeddingService::cosine_similarity(&vector, stored),
+                    ..Default::default()
+                })
+                .collect();
+            hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            hits.truncate(limit as usize);
+            Ok(hits)
+        }
+
+        async fn count(&self, collection: &str, filter: Option<Filter>) -> Result<u64> {
+            let collections = self.collections.lock().unwrap();
+            Ok(collections
+                .get(collection)
+                .into_iter()
+                .flatten()
+                .filter(|(id, (_, payload))| filter_holds(id, payload, &filter))
+                .count() as u64)
+        }
+    }
+
+    async fn fake_index() -> QdrantVectorIndex {
+        QdrantVectorIndex::new(Arc::new(FakeQdrant::default()), "cards", 2)
+            .await
+            .unwrap()
+    }
+
+    fn point(did: &str, embedding: Vec<f32>, payload: VectorPayload) -> VectorPoint {
+        VectorPoint {
+            did: did.to_string(),
+            embedding,
+            payload,
+        }
+    }
+
+    fn payload(model: PricingModel, methods: &[&str], trust: f64) -> VectorPayload {
+        VectorPayload {
+            pricing_model: Some(model),
+            payment_methods: methods.iter().map(|m| m.to_string()).collect(),
+            trust_score: Some(trust),
+        }
+    }
+
+    // ========== TDD Tests: filter translation ==========
+
+    #[test]
+    fn test_empty_filter_is_not_pushed_down() {
+        assert!(QdrantVectorIndex::filter(&VectorFilter::default()).is_none());
+    }
+
+    #[test]
+    fn test_filter_uses_keyword_matches_and_trust_range() {
+        let filter = QdrantVectorIndex::filter(&VectorFilter {
+            pricing_models: vec![PricingModel::PerToken],
+            payment_methods: vec!["x402".to_string()],
+            min_trust: Some(0.6),
+        })
+        .unwrap();
+
+        assert_eq!(filter.must.len(), 3);
+        let keys: Vec<&str> = filter
+            .must
+            .iter()
+            .filter_map(|c| match c.condition_one_of.as_ref() {
+                Some(ConditionOneOf::Field(field)) => Some(field.key.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                PRICING_MODEL_FIELD,
+                PAYMENT_METHODS_FIELD,
+                TRUST_SCORE_FIELD
+            ]
+        );
+        let Some(ConditionOneOf::Field(pricing)) = filter.must[0].condition_one_of.as_ref() else {
+            panic!("expected field condition");
+        };
+        assert_eq!(
+            pricing.r#match.as_ref().unwrap().match_value,
+            Some(MatchValue::Keywords(RepeatedStrings {
+                strings: vec!["per_token".to_string()]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_point_id_is_stable_uuid_per_did() {
+        let a = QdrantVectorIndex::point_id("did:agoramesh:base:a");
+
+        assert_eq!(a, QdrantVectorIndex::point_id("did:agoramesh:base:a"));
+        assert_ne!(a, QdrantVectorIndex::point_id("did:agoramesh:base:b"));
+        assert!(Uuid::parse_str(&id_key(&a)).is_ok());
+    }
+
+    // ========== TDD Tests: QdrantVectorIndex ==========
+
+    #[tokio::test]
+    async fn test_search_returns_dids_by_similarity() {
+        let index = fake_index().await;
+        index
+            .upsert(vec![
+                point("did:a", vec![1.0, 0.0], VectorPayload::default()),
+                point("did:b", vec![0.0, 1.0], VectorPayload::default()),
+            ])
+            .await
+            .unwrap();
+
+        let matches = index
+            .search(&vec![0.9, 0.1], &VectorFilter::default(), 10)
+            .await
+            .unwrap();
+
+        let dids: Vec<&str> = matches.iter().map(|m| m.did.as_str()).collect();
+        assert_eq!(dids, vec!["did:a", "did:b"]);
+        assert!(matches[0].similarity > matches[1].similarity);
+    }
+
+    #[tokio::test]
+    async fn test_search_pushes_down_payload_filters() {
+        let index = fake_index().await;
+        index
+            .upsert(vec![
+                point(
+                    "did:per-request",
+                    vec![1.0, 0.0],
+                    payload(PricingModel::PerRequest, &["x402"], 0.9),
+                ),
+                point(
+                    "did:per-token",
+                    vec![1.0, 0.1],
+                    payload(PricingModel::PerToken, &["x402", "escrow"], 0.9),
+                ),
+                point(
+                    "did:untrusted",
+                    vec![1.0, 0.2],
+                    payload(PricingModel::PerToken, &["escrow"], 0.1),
+                ),
+            ])
+            .await
+            .unwrap();
+
+        let by_model = VectorFilter {
+            pricing_models: vec![PricingModel::PerToken],
+            ..Default::default()
+        };
+        let by_method = VectorFilter {
+            payment_methods: vec!["escrow".to_string()],
+            min_trust: Some(0.5),
+            ..Default::default()
+        };
+
+        let query = vec![1.0, 0.0];
+        let dids = |matches: Vec<VectorMatch>| -> Vec<String> {
+            matches.into_iter().map(|m| m.did).collect()
+        };
+        assert_eq!(
+            dids(index.search(&query, &by_model, 10).await.unwrap()),
+            vec!["did:per-token", "did:untrusted"]
+        );
+        assert_eq!(
+            dids(index.search(&query, &by_method, 10).await.unwrap()),
+            vec!["did:per-token"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_rejects_wrong_dimension() {
+        let index = fake_index().await;
+
+        let result = index
+            .upsert(vec![point(
+                "did:a",
+                vec![1.0, 0.0, 0.0],
+                VectorPayload::default(),
+            )])
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_vector_dimension() {
+        let params =
+            |size| VectorsConfig::Params(VectorParamsBuilder::new(size, Distance::Cosine).build());
+
+        assert!(check_vector_dimension("cards", Some(&params(384)), 384).is_ok());
+        assert!(matches!(
+            check_vector_dimension("cards", Some(&params(768)), 384),
+            Err(Error::Search(_))
+        ));
+        assert!(check_vector_dimension("cards", None, 384).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_existing_collection_with_other_dimension_is_refused() {
+        let backend = Arc::new(FakeQdrant::default());
+        QdrantVectorIndex::new(backend.clone(), "cards", 2)
+            .await
+            .unwrap();
+
+        let result = QdrantVectorIndex::new(backend, "cards", 3).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remove_len_and_clear() {
+        let index = fake_index().await;
+        index
+            .upsert(vec![
+                point("did:a", vec![1.0, 0.0], VectorPayload::default()),
+                point("did:b", vec![0.0, 1.0], VectorPayload::default()),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(index.len().await.unwrap(), 2);
+
+        assert!(index.remove("did:a").await.unwrap());
+        assert!(!index.remove("did:a").await.unwrap());
+        assert_eq!(index.len().await.unwrap(), 1);
+
+        index.clear().await.unwrap();
+        assert_eq!(index.len().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,475 @@
+//! Trust-aware re-ranking of search results.
+//!
+//! Relevance scores from hybrid or keyword search are blended with live
+//! signals about each agent:
+//! - Composite trust score from [`TrustService`]
+//! - Stake component of that score
+//! - Base price (cheaper agents score higher)
+//! - Recency of the agent's last recorded activity
+//!
+//! Trust data for a whole result list is fetched in one batch through a
+//! [`TrustCache`], rather than with one lookup per result.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::discovery::CapabilityCard;
+use crate::trust::{TrustInfo, TrustService};
+use crate::trust_cache::TrustCache;
+
+/// Default half-life of the recency signal (30 days).
+pub const DEFAULT_RECENCY_HALF_LIFE_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Default price at which the price signal is 0.5: $1 USDC (6 decimals).
+pub const DEFAULT_REFERENCE_PRICE: u64 = 1_000_000;
+
+/// Weights for blending relevance with trust signals.
+///
+/// Weights are relative: the blended score is divided by their sum, so it
+/// stays within 0.0 - 1.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingWeights {
+    /// Weight for the search relevance score.
+    pub relevance: f32,
+
+    /// Weight for the live composite trust score.
+    pub trust: f32,
+
+    /// Weight for the stake component of trust.
+    pub stake: f32,
+
+    /// Weight for price (cheaper is better).
+    pub price: f32,
+
+    /// Weight for recent activity.
+    pub recency: f32,
+
+    /// Seconds after which the recency signal halves.
+    pub recency_half_life_secs: u64,
+
+    /// Base price (USDC, 6 decimals) at which the price signal is 0.5.
+    pub reference_price: u64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            relevance: 0.6,
+            trust: 0.25,
+            stake: 0.05,
+            price: 0.05,
+            recency: 0.05,
+            recency_half_life_secs: DEFAULT_RECENCY_HALF_LIFE_SECS,
+            reference_price: DEFAULT_REFERENCE_PRICE,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// Weights that keep the pure relevance order.
+    pub fn relevance_only() -> Self {
+        Self {
+            relevance: 1.0,
+            trust: 0.0,
+            stake: 0.0,
+            price: 0.0,
+            recency: 0.0,
+            ..Default::default()
+        }
+    }
+
+    /// Blend a relevance score with an agent's trust signals.
+    ///
+    /// Agents without trust data score 0 on trust, stake and recency;
+    /// unpriced cards score 0 on price.
+    pub fn score(
+        &self,
+        relevance: f32,
+        card: &CapabilityCard,
+        trust: Option<&TrustInfo>,
+        now: u64,
+    ) -> f32 {
+        let total = self.relevance + self.trust + self.stake + self.price + self.recency;
+        if total <= 0.0 {
+            return relevance;
+        }
+
+        let (trust_score, stake_score, recency_score) = match trust {
+            Some(info) => (
+                info.score as f32,
+                info.stake_score as f32,
+                self.recency_score(info.last_activity, now),
+            ),
+            None => (0.0, 0.0, 0.0),
+        };
+
+        let blended = self.relevance * relevance
+            + self.trust * trust_score
+            + self.stake * stake_score
+            + self.price * self.price_score(card)
+            + self.recency * recency_score;
+
+        blended / total
+    }
+
+    /// Price signal: 1.0 for free, 0.5 at the reference price.
+    fn price_score(&self, card: &CapabilityCard) -> f32 {
+        let Some(price) = card
+            .agoramesh
+            .as_ref()
+            .and_then(|ext| ext.pricing.as_ref())
+            .map(|pricing| pricing.base_price)
+        else {
+            return 0.0;
+        };
+
+        let reference = self.reference_price.max(1) as f64;
+        (reference / (reference + price as f64)) as f32
+    }
+
+    /// Recency signal: 1.0 when just active, halving every half-life.
+    fn recency_score(&self, last_activity: u64, now: u64) -> f32 {
+        if last_activity == 0 {
+            return 0.0;
+        }
+
+        let age = now.saturating_sub(last_activity) as f64;
+        let half_life = self.recency_half_life_secs.max(1) as f64;
+        0.5f64.powf(age / half_life) as f32
+    }
+}
+
+/// A re-ranked search result.
+#[derive(Debug, Clone)]
+pub struct Ranked<T> {
+    /// Blended ranking score (0.0 - 1.0).
+    pub score: f32,
+
+    /// Live trust data used for ranking, if the agent could be looked up.
+    pub trust: Option<TrustInfo>,
+
+    /// The ranked item.
+    pub item: T,
+}
+
+/// Re-ranks search results with live trust data.
+#[derive(Clone)]
+pub struct TrustRanker {
+    trust: Arc<TrustService>,
+    cache: Arc<TrustCache>,
+}
+
+impl TrustRanker {
+    /// Create a ranker reading trust from `trust` through `cache`.
+    pub fn new(trust: Arc<TrustService>, cache: Arc<TrustCache>) -> Self {
+        Self { trust, cache }
+    }
+
+    /// Get the trust cache.
+    pub fn cache(&self) -> &Arc<TrustCache> {
+        &self.cache
+    }
+
+    /// Fetch trust data for `dids` in one batch.
+    ///
+    /// Lookup failures are logged and yield no data, so ranking falls
+    /// back to relevance and price.
+    pub async fn lookup(&self, dids: &[String]) -> HashMap<String, TrustInfo> {
+        let trust = self.trust.clone();
+        match self
+            .cache
+            .get_or_load_many(dids, |missing| async move {
+                trust.get_trust_many(&missing).await
+            })
+            .await
+        {
+            Ok(found) => found
+                .into_iter()
+                .map(|(did, cached)| (did, cached.info))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to look up trust for ranking: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Blend each item's relevance with its agent's trust signals and
+    /// order the items best first.
+    ///
+    /// `items` pairs a relevance score (0.0 - 1.0) with each item; `card`
+    /// returns the item's capability card, whose DID is used for the trust
+    /// lookup. Ties keep their input order.
+    pub async fn rerank<T>(
+        &self,
+        weights: &RankingWeights,
+        items: Vec<(f32, T)>,
+        card: impl Fn(&T) -> &CapabilityCard,
+    ) -> Vec<Ranked<T>> {
+        let dids: Vec<String> = items
+            .iter()
+            .filter_map(|(_, item)| card_did(card(item)))
+            .map(str::to_string)
+            .collect();
+        let trust = self.lookup(&dids).await;
+        let now = unix_now();
+
+        let mut ranked: Vec<Ranked<T>> = items
+            .into_iter()
+            .map(|(relevance, item)| {
+                let info = card_did(card(&item))
+                    .and_then(|did| trust.get(did))
+                    .cloned();
+                Ranked {
+                    score: weights.score(relevance, card(&item), info.as_ref(), now),
+                    trust: info,
+                    item,
+                }
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        ranked
+    }
+}
+
+fn card_did(card: &CapabilityCard) -> Option<&str> {
+    card.agoramesh.as_ref().map(|ext| ext.did.as_str())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{AgoraMeshExtension, PricingInfo, PricingModel};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn card(did: &str, price: Option<u64>) -> CapabilityCard {
+        CapabilityCard {
+            name: did.to_string(),
+            description: "Test agent".to_string(),
+            url: "https://agent.example.com".to_string(),
+            provider: None,
+            skills: vec![],
+            authentication: None,
+            agoramesh: Some(AgoraMeshExtension {
+                did: did.to_string(),
+                trust_score: Some(0.99),
+                stake: None,
+                pricing: price.map(|base_price| PricingInfo {
+                    base_price,
+                    currency: "USDC".to_string(),
+                    model: PricingModel::PerRequest,
+                }),
+                payment_methods: vec![],
+                version: None,
+                updated_at: None,
+                proof: None,
+            }),
+        }
+    }
+
+    fn trust_info(score: f64, stake_score: f64, last_activity: u64) -> TrustInfo {
+        TrustInfo {
+            did: "did:agoramesh:base:agent".to_string(),
+            score,
+            reputation: 0.0,
+            stake_score,
+            endorsement_score: 0.0,
+            stake_amount: 0,
+            successful_transactions: 0,
+            failed_transactions: 0,
+            endorsement_count: 0,
+            last_activity,
+        }
+    }
+
+    fn only(f: impl FnOnce(&mut RankingWeights)) -> RankingWeights {
+        let mut weights = RankingWeights {
+            relevance: 0.0,
+            ..RankingWeights::relevance_only()
+        };
+        f(&mut weights);
+        weights
+    }
+
+    fn ranker() -> (Arc<TrustService>, TrustRanker) {
+        let trust = Arc::new(TrustService::new(
+            "https://sepolia.base.org".to_string(),
+            None,
+        ));
+        let ranker = TrustRanker::new(trust.clone(), Arc::new(TrustCache::with_defaults()));
+        (trust, ranker)
+    }
+
+    // ========== TDD Tests: RankingWeights ==========
+
+    #[test]
+    fn test_relevance_only_keeps_relevance_score() {
+        let weights = RankingWeights::relevance_only();
+        let info = trust_info(1.0, 1.0, NOW);
+
+        let score = weights.score(0.42, &card("did:a", Some(0)), Some(&info), NOW);
+
+        assert!((score - 0.42).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_score_is_normalized_by_total_weight() {
+        let weights = RankingWeights {
+            relevance: 2.0,
+            trust: 2.0,
+            ..RankingWeights::relevance_only()
+        };
+        let info = trust_info(0.5, 0.0, 0);
+
+        let score = weights.score(1.0, &card("did:a", None), Some(&info), NOW);
+
+        assert!((score - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_score_uses_live_trust_not_card_trust() {
+        let weights = only(|w| w.trust = 1.0);
+
+        let without = weights.score(1.0, &card("did:a", None), None, NOW);
+        let with = weights.score(
+            1.0,
+            &card("did:a", None),
+            Some(&trust_info(0.3, 0.0, 0)),
+            NOW,
+        );
+
+        assert_eq!(without, 0.0, "Self-reported trust must not count");
+        assert!((with - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_score_uses_stake_component() {
+        let weights = only(|w| w.stake = 1.0);
+        let info = trust_info(0.9, 0.4, 0);
+
+        let score = weights.score(0.0, &card("did:a", None), Some(&info), NOW);
+
+        assert!((score - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_price_score_prefers_cheaper_agents() {
+        let weights = only(|w| w.price = 1.0);
+
+        let free = weights.score(0.0, &card("did:a", Some(0)), None, NOW);
+        let reference = weights.score(
+            0.0,
+            &card("did:a", Some(DEFAULT_REFERENCE_PRICE)),
+            None,
+            NOW,
+        );
+        let unpriced = weights.score(0.0, &card("did:a", None), None, NOW);
+
+        assert!((free - 1.0).abs() < 1e-6);
+        assert!((reference - 0.5).abs() < 1e-6);
+        assert_eq!(unpriced, 0.0);
+    }
+
+    #[test]
+    fn test_recency_score_halves_every_half_life() {
+        let weights = only(|w| w.recency = 1.0);
+        let score = |last_activity| {
+            weights.score(
+                0.0,
+                &card("did:a", None),
+                Some(&trust_info(0.0, 0.0, last_activity)),
+                NOW,
+            )
+        };
+
+        assert!((score(NOW) - 1.0).abs() < 1e-6);
+        assert!((score(NOW - DEFAULT_RECENCY_HALF_LIFE_SECS) - 0.5).abs() < 1e-6);
+        assert_eq!(score(0), 0.0, "Never-active agents are not recent");
+    }
+
+    #[test]
+    fn test_zero_weights_fall_back_to_relevance() {
+        let weights = only(|_| {});
+
+        assert_eq!(weights.score(0.7, &card("did:a", None), None, NOW), 0.7);
+    }
+
+    #[test]
+    fn test_weights_parse_with_defaults() {
+        let weights: RankingWeights = toml::from_str("trust = 0.5").unwrap();
+
+        assert_eq!(weights.trust, 0.5);
+        assert_eq!(weights.relevance, RankingWeights::default().relevance);
+    }
+
+    // ========== TDD Tests: TrustRanker ==========
+
+    #[tokio::test]
+    async fn test_rerank_promotes_trusted_agent() {
+        let (trust, ranker) = ranker();
+        trust.seed_trust_data("did:agoramesh:base:trusted", 10_000_000_000, 100, 0, 5);
+        let items = vec![
+            (0.8, card("did:agoramesh:base:unknown", None)),
+            (0.7, card("did:agoramesh:base:trusted", None)),
+        ];
+
+        let ranked = ranker
+            .rerank(&RankingWeights::default(), items, |card| card)
+            .await;
+
+        assert_eq!(
+            card_did(&ranked[0].item),
+            Some("did:agoramesh:base:trusted")
+        );
+        assert!(ranked[0].trust.as_ref().unwrap().score > 0.5);
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_keeps_input_order_for_ties() {
+        let (_, ranker) = ranker();
+        let items = vec![
+            (0.5, card("did:agoramesh:base:first", None)),
+            (0.5, card("did:agoramesh:base:second", None)),
+        ];
+
+        let ranked = ranker
+            .rerank(&RankingWeights::default(), items, |card| card)
+            .await;
+
+        assert_eq!(card_did(&ranked[0].item), Some("did:agoramesh:base:first"));
+        assert_eq!(card_did(&ranked[1].item), Some("did:agoramesh:base:second"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_goes_through_cache() {
+        let (_, ranker) = ranker();
+        let dids = vec![
+            "did:agoramesh:base:a".to_string(),
+            "did:agoramesh:base:b".to_string(),
+        ];
+
+        assert_eq!(ranker.lookup(&dids).await.len(), 2);
+        assert_eq!(ranker.lookup(&dids).await.len(), 2);
+
+        let stats = ranker.cache().stats();
+        assert_eq!(stats.misses.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(stats.hits.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+}
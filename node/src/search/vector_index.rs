@@ -0,0 +1,478 @@
+//! Vector index backends for hybrid search.
+//!
+//! [`HybridSearch`](super::HybridSearch) delegates nearest-neighbour lookups
+//! to a [`VectorIndex`]. The default [`InMemoryVectorIndex`] does a linear
+//! cosine scan; [`HnswIndex`](super::HnswIndex) answers approximately from an
+//! embedded graph; [`QdrantVectorIndex`](super::QdrantVectorIndex) pushes both
+//! the similarity search and payload filters down into Qdrant.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::embedding::{Embedding, EmbeddingService};
+use super::hnsw::{HnswConfig, HnswIndex};
+use super::qdrant::{QdrantConfig, QdrantVectorIndex};
+use super::ranking::RankingWeights;
+use crate::discovery::{CapabilityCard, NetworkSearchConfig, PricingModel};
+use crate::error::{Error, Result};
+
+/// Filterable card attributes stored next to each vector.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VectorPayload {
+    /// Pricing model of the card, if priced.
+    pub pricing_model: Option<PricingModel>,
+
+    /// Supported payment methods.
+    pub payment_methods: Vec<String>,
+
+    /// Advertised trust score (0.0 - 1.0).
+    pub trust_score: Option<f64>,
+}
+
+impl VectorPayload {
+    /// Extract the filterable attributes of a capability card.
+    pub fn from_card(card: &CapabilityCard) -> Self {
+        let Some(ext) = card.agoramesh.as_ref() else {
+            return Self::default();
+        };
+
+        Self {
+            pricing_model: ext.pricing.as_ref().map(|p| p.model.clone()),
+            payment_methods: ext.payment_methods.clone(),
+            trust_score: ext.trust_score,
+        }
+    }
+}
+
+/// Payload filter applied during vector search.
+///
+/// Empty fields do not constrain the results.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorFilter {
+    /// Accept cards using any of these pricing models.
+    pub pricing_models: Vec<PricingModel>,
+
+    /// Accept cards supporting any of these payment methods.
+    pub payment_methods: Vec<String>,
+
+    /// Minimum advertised trust score.
+    pub min_trust: Option<f64>,
+}
+
+impl VectorFilter {
+    /// Check whether the filter has no constraints.
+    pub fn is_empty(&self) -> bool {
+        self.pricing_models.is_empty()
+            && self.payment_methods.is_empty()
+            && self.min_trust.is_none()
+    }
+
+    /// Check whether a payload satisfies the filter.
+    pub fn matches(&self, payload: &VectorPayload) -> bool {
+        if !self.pricing_models.is_empty()
+            && !payload
+                .pricing_model
+                .as_ref()
+                .is_some_and(|model| self.pricing_models.contains(model))
+        {
+            return false;
+        }
+
+        if !self.payment_methods.is_empty()
+            && !payload
+                .payment_methods
+                .iter()
+                .any(|method| self.payment_methods.contains(method))
+        {
+            return false;
+        }
+
+        if let Some(min_trust) = self.min_trust {
+            if payload.trust_score.unwrap_or(0.0) < min_trust {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check whether a capability card satisfies the filter.
+    pub fn matches_card(&self, card: &CapabilityCard) -> bool {
+        self.is_empty() || self.matches(&VectorPayload::from_card(card))
+    }
+}
+
+/// A vector to insert into an index.
+#[derive(Debug, Clone)]
+pub struct VectorPoint {
+    /// Agent DID the vector belongs to.
+    pub did: String,
+
+    /// Card embedding.
+    pub embedding: Embedding,
+
+    /// Filterable card attributes.
+    pub payload: VectorPayload,
+}
+
+/// A nearest-neighbour search hit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorMatch {
+    /// Agent DID.
+    pub did: String,
+
+    /// Cosine similarity to the query (-1.0 - 1.0).
+    pub similarity: f32,
+}
+
+/// Storage and nearest-neighbour lookup for card embeddings.
+#[async_trait]
+pub trait VectorIndex: Send + Sync {
+    /// Short backend identifier used in logs.
+    fn name(&self) -> &str;
+
+    /// Insert or replace vectors, keyed by DID.
+    async fn upsert(&self, points: Vec<VectorPoint>) -> Result<()>;
+
+    /// Remove the vector for a DID. Returns whether it existed.
+    async fn remove(&self, did: &str) -> Result<bool>;
+
+    /// Find the `limit` vectors most similar to `query` that pass `filter`,
+    /// most similar first.
+    async fn search(
+        &self,
+        query: &Embedding,
+        filter: &VectorFilter,
+        limit: usize,
+    ) -> Result<Vec<VectorMatch>>;
+
+    /// Number of indexed vectors.
+    async fn len(&self) -> Result<usize>;
+
+    /// Check whether the index holds no vectors.
+    async fn is_empty(&self) -> Result<bool> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// Remove every vector.
+    async fn clear(&self) -> Result<()>;
+
+    /// Remove vectors whose DID is not in `dids`. Returns how many were
+    /// removed.
+    ///
+    /// Used after rehydration to drop vectors for cards that no longer
+    /// exist. Backends that do not outlive the process need not implement it.
+    async fn retain(&self, dids: &HashSet<String>) -> Result<usize> {
+        let _ = dids;
+        Ok(0)
+    }
+
+    /// Persist pending changes, for backends that keep local state.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Vector index backend selection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum VectorIndexConfig {
+    /// Brute-force scan held in memory.
+    #[default]
+    Memory,
+
+    /// Approximate search over an embedded HNSW graph.
+    Hnsw(HnswConfig),
+
+    /// External Qdrant collection.
+    Qdrant(QdrantConfig),
+}
+
+impl VectorIndexConfig {
+    /// Build the configured index for vectors of `dimension`.
+    pub async fn build(&self, dimension: usize) -> Result<Arc<dyn VectorIndex>> {
+        match self {
+            VectorIndexConfig::Memory => Ok(Arc::new(InMemoryVectorIndex::new())),
+            VectorIndexConfig::Hnsw(config) => Ok(Arc::new(HnswIndex::open(config, dimension)?)),
+            VectorIndexConfig::Qdrant(config) => Ok(Arc::new(
+                QdrantVectorIndex::connect(config, dimension).await?,
+            )),
+        }
+    }
+}
+
+/// Semantic search configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// Where card embeddings are indexed.
+    #[serde(default)]
+    pub vector_index: VectorIndexConfig,
+
+    /// How relevance is blended with trust signals when ranking results.
+    #[serde(default)]
+    pub ranking: RankingWeights,
+
+    /// How `scope=network` queries fan out to connected peers.
+    #[serde(default)]
+    pub network: NetworkSearchConfig,
+}
+
+// ========== In-Memory Index ==========
+
+/// Brute-force vector index; every search scans all vectors.
+#[derive(Default)]
+pub struct InMemoryVectorIndex {
+    points: RwLock<HashMap<String, (Embedding, VectorPayload)>>,
+}
+
+impl InMemoryVectorIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the stored embedding for a DID.
+    pub fn embedding(&self, did: &str) -> Option<Embedding> {
+        self.points
+            .read()
+            .ok()?
+            .get(did)
+            .map(|(embedding, _)| embedding.clone())
+    }
+}
+
+fn lock_poisoned<E: std::fmt::Display>(e: E) -> Error {
+    Error::Search(format!("Vector index lock poisoned: {}", e))
+}
+
+#[async_trait]
+impl VectorIndex for InMemoryVectorIndex {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    async fn upsert(&self, points: Vec<VectorPoint>) -> Result<()> {
+        let mut guard = self.points.write().map_err(lock_poisoned)?;
+        for point in points {
+            guard.insert(point.did, (point.embedding, point.payload));
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, did: &str) -> Result<bool> {
+        let mut guard = self.points.write().map_err(lock_poisoned)?;
+        Ok(guard.remove(did).is_some())
+    }
+
+    async fn search(
+        &self,
+        query: &Embedding,
+        filter: &VectorFilter,
+        limit: usize,
+    ) -> Result<Vec<VectorMatch>> {
+        let guard = self.points.read().map_err(lock_poisoned)?;
+
+        let mut matches: Vec<VectorMatch> = guard
+            .iter()
+            .filter(|(_, (_, payload))| filter.matches(payload))
+            .map(|(did, (embedding, _))| VectorMatch {
+                did: did.clone(),
+                similarity: EmbeddingService::cosine_similarity(query, embedding),
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        matches.truncate(limit);
+
+        Ok(matches)
+    }
+
+    async fn len(&self) -> Result<usize> {
+        Ok(self.points.read().map_err(lock_poisoned)?.len())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.points.write().map_err(lock_poisoned)?.clear();
+        Ok(())
+    }
+
+    async fn retain(&self, dids: &HashSet<String>) -> Result<usize> {
+        let mut guard = self.points.write().map_err(lock_poisoned)?;
+        let before = guard.len();
+        guard.retain(|did, _| dids.contains(did));
+        Ok(before - guard.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(did: &str, embedding: Vec<f32>, payload: VectorPayload) -> VectorPoint {
+        VectorPoint {
+            did: did.to_string(),
+            embedding,
+            payload,
+        }
+    }
+
+    fn payload(model: PricingModel, methods: &[&str], trust: f64) -> VectorPayload {
+        VectorPayload {
+            pricing_model: Some(model),
+            payment_methods: methods.iter().map(|m| m.to_string()).collect(),
+            trust_score: Some(trust),
+        }
+    }
+
+    // ========== TDD Tests: VectorFilter ==========
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = VectorFilter::default();
+
+        assert!(filter.is_empty());
+        assert!(filter.matches(&VectorPayload::default()));
+    }
+
+    #[test]
+    fn test_filter_requires_all_constraints() {
+        let filter = VectorFilter {
+            pricing_models: vec![PricingModel::PerRequest],
+            payment_methods: vec!["x402".to_string()],
+            min_trust: Some(0.5),
+        };
+
+        assert!(filter.matches(&payload(PricingModel::PerRequest, &["x402", "escrow"], 0.7)));
+        assert!(!filter.matches(&payload(PricingModel::PerToken, &["x402"], 0.7)));
+        assert!(!filter.matches(&payload(PricingModel::PerRequest, &["escrow"], 0.7)));
+        assert!(!filter.matches(&payload(PricingModel::PerRequest, &["x402"], 0.4)));
+        assert!(!filter.matches(&VectorPayload::default()));
+    }
+
+    // ========== TDD Tests: InMemoryVectorIndex ==========
+
+    #[tokio::test]
+    async fn test_memory_index_ranks_by_similarity() {
+        let index = InMemoryVectorIndex::new();
+        index
+            .upsert(vec![
+                point("did:a", vec![1.0, 0.0], VectorPayload::default()),
+                point("did:b", vec![0.7, 0.7], VectorPayload::default()),
+                point("did:c", vec![0.0, 1.0], VectorPayload::default()),
+            ])
+            .await
+            .unwrap();
+
+        let matches = index
+            .search(&vec![1.0, 0.1], &VectorFilter::default(), 2)
+            .await
+            .unwrap();
+
+        let dids: Vec<&str> = matches.iter().map(|m| m.did.as_str()).collect();
+        assert_eq!(dids, vec!["did:a", "did:b"]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_index_applies_filter() {
+        let index = InMemoryVectorIndex::new();
+        index
+            .upsert(vec![
+                point(
+                    "did:cheap",
+                    vec![1.0, 0.0],
+                    payload(PricingModel::PerRequest, &["x402"], 0.2),
+                ),
+                point(
+                    "did:trusted",
+                    vec![0.5, 0.5],
+                    payload(PricingModel::PerRequest, &["x402"], 0.9),
+                ),
+            ])
+            .await
+            .unwrap();
+        let filter = VectorFilter {
+            min_trust: Some(0.5),
+            ..Default::default()
+        };
+
+        let matches = index.search(&vec![1.0, 0.0], &filter, 10).await.unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].did, "did:trusted");
+    }
+
+    #[tokio::test]
+    async fn test_memory_index_upsert_replaces_and_remove_deletes() {
+        let index = InMemoryVectorIndex::new();
+        index
+            .upsert(vec![point("did:a", vec![1.0], VectorPayload::default())])
+            .await
+            .unwrap();
+        index
+            .upsert(vec![point("did:a", vec![2.0], VectorPayload::default())])
+            .await
+            .unwrap();
+
+        assert_eq!(index.len().await.unwrap(), 1);
+        assert_eq!(index.embedding("did:a"), Some(vec![2.0]));
+
+        assert!(index.remove("did:a").await.unwrap());
+        assert!(!index.remove("did:a").await.unwrap());
+        assert_eq!(index.len().await.unwrap(), 0);
+    }
+
+    // ========== TDD Tests: VectorIndexConfig ==========
+
+    #[test]
+    fn test_vector_index_config_defaults_to_memory() {
+        let config: SearchConfig = toml::from_str("").unwrap();
+
+        assert!(matches!(config.vector_index, VectorIndexConfig::Memory));
+    }
+
+    #[test]
+    fn test_vector_index_config_parses_qdrant() {
+        let config: SearchConfig = toml::from_str(
+            r#"
+            [vector_index]
+            backend = "qdrant"
+            url = "http://localhost:6334"
+            "#,
+        )
+        .unwrap();
+
+        let VectorIndexConfig::Qdrant(qdrant) = config.vector_index else {
+            panic!("expected qdrant backend");
+        };
+        assert_eq!(qdrant.url, "http://localhost:6334");
+        assert_eq!(qdrant.collection, "agoramesh_cards");
+    }
+
+    #[test]
+    fn test_vector_index_config_parses_hnsw() {
+        let config: SearchConfig = toml::from_str(
+            r#"
+            [vector_index]
+            backend = "hnsw"
+            ef_search = 128
+            snapshot_path = "/tmp/hnsw.snapshot"
+            "#,
+        )
+        .unwrap();
+
+        let VectorIndexConfig::Hnsw(hnsw) = config.vector_index else {
+            panic!("expected hnsw backend");
+        };
+        assert_eq!(hnsw.m, 16);
+        assert_eq!(hnsw.ef_construction, 200);
+        assert_eq!(hnsw.ef_search, 128);
+        assert_eq!(hnsw.snapshot_path.as_deref(), Some("/tmp/hnsw.snapshot"));
+    }
+}
@@ -8,10 +8,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::contract::TrustRegistryClient;
+use crate::contract::{DidHashIndex, TrustRegistryClient};
 use crate::error::{Error, Result};
+use crate::persistence::{Endorsement, Store, TrustData, TrustDataStore};
 
 /// Trust information for an agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +43,10 @@ pub struct TrustInfo {
 
     /// Number of endorsements received.
     pub endorsement_count: u64,
+
+    /// Last recorded activity (Unix seconds, 0 if never active).
+    #[serde(default)]
+    pub last_activity: u64,
 }
 
 /// Trust score calculation weights.
@@ -97,8 +102,19 @@ pub struct TrustService {
     /// Contract client for on-chain operations.
     contract_client: Option<TrustRegistryClient>,
 
-    /// Local cache of trust data (for testing and offline mode).
+    /// Local cache of trust data, kept in front of the store when one is attached.
     cache: RwLock<HashMap<String, TrustData>>,
+
+    /// Serialises read-modify-write updates, so the cache lock is never
+    /// held across store I/O.
+    updates: Mutex<()>,
+
+    /// Optional durable store; cache misses read through to it and every
+    /// local update is written through to it.
+    store: Option<TrustDataStore>,
+
+    /// Optional contract hash index, recording every DID with trust data.
+    did_index: Option<Arc<DidHashIndex>>,
 }
 
 /// Decay rate per period (5% = 0.05)
@@ -119,28 +135,6 @@ pub const MAX_ENDORSEMENTS_COUNTED: usize = 10;
 /// Normalization factor for endorsement score (divide total by this)
 pub const ENDORSEMENT_NORMALIZATION: f64 = 3.0;
 
-/// Endorsement with hop distance information.
-#[derive(Debug, Clone)]
-struct EndorsementData {
-    /// DID of the endorser
-    endorser_did: String,
-    /// Hop distance from trust root (1 = direct, 2 = one intermediary, etc.)
-    hop_distance: u32,
-}
-
-/// Internal trust data storage.
-#[derive(Debug, Clone, Default)]
-struct TrustData {
-    stake_amount: u64,
-    successful_transactions: u64,
-    failed_transactions: u64,
-    endorsement_count: u64,
-    /// Unix timestamp of last activity (for decay calculation)
-    last_activity_timestamp: u64,
-    /// List of endorsements with hop distances
-    endorsements: Vec<EndorsementData>,
-}
-
 impl TrustService {
     /// Create a new trust service.
     ///
@@ -169,12 +163,139 @@ impl TrustService {
             registry_address,
             contract_client,
             cache: RwLock::new(HashMap::new()),
+            updates: Mutex::new(()),
+            store: None,
+            did_index: None,
+        }
+    }
+
+    /// Create a new trust service backed by a durable store.
+    ///
+    /// Trust history, endorsements and decay timestamps are kept in `store`;
+    /// the in-memory map acts as a read-through cache in front of it.
+    pub fn with_store(
+        rpc_url: String,
+        registry_address: Option<String>,
+        store: Arc<dyn Store>,
+    ) -> Self {
+        Self::new(rpc_url, registry_address).with_trust_store(TrustDataStore::new(store))
+    }
+
+    /// Attach a durable trust data store.
+    ///
+    /// Seeds, transactions and endorsements are written through to the
+    /// store. Call [`Self::rehydrate`] after attaching to load persisted data.
+    pub fn with_trust_store(mut self, store: TrustDataStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Record every DID with trust data in `index`.
+    pub fn with_did_index(mut self, index: Arc<DidHashIndex>) -> Self {
+        self.did_index = Some(index);
+        self
+    }
+
+    /// Add a DID to the contract hash index, if attached.
+    fn index_did(&self, did: &str) -> Result<()> {
+        match self.did_index {
+            Some(ref index) => index.insert(did),
+            None => Ok(()),
+        }
+    }
+
+    /// Load all persisted trust data into the local cache.
+    ///
+    /// Returns the number of agents loaded (0 when no store is attached).
+    pub fn rehydrate(&self) -> Result<usize> {
+        let Some(ref store) = self.store else {
+            return Ok(0);
+        };
+
+        let entries = store.all()?;
+        let count = entries.len();
+        for (did, _) in &entries {
+            self.index_did(did)?;
         }
+
+        self.cache
+            .write()
+            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?
+            .extend(entries);
+
+        Ok(count)
+    }
+
+    /// Write a cache entry through to the durable store, if attached.
+    fn persist(&self, did: &str, data: &TrustData) -> Result<()> {
+        match self.store {
+            Some(ref store) => store.put(did, data),
+            None => Ok(()),
+        }
+    }
+
+    /// Look up trust data, reading through to the store on a cache miss.
+    fn lookup(&self, did: &str) -> Result<Option<TrustData>> {
+        {
+            let cache = self
+                .cache
+                .read()
+                .map_err(|e| Error::Trust(format!("Failed to acquire cache read lock: {}", e)))?;
+            if let Some(data) = cache.get(did) {
+                return Ok(Some(data.clone()));
+            }
+        }
+
+        let Some(ref store) = self.store else {
+            return Ok(None);
+        };
+        let loaded = store.get(did)?;
+        if let Some(ref data) = loaded {
+            if let Ok(mut cache) = self.cache.write() {
+                cache.entry(did.to_string()).or_insert_with(|| data.clone());
+            }
+            self.index_did(did)?;
+        }
+
+        Ok(loaded)
+    }
+
+    /// Apply an update to an agent's trust data and write it through to the store.
+    ///
+    /// On a cache miss the current record is loaded from the store first, so
+    /// updates never clobber persisted history. The update is persisted
+    /// before the cache changes, so a failed write leaves both untouched.
+    fn update<F>(&self, did: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut TrustData),
+    {
+        let _update = self
+            .updates
+            .lock()
+            .map_err(|e| Error::Trust(format!("Failed to acquire update lock: {}", e)))?;
+
+        let mut data = self.lookup(did)?.unwrap_or_default();
+        f(&mut data);
+        self.persist(did, &data)?;
+        self.index_did(did)?;
+
+        self.cache
+            .write()
+            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?
+            .insert(did.to_string(), data);
+        Ok(())
+    }
+
+    /// Whether trust data is known for an agent, in the cache or the store.
+    pub fn has_trust_data(&self, did: &str) -> Result<bool> {
+        Ok(self.lookup(did)?.is_some())
     }
 
     /// Seed trust data for an agent.
     ///
     /// Used to set initial trust data for known agents (e.g. on startup).
+    /// Overwrites any existing record; check [`has_trust_data`](Self::has_trust_data)
+    /// first to keep persisted history.
     /// Uses current timestamp so no decay is applied initially.
     pub fn seed_trust_data(
         &self,
@@ -204,18 +325,23 @@ impl TrustService {
         endorsement_count: u64,
         last_activity_timestamp: u64,
     ) {
+        let data = TrustData {
+            stake_amount,
+            successful_transactions: successful_txs,
+            failed_transactions: failed_txs,
+            endorsement_count,
+            last_activity: last_activity_timestamp,
+            ..Default::default()
+        };
+
+        if let Err(e) = self.persist(did, &data) {
+            tracing::warn!("Failed to persist seeded trust data for {}: {}", did, e);
+        }
+        if let Err(e) = self.index_did(did) {
+            tracing::warn!("Failed to index seeded trust data for {}: {}", did, e);
+        }
         if let Ok(mut cache) = self.cache.write() {
-            cache.insert(
-                did.to_string(),
-                TrustData {
-                    stake_amount,
-                    successful_transactions: successful_txs,
-                    failed_transactions: failed_txs,
-                    endorsement_count,
-                    last_activity_timestamp,
-                    endorsements: Vec::new(),
-                },
-            );
+            cache.insert(did.to_string(), data);
         }
     }
 
@@ -273,26 +399,55 @@ impl TrustService {
             )));
         }
 
-        // Get data from cache (or default for unknown agents)
-        let data = {
+        // Get data from cache or store (or default for unknown agents)
+        let data = self.lookup(did)?.unwrap_or_default();
+
+        Ok(self.trust_info(did, &data))
+    }
+
+    /// Get trust information for many agents at once.
+    ///
+    /// Cached records are read under a single lock; only the rest are
+    /// loaded from the store. DIDs with an invalid format are skipped, so
+    /// the result may be shorter than `dids`.
+    pub async fn get_trust_many(&self, dids: &[String]) -> Result<Vec<TrustInfo>> {
+        let dids: Vec<&String> = dids.iter().filter(|did| did.starts_with("did:")).collect();
+
+        let cached: Vec<Option<TrustData>> = {
             let cache = self
                 .cache
                 .read()
                 .map_err(|e| Error::Trust(format!("Failed to acquire cache read lock: {}", e)))?;
-            cache.get(did).cloned().unwrap_or_default()
+            dids.iter()
+                .map(|did| cache.get(did.as_str()).cloned())
+                .collect()
         };
 
+        dids.into_iter()
+            .zip(cached)
+            .map(|(did, data)| {
+                let data = match data {
+                    Some(data) => data,
+                    None => self.lookup(did)?.unwrap_or_default(),
+                };
+                Ok(self.trust_info(did, &data))
+            })
+            .collect()
+    }
+
+    /// Compute trust information from an agent's raw trust data.
+    fn trust_info(&self, did: &str, data: &TrustData) -> TrustInfo {
         // Calculate component scores
-        let reputation = self.calculate_reputation(&data);
+        let reputation = self.calculate_reputation(data);
         let stake_score = self.calculate_stake_score(data.stake_amount);
-        let endorsement_score = self.calculate_endorsement_score(&data);
+        let endorsement_score = self.calculate_endorsement_score(data);
 
         // Calculate composite score using weights
         let score = self.weights.reputation * reputation
             + self.weights.stake * stake_score
             + self.weights.endorsements * endorsement_score;
 
-        Ok(TrustInfo {
+        TrustInfo {
             did: did.to_string(),
             score,
             reputation,
@@ -302,7 +457,8 @@ impl TrustService {
             successful_transactions: data.successful_transactions,
             failed_transactions: data.failed_transactions,
             endorsement_count: data.endorsement_count,
-        })
+            last_activity: data.last_activity,
+        }
     }
 
     /// Get on-chain trust score for an agent.
@@ -374,7 +530,7 @@ impl TrustService {
         let base_reputation = success_rate * (0.5 + 0.5 * volume_factor);
 
         // Apply time decay based on inactivity
-        let decay_factor = self.calculate_decay_factor(data.last_activity_timestamp);
+        let decay_factor = self.calculate_decay_factor(data.last_activity);
 
         // Clamp to [0.0, 1.0]
         (base_reputation * decay_factor).clamp(0.0, 1.0)
@@ -434,7 +590,6 @@ impl TrustService {
             return 0.0;
         }
 
-        let cache = self.cache.read().ok();
         let mut total_contribution = 0.0;
         let mut counted = 0;
 
@@ -450,10 +605,11 @@ impl TrustService {
             }
 
             // Get endorser's reputation
-            let endorser_reputation = cache
-                .as_ref()
-                .and_then(|c| c.get(&endorsement.endorser_did))
-                .map(|data| self.calculate_reputation(data))
+            let endorser_reputation = self
+                .lookup(&endorsement.endorser_did)
+                .ok()
+                .flatten()
+                .map(|data| self.calculate_reputation(&data))
                 .unwrap_or(0.0);
 
             // Calculate hop decay: 0.9^hop_distance
@@ -493,7 +649,7 @@ impl TrustService {
     /// # Errors
     ///
     /// Returns error if DID format is invalid.
-    pub async fn record_success(&self, did: &str, _amount: u64) -> Result<()> {
+    pub async fn record_success(&self, did: &str, amount: u64) -> Result<()> {
         // Validate DID format
         if !did.starts_with("did:") {
             return Err(Error::Trust(format!(
@@ -502,16 +658,12 @@ impl TrustService {
             )));
         }
 
-        // Update cache
-        let mut cache = self
-            .cache
-            .write()
-            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?;
-
-        let data = cache.entry(did.to_string()).or_default();
-        data.successful_transactions += 1;
-        // Reset decay timer on activity
-        data.last_activity_timestamp = current_timestamp();
+        self.update(did, |data| {
+            data.successful_transactions += 1;
+            data.total_volume = data.total_volume.saturating_add(amount);
+            // Reset decay timer on activity
+            data.last_activity = current_timestamp();
+        })?;
 
         // Note: On-chain recording requires ORACLE_ROLE and a configured signer.
         // The contract client supports read operations; write operations require
@@ -542,16 +694,11 @@ impl TrustService {
             )));
         }
 
-        // Update cache
-        let mut cache = self
-            .cache
-            .write()
-            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?;
-
-        let data = cache.entry(did.to_string()).or_default();
-        data.failed_transactions += 1;
-        // Reset decay timer on activity (even failures count as activity)
-        data.last_activity_timestamp = current_timestamp();
+        self.update(did, |data| {
+            data.failed_transactions += 1;
+            // Reset decay timer on activity (even failures count as activity)
+            data.last_activity = current_timestamp();
+        })?;
 
         // Note: On-chain recording requires ORACLE_ROLE and a configured signer.
         // The contract client supports read operations; write operations require
@@ -595,24 +742,20 @@ impl TrustService {
 
         // Update cache - use a generic "unknown" endorser for simple API
         // The endorsement will contribute based on the generic endorser's trust (0)
-        let mut cache = self
-            .cache
-            .write()
-            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?;
-
-        let data = cache.entry(target_did.to_string()).or_default();
-        data.endorsement_count += 1;
-
-        // For backward compatibility, add a simple endorsement at hop 1
-        // This endorsement won't contribute to score since the endorser doesn't exist
-        // but the count is tracked for legacy callers
-        data.endorsements.push(EndorsementData {
-            endorser_did: format!(
-                "did:agoramesh:base:anonymous-endorser-{}",
-                data.endorsement_count
-            ),
-            hop_distance: 1,
-        });
+        self.update(target_did, |data| {
+            data.endorsement_count += 1;
+
+            // For backward compatibility, add a simple endorsement at hop 1
+            // This endorsement won't contribute to score since the endorser doesn't exist
+            // but the count is tracked for legacy callers
+            data.endorsements.push(Endorsement {
+                endorser_did: format!(
+                    "did:agoramesh:base:anonymous-endorser-{}",
+                    data.endorsement_count
+                ),
+                hop_distance: 1,
+            });
+        })?;
 
         // Note: On-chain endorsement requires the caller to be a registered agent.
         // The contract client supports read operations; write operations require
@@ -662,19 +805,117 @@ impl TrustService {
             )));
         }
 
-        // Update cache
-        let mut cache = self
-            .cache
-            .write()
-            .map_err(|e| Error::Trust(format!("Failed to acquire cache write lock: {}", e)))?;
+        self.update(target_did, |data| {
+            data.endorsement_count += 1;
+            data.endorsements.push(Endorsement {
+                endorser_did: endorser_did.to_string(),
+                hop_distance,
+            });
+        })
+    }
+
+    /// Remove an endorsement previously recorded for `target_did`.
+    ///
+    /// Returns `true` if an endorsement from `endorser_did` was found.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either DID format is invalid.
+    pub fn remove_endorsement(&self, endorser_did: &str, target_did: &str) -> Result<bool> {
+        Self::check_did(endorser_did)?;
+        Self::check_did(target_did)?;
+
+        if !self.has_endorsement(endorser_did, target_did)? {
+            return Ok(false);
+        }
+
+        self.update(target_did, |data| {
+            data.endorsements
+                .retain(|endorsement| endorsement.endorser_did != endorser_did);
+            data.endorsement_count = data.endorsement_count.saturating_sub(1);
+        })?;
+
+        Ok(true)
+    }
+
+    /// Check whether `target_did` holds an endorsement from `endorser_did`.
+    pub fn has_endorsement(&self, endorser_did: &str, target_did: &str) -> Result<bool> {
+        Ok(self.lookup(target_did)?.is_some_and(|data| {
+            data.endorsements
+                .iter()
+                .any(|endorsement| endorsement.endorser_did == endorser_did)
+        }))
+    }
+
+    /// Apply an on-chain `ReputationUpdated` event to the local record.
+    ///
+    /// The TrustRegistry reports a reputation score in basis points
+    /// (0-10000) and the total transaction count. The score is used as the
+    /// success ratio to split the count into successful and failed
+    /// transactions, and counts as activity for decay purposes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if DID format is invalid.
+    pub fn apply_onchain_reputation(
+        &self,
+        did: &str,
+        score_bps: u64,
+        total_transactions: u64,
+    ) -> Result<()> {
+        Self::check_did(did)?;
+
+        let successful =
+            (total_transactions as u128 * score_bps.min(10_000) as u128 / 10_000) as u64;
+        self.update(did, |data| {
+            data.successful_transactions = successful;
+            data.failed_transactions = total_transactions - successful;
+            data.last_activity = current_timestamp();
+        })
+    }
+
+    /// Add an on-chain stake deposit to the local record.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if DID format is invalid.
+    pub fn add_stake(&self, did: &str, amount: u64) -> Result<()> {
+        Self::check_did(did)?;
+
+        self.update(did, |data| {
+            data.stake_amount = data.stake_amount.saturating_add(amount);
+        })
+    }
+
+    /// Subtract slashed stake from the local record (never below zero).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if DID format is invalid.
+    pub fn slash_stake(&self, did: &str, amount: u64) -> Result<()> {
+        Self::check_did(did)?;
 
-        let data = cache.entry(target_did.to_string()).or_default();
-        data.endorsement_count += 1;
-        data.endorsements.push(EndorsementData {
-            endorser_did: endorser_did.to_string(),
-            hop_distance,
-        });
+        self.update(did, |data| {
+            data.stake_amount = data.stake_amount.saturating_sub(amount);
+        })
+    }
 
+    /// DIDs with locally known trust data.
+    pub fn known_dids(&self) -> Result<Vec<String>> {
+        let cache = self
+            .cache
+            .read()
+            .map_err(|e| Error::Trust(format!("Failed to acquire cache read lock: {}", e)))?;
+        Ok(cache.keys().cloned().collect())
+    }
+
+    fn check_did(did: &str) -> Result<()> {
+        if !did.starts_with("did:") {
+            return Err(Error::Trust(format!(
+                "Invalid DID format: '{}'. DID must start with 'did:'",
+                did
+            )));
+        }
         Ok(())
     }
 }
@@ -794,6 +1035,76 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("DID"));
     }
 
+    #[tokio::test]
+    async fn test_get_trust_reports_last_activity() {
+        let service = test_service();
+        let did = "did:agoramesh:base:active-agent";
+        service.record_success(did, 0).await.unwrap();
+
+        let trust = service.get_trust(did).await.unwrap();
+
+        assert!(trust.last_activity > 0);
+    }
+
+    // ========== TDD Tests: get_trust_many() ==========
+
+    #[tokio::test]
+    async fn test_get_trust_many_matches_get_trust() {
+        // Arrange
+        let service = test_service();
+        let staked = "did:agoramesh:base:batch-staked";
+        let unknown = "did:agoramesh:base:batch-unknown";
+        service.set_trust_data(staked, 2_500_000_000, 10, 1, 2);
+
+        // Act
+        let batch = service
+            .get_trust_many(&[staked.to_string(), unknown.to_string()])
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(batch.len(), 2);
+        let single = service.get_trust(staked).await.unwrap();
+        assert_eq!(batch[0].did, staked);
+        assert_eq!(batch[0].score, single.score);
+        assert_eq!(batch[0].stake_amount, 2_500_000_000);
+        assert_eq!(batch[1].did, unknown);
+        assert_eq!(batch[1].score, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_get_trust_many_skips_invalid_dids() {
+        let service = test_service();
+
+        let batch = service
+            .get_trust_many(&[
+                "invalid-did".to_string(),
+                "did:agoramesh:base:valid".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].did, "did:agoramesh:base:valid");
+    }
+
+    #[tokio::test]
+    async fn test_get_trust_many_reads_through_to_store() {
+        // Arrange: trust data only in the shared backend
+        let store = memory_trust_store();
+        let did = "did:agoramesh:base:batch-persisted";
+        test_service()
+            .with_trust_store(store.clone())
+            .seed_trust_data(did, 0, 7, 0, 0);
+        let service = test_service().with_trust_store(store);
+
+        // Act
+        let batch = service.get_trust_many(&[did.to_string()]).await.unwrap();
+
+        // Assert
+        assert_eq!(batch[0].successful_transactions, 7);
+    }
+
     // ========== TDD Tests: verify() ==========
 
     #[tokio::test]
@@ -1583,4 +1894,276 @@ mod tests {
             trust.endorsement_score
         );
     }
+
+    // ========== TDD Tests: Persistence ==========
+
+    fn memory_trust_store() -> TrustDataStore {
+        TrustDataStore::new(std::sync::Arc::new(crate::persistence::MemoryStore::new()))
+    }
+
+    #[tokio::test]
+    async fn test_record_success_writes_through_to_store() {
+        // Arrange
+        let store = memory_trust_store();
+        let service = test_service().with_trust_store(store.clone());
+        let did = "did:agoramesh:base:persisted-success";
+
+        // Act
+        service.record_success(did, 250_000).await.unwrap();
+        service.record_failure(did, "timeout").await.unwrap();
+        service.endorse(did, 0.5).await.unwrap();
+
+        // Assert
+        let persisted = store
+            .get(did)
+            .unwrap()
+            .expect("Trust data should be persisted");
+        assert_eq!(persisted.successful_transactions, 1);
+        assert_eq!(persisted.failed_transactions, 1);
+        assert_eq!(persisted.endorsement_count, 1);
+        assert_eq!(persisted.total_volume, 250_000);
+        assert!(persisted.last_activity > 0);
+    }
+
+    /// Store whose writes always fail.
+    struct ReadOnlyStore(crate::persistence::MemoryStore);
+
+    impl Store for ReadOnlyStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            self.0.get(key)
+        }
+        fn put(&self, _key: &str, _value: &[u8]) -> Result<()> {
+            Err(Error::Persistence("read-only store".to_string()))
+        }
+        fn delete(&self, _key: &str) -> Result<()> {
+            Err(Error::Persistence("read-only store".to_string()))
+        }
+        fn contains(&self, key: &str) -> Result<bool> {
+            self.0.contains(key)
+        }
+        fn iter_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+            self.0.iter_prefix(prefix)
+        }
+        fn keys(&self) -> Result<Vec<String>> {
+            self.0.keys()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_cached_trust_unchanged() {
+        // Arrange
+        let store = TrustDataStore::new(std::sync::Arc::new(ReadOnlyStore(
+            crate::persistence::MemoryStore::new(),
+        )));
+        let service = test_service().with_trust_store(store);
+        let did = "did:agoramesh:base:unwritable";
+
+        // Act
+        let result = service.record_success(did, 1_000).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(!service.has_trust_data(did).unwrap());
+        let trust = service.get_trust(did).await.unwrap();
+        assert_eq!(trust.successful_transactions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rehydrate_restores_trust_after_restart() {
+        // Arrange: first "run" records activity
+        let store = memory_trust_store();
+        let did = "did:agoramesh:base:restarted";
+        {
+            let service = test_service().with_trust_store(store.clone());
+            service.seed_trust_data(did, 2_500_000_000, 10, 0, 0);
+            service.record_success(did, 0).await.unwrap();
+        }
+
+        // Act: second "run" starts empty and rehydrates
+        let service = test_service().with_trust_store(store);
+        let loaded = service.rehydrate().unwrap();
+
+        // Assert
+        assert_eq!(loaded, 1);
+        let trust = service.get_trust(did).await.unwrap();
+        assert_eq!(trust.successful_transactions, 11);
+        assert_eq!(trust.stake_amount, 2_500_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_has_trust_data_sees_persisted_records_after_restart() {
+        let store = memory_trust_store();
+        let did = "did:agoramesh:base:seeded-once";
+        {
+            let service = test_service().with_trust_store(store.clone());
+            assert!(!service.has_trust_data(did).unwrap());
+            service.seed_trust_data(did, 0, 5, 0, 0);
+            service.record_success(did, 0).await.unwrap();
+        }
+
+        let service = test_service().with_trust_store(store);
+
+        assert!(service.has_trust_data(did).unwrap());
+        assert_eq!(
+            service
+                .get_trust(did)
+                .await
+                .unwrap()
+                .successful_transactions,
+            6
+        );
+    }
+
+    #[test]
+    fn test_rehydrate_without_store_is_noop() {
+        let service = test_service();
+        assert_eq!(service.rehydrate().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_with_store_reads_through_on_cache_miss() {
+        // Arrange: another service instance wrote to the shared backend
+        let backend: Arc<dyn Store> = Arc::new(crate::persistence::MemoryStore::new());
+        let did = "did:agoramesh:base:read-through";
+        TrustService::with_store(
+            "https://sepolia.base.org".to_string(),
+            None,
+            backend.clone(),
+        )
+        .seed_trust_data(did, 1_000_000_000, 20, 1, 0);
+
+        // Act: a fresh service without rehydration
+        let service =
+            TrustService::with_store("https://sepolia.base.org".to_string(), None, backend);
+        let trust = service.get_trust(did).await.unwrap();
+
+        // Assert
+        assert_eq!(trust.stake_amount, 1_000_000_000);
+        assert_eq!(trust.successful_transactions, 20);
+    }
+
+    #[tokio::test]
+    async fn test_update_on_cache_miss_preserves_stored_history() {
+        // Arrange
+        let store = memory_trust_store();
+        let did = "did:agoramesh:base:no-clobber";
+        test_service()
+            .with_trust_store(store.clone())
+            .seed_trust_data(did, 0, 7, 2, 0);
+
+        // Act: new service has a cold cache
+        let service = test_service().with_trust_store(store.clone());
+        service.record_success(did, 100).await.unwrap();
+
+        // Assert
+        let persisted = store.get(did).unwrap().unwrap();
+        assert_eq!(persisted.successful_transactions, 8);
+        assert_eq!(persisted.failed_transactions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_endorsement_graph_survives_restart() {
+        // Arrange
+        let store = memory_trust_store();
+        let endorser = "did:agoramesh:base:durable-endorser";
+        let target = "did:agoramesh:base:durable-target";
+        {
+            let service = test_service().with_trust_store(store.clone());
+            service.seed_trust_data(endorser, 0, 100, 0, 0);
+            service
+                .add_endorsement_with_hop(endorser, target, 1)
+                .await
+                .unwrap();
+        }
+        let before = {
+            let service = test_service().with_trust_store(store.clone());
+            service.rehydrate().unwrap();
+            service.get_trust(target).await.unwrap().endorsement_score
+        };
+
+        // Act: cold cache, endorser and target both read through
+        let service = test_service().with_trust_store(store.clone());
+        let trust = service.get_trust(target).await.unwrap();
+
+        // Assert
+        let persisted = store.get(target).unwrap().unwrap();
+        assert_eq!(persisted.endorsements.len(), 1);
+        assert_eq!(persisted.endorsements[0].endorser_did, endorser);
+        assert!(trust.endorsement_score > 0.0);
+        assert!((trust.endorsement_score - before).abs() < 0.001);
+    }
+
+    // ========== TDD Tests: on-chain event updates ==========
+
+    #[tokio::test]
+    async fn test_apply_onchain_reputation_splits_transactions_by_score() {
+        let service = test_service();
+        let did = "did:agoramesh:base:onchain-agent";
+
+        service.apply_onchain_reputation(did, 9_000, 100).unwrap();
+
+        let trust = service.get_trust(did).await.unwrap();
+        assert_eq!(trust.successful_transactions, 90);
+        assert_eq!(trust.failed_transactions, 10);
+    }
+
+    #[tokio::test]
+    async fn test_apply_onchain_reputation_clamps_score() {
+        let service = test_service();
+        let did = "did:agoramesh:base:onchain-agent";
+
+        service.apply_onchain_reputation(did, 20_000, 7).unwrap();
+
+        let trust = service.get_trust(did).await.unwrap();
+        assert_eq!(trust.successful_transactions, 7);
+        assert_eq!(trust.failed_transactions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stake_deposit_and_slash() {
+        let service = test_service();
+        let did = "did:agoramesh:base:staker";
+
+        service.add_stake(did, 5_000).unwrap();
+        service.add_stake(did, 1_000).unwrap();
+        service.slash_stake(did, 2_000).unwrap();
+        assert_eq!(service.get_trust(did).await.unwrap().stake_amount, 4_000);
+
+        service.slash_stake(did, 10_000).unwrap();
+        assert_eq!(service.get_trust(did).await.unwrap().stake_amount, 0);
+    }
+
+    #[tokio::test]
+    async fn test_remove_endorsement() {
+        let service = test_service();
+        let endorser = "did:agoramesh:base:endorser";
+        let target = "did:agoramesh:base:target";
+
+        service
+            .add_endorsement_with_hop(endorser, target, 1)
+            .await
+            .unwrap();
+        assert!(service.has_endorsement(endorser, target).unwrap());
+
+        assert!(service.remove_endorsement(endorser, target).unwrap());
+        assert!(!service.remove_endorsement(endorser, target).unwrap());
+
+        assert!(!service.has_endorsement(endorser, target).unwrap());
+        assert_eq!(
+            service.get_trust(target).await.unwrap().endorsement_count,
+            0
+        );
+    }
+
+    #[test]
+    fn test_onchain_updates_validate_did() {
+        let service = test_service();
+
+        assert!(service.add_stake("invalid", 1).is_err());
+        assert!(service.slash_stake("invalid", 1).is_err());
+        assert!(service.apply_onchain_reputation("invalid", 1, 1).is_err());
+        assert!(service
+            .remove_endorsement("invalid", "did:agoramesh:base:x")
+            .is_err());
+    }
 }
@@ -7,6 +7,7 @@
 //!
 //! - **TTL-based expiration**: Trust scores expire after configurable duration
 //! - **Async loading**: Coalesced concurrent requests with `get_with`
+//! - **Batch loading**: One loader call for all misses with `get_or_load_many`
 //! - **Thread-safe**: Lock-free operations via Moka
 //! - **Metrics integration**: Cache hit/miss tracking
 //!
@@ -25,6 +26,7 @@
 //! ```
 
 use moka::future::Cache;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -266,6 +268,52 @@ impl TrustCache {
         }
     }
 
+    /// Get cached trust scores for many DIDs, loading every miss in one call.
+    ///
+    /// `loader` receives the DIDs that were not cached and is not called
+    /// when all of them are hits. DIDs the loader returns no data for are
+    /// left out of the result.
+    pub async fn get_or_load_many<F, Fut, E>(
+        &self,
+        dids: &[String],
+        loader: F,
+    ) -> Result<HashMap<String, CachedTrustInfo>, E>
+    where
+        F: FnOnce(Vec<String>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<TrustInfo>, E>>,
+    {
+        let mut found = HashMap::with_capacity(dids.len());
+        let mut missing = vec![];
+        let mut seen = HashSet::with_capacity(dids.len());
+
+        for did in dids {
+            if !seen.insert(did.as_str()) {
+                continue;
+            }
+            match self.get(did).await {
+                Some(cached) => {
+                    found.insert(did.clone(), cached);
+                }
+                None => missing.push(did.clone()),
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(found);
+        }
+
+        for info in loader(missing).await? {
+            let did = info.did.clone();
+            let cached = CachedTrustInfo::new(info);
+            if let Some(cache) = &self.cache {
+                cache.insert(did.clone(), cached.clone()).await;
+            }
+            found.insert(did, cached);
+        }
+
+        Ok(found)
+    }
+
     /// Invalidate a cached entry.
     pub async fn invalidate(&self, did: &str) {
         if let Some(cache) = &self.cache {
@@ -317,6 +365,7 @@ mod tests {
             successful_transactions: 100,
             failed_transactions: 5,
             endorsement_count: 10,
+            last_activity: 0,
         }
     }
 
@@ -615,6 +664,109 @@ mod tests {
         assert_eq!(result.unwrap_err(), "loader failed");
     }
 
+    // ========== get_or_load_many Tests ==========
+
+    fn dids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|did| did.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load_many_loads_only_misses_in_one_call() {
+        let cache = TrustCache::with_defaults();
+        cache
+            .insert("did:test:cached", test_trust_info("did:test:cached", 0.9))
+            .await;
+
+        let mut calls = vec![];
+        let result = cache
+            .get_or_load_many(
+                &dids(&["did:test:cached", "did:test:a", "did:test:b"]),
+                |missing| {
+                    calls.push(missing.clone());
+                    async move {
+                        Ok::<_, std::io::Error>(
+                            missing
+                                .iter()
+                                .map(|did| test_trust_info(did, 0.5))
+                                .collect(),
+                        )
+                    }
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(calls, vec![dids(&["did:test:a", "did:test:b"])]);
+        assert_eq!(result.len(), 3);
+        assert!((result["did:test:cached"].info.score - 0.9).abs() < 0.001);
+        assert!((result["did:test:a"].info.score - 0.5).abs() < 0.001);
+        assert_eq!(cache.stats().hits.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.stats().misses.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load_many_caches_loaded_values() {
+        let cache = TrustCache::with_defaults();
+        let wanted = dids(&["did:test:a", "did:test:a", "did:test:b"]);
+
+        cache
+            .get_or_load_many(&wanted, |missing| async move {
+                assert_eq!(missing.len(), 2, "Duplicates should be loaded once");
+                Ok::<_, std::io::Error>(
+                    missing
+                        .iter()
+                        .map(|did| test_trust_info(did, 0.4))
+                        .collect(),
+                )
+            })
+            .await
+            .unwrap();
+
+        let mut loader_called = false;
+        let result = cache
+            .get_or_load_many(&wanted, |_| {
+                loader_called = true;
+                async { Ok::<_, std::io::Error>(vec![]) }
+            })
+            .await
+            .unwrap();
+
+        assert!(!loader_called, "All DIDs should be cached");
+        assert_eq!(result.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load_many_propagates_error() {
+        let cache = TrustCache::with_defaults();
+
+        let result = cache
+            .get_or_load_many(&dids(&["did:test:error"]), |_| async {
+                Err("loader failed")
+            })
+            .await;
+
+        assert_eq!(result.unwrap_err(), "loader failed");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cache_get_or_load_many_always_loads() {
+        let cache = TrustCache::disabled();
+        let mut calls = 0;
+
+        for _ in 0..2 {
+            let result = cache
+                .get_or_load_many(&dids(&["did:test:a"]), |missing| {
+                    calls += 1;
+                    async move { Ok::<_, std::io::Error>(vec![test_trust_info(&missing[0], 0.5)]) }
+                })
+                .await
+                .unwrap();
+            assert_eq!(result.len(), 1);
+        }
+
+        assert_eq!(calls, 2);
+    }
+
     // ========== RED Phase: Invalidation Tests ==========
 
     #[tokio::test]
@@ -121,7 +121,7 @@ impl Default for TestServer {
 /// Create a test AppState with default configuration.
 pub fn create_test_state() -> AppState {
     AppState {
-        discovery: Arc::new(DiscoveryService::new()),
+        discovery: Arc::new(DiscoveryService::new().with_required_card_signatures(false)),
         trust: Arc::new(agoramesh_node::TrustService::new(
             "https://sepolia.base.org".to_string(),
             None,
@@ -133,13 +133,18 @@ pub fn create_test_state() -> AppState {
         metrics: Arc::new(MetricsService::new(MetricsConfig::default())),
         hybrid_search: None,
         api_token: None,
+        message_handler: None,
+        did_document: None,
+        did_resolver: None,
+        nat_state: Default::default(),
+        arbitrator: None,
     }
 }
 
 /// Create a test AppState with custom rate limiting.
 pub fn create_test_state_with_rate_limit(config: RateLimitConfig) -> AppState {
     AppState {
-        discovery: Arc::new(DiscoveryService::new()),
+        discovery: Arc::new(DiscoveryService::new().with_required_card_signatures(false)),
         trust: Arc::new(agoramesh_node::TrustService::new(
             "https://sepolia.base.org".to_string(),
             None,
@@ -151,6 +156,11 @@ pub fn create_test_state_with_rate_limit(config: RateLimitConfig) -> AppState {
         metrics: Arc::new(MetricsService::new(MetricsConfig::default())),
         hybrid_search: None,
         api_token: None,
+        message_handler: None,
+        did_document: None,
+        did_resolver: None,
+        nat_state: Default::default(),
+        arbitrator: None,
     }
 }
 
@@ -225,6 +225,7 @@ async fn test_trust_cache_operations() {
         successful_transactions: 100,
         failed_transactions: 5,
         endorsement_count: 10,
+        last_activity: 0,
     };
 
     // Insert into cache
@@ -247,6 +248,7 @@ async fn test_trust_cache_operations() {
         successful_transactions: 150,
         failed_transactions: 5,
         endorsement_count: 15,
+        last_activity: 0,
     };
     cache.insert(did, updated_info).await;
 
@@ -287,7 +289,7 @@ async fn test_trust_endorsement_recording() {
 #[tokio::test]
 async fn test_discovered_agents_have_trust_data() {
     // When an agent is discovered, we should be able to query their trust
-    let discovery = DiscoveryService::new();
+    let discovery = DiscoveryService::new().with_required_card_signatures(false);
     let trust = TrustService::new("https://sepolia.base.org".to_string(), None);
 
     let did = "did:agoramesh:base:discovered-agent";
@@ -302,6 +304,7 @@ async fn test_discovered_agents_have_trust_data() {
             id: "analysis".to_string(),
             name: "Analysis".to_string(),
             description: Some("Provides analysis services".to_string()),
+            tags: vec![],
             input_schema: None,
             output_schema: None,
         }],
@@ -312,6 +315,9 @@ async fn test_discovered_agents_have_trust_data() {
             stake: Some(500_000_000),
             pricing: None,
             payment_methods: vec!["x402".to_string()],
+            version: None,
+            updated_at: None,
+            proof: None,
         }),
     };
 
@@ -332,7 +338,7 @@ async fn test_discovered_agents_have_trust_data() {
 #[tokio::test]
 async fn test_search_returns_registered_agents() {
     // Search results should find registered agents
-    let discovery = DiscoveryService::new();
+    let discovery = DiscoveryService::new().with_required_card_signatures(false);
 
     // Register multiple agents
     for i in 0..5 {
@@ -347,6 +353,7 @@ async fn test_search_returns_registered_agents() {
                 id: "service".to_string(),
                 name: "Service".to_string(),
                 description: Some("Generic service".to_string()),
+                tags: vec![],
                 input_schema: None,
                 output_schema: None,
             }],
@@ -357,6 +364,9 @@ async fn test_search_returns_registered_agents() {
                 stake: Some(500_000_000),
                 pricing: None,
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         };
 
@@ -417,7 +427,7 @@ async fn test_circuit_breaker_has_metrics() {
 #[tokio::test]
 async fn test_multi_agent_collaboration_setup() {
     // Test setting up multiple agents that could collaborate
-    let discovery = DiscoveryService::new();
+    let discovery = DiscoveryService::new().with_required_card_signatures(false);
     let cache = TrustCache::new(TrustCacheConfig::default());
 
     // Create a network of collaborating agents
@@ -454,6 +464,7 @@ async fn test_multi_agent_collaboration_setup() {
                     id: c.to_string(),
                     name: c.to_string(),
                     description: None,
+                    tags: vec![],
                     input_schema: None,
                     output_schema: None,
                 })
@@ -465,6 +476,9 @@ async fn test_multi_agent_collaboration_setup() {
                 stake: Some(1_000_000_000),
                 pricing: None,
                 payment_methods: vec!["x402".to_string()],
+                version: None,
+                updated_at: None,
+                proof: None,
             }),
         };
 
@@ -481,6 +495,7 @@ async fn test_multi_agent_collaboration_setup() {
             successful_transactions: 50,
             failed_transactions: 2,
             endorsement_count: 5,
+            last_activity: 0,
         };
         cache.insert(did, trust_info).await;
     }
@@ -545,7 +560,7 @@ async fn test_escrow_dispute_resolution_flow() {
 #[tokio::test]
 async fn test_concurrent_agent_operations() {
     // Test that concurrent operations don't cause data corruption
-    let discovery = Arc::new(DiscoveryService::new());
+    let discovery = Arc::new(DiscoveryService::new().with_required_card_signatures(false));
 
     let mut handles = vec![];
 
@@ -567,6 +582,9 @@ async fn test_concurrent_agent_operations() {
                     stake: Some(500_000_000),
                     pricing: None,
                     payment_methods: vec!["x402".to_string()],
+                    version: None,
+                    updated_at: None,
+                    proof: None,
                 }),
             };
             discovery.register(&card).await.unwrap();
@@ -614,6 +632,7 @@ async fn test_trust_cache_under_concurrent_access() {
                     successful_transactions: 100,
                     failed_transactions: 5,
                     endorsement_count: 3,
+                    last_activity: 0,
                 };
                 cache.insert(&did, info).await;
             }
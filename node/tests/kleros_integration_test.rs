@@ -0,0 +1,296 @@
+//! Kleros Integration Tests against a local anvil node.
+//!
+//! Deploys `contracts/test/mocks/MockArbitrator.sol` (a minimal ERC-792
+//! arbitrator) to anvil and drives it through `KlerosClient`:
+//! - Cost queries with `KlerosConfig::encode_extra_data`
+//! - Signed `createDispute` transactions and `DisputeCreation` parsing
+//! - Status, ruling and appeal period reads
+//! - AI arbitrator appeal escalation (Tier 2 → Tier 3)
+//!
+//! ## Running Tests
+//!
+//! Requires `anvil` on `PATH` and compiled contract artifacts:
+//!
+//! ```bash
+//! (cd ../contracts && forge build)
+//! cargo test --test kleros_integration_test -- --ignored --test-threads=1
+//! ```
+
+use std::path::PathBuf;
+
+use alloy::network::TransactionBuilder;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+
+use agoramesh_node::{
+    AIArbitrationConfig, AIArbitrator, AIDisputeState, DisputeStatus, Evidence, EvidenceType,
+    KlerosClient, KlerosConfig, Ruling,
+};
+
+/// Base cost per juror charged by the mock arbitrator (0.001 ETH).
+const BASE_COST_WEI: u64 = 1_000_000_000_000_000;
+
+sol! {
+    #[sol(rpc)]
+    interface IMockArbitrator {
+        function giveRuling(uint256 _disputeID, uint256 _ruling, uint256 _appealWindow) external;
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Running anvil node with a deployed mock arbitrator.
+struct MockChain {
+    anvil: AnvilInstance,
+    arbitrator: Address,
+}
+
+impl MockChain {
+    async fn start() -> Self {
+        let anvil = Anvil::new().try_spawn().expect("anvil must be on PATH");
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .connect_http(anvil.endpoint_url());
+
+        let mut code = mock_arbitrator_bytecode();
+        code.extend_from_slice(&U256::from(BASE_COST_WEI).to_be_bytes::<32>());
+
+        let receipt = provider
+            .send_transaction(TransactionRequest::default().with_deploy_code(Bytes::from(code)))
+            .await
+            .expect("deploy transaction should be accepted")
+            .get_receipt()
+            .await
+            .expect("deploy transaction should be mined");
+        let arbitrator = receipt
+            .contract_address
+            .expect("deployment receipt should carry the contract address");
+
+        Self { anvil, arbitrator }
+    }
+
+    fn config(&self) -> KlerosConfig {
+        KlerosConfig::new(self.anvil.endpoint(), self.arbitrator.to_string())
+            .with_signer_key(alloy::hex::encode(self.anvil.keys()[0].to_bytes()))
+    }
+
+    fn client(&self) -> KlerosClient {
+        KlerosClient::new(self.config()).unwrap()
+    }
+
+    /// Rule on a dispute through the mock's test hook.
+    async fn give_ruling(&self, dispute_id: U256, ruling: Ruling, appeal_window_secs: u64) {
+        let signer: PrivateKeySigner = self.anvil.keys()[0].clone().into();
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .connect_http(self.anvil.endpoint_url());
+        let mock = IMockArbitrator::new(self.arbitrator, provider);
+
+        mock.giveRuling(
+            dispute_id,
+            U256::from(ruling as u64),
+            U256::from(appeal_window_secs),
+        )
+        .send()
+        .await
+        .expect("giveRuling should be accepted")
+        .get_receipt()
+        .await
+        .expect("giveRuling should be mined");
+    }
+}
+
+/// Creation bytecode from the forge artifact.
+fn mock_arbitrator_bytecode() -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../contracts/out/MockArbitrator.sol/MockArbitrator.json");
+    let artifact = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "run `forge build` in contracts/ ({}): {}",
+            path.display(),
+            e
+        )
+    });
+    let artifact: serde_json::Value = serde_json::from_str(&artifact).unwrap();
+    let object = artifact["bytecode"]["object"]
+        .as_str()
+        .expect("artifact should contain bytecode.object");
+
+    alloy::hex::decode(object).unwrap()
+}
+
+// ============================================================================
+// KlerosClient Tests
+// ============================================================================
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_arbitration_and_appeal_cost_use_extra_data() {
+    let chain = MockChain::start().await;
+    let client = KlerosClient::new(chain.config().with_jurors(5)).unwrap();
+
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+    let appeal_cost = client.get_appeal_cost(U256::ZERO).await.unwrap();
+
+    // Mock charges base cost per juror encoded in extraData
+    assert_eq!(cost, U256::from(5 * BASE_COST_WEI));
+    assert_eq!(appeal_cost, cost * U256::from(2));
+}
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_create_dispute_returns_ids_from_dispute_creation_event() {
+    let chain = MockChain::start().await;
+    let client = chain.client();
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+
+    let first = client
+        .create_dispute("ipfs://QmFirst", 3, cost)
+        .await
+        .unwrap();
+    let second = client
+        .create_dispute("ipfs://QmSecond", 3, cost)
+        .await
+        .unwrap();
+
+    assert_eq!(first, U256::ZERO);
+    assert_eq!(second, U256::from(1));
+    assert_eq!(
+        client
+            .stats()
+            .disputes_created
+            .load(std::sync::atomic::Ordering::Relaxed),
+        2
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_create_dispute_underpaid_reverts() {
+    let chain = MockChain::start().await;
+    let client = chain.client();
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+
+    let result = client
+        .create_dispute("ipfs://QmUnderpaid", 3, cost - U256::from(1))
+        .await;
+
+    assert!(result.is_err(), "Underpaid dispute should be rejected");
+    assert_eq!(
+        client
+            .stats()
+            .rpc_errors
+            .load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_new_dispute_is_waiting_without_ruling() {
+    let chain = MockChain::start().await;
+    let client = chain.client();
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+    let dispute_id = client
+        .create_dispute("ipfs://QmWaiting", 3, cost)
+        .await
+        .unwrap();
+
+    let dispute = client.get_dispute(dispute_id, 3).await.unwrap();
+
+    assert_eq!(dispute.status, DisputeStatus::Waiting);
+    assert_eq!(dispute.ruling, Ruling::None);
+    assert!(dispute.appeal_period.is_none());
+    assert!(client
+        .get_appeal_period(dispute_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_ruled_dispute_reports_ruling_and_appeal_period() {
+    let chain = MockChain::start().await;
+    let client = chain.client();
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+    let dispute_id = client
+        .create_dispute("ipfs://QmRuled", 3, cost)
+        .await
+        .unwrap();
+
+    chain
+        .give_ruling(dispute_id, Ruling::FavorProvider, 3600)
+        .await;
+    let dispute = client.get_dispute(dispute_id, 3).await.unwrap();
+
+    assert_eq!(dispute.status, DisputeStatus::Appealable);
+    assert_eq!(dispute.ruling, Ruling::FavorProvider);
+    let period = dispute.appeal_period.expect("appeal period should be open");
+    assert_eq!(period.end - period.start, 3600);
+    assert!(period.is_active());
+}
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_final_ruling_is_solved() {
+    let chain = MockChain::start().await;
+    let client = chain.client();
+    let cost = client.get_arbitration_cost(3).await.unwrap();
+    let dispute_id = client
+        .create_dispute("ipfs://QmSolved", 3, cost)
+        .await
+        .unwrap();
+
+    chain.give_ruling(dispute_id, Ruling::Split, 0).await;
+
+    assert_eq!(
+        client.get_dispute_status(dispute_id).await.unwrap(),
+        DisputeStatus::Solved
+    );
+    assert_eq!(
+        client.get_current_ruling(dispute_id).await.unwrap(),
+        Ruling::Split
+    );
+}
+
+// ============================================================================
+// AI Arbitrator Escalation
+// ============================================================================
+
+#[tokio::test]
+#[ignore = "requires anvil and `forge build` in contracts/"]
+async fn test_ai_arbitrator_appeals_to_kleros_on_chain() {
+    let chain = MockChain::start().await;
+    let arbitrator =
+        AIArbitrator::new(AIArbitrationConfig::default().with_kleros(chain.config())).unwrap();
+
+    let dispute_id = arbitrator
+        .create_dispute("escrow-123", "did:client", "did:provider", 100_000_000)
+        .unwrap();
+    let evidence = Evidence::new(
+        "did:client",
+        EvidenceType::Text,
+        "Complaint",
+        "The service was not delivered",
+    );
+    arbitrator.submit_evidence(&dispute_id, evidence).unwrap();
+    let ruling = arbitrator.request_ruling(&dispute_id).await.unwrap();
+    assert!(ruling.can_appeal());
+
+    let kleros_id = arbitrator.appeal_to_kleros(&dispute_id).await.unwrap();
+
+    let dispute = arbitrator.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.state, AIDisputeState::Appealed);
+    assert_eq!(dispute.kleros_dispute_id, Some(kleros_id));
+
+    let status = chain.client().get_dispute_status(kleros_id).await.unwrap();
+    assert_eq!(status, DisputeStatus::Waiting);
+}
@@ -157,6 +157,7 @@ async fn test_concurrent_trust_cache_operations() {
                 successful_transactions: 100,
                 failed_transactions: 5,
                 endorsement_count: 3,
+                last_activity: 0,
             };
             cache.insert(&did, info).await;
         });
@@ -234,6 +235,7 @@ async fn test_trust_cache_stress() {
                         successful_transactions: 100,
                         failed_transactions: 5,
                         endorsement_count: 3,
+                        last_activity: 0,
                     };
                     cache.insert(&did, info).await;
                 } else {
@@ -546,6 +548,7 @@ fn create_test_card(did: &str) -> CapabilityCard {
             id: "code-review".to_string(),
             name: "Code Review".to_string(),
             description: Some("Automated code review service".to_string()),
+            tags: vec![],
             input_schema: None,
             output_schema: None,
         }],
@@ -556,6 +559,9 @@ fn create_test_card(did: &str) -> CapabilityCard {
             stake: Some(1_000_000_000),
             pricing: None,
             payment_methods: vec!["x402".to_string()],
+            version: None,
+            updated_at: None,
+            proof: None,
         }),
     }
 }